@@ -4,11 +4,14 @@ use clap::{Args, Parser, Subcommand};
 use goose::config::{Config, ExtensionConfig};
 
 use crate::commands::bench::agent_generator;
-use crate::commands::configure::handle_configure;
+use crate::commands::configure::{handle_configure, handle_list_models};
 use crate::commands::info::handle_info;
-use crate::commands::mcp::run_server;
+use crate::commands::mcp::{handle_inspect, run_server};
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
-use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
+use crate::commands::recipe::{
+    handle_deeplink, handle_list, handle_preview, handle_register, handle_share, handle_validate,
+    parse_expiry,
+};
 // Import the new handlers from commands::schedule
 use crate::commands::schedule::{
     handle_schedule_add, handle_schedule_cron_help, handle_schedule_list, handle_schedule_remove,
@@ -97,6 +100,9 @@ enum SessionCommand {
             long_help = "Sort sessions by date in ascending order (oldest first). Default is descending order (newest first)."
         )]
         ascending: bool,
+
+        #[arg(long, help = "Only list sessions with this tag")]
+        tag: Option<String>,
     },
     #[command(about = "Remove sessions. Runs interactively if no ID or regex is provided.")]
     Remove {
@@ -118,6 +124,61 @@ enum SessionCommand {
         )]
         output: Option<PathBuf>,
     },
+    #[command(about = "Show usage statistics for a session")]
+    Stats {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+    },
+    #[command(about = "Compare the messages of two sessions")]
+    Diff {
+        #[arg(help = "First session, by name or path to the session file")]
+        session_a: String,
+
+        #[arg(help = "Second session, by name or path to the session file")]
+        session_b: String,
+    },
+    #[command(about = "Check that a session's messages match its stored content hash")]
+    Verify {
+        #[arg(help = "Session to verify, by name or path to the session file")]
+        session: String,
+    },
+    #[command(about = "Shrink a session file by replacing repeated tool results with references")]
+    Compress {
+        #[arg(help = "Session to compress, by name or path to the session file")]
+        session: String,
+    },
+    #[command(about = "Drop all but the most recent messages of a session")]
+    Trim {
+        #[arg(help = "Session to trim, by name or path to the session file")]
+        session: String,
+        #[arg(
+            long = "last-n-messages",
+            value_name = "N",
+            help = "Number of messages to keep, fewer when the first would not be a user message"
+        )]
+        last_n_messages: usize,
+    },
+    #[command(about = "Show the sessions a session was forked from")]
+    Lineage {
+        #[arg(help = "Session to trace, by name or path to the session file")]
+        session: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum McpCommand {
+    #[command(about = "List the tools, resources and prompts an MCP server exposes")]
+    Inspect {
+        #[arg(help = "Command that starts the stdio MCP server")]
+        server: String,
+
+        #[arg(
+            help = "Arguments passed to the server command",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -267,13 +328,109 @@ enum RecipeCommand {
         )]
         verbose: bool,
     },
+
+    /// Register a recipe under a name so it can be used by that name from anywhere
+    #[command(about = "Register a recipe by name")]
+    Register {
+        /// Name to register the recipe under
+        #[arg(
+            long = "name",
+            value_name = "NAME",
+            help = "Name to register the recipe under"
+        )]
+        name: String,
+
+        /// Recipe name or full path to the recipe file to register
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to register")]
+        recipe_name: String,
+    },
+
+    /// Upload a recipe to a paste service and print its URL
+    #[command(about = "Share a recipe through a paste service")]
+    Share {
+        /// Recipe name or full path to the recipe file to share
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to share")]
+        recipe_name: String,
+
+        /// How long the paste is kept, if the paste service supports expiry
+        #[arg(
+            long = "expires",
+            value_name = "DURATION",
+            help = "How long the paste is kept, e.g. 24h or 7d, if the service supports it",
+            long_help = "How long the paste is kept, e.g. 30m, 24h or 7d. Sent to services that support expiry, like rustypaste; paste.rs keeps pastes forever. Set GOOSE_RECIPE_SHARE_URL to use another service.",
+            value_parser = parse_expiry
+        )]
+        expires: Option<String>,
+    },
+
+    /// Print a recipe with its parameters filled in, without running it
+    #[command(about = "Preview a rendered recipe")]
+    Preview {
+        /// Recipe name or full path to the recipe file to preview
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to preview")]
+        recipe_name: String,
+
+        #[arg(
+            long = "param",
+            value_name = "KEY=VALUE",
+            help = "Recipe parameter (e.g., --param environment=staging)",
+            long_help = "Key-value parameter to render the recipe with. Can be specified multiple times. Parameters that are not given use their default or stay as template variables.",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        params: Vec<(String, String)>,
+
+        #[arg(
+            long = "compare",
+            value_name = "KEY=VALUE",
+            help = "Show a diff against the recipe rendered with these parameters changed",
+            long_help = "Key-value parameter that overrides --param for a second rendering. When given, a diff between the two renderings is printed instead of the recipe. Can be specified multiple times.",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        compare: Vec<(String, String)>,
+    },
+
+    /// Run a recipe, filling in its parameters from the command line
+    #[command(about = "Run a recipe")]
+    Run {
+        /// Recipe name or full path to the recipe file to run
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to run")]
+        recipe_name: String,
+
+        #[arg(
+            long = "param",
+            value_name = "KEY=VALUE",
+            help = "Recipe parameter (e.g., --param environment=staging)",
+            long_help = "Key-value parameter to pass to the recipe. Can be specified multiple times.",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        params: Vec<(String, String)>,
+
+        /// Continue in interactive mode after the recipe's prompt
+        #[arg(
+            short = 's',
+            long = "interactive",
+            help = "Continue in interactive mode after the recipe's prompt"
+        )]
+        interactive: bool,
+    },
 }
 
 #[derive(Subcommand)]
 enum Command {
     /// Configure Goose settings
     #[command(about = "Configure Goose settings")]
-    Configure {},
+    Configure {
+        /// List the models a provider offers instead of running the configuration dialog
+        #[arg(
+            long = "list-models",
+            value_name = "PROVIDER",
+            help = "List the models a provider offers and what they support"
+        )]
+        list_models: Option<String>,
+    },
 
     /// Display Goose configuration information
     #[command(about = "Display Goose information")]
@@ -284,8 +441,18 @@ enum Command {
     },
 
     /// Manage system prompts and behaviors
-    #[command(about = "Run one of the mcp servers bundled with goose")]
-    Mcp { name: String },
+    #[command(
+        about = "Run one of the mcp servers bundled with goose",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Mcp {
+        #[arg(required = true)]
+        name: Option<String>,
+
+        #[command(subcommand)]
+        command: Option<McpCommand>,
+    },
 
     /// Start or resume interactive chat sessions
     #[command(
@@ -689,8 +856,11 @@ pub struct InputConfig {
 pub struct RecipeInfo {
     pub session_settings: Option<SessionSettings>,
     pub sub_recipes: Option<Vec<goose::recipe::SubRecipe>>,
+    pub sub_recipe_execution_mode: Option<goose::recipe::SubRecipeExecutionMode>,
+    pub audit: Option<goose::recipe::RecipeAuditConfig>,
     pub final_output_response: Option<goose::recipe::Response>,
     pub retry_config: Option<goose::agents::types::RetryConfig>,
+    pub activities: Option<Vec<String>>,
 }
 
 pub async fn cli() -> Result<()> {
@@ -702,17 +872,28 @@ pub async fn cli() -> Result<()> {
     }
 
     match cli.command {
-        Some(Command::Configure {}) => {
-            let _ = handle_configure().await;
+        Some(Command::Configure { list_models }) => {
+            match list_models {
+                Some(provider) => handle_list_models(&provider).await?,
+                None => {
+                    let _ = handle_configure().await;
+                }
+            }
             return Ok(());
         }
         Some(Command::Info { verbose }) => {
             handle_info(verbose)?;
             return Ok(());
         }
-        Some(Command::Mcp { name }) => {
-            let _ = run_server(&name).await;
-        }
+        Some(Command::Mcp { name, command }) => match command {
+            Some(McpCommand::Inspect { server, args }) => {
+                handle_inspect(&server, args).await?;
+            }
+            None => {
+                let name = name.expect("clap requires a server name without a subcommand");
+                let _ = run_server(&name).await;
+            }
+        },
         Some(Command::Session {
             command,
             identifier,
@@ -731,8 +912,9 @@ pub async fn cli() -> Result<()> {
                     verbose,
                     format,
                     ascending,
+                    tag,
                 }) => {
-                    handle_session_list(verbose, format, ascending)?;
+                    handle_session_list(verbose, format, ascending, tag)?;
                     Ok(())
                 }
                 Some(SessionCommand::Remove { id, regex }) => {
@@ -756,6 +938,48 @@ pub async fn cli() -> Result<()> {
                     crate::commands::session::handle_session_export(session_identifier, output)?;
                     Ok(())
                 }
+                Some(SessionCommand::Stats { identifier }) => {
+                    let session_identifier = if let Some(id) = identifier {
+                        extract_identifier(id)
+                    } else {
+                        match crate::commands::session::prompt_interactive_session_selection() {
+                            Ok(id) => id,
+                            Err(e) => {
+                                eprintln!("Error: {}", e);
+                                return Ok(());
+                            }
+                        }
+                    };
+
+                    crate::commands::session::handle_session_stats(session_identifier)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Diff {
+                    session_a,
+                    session_b,
+                }) => {
+                    crate::commands::session::handle_session_diff(session_a, session_b)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Verify { session }) => {
+                    crate::commands::session::handle_session_verify(session)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Compress { session }) => {
+                    crate::commands::session::handle_session_compress(session)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Trim {
+                    session,
+                    last_n_messages,
+                }) => {
+                    crate::commands::session::handle_session_trim(session, last_n_messages)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Lineage { session }) => {
+                    crate::commands::session::handle_session_lineage(session)?;
+                    Ok(())
+                }
                 None => {
                     // Run session command by default
                     let mut session: crate::Session = build_session(SessionBuilderConfig {
@@ -778,8 +1002,11 @@ pub async fn cli() -> Result<()> {
                         interactive: true,
                         quiet: false,
                         sub_recipes: None,
+                        sub_recipe_execution_mode: None,
+                        audit: None,
                         final_output_response: None,
                         retry_config: None,
+                        activities: None,
                     })
                     .await;
                     setup_logging(
@@ -918,10 +1145,15 @@ pub async fn cli() -> Result<()> {
                 interactive, // Use the interactive flag from the Run command
                 quiet,
                 sub_recipes: recipe_info.as_ref().and_then(|r| r.sub_recipes.clone()),
+                sub_recipe_execution_mode: recipe_info
+                    .as_ref()
+                    .and_then(|r| r.sub_recipe_execution_mode),
+                audit: recipe_info.as_ref().and_then(|r| r.audit.clone()),
                 final_output_response: recipe_info
                     .as_ref()
                     .and_then(|r| r.final_output_response.clone()),
                 retry_config: recipe_info.as_ref().and_then(|r| r.retry_config.clone()),
+                activities: recipe_info.as_ref().and_then(|r| r.activities.clone()),
             })
             .await;
 
@@ -1019,6 +1251,62 @@ pub async fn cli() -> Result<()> {
                 RecipeCommand::List { format, verbose } => {
                     handle_list(&format, verbose)?;
                 }
+                RecipeCommand::Register { name, recipe_name } => {
+                    handle_register(&name, &recipe_name)?;
+                }
+                RecipeCommand::Share {
+                    recipe_name,
+                    expires,
+                } => {
+                    handle_share(&recipe_name, expires.as_deref()).await?;
+                }
+                RecipeCommand::Preview {
+                    recipe_name,
+                    params,
+                    compare,
+                } => {
+                    handle_preview(&recipe_name, params, compare)?;
+                }
+                RecipeCommand::Run {
+                    recipe_name,
+                    params,
+                    interactive,
+                } => {
+                    let (input_config, recipe_info) =
+                        extract_recipe_info_from_cli(recipe_name, params, Vec::new())?;
+                    let mut session = build_session(SessionBuilderConfig {
+                        extensions_override: input_config.extensions_override,
+                        additional_system_prompt: input_config.additional_system_prompt,
+                        settings: recipe_info.session_settings,
+                        interactive,
+                        sub_recipes: recipe_info.sub_recipes,
+                        sub_recipe_execution_mode: recipe_info.sub_recipe_execution_mode,
+                        audit: recipe_info.audit,
+                        final_output_response: recipe_info.final_output_response,
+                        retry_config: recipe_info.retry_config,
+                        activities: recipe_info.activities,
+                        ..Default::default()
+                    })
+                    .await;
+
+                    setup_logging(
+                        session
+                            .session_file()
+                            .as_ref()
+                            .and_then(|p| p.file_stem())
+                            .and_then(|s| s.to_str()),
+                        None,
+                    )?;
+
+                    if interactive {
+                        let _ = session.interactive(input_config.contents).await;
+                    } else if let Some(contents) = input_config.contents {
+                        let _ = session.headless(contents).await;
+                    } else {
+                        eprintln!("Error: the recipe has no prompt to run in headless mode, use --interactive");
+                        std::process::exit(1);
+                    }
+                }
             }
             return Ok(());
         }
@@ -1052,8 +1340,11 @@ pub async fn cli() -> Result<()> {
                     interactive: true, // Default case is always interactive
                     quiet: false,
                     sub_recipes: None,
+                    sub_recipe_execution_mode: None,
+                    audit: None,
                     final_output_response: None,
                     retry_config: None,
+                    activities: None,
                 })
                 .await;
                 setup_logging(
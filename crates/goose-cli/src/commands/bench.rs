@@ -51,8 +51,11 @@ pub async fn agent_generator(
         max_turns: None,
         quiet: false,
         sub_recipes: None,
+        sub_recipe_execution_mode: None,
+        audit: None,
         final_output_response: None,
         retry_config: None,
+        activities: None,
     })
     .await;
 
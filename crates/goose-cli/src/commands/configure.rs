@@ -430,6 +430,47 @@ pub async fn configure_provider_dialog() -> Result<bool, Box<dyn Error>> {
     }
 }
 
+/// Print the models a provider offers, as reported by its models API
+pub async fn handle_list_models(provider_name: &str) -> anyhow::Result<()> {
+    let provider_meta = providers()
+        .into_iter()
+        .find(|p| p.name == provider_name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider '{}'", provider_name))?;
+    let model_config = goose::model::ModelConfig::new(provider_meta.default_model.clone());
+    let provider = create(provider_name, model_config)?;
+    let models = provider.list_models().await?;
+
+    if models.is_empty() {
+        println!("{} did not report any models", provider_meta.display_name);
+        return Ok(());
+    }
+
+    let yes_no = |supported: bool| if supported { "yes" } else { "no" };
+    let width = models.iter().map(|m| m.id.len()).max().unwrap_or(0);
+    println!(
+        "{}",
+        style(format!(
+            "{:<width$}  {:>8}  {:<5}  {}",
+            "MODEL", "CONTEXT", "TOOLS", "VISION"
+        ))
+        .bold()
+    );
+    for model in models {
+        let context = model
+            .context_window
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<width$}  {:>8}  {:<5}  {}",
+            model.id,
+            context,
+            yes_no(model.supports_tools),
+            yes_no(model.supports_vision)
+        );
+    }
+    Ok(())
+}
+
 /// Configure extensions that can be used with goose
 /// Dialog for toggling which extensions are enabled/disabled
 pub fn toggle_extensions_dialog() -> Result<(), Box<dyn Error>> {
@@ -1,12 +1,20 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use console::style;
+use goose::config::{Config, DEFAULT_EXTENSION_TIMEOUT};
 use goose_mcp::{
     ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, MemoryRouter, TutorialRouter,
 };
+use mcp_client::client::{ClientCapabilities, ClientInfo, McpClient, McpClientTrait};
+use mcp_client::transport::{StdioTransport, Transport};
 use mcp_server::router::RouterService;
-use mcp_server::{BoundedService, ByteTransport, Server};
+use mcp_server::{BoundedService, ByteTransport, FileAuditSink, Server};
+use tabled::settings::{object::Columns, Style, Width};
+use tabled::{Table, Tabled};
 use tokio::io::{stdin, stdout};
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Notify;
 
 #[cfg(unix)]
@@ -16,6 +24,52 @@ use nix::unistd::getpgrp;
 #[cfg(unix)]
 use nix::unistd::Pid;
 
+/// The audit log set with GOOSE_AUDIT_LOG, which the session sets to "off" or its own file when
+/// running a recipe with `audit` configured
+fn audit_sink() -> Option<FileAuditSink> {
+    let config = Config::global();
+    let path = config
+        .get_param::<String>("GOOSE_AUDIT_LOG")
+        .ok()
+        .filter(|path| !path.is_empty() && path != "off")?;
+    let path = std::path::PathBuf::from(path);
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            tracing::error!(
+                path = %path.display(),
+                error = %e,
+                "Failed to create audit log directory"
+            );
+        }
+    }
+
+    let redact_patterns = config
+        .get_param::<Vec<String>>("GOOSE_AUDIT_REDACT_PATTERNS")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|pattern| match regex::Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                tracing::warn!(
+                    pattern = %pattern,
+                    error = %e,
+                    "Ignoring invalid audit redact pattern"
+                );
+                None
+            }
+        })
+        .collect();
+    Some(
+        FileAuditSink::new(path)
+            .with_tool_outputs(
+                config
+                    .get_param("GOOSE_AUDIT_INCLUDE_TOOL_OUTPUTS")
+                    .unwrap_or(false),
+            )
+            .with_redact_patterns(redact_patterns),
+    )
+}
+
 pub async fn run_server(name: &str) -> Result<()> {
     // Initialize logging
     crate::logging::setup_logging(Some(&format!("mcp-{name}")), None)?;
@@ -45,8 +99,18 @@ pub async fn run_server(name: &str) -> Result<()> {
     });
 
     // Create and run the server
-    let server = Server::new(router.unwrap_or_else(|| panic!("Unknown server requested {}", name)));
-    let transport = ByteTransport::new(stdin(), stdout());
+    let mut server =
+        Server::new(router.unwrap_or_else(|| panic!("Unknown server requested {}", name)));
+    if let Some(sink) = audit_sink() {
+        tracing::info!(path = %sink.path().display(), "Recording requests to the audit log");
+        server = server.with_audit_sink(sink);
+    }
+    // Messages larger than the read buffer are common with big file operations
+    let buffer_size_mb = Config::global()
+        .get_param::<usize>("GOOSE_TRANSPORT_BUFFER_SIZE_MB")
+        .unwrap_or(2)
+        .max(1);
+    let transport = ByteTransport::with_capacity(stdin(), stdout(), buffer_size_mb * 1024 * 1024);
 
     tracing::info!("Server initialized and ready to handle requests");
 
@@ -69,3 +133,156 @@ pub async fn run_server(name: &str) -> Result<()> {
         }
     }
 }
+
+/// Widest a description column gets before its text wraps
+const DESCRIPTION_WIDTH: usize = 60;
+
+#[derive(Tabled)]
+struct ToolRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Description")]
+    description: String,
+}
+
+#[derive(Tabled)]
+struct ResourceRow {
+    #[tabled(rename = "URI")]
+    uri: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "MIME type")]
+    mime_type: String,
+    #[tabled(rename = "Description")]
+    description: String,
+}
+
+#[derive(Tabled)]
+struct PromptRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Arguments")]
+    arguments: String,
+    #[tabled(rename = "Description")]
+    description: String,
+}
+
+fn print_table<R: Tabled>(title: &str, rows: Vec<R>) {
+    println!("\n{} ({})", style(title).green().bold(), rows.len());
+    if rows.is_empty() {
+        println!("{}", style("  none").dim());
+        return;
+    }
+    let mut table = Table::new(rows);
+    table.with(Style::rounded()).modify(
+        Columns::last(),
+        Width::wrap(DESCRIPTION_WIDTH).keep_words(true),
+    );
+    println!("{}", table);
+}
+
+/// Start a stdio MCP server and print what it exposes: its name and version, and the
+/// tools, resources and prompts it lists
+pub async fn handle_inspect(command: &str, args: Vec<String>) -> Result<()> {
+    let transport = StdioTransport::new(command, args, HashMap::new());
+    let handle = transport
+        .start()
+        .await
+        .with_context(|| format!("Failed to start MCP server '{}'", command))?;
+    let mut client =
+        McpClient::connect(handle, Duration::from_secs(DEFAULT_EXTENSION_TIMEOUT)).await?;
+
+    let info = ClientInfo {
+        name: "goose".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let init_result = client
+        .initialize(info, ClientCapabilities::default())
+        .await
+        .context("Failed to initialize MCP server")?;
+
+    println!(
+        "{} {} {}",
+        style("Server:").bold(),
+        init_result.server_info.name,
+        style(format!("v{}", init_result.server_info.version)).dim()
+    );
+    println!(
+        "{} {}",
+        style("Protocol:").bold(),
+        init_result.protocol_version
+    );
+
+    let capabilities = &init_result.capabilities;
+
+    if capabilities.tools.is_some() {
+        let mut tools = Vec::new();
+        let mut cursor = None;
+        loop {
+            let result = client.list_tools(cursor).await?;
+            tools.extend(result.tools);
+            cursor = result.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let rows = tools
+            .into_iter()
+            .map(|tool| ToolRow {
+                name: tool.name.to_string(),
+                description: tool.description.unwrap_or_default().to_string(),
+            })
+            .collect();
+        print_table("Tools", rows);
+    }
+
+    if capabilities.resources.is_some() {
+        let mut resources = Vec::new();
+        let mut cursor = None;
+        loop {
+            let result = client.list_resources(cursor).await?;
+            resources.extend(result.resources);
+            cursor = result.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let rows = resources
+            .into_iter()
+            .map(|resource| ResourceRow {
+                uri: resource.uri.clone(),
+                name: resource.name.clone(),
+                mime_type: resource.mime_type.clone().unwrap_or_default(),
+                description: resource.description.clone().unwrap_or_default(),
+            })
+            .collect();
+        print_table("Resources", rows);
+    }
+
+    if capabilities.prompts.is_some() {
+        let prompts = client.list_prompts(None).await?.prompts;
+        let rows = prompts
+            .into_iter()
+            .map(|prompt| PromptRow {
+                arguments: prompt
+                    .arguments
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|arg| {
+                        if arg.required.unwrap_or(false) {
+                            arg.name.clone()
+                        } else {
+                            format!("[{}]", arg.name)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                name: prompt.name,
+                description: prompt.description.unwrap_or_default(),
+            })
+            .collect();
+        print_table("Prompts", rows);
+    }
+
+    Ok(())
+}
@@ -1,11 +1,18 @@
 use anyhow::Result;
 use console::style;
+use std::collections::HashMap;
 
 use crate::recipes::github_recipe::RecipeSource;
 use crate::recipes::recipe::load_recipe_for_validation;
 use crate::recipes::search_recipe::list_available_recipes;
+use goose::config::Config;
+use goose::recipe::registry::RecipeRegistry;
+use goose::recipe::Recipe;
 use goose::recipe_deeplink;
 
+/// Where `goose recipe share` uploads recipes unless GOOSE_RECIPE_SHARE_URL is set
+const DEFAULT_RECIPE_SHARE_URL: &str = "https://paste.rs/";
+
 /// Validates a recipe file
 ///
 /// # Arguments
@@ -68,7 +75,161 @@ pub fn handle_deeplink(recipe_name: &str) -> Result<String> {
     }
 }
 
-/// Lists all available recipes from local paths and GitHub repositories
+/// Registers a recipe file under a name so it can be run by that name from anywhere
+///
+/// # Arguments
+///
+/// * `name` - Name to register the recipe under
+/// * `recipe_name` - Recipe name or path to the recipe file
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub fn handle_register(name: &str, recipe_name: &str) -> Result<()> {
+    let recipe = load_recipe_for_validation(recipe_name)?;
+    let title = recipe.title.clone();
+
+    let registry = RecipeRegistry::global();
+    let replaced = registry.register(name, recipe).is_some();
+    registry.persist()?;
+
+    println!(
+        "{} {} recipe {} ({})",
+        style("✓").green().bold(),
+        if replaced { "Updated" } else { "Registered" },
+        name,
+        title
+    );
+    Ok(())
+}
+
+/// Prints a recipe rendered with the given parameters, or the diff against a second rendering
+/// with the `compare` parameters changed
+///
+/// # Arguments
+///
+/// * `recipe_name` - Recipe name or path to the recipe file
+/// * `params` - Parameter values to render the recipe with
+/// * `compare` - Parameter values that override `params` for the rendering to diff against
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub fn handle_preview(
+    recipe_name: &str,
+    params: Vec<(String, String)>,
+    compare: Vec<(String, String)>,
+) -> Result<()> {
+    let recipe = load_recipe_for_validation(recipe_name)?;
+    let params: HashMap<String, String> = params.into_iter().collect();
+    if compare.is_empty() {
+        print!("{}", recipe.render_preview(&params)?);
+        return Ok(());
+    }
+
+    let mut compared = params.clone();
+    compared.extend(compare);
+    let diff = recipe.diff_preview(&params, &compared)?;
+    if diff.is_empty() {
+        println!(
+            "{} Both sets of parameters render the same recipe",
+            style("✓").green().bold()
+        );
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
+/// Checks a paste expiry such as `30m`, `24h` or `7d`
+pub fn parse_expiry(s: &str) -> Result<String, String> {
+    let valid = s
+        .strip_suffix(['s', 'm', 'h', 'd', 'w'])
+        .and_then(|amount| amount.parse::<u64>().ok())
+        .is_some_and(|amount| amount > 0);
+    if valid {
+        Ok(s.to_string())
+    } else {
+        Err(format!(
+            "invalid expiry '{}', expected a number followed by s, m, h, d or w, e.g. 24h",
+            s
+        ))
+    }
+}
+
+/// Uploads the recipe as YAML to a paste service and returns the URL of the paste. The expiry
+/// is sent as an `expire` header, which services like rustypaste honor and others ignore.
+pub async fn share_recipe(
+    recipe: &Recipe,
+    share_url: &str,
+    expires: Option<&str>,
+) -> Result<String> {
+    let yaml = serde_yaml::to_string(recipe)?;
+    let mut request = reqwest::Client::new()
+        .post(share_url)
+        .header(reqwest::header::CONTENT_TYPE, "text/yaml")
+        .body(yaml);
+    if let Some(expires) = expires {
+        request = request.header("expire", expires);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    // paste.rs answers 206 when it cut the paste short
+    if status != reqwest::StatusCode::OK && status != reqwest::StatusCode::CREATED {
+        return Err(anyhow::anyhow!(
+            "{} rejected the recipe ({}): {}",
+            share_url,
+            status,
+            body.trim()
+        ));
+    }
+    let url = body.trim();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(anyhow::anyhow!(
+            "{} did not answer with a URL: {}",
+            share_url,
+            url
+        ));
+    }
+    Ok(url.to_string())
+}
+
+/// Shares a recipe through the paste service configured with GOOSE_RECIPE_SHARE_URL
+///
+/// # Arguments
+///
+/// * `recipe_name` - Recipe name or path to the recipe file
+/// * `expires` - How long the paste should be kept, e.g. `24h`
+///
+/// # Returns
+///
+/// The URL of the shared recipe
+pub async fn handle_share(recipe_name: &str, expires: Option<&str>) -> Result<String> {
+    let recipe = load_recipe_for_validation(recipe_name)?;
+    let share_url = Config::global()
+        .get_param::<String>("GOOSE_RECIPE_SHARE_URL")
+        .unwrap_or_else(|_| DEFAULT_RECIPE_SHARE_URL.to_string());
+
+    match share_recipe(&recipe, &share_url, expires).await {
+        Ok(url) => {
+            println!(
+                "{} Shared recipe: {}",
+                style("✓").green().bold(),
+                recipe.title
+            );
+            println!("{}", url);
+            Ok(url)
+        }
+        Err(err) => {
+            println!("{} {}", style("✗").red().bold(), err);
+            Err(err)
+        }
+    }
+}
+
+/// Lists all available recipes from local paths, the recipe registry and GitHub repositories
 ///
 /// # Arguments
 ///
@@ -100,6 +261,7 @@ pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
                     let source_info = match recipe.source {
                         RecipeSource::Local => format!("local: {}", recipe.path),
                         RecipeSource::GitHub => format!("github: {}", recipe.path),
+                        RecipeSource::Registry => format!("registry: {}", recipe.path),
                     };
 
                     let description = if let Some(desc) = &recipe.description {
@@ -204,6 +366,53 @@ response:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_expiry() {
+        assert_eq!(parse_expiry("24h"), Ok("24h".to_string()));
+        assert_eq!(parse_expiry("7d"), Ok("7d".to_string()));
+        assert!(parse_expiry("0h").is_err());
+        assert!(parse_expiry("24").is_err());
+        assert!(parse_expiry("h").is_err());
+        assert!(parse_expiry("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_share_recipe() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            // Read until the whole YAML body has arrived
+            while !String::from_utf8_lossy(&request).contains("Test prompt content") {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = "https://paste.example/abc\n";
+            let response = format!(
+                "HTTP/1.1 201 Created\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let recipe = Recipe::from_content(VALID_RECIPE_CONTENT).unwrap();
+        let url = share_recipe(&recipe, &format!("http://{}/", addr), Some("24h"))
+            .await
+            .unwrap();
+        assert_eq!(url, "https://paste.example/abc");
+
+        let request = server.await.unwrap().to_lowercase();
+        assert!(request.starts_with("post / "));
+        assert!(request.contains("expire: 24h"));
+        assert!(request.contains("title: test recipe with valid json schema"));
+    }
+
     #[test]
     fn test_handle_validation_valid_recipe() {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -1,10 +1,12 @@
-use crate::session::message_to_markdown;
+use crate::session::{message_to_markdown, SessionStatistics};
 use anyhow::{Context, Result};
 use cliclack::{confirm, multiselect, select};
+use goose::message::{Message, MessageContent};
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
 use goose::session::{self, Identifier};
 use goose::utils::safe_truncate;
 use regex::Regex;
+use similar::{ChangeTag, TextDiff};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -115,14 +117,19 @@ pub fn handle_session_remove(id: Option<String>, regex_string: Option<String>) -
     remove_sessions(matched_sessions)
 }
 
-pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Result<()> {
+pub fn handle_session_list(
+    verbose: bool,
+    format: String,
+    ascending: bool,
+    tag: Option<String>,
+) -> Result<()> {
     let sort_order = if ascending {
         SortOrder::Ascending
     } else {
         SortOrder::Descending
     };
 
-    let sessions = match get_valid_sorted_sessions(sort_order) {
+    let mut sessions = match get_valid_sorted_sessions(sort_order) {
         Ok(sessions) => sessions,
         Err(e) => {
             tracing::error!("Failed to list sessions: {:?}", e);
@@ -130,6 +137,17 @@ pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Re
         }
     };
 
+    if let Some(tag) = tag {
+        let tagged: Vec<String> = session::sessions_by_tag(&tag)?
+            .into_iter()
+            .filter_map(|id| match id {
+                Identifier::Name(name) => Some(name),
+                Identifier::Path(_) => None,
+            })
+            .collect();
+        sessions.retain(|session| tagged.contains(&session.id));
+    }
+
     match format.as_str() {
         "json" => {
             println!("{}", serde_json::to_string(&sessions)?);
@@ -209,6 +227,379 @@ pub fn handle_session_export(identifier: Identifier, output_path: Option<PathBuf
     Ok(())
 }
 
+/// Print usage statistics for a session without creating a full Session object
+pub fn handle_session_stats(identifier: Identifier) -> Result<()> {
+    let session_file_path = goose::session::get_path(identifier)
+        .map_err(|e| anyhow::anyhow!("Invalid session identifier: {}", e))?;
+
+    if !session_file_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            session_file_path.display()
+        ));
+    }
+
+    let messages = goose::session::read_messages(&session_file_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read session messages: {}", e))?;
+    let metadata = goose::session::read_metadata(&session_file_path).ok();
+
+    let stats = SessionStatistics::from_messages(&messages, metadata.as_ref());
+    println!("Session: {}", session_file_path.display());
+    println!("{}", stats);
+
+    Ok(())
+}
+
+pub fn handle_session_verify(session: String) -> Result<()> {
+    let path = goose::session::get_path(identifier_from_arg(&session))
+        .map_err(|e| anyhow::anyhow!("Invalid session identifier '{}': {}", session, e))?;
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            path.display()
+        ));
+    }
+
+    let metadata = goose::session::read_metadata(&path)?;
+    if metadata.content_hash.is_none() {
+        println!(
+            "Session {} has no content hash, it was last saved by an older version of goose",
+            path.display()
+        );
+        return Ok(());
+    }
+
+    match goose::session::read_messages(&path) {
+        Ok(_) => {
+            println!("PASS: {}", path.display());
+            Ok(())
+        }
+        Err(e) if e.downcast_ref::<goose::session::SessionError>().is_some() => {
+            println!("FAIL: {}", path.display());
+            Err(e)
+        }
+        Err(e) => Err(anyhow::anyhow!("Failed to read session messages: {}", e)),
+    }
+}
+
+/// Rewrite a session with every repeated tool result replaced by a reference to the first one
+pub fn handle_session_compress(session: String) -> Result<()> {
+    let (path, mut messages) = load_session_messages(&session)?;
+    let metadata = goose::session::read_metadata(&path)?;
+    let size_before = fs::metadata(&path)?.len();
+
+    let replaced = goose::session::dedupe_tool_responses(&mut messages);
+    if replaced == 0 {
+        println!("{} has no repeated tool results", path.display());
+        return Ok(());
+    }
+    goose::session::storage::save_messages_with_metadata(&path, &metadata, &messages)?;
+
+    let size_after = fs::metadata(&path)?.len();
+    let saved = size_before.saturating_sub(size_after);
+    println!(
+        "Compressed {}: replaced {} repeated tool result{}, {} -> {} bytes ({} bytes, {:.1}% smaller)",
+        path.display(),
+        replaced,
+        if replaced == 1 { "" } else { "s" },
+        size_before,
+        size_after,
+        saved,
+        saved as f64 * 100.0 / size_before.max(1) as f64
+    );
+    Ok(())
+}
+
+pub fn handle_session_trim(session: String, last_n_messages: usize) -> Result<()> {
+    let (path, mut messages) = load_session_messages(&session)?;
+    let mut metadata = goose::session::read_metadata(&path)?;
+    let size_before = fs::metadata(&path)?.len();
+
+    let removed = goose::session::trim_to_last_messages(&mut messages, last_n_messages);
+    if removed == 0 {
+        println!(
+            "{} has {} message{}, nothing to trim",
+            path.display(),
+            messages.len(),
+            if messages.len() == 1 { "" } else { "s" }
+        );
+        return Ok(());
+    }
+    metadata.message_count = messages.len();
+    goose::session::storage::save_messages_with_metadata(&path, &metadata, &messages)?;
+
+    let size_after = fs::metadata(&path)?.len();
+    println!(
+        "Trimmed {}: removed {} message{}, {} left, {} -> {} bytes",
+        path.display(),
+        removed,
+        if removed == 1 { "" } else { "s" },
+        messages.len(),
+        size_before,
+        size_after
+    );
+    Ok(())
+}
+
+/// Print the session followed by each session it was forked from, oldest last
+pub fn handle_session_lineage(session: String) -> Result<()> {
+    let lineage = goose::session::session_lineage(&identifier_from_arg(&session))
+        .map_err(|e| anyhow::anyhow!("Failed to read the lineage of '{}': {}", session, e))?;
+
+    let mut name = session;
+    for (depth, metadata) in lineage.iter().enumerate() {
+        let description = if metadata.description.is_empty() {
+            "(no description)"
+        } else {
+            metadata.description.as_str()
+        };
+        println!(
+            "{}{} - {} ({} messages)",
+            "  ".repeat(depth),
+            name,
+            description,
+            metadata.message_count
+        );
+        match &metadata.parent_session_id {
+            Some(parent) => name = parent.clone(),
+            None => return Ok(()),
+        }
+    }
+    // The loop only ends here when a parent was deleted
+    println!("{}{} - (deleted)", "  ".repeat(lineage.len()), name);
+    Ok(())
+}
+
+/// Session arguments on the command line are names unless they look like a session file
+fn identifier_from_arg(arg: &str) -> Identifier {
+    let path = Path::new(arg);
+    if path.extension().is_some_and(|ext| ext == "jsonl") || path.components().count() > 1 {
+        Identifier::Path(path.to_path_buf())
+    } else {
+        Identifier::Name(arg.to_string())
+    }
+}
+
+fn load_session_messages(arg: &str) -> Result<(PathBuf, Vec<Message>)> {
+    let path = goose::session::get_path(identifier_from_arg(arg))
+        .map_err(|e| anyhow::anyhow!("Invalid session identifier '{}': {}", arg, e))?;
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            path.display()
+        ));
+    }
+    let messages = goose::session::read_messages(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read session messages: {}", e))?;
+    Ok((path, messages))
+}
+
+/// The comparable parts of a message, tool arguments and results are kept apart so
+/// a changed result does not hide identical arguments
+#[derive(Default, PartialEq)]
+struct MessageParts {
+    text: String,
+    tool_arguments: String,
+    tool_results: String,
+}
+
+impl From<&Message> for MessageParts {
+    fn from(message: &Message) -> Self {
+        let mut text = Vec::new();
+        let mut tool_arguments = Vec::new();
+        let mut tool_results = Vec::new();
+
+        for content in &message.content {
+            match content {
+                MessageContent::Text(t) => text.push(t.text.clone()),
+                MessageContent::ToolRequest(request) => {
+                    let call = match &request.tool_call {
+                        Ok(call) => serde_json::json!({
+                            "name": call.name,
+                            "arguments": call.arguments,
+                        }),
+                        Err(e) => serde_json::json!({ "error": e.to_string() }),
+                    };
+                    tool_arguments.push(serde_json::to_string_pretty(&call).unwrap_or_default());
+                }
+                MessageContent::ToolResponse(response) => {
+                    let result = match &response.tool_result {
+                        Ok(contents) => serde_json::to_value(contents).unwrap_or_default(),
+                        Err(e) => serde_json::json!({ "error": e.to_string() }),
+                    };
+                    tool_results.push(serde_json::to_string_pretty(&result).unwrap_or_default());
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            text: text.join("\n"),
+            tool_arguments: tool_arguments.join("\n"),
+            tool_results: tool_results.join("\n"),
+        }
+    }
+}
+
+fn pad_column(line: &str, width: usize) -> String {
+    let line = safe_truncate(line, width);
+    let padding = width.saturating_sub(line.chars().count());
+    format!("{}{}", line, " ".repeat(padding))
+}
+
+/// Print two texts next to each other, removed lines on the left in red and added
+/// lines on the right in green
+fn print_side_by_side(old: &str, new: &str, column_width: usize) {
+    let diff = TextDiff::from_lines(old, new);
+    let mut removed: Vec<String> = Vec::new();
+    let mut added: Vec<String> = Vec::new();
+
+    let flush = |removed: &mut Vec<String>, added: &mut Vec<String>| {
+        for row in 0..removed.len().max(added.len()) {
+            let left = removed.get(row).map(String::as_str).unwrap_or("");
+            let right = added.get(row).map(String::as_str).unwrap_or("");
+            println!(
+                "{} {} {}",
+                console::style(pad_column(left, column_width)).red(),
+                console::style("│").dim(),
+                console::style(safe_truncate(right, column_width)).green()
+            );
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for change in diff.iter_all_changes() {
+        let line = change.value().trim_end_matches('\n').to_string();
+        match change.tag() {
+            ChangeTag::Delete => removed.push(line),
+            ChangeTag::Insert => added.push(line),
+            ChangeTag::Equal => {
+                flush(&mut removed, &mut added);
+                println!(
+                    "{} {} {}",
+                    console::style(pad_column(&line, column_width)).dim(),
+                    console::style("│").dim(),
+                    console::style(safe_truncate(&line, column_width)).dim()
+                );
+            }
+        }
+    }
+    flush(&mut removed, &mut added);
+}
+
+fn print_section_diff(label: &str, old: &str, new: &str, column_width: usize) {
+    if old == new {
+        return;
+    }
+    println!("  {}", console::style(label).cyan());
+    print_side_by_side(old, new, column_width);
+}
+
+fn role_name(message: &Message) -> &'static str {
+    match message.role {
+        rmcp::model::Role::User => "user",
+        rmcp::model::Role::Assistant => "assistant",
+    }
+}
+
+/// Compare two sessions message by message and print a side by side diff of the ones that differ
+pub fn handle_session_diff(session_a: String, session_b: String) -> Result<()> {
+    let (path_a, messages_a) = load_session_messages(&session_a)?;
+    let (path_b, messages_b) = load_session_messages(&session_b)?;
+
+    let terminal_width = console::Term::stdout().size().1 as usize;
+    let column_width = (terminal_width.max(40) - 3) / 2;
+
+    println!(
+        "{} {}",
+        console::style(pad_column(&path_a.display().to_string(), column_width))
+            .red()
+            .bold(),
+        console::style(path_b.display()).green().bold()
+    );
+
+    let total = messages_a.len().max(messages_b.len());
+    let mut differing = 0;
+    for index in 0..total {
+        match (messages_a.get(index), messages_b.get(index)) {
+            (Some(a), Some(b)) => {
+                let (parts_a, parts_b) = (MessageParts::from(a), MessageParts::from(b));
+                if a.role == b.role && parts_a == parts_b {
+                    continue;
+                }
+                differing += 1;
+                println!(
+                    "\n{}",
+                    console::style(format!(
+                        "Message {} ({} / {})",
+                        index + 1,
+                        role_name(a),
+                        role_name(b)
+                    ))
+                    .bold()
+                );
+                print_section_diff("text", &parts_a.text, &parts_b.text, column_width);
+                print_section_diff(
+                    "tool arguments",
+                    &parts_a.tool_arguments,
+                    &parts_b.tool_arguments,
+                    column_width,
+                );
+                print_section_diff(
+                    "tool results",
+                    &parts_a.tool_results,
+                    &parts_b.tool_results,
+                    column_width,
+                );
+            }
+            (Some(message), None) | (None, Some(message)) => {
+                differing += 1;
+                let only_in_a = index < messages_a.len();
+                println!(
+                    "\n{}",
+                    console::style(format!(
+                        "Message {} ({}) only in {}",
+                        index + 1,
+                        role_name(message),
+                        if only_in_a { &session_a } else { &session_b }
+                    ))
+                    .bold()
+                );
+                let parts = MessageParts::from(message);
+                let empty = MessageParts::default();
+                let (old, new) = if only_in_a {
+                    (&parts, &empty)
+                } else {
+                    (&empty, &parts)
+                };
+                print_section_diff("text", &old.text, &new.text, column_width);
+                print_section_diff(
+                    "tool arguments",
+                    &old.tool_arguments,
+                    &new.tool_arguments,
+                    column_width,
+                );
+                print_section_diff(
+                    "tool results",
+                    &old.tool_results,
+                    &new.tool_results,
+                    column_width,
+                );
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    println!(
+        "\n{} of {} messages differ",
+        console::style(differing).bold(),
+        total
+    );
+
+    Ok(())
+}
+
 /// Convert a list of messages to markdown format for session export
 ///
 /// This function handles the formatting of a complete session including headers,
@@ -34,6 +34,7 @@ pub fn extract_recipe_info_from_cli(
                         values: None,
                         sequential_when_repeated: true,
                         description: None,
+                        dependencies: None,
                     };
                     all_sub_recipes.push(additional_sub_recipe);
                 }
@@ -47,6 +48,18 @@ pub fn extract_recipe_info_from_cli(
             }
         }
     }
+    if let Some(settings) = &recipe.settings {
+        if settings
+            .system_prompt_override
+            .as_ref()
+            .is_some_and(|prompt| prompt.trim().is_empty())
+        {
+            return Err(anyhow!(
+                "settings.system_prompt_override must not be empty, remove it to use the default system prompt"
+            ));
+        }
+    }
+
     let input_config = InputConfig {
         contents: recipe.prompt.filter(|s| !s.trim().is_empty()),
         extensions_override: recipe.extensions,
@@ -58,10 +71,15 @@ pub fn extract_recipe_info_from_cli(
             goose_provider: s.goose_provider,
             goose_model: s.goose_model,
             temperature: s.temperature,
+            system_prompt_override: s.system_prompt_override,
+            max_turns: s.max_turns,
         }),
         sub_recipes: Some(all_sub_recipes),
+        sub_recipe_execution_mode: recipe.execution_mode,
+        audit: recipe.audit,
         final_output_response: recipe.response,
         retry_config: recipe.retry,
+        activities: recipe.activities,
     };
 
     Ok((input_config, recipe_info))
@@ -113,6 +131,7 @@ mod tests {
         assert_eq!(settings.goose_provider, Some("test_provider".to_string()));
         assert_eq!(settings.goose_model, Some("test_model".to_string()));
         assert_eq!(settings.temperature, Some(0.7));
+        assert_eq!(settings.max_turns, Some(25));
 
         assert!(sub_recipes.is_some());
         let sub_recipes = sub_recipes.unwrap();
@@ -227,6 +246,7 @@ settings:
   goose_provider: test_provider
   goose_model: test_model
   temperature: 0.7
+  max_turns: 25
 sub_recipes:
 - path: existing_sub_recipe.yaml
   name: existing_sub_recipe        
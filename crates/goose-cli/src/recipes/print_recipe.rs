@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use console::style;
 use goose::recipe::{Recipe, BUILT_IN_RECIPE_DIR_PARAM};
@@ -33,7 +34,7 @@ pub fn print_recipe_explanation(recipe: &Recipe) {
     }
 }
 
-pub fn print_parameters_with_values(params: HashMap<String, String>) {
+pub fn print_parameters_with_values<V: fmt::Display>(params: HashMap<String, V>) {
     for (key, value) in params {
         let label = if key == BUILT_IN_RECIPE_DIR_PARAM {
             " (built-in)"
@@ -44,8 +45,8 @@ pub fn print_parameters_with_values(params: HashMap<String, String>) {
     }
 }
 
-pub fn print_required_parameters_for_template(
-    params_for_template: HashMap<String, String>,
+pub fn print_required_parameters_for_template<V: fmt::Display>(
+    params_for_template: HashMap<String, V>,
     missing_params: Vec<String>,
 ) {
     if !params_for_template.is_empty() {
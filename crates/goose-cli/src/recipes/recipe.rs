@@ -9,16 +9,43 @@ use goose::recipe::build_recipe::{
 };
 use goose::recipe::read_recipe_file_content::RecipeFile;
 use goose::recipe::template_recipe::render_recipe_for_preview;
-use goose::recipe::Recipe;
+use goose::recipe::{Recipe, RecipeParameter, RecipeParameterInputType};
 use std::collections::HashMap;
 
 pub const RECIPE_FILE_EXTENSIONS: &[&str] = &["yaml", "json"];
 
-fn create_user_prompt_callback() -> impl Fn(&str, &str) -> Result<String> {
-    |key: &str, description: &str| -> Result<String> {
-        let input_value =
-            cliclack::input(format!("Please enter {} ({})", key, description)).interact()?;
-        Ok(input_value)
+fn create_user_prompt_callback() -> impl Fn(&RecipeParameter) -> Result<String> {
+    |param: &RecipeParameter| -> Result<String> {
+        let (key, description) = (&param.key, &param.description);
+        match (&param.input_type, &param.options) {
+            // Array values are comma separated, the same as on the command line
+            (RecipeParameterInputType::Array { .. }, Some(options)) => {
+                let mut selector = cliclack::multiselect(format!(
+                    "Please select {} ({}, use \"space\" to toggle and \"enter\" to submit)",
+                    key, description
+                ))
+                .required(false);
+                for option in options {
+                    selector = selector.item(option.clone(), option, "");
+                }
+                let selected: Vec<String> = selector.interact()?;
+                Ok(selected.join(","))
+            }
+            (RecipeParameterInputType::Array { .. }, None) => {
+                let input_value = cliclack::input(format!(
+                    "Please enter {} as comma separated values ({})",
+                    key, description
+                ))
+                .interact()?;
+                Ok(input_value)
+            }
+            _ => {
+                let input_value =
+                    cliclack::input(format!("Please enter {} ({})", key, description))
+                        .interact()?;
+                Ok(input_value)
+            }
+        }
     }
 }
 
@@ -65,7 +92,7 @@ pub fn load_recipe_for_validation(recipe_name: &str) -> Result<Recipe> {
     let recipe = render_recipe_for_preview(
         recipe_file_content,
         recipe_dir_str.to_string(),
-        &HashMap::new(),
+        &HashMap::<String, String>::new(),
     )?;
 
     if let Some(response) = &recipe.response {
@@ -86,7 +113,7 @@ pub fn explain_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Resul
         &params,
         recipe_parameters,
         &recipe_dir_str,
-        None::<fn(&str, &str) -> Result<String>>,
+        None::<fn(&RecipeParameter) -> Result<String>>,
     )?;
     let recipe = render_recipe_for_preview(
         recipe_file_content,
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Result};
 use goose::config::Config;
 use goose::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
+use goose::recipe::registry::RecipeRegistry;
 use goose::recipe::template_recipe::parse_recipe_content;
 use std::env;
 use std::fs;
@@ -29,13 +30,15 @@ pub fn retrieve_recipe_file(recipe_name: &str) -> Result<RecipeFile> {
             recipe_name
         ));
     }
-    retrieve_recipe_from_local_path(recipe_name).or_else(|e| {
-        if let Some(recipe_repo_full_name) = configured_github_recipe_repo() {
-            retrieve_recipe_from_github(recipe_name, &recipe_repo_full_name)
-        } else {
-            Err(e)
-        }
-    })
+    retrieve_recipe_from_local_path(recipe_name)
+        .or_else(|e| retrieve_recipe_from_registry(recipe_name).ok_or(e))
+        .or_else(|e| {
+            if let Some(recipe_repo_full_name) = configured_github_recipe_repo() {
+                retrieve_recipe_from_github(recipe_name, &recipe_repo_full_name)
+            } else {
+                Err(e)
+            }
+        })
 }
 
 fn is_file_path(recipe_name: &str) -> bool {
@@ -92,6 +95,19 @@ fn retrieve_recipe_from_local_path(recipe_name: &str) -> Result<RecipeFile> {
     ))
 }
 
+// Registered recipes are stored parsed, hand them back as yaml like a recipe file
+fn retrieve_recipe_from_registry(recipe_name: &str) -> Option<RecipeFile> {
+    let recipe = RecipeRegistry::global().get(recipe_name)?;
+    let content = serde_yaml::to_string(&recipe).ok()?;
+    let file_path = RecipeRegistry::default_path();
+    let parent_dir = file_path.parent()?.to_path_buf();
+    Some(RecipeFile {
+        content,
+        parent_dir,
+        file_path,
+    })
+}
+
 fn configured_github_recipe_repo() -> Option<String> {
     let config = Config::global();
     match config.get_param(GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY) {
@@ -100,7 +116,7 @@ fn configured_github_recipe_repo() -> Option<String> {
     }
 }
 
-/// Lists all available recipes from local paths and GitHub repositories
+/// Lists all available recipes from local paths, the recipe registry and GitHub repositories
 pub fn list_available_recipes() -> Result<Vec<RecipeInfo>> {
     let mut recipes = Vec::new();
 
@@ -109,6 +125,21 @@ pub fn list_available_recipes() -> Result<Vec<RecipeInfo>> {
         recipes.extend(local_recipes);
     }
 
+    // Add registered recipes
+    let registry_path = RecipeRegistry::default_path().to_string_lossy().to_string();
+    recipes.extend(
+        RecipeRegistry::global()
+            .list()
+            .into_iter()
+            .map(|(name, recipe)| RecipeInfo {
+                name,
+                source: RecipeSource::Registry,
+                path: registry_path.clone(),
+                title: Some(recipe.title),
+                description: Some(recipe.description),
+            }),
+    );
+
     // Search GitHub recipes if configured
     if let Some(repo) = configured_github_recipe_repo() {
         if let Ok(github_recipes) = list_github_recipes(&repo) {
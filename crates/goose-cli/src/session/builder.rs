@@ -4,7 +4,7 @@ use goose::agents::types::RetryConfig;
 use goose::agents::Agent;
 use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
 use goose::providers::create;
-use goose::recipe::{Response, SubRecipe};
+use goose::recipe::{RecipeAuditConfig, Response, SubRecipe, SubRecipeExecutionMode};
 use goose::session;
 use goose::session::Identifier;
 use mcp_client::transport::Error as McpClientError;
@@ -59,10 +59,16 @@ pub struct SessionBuilderConfig {
     pub quiet: bool,
     /// Sub-recipes to add to the session
     pub sub_recipes: Option<Vec<SubRecipe>>,
+    /// How tasks from the sub-recipes are dispatched
+    pub sub_recipe_execution_mode: Option<SubRecipeExecutionMode>,
+    /// Audit logging for the session's builtin extensions, overriding GOOSE_AUDIT_LOG
+    pub audit: Option<RecipeAuditConfig>,
     /// Final output expected response
     pub final_output_response: Option<Response>,
     /// Retry configuration for automated validation and recovery
     pub retry_config: Option<RetryConfig>,
+    /// Recipe activities to show while the session is loading
+    pub activities: Option<Vec<String>>,
 }
 
 /// Offers to help debug an extension failure by creating a minimal debugging session
@@ -172,6 +178,8 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub system_prompt_override: Option<String>,
+    pub max_turns: Option<u32>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
@@ -211,6 +219,10 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
     if let Some(sub_recipes) = session_config.sub_recipes {
         agent.add_sub_recipes(sub_recipes).await;
     }
+    agent
+        .set_sub_recipe_execution_mode(session_config.sub_recipe_execution_mode)
+        .await;
+    agent.set_audit_config(session_config.audit).await;
 
     if let Some(final_output_response) = session_config.final_output_response {
         agent.add_final_output_tool(final_output_response).await;
@@ -409,11 +421,18 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         session_file.clone(),
         session_config.debug,
         session_config.scheduled_job_id.clone(),
-        session_config.max_turns,
+        // A --max-turns flag wins over the recipe's own limit
+        session_config
+            .max_turns
+            .or(session_config.settings.as_ref().and_then(|s| s.max_turns)),
         edit_mode,
         session_config.retry_config.clone(),
     );
 
+    if let Some(activities) = session_config.activities.clone() {
+        session.set_activities(activities);
+    }
+
     // Add extensions if provided
     for extension_str in session_config.extensions {
         if let Err(e) = session.add_extension(extension_str.clone()).await {
@@ -567,6 +586,15 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         session.agent.override_system_prompt(override_prompt).await;
     }
 
+    // A recipe's system prompt replaces everything above
+    if let Some(system_prompt) = session_config
+        .settings
+        .as_ref()
+        .and_then(|s| s.system_prompt_override.clone())
+    {
+        session.agent.replace_system_prompt(system_prompt).await;
+    }
+
     // Display session information unless in quiet mode
     if !session_config.quiet {
         output::display_session_info(
@@ -606,8 +634,11 @@ mod tests {
             interactive: true,
             quiet: false,
             sub_recipes: None,
+            sub_recipe_execution_mode: None,
+            audit: None,
             final_output_response: None,
             retry_config: None,
+            activities: None,
         };
 
         assert_eq!(config.extensions.len(), 1);
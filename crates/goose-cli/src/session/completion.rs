@@ -402,6 +402,7 @@ mod tests {
     use super::*;
     use crate::session::output;
     use std::sync::{Arc, RwLock};
+    use std::time::{Duration, Instant};
 
     // Helper function to create a test completion cache
     fn create_test_cache() -> Arc<RwLock<CompletionCache>> {
@@ -604,4 +605,14 @@ mod tests {
             .unwrap();
         assert_eq!(candidates.len(), 0);
     }
+
+    #[test]
+    fn test_cache_freshness() {
+        let mut cache = CompletionCache::new();
+        assert!(!cache.is_fresh(Duration::from_secs(300)));
+
+        cache.last_updated = Some(Instant::now());
+        assert!(cache.is_fresh(Duration::from_secs(300)));
+        assert!(!cache.is_fresh(Duration::ZERO));
+    }
 }
@@ -0,0 +1,90 @@
+use goose::message::{Message, MessageContent};
+use rmcp::model::Role;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Queues messages for a session from outside of it, e.g. a monitor telling the agent to stop
+/// using a rate-limited tool. Cloning shares the queue, see `Session::message_injector`.
+#[derive(Clone, Default)]
+pub struct MessageInjector {
+    pending: Arc<Mutex<Vec<Message>>>,
+    notify: Arc<Notify>,
+}
+
+impl MessageInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a message, a running agent loop picks it up at the next point it can
+    /// take a new message without breaking a tool call
+    pub fn inject(&self, role: Role, text: impl Into<String>) {
+        let message = match role {
+            Role::User => Message::user(),
+            Role::Assistant => Message::assistant(),
+        }
+        .with_text(text);
+        self.pending.lock().unwrap().push(message);
+        self.notify.notify_one();
+    }
+
+    /// Resolves once a message has been queued since the last call
+    pub async fn notified(&self) {
+        self.notify.notified().await
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.lock().unwrap().is_empty()
+    }
+
+    pub fn take(&self) -> Vec<Message> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+}
+
+/// Whether the agent has just received tool results and has not asked the model for the next
+/// step yet, the only point mid-reply where messages can be added without orphaning a tool call
+pub fn between_tool_turns(messages: &[Message]) -> bool {
+    messages.last().is_some_and(|last| {
+        last.role == Role::User
+            && last
+                .content
+                .iter()
+                .any(|c| matches!(c, MessageContent::ToolResponse(_)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+
+    #[tokio::test]
+    async fn test_inject_and_take() {
+        let injector = MessageInjector::new();
+        let monitor = injector.clone();
+
+        monitor.inject(Role::User, "stop using the search tool, it is rate limited");
+        // The permit is stored, so a loop that starts waiting later still wakes up
+        injector.notified().await;
+        assert!(injector.has_pending());
+
+        let messages = injector.take();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(
+            messages[0].as_concat_text(),
+            "stop using the search tool, it is rate limited"
+        );
+        assert!(!monitor.has_pending());
+    }
+
+    #[test]
+    fn test_between_tool_turns() {
+        let mut messages = vec![Message::user().with_text("hi")];
+        assert!(!between_tool_turns(&messages));
+
+        messages.push(Message::user().with_tool_response("1", Ok(vec![Content::text("done")])));
+        assert!(between_tool_turns(&messages));
+    }
+}
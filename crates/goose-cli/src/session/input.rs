@@ -21,6 +21,8 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Summarize,
+    Tag(Vec<String>),
+    SetProvider { provider: String, model: String },
 }
 
 #[derive(Debug)]
@@ -96,6 +98,8 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_SUMMARIZE: &str = "/summarize";
+    const CMD_TAG: &str = "/tag";
+    const CMD_MODEL: &str = "/model";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -156,6 +160,25 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_SUMMARIZE => Some(InputResult::Summarize),
+        s if s == CMD_TAG || s.starts_with("/tag ") => Some(InputResult::Tag(
+            s[CMD_TAG.len()..]
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+        )),
+        s if s == CMD_MODEL || s.starts_with("/model ") => {
+            let args: Vec<&str> = s[CMD_MODEL.len()..].split_whitespace().collect();
+            match args.as_slice() {
+                [provider, model] => Some(InputResult::SetProvider {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                }),
+                _ => {
+                    println!("Usage: /model <provider> <model>");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
         _ => None,
     }
 }
@@ -266,6 +289,8 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /summarize - Summarize the current conversation to reduce context length while preserving key information.
+/tag [tags...] - Label this session with space separated tags, replacing any existing ones. Without tags, removes them.
+/model <provider> <model> - Switch this session to another provider and model, keeping the conversation so far.
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
@@ -326,6 +351,41 @@ mod tests {
         assert!(handle_slash_command("/unknown").is_none());
     }
 
+    #[test]
+    fn test_tag_command() {
+        if let Some(InputResult::Tag(tags)) = handle_slash_command("/tag release  bugfix") {
+            assert_eq!(tags, vec!["release", "bugfix"]);
+        } else {
+            panic!("Expected Tag");
+        }
+
+        if let Some(InputResult::Tag(tags)) = handle_slash_command("/tag") {
+            assert!(tags.is_empty());
+        } else {
+            panic!("Expected Tag");
+        }
+
+        assert!(handle_slash_command("/tags").is_none());
+    }
+
+    #[test]
+    fn test_model_command() {
+        if let Some(InputResult::SetProvider { provider, model }) =
+            handle_slash_command("/model openai  gpt-4o")
+        {
+            assert_eq!(provider, "openai");
+            assert_eq!(model, "gpt-4o");
+        } else {
+            panic!("Expected SetProvider");
+        }
+
+        assert!(matches!(
+            handle_slash_command("/model openai"),
+            Some(InputResult::Retry)
+        ));
+        assert!(handle_slash_command("/models").is_none());
+    }
+
     #[test]
     fn test_prompts_command() {
         // Test basic prompts command
@@ -1,9 +1,11 @@
 mod builder;
 mod completion;
 mod export;
+mod injector;
 mod input;
 mod output;
 mod prompt;
+mod stats;
 mod task_execution_display;
 mod thinking;
 
@@ -23,14 +25,19 @@ use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
 pub use goose::session::Identifier;
 use goose::utils::safe_truncate;
+use injector::between_tool_turns;
+pub use injector::MessageInjector;
+pub use stats::SessionStatistics;
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
 use etcetera::{choose_app_strategy, AppStrategy};
+use futures::{Stream, StreamExt};
 use goose::agents::extension::{Envs, ExtensionConfig};
 use goose::agents::types::RetryConfig;
 use goose::agents::{Agent, SessionConfig};
 use goose::config::Config;
+use goose::context_mgmt::{truncate, ContextStrategy};
 use goose::message::{Message, MessageContent};
 use goose::providers::pricing::initialize_pricing_cache;
 use goose::session;
@@ -45,10 +52,16 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio;
 use tokio_util::sync::CancellationToken;
 
+/// Tool results larger than this are pruned before the context strategy runs
+const PRUNED_TOOL_RESULT_BYTES: usize = 20_000;
+
+/// How long prompt completions are reused when GOOSE_COMPLETION_CACHE_TTL_SECS is not set
+const DEFAULT_COMPLETION_CACHE_TTL_SECS: u64 = 300;
+
 pub enum RunMode {
     Normal,
     Plan,
@@ -60,19 +73,25 @@ pub struct Session {
     session_file: Option<PathBuf>,
     // Cache for completion data - using std::sync for thread safety without async
     completion_cache: Arc<std::sync::RwLock<CompletionCache>>,
+    // How long the completion cache is used before extensions are asked for their prompts again
+    cache_ttl: Duration,
     debug: bool, // New field for debug mode
     run_mode: RunMode,
     scheduled_job_id: Option<String>, // ID of the scheduled job that triggered this session
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    injector: MessageInjector,
+    // Recipe activities to cycle through until the first response arrives
+    activities: Option<Vec<String>>,
 }
 
 // Cache structure for completion data
 struct CompletionCache {
     prompts: HashMap<String, Vec<String>>,
     prompt_info: HashMap<String, output::PromptInfo>,
-    last_updated: Instant,
+    // None until the cache is first filled and again once it is invalidated
+    last_updated: Option<Instant>,
 }
 
 impl CompletionCache {
@@ -80,7 +99,15 @@ impl CompletionCache {
         Self {
             prompts: HashMap::new(),
             prompt_info: HashMap::new(),
-            last_updated: Instant::now(),
+            last_updated: None,
+        }
+    }
+
+    /// Whether the cache was filled less than `ttl` ago
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        match self.last_updated {
+            Some(last_updated) => last_updated.elapsed() < ttl,
+            None => false,
         }
     }
 }
@@ -146,15 +173,27 @@ impl Session {
             messages,
             session_file,
             completion_cache: Arc::new(std::sync::RwLock::new(CompletionCache::new())),
+            cache_ttl: Duration::from_secs(
+                Config::global()
+                    .get_param("GOOSE_COMPLETION_CACHE_TTL_SECS")
+                    .unwrap_or(DEFAULT_COMPLETION_CACHE_TTL_SECS),
+            ),
             debug,
             run_mode: RunMode::Normal,
             scheduled_job_id,
             max_turns,
             edit_mode,
             retry_config,
+            injector: MessageInjector::new(),
+            activities: None,
         }
     }
 
+    /// Show these activities while waiting for the first response of the session
+    pub fn set_activities(&mut self, activities: Vec<String>) {
+        self.activities = Some(activities);
+    }
+
     /// Helper function to summarize context messages
     async fn summarize_context_messages(
         messages: &mut Vec<Message>,
@@ -467,6 +506,9 @@ impl Session {
 
         output::display_greeting();
         loop {
+            // Refresh completions once they expire or extensions changed
+            self.update_completion_cache().await?;
+
             // Display context usage before each prompt
             self.display_context_usage().await?;
 
@@ -684,6 +726,31 @@ impl Session {
 
                     continue;
                 }
+                InputResult::Tag(tags) => {
+                    save_history(&mut editor);
+
+                    match self.set_tags(tags) {
+                        Ok(tags) if tags.is_empty() => {
+                            println!("{}", console::style("Removed session tags").green())
+                        }
+                        Ok(tags) => println!(
+                            "{}",
+                            console::style(format!("Tagged session: {}", tags.join(", "))).green()
+                        ),
+                        Err(e) => {
+                            println!("{}: {}", console::style("Failed to tag session").red(), e)
+                        }
+                    }
+                    continue;
+                }
+                InputResult::SetProvider { provider, model } => {
+                    save_history(&mut editor);
+
+                    if let Err(e) = self.set_provider(&provider, &model).await {
+                        output::render_error(&format!("Failed to switch model: {}", e));
+                    }
+                    continue;
+                }
                 InputResult::Summarize => {
                     save_history(&mut editor);
 
@@ -840,34 +907,86 @@ impl Session {
         self.process_message(message).await
     }
 
+    /// Send a message and return the agent's events as they arrive, without rendering anything.
+    /// This is for embedding goose in other UIs: messages from the agent are added to the session
+    /// history as they stream, and confirmation requests are passed through for the caller.
+    pub async fn headless_stream(
+        &mut self,
+        message: String,
+    ) -> Result<impl Stream<Item = Result<AgentEvent>> + '_> {
+        self.push_message(Message::user().with_text(&message));
+        if let Some(session_file) = &self.session_file {
+            let working_dir = std::env::current_dir().ok();
+            session::persist_messages_with_schedule_id(
+                session_file,
+                &self.messages,
+                Some(self.agent.provider().await?),
+                self.scheduled_job_id.clone(),
+                working_dir,
+            )
+            .await?;
+        }
+
+        let session_config = self.session_config();
+        let Session {
+            agent, messages, ..
+        } = self;
+        let stream = agent.reply(messages, session_config, None).await?;
+
+        Ok(stream.inspect(move |event| {
+            if let Ok(AgentEvent::Message(message)) = event {
+                push_message(messages, message.clone());
+            }
+        }))
+    }
+
+    fn session_config(&self) -> Option<SessionConfig> {
+        self.session_file.as_ref().map(|s| SessionConfig {
+            id: session::Identifier::Path(s.clone()),
+            working_dir: std::env::current_dir().unwrap_or_default(),
+            schedule_id: self.scheduled_job_id.clone(),
+            execution_mode: None,
+            max_turns: self.max_turns,
+            retry_config: self.retry_config.clone(),
+        })
+    }
+
     async fn process_agent_response(&mut self, interactive: bool) -> Result<()> {
         let cancel_token = CancellationToken::new();
         let cancel_token_clone = cancel_token.clone();
 
-        let session_config = self.session_file.as_ref().map(|s| {
-            let session_id = session::Identifier::Path(s.clone());
-            SessionConfig {
-                id: session_id.clone(),
-                working_dir: std::env::current_dir().unwrap_or_default(),
-                schedule_id: self.scheduled_job_id.clone(),
-                execution_mode: None,
-                max_turns: self.max_turns,
-                retry_config: self.retry_config.clone(),
-            }
-        });
+        let session_config = self.session_config();
         let mut stream = self
             .agent
             .reply(&self.messages, session_config.clone(), Some(cancel_token))
             .await?;
 
         let mut progress_bars = output::McpSpinners::new();
+        let injector = self.injector.clone();
+        let mut injection_requested = injector.has_pending();
+        let mut streaming_text = false;
+
+        let mut activity_ticker = self.activities.take().and_then(|activities| {
+            // The activities take the place of the thinking spinner while they're showing
+            if interactive {
+                output::hide_thinking();
+            }
+            output::ActivityTicker::start(activities)
+        });
+        let mut assistant_turns = 0u32;
+        let mut last_assistant_id: Option<String> = None;
 
-        use futures::StreamExt;
         loop {
             tokio::select! {
+                _ = injector.notified() => {
+                    injection_requested = true;
+                }
                 result = stream.next() => {
                     match result {
                         Some(Ok(AgentEvent::Message(message))) => {
+                            // Dropping the ticker clears it
+                            drop(activity_ticker.take());
+
                             // If it's a confirmation request, get approval but otherwise do not render/persist
                             if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
@@ -880,6 +999,7 @@ impl Session {
                                     .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
                                     .item(Permission::AlwaysAllow, "Always Allow", "Always allow the tool call")
                                     .item(Permission::DenyOnce, "Deny", "Deny the tool call")
+                                    .item(Permission::AlwaysDeny, "Always Deny", "Deny this tool for the rest of the session")
                                     .item(Permission::Cancel, "Cancel", "Cancel the AI response and tool call")
                                     .interact();
 
@@ -924,19 +1044,49 @@ impl Session {
                                         permission,
                                     },).await;
                                 }
-                            } else if let Some(MessageContent::ContextLengthExceeded(_)) = message.content.first() {
+                            } else if let Some(MessageContent::ContextLengthExceeded(exceeded)) = message.content.first() {
                                 output::hide_thinking();
+                                if let Some(report) = &exceeded.report {
+                                    output::render_context_size_report(report);
+                                }
+
+                                // Large tool results usually fill the context, dropping them may be enough.
+                                // The stream still borrows the agent, so only the messages are borrowed here
+                                let pruned = truncate::prune_tool_results(&mut self.messages, PRUNED_TOOL_RESULT_BYTES);
+                                if pruned > 0 {
+                                    let msg = format!(
+                                        "Context maxed out - pruned {} large tool result{}.",
+                                        pruned,
+                                        if pruned == 1 { "" } else { "s" }
+                                    );
+                                    output::render_text(&msg, Some(Color::Yellow), true);
+                                    if let Some(session_file) = &self.session_file {
+                                        let working_dir = std::env::current_dir().ok();
+                                        session::persist_messages_with_schedule_id(
+                                            session_file,
+                                            &self.messages,
+                                            None,
+                                            self.scheduled_job_id.clone(),
+                                            working_dir,
+                                        )
+                                        .await?;
+                                    }
+                                    stream = self
+                                        .agent
+                                        .reply(&self.messages, session_config.clone(), Some(cancel_token_clone.clone()))
+                                        .await?;
+                                    continue;
+                                }
 
                                 // Check for user-configured default context strategy
                                 let config = Config::global();
                                 let context_strategy = config.get_param::<String>("GOOSE_CONTEXT_STRATEGY")
                                     .unwrap_or_else(|_| if interactive { "prompt".to_string() } else { "summarize".to_string() });
 
-                                let selected = match context_strategy.as_str() {
-                                    "clear" => "clear",
-                                    "truncate" => "truncate",
-                                    "summarize" => "summarize",
-                                    _ => {
+                                let configured_strategy = ContextStrategy::from_config(&context_strategy);
+                                let selected = match configured_strategy {
+                                    Some(strategy) => strategy.resolve(&self.messages).as_str(),
+                                    None => {
                                         if interactive {
                                             // In interactive mode with no default, ask the user what to do
                                             let prompt = "The model's context length is maxed out. You will need to reduce the # msgs. Do you want to?".to_string();
@@ -955,7 +1105,7 @@ impl Session {
                                 match selected {
                                     "clear" => {
                                         self.messages.clear();
-                                        let msg = if context_strategy == "clear" {
+                                        let msg = if configured_strategy.is_some() {
                                             format!("Context maxed out - automatically cleared session.\n{}", "-".repeat(50))
                                         } else {
                                             format!("Session cleared.\n{}", "-".repeat(50))
@@ -966,7 +1116,7 @@ impl Session {
                                     "truncate" => {
                                         // Truncate messages to fit within context length
                                         let (truncated_messages, _) = self.agent.truncate_context(&self.messages).await?;
-                                        let msg = if context_strategy == "truncate" {
+                                        let msg = if configured_strategy.is_some() {
                                             format!("Context maxed out - automatically truncated messages.\n{}\nGoose tried its best to truncate messages for you.", "-".repeat(50))
                                         } else {
                                             format!("Context maxed out\n{}\nGoose tried its best to truncate messages for you.", "-".repeat(50))
@@ -977,7 +1127,7 @@ impl Session {
                                     }
                                     "summarize" => {
                                         // Use the helper function to summarize context
-                                        let message_suffix = if context_strategy == "summarize" {
+                                        let message_suffix = if configured_strategy.is_some() {
                                             "Goose automatically summarized messages for you."
                                         } else if interactive {
                                             "Goose summarized messages for you."
@@ -1020,7 +1170,64 @@ impl Session {
 
                                 if interactive {output::hide_thinking()};
                                 let _ = progress_bars.hide();
-                                output::render_message(&message, self.debug);
+                                // Streamed text is printed piece by piece, push_message has already
+                                // added each piece to the message it belongs to
+                                match output::streamed_text(&message) {
+                                    Some(text) => {
+                                        output::stream_text(&text);
+                                        streaming_text = true;
+                                    }
+                                    None => {
+                                        if std::mem::take(&mut streaming_text) {
+                                            println!();
+                                        }
+                                        output::render_message(&message, self.debug);
+                                    }
+                                }
+
+                                // Streamed pieces of one response share its id, count the response once
+                                if message.role == rmcp::model::Role::Assistant
+                                    && (message.id.is_none() || message.id != last_assistant_id)
+                                {
+                                    assistant_turns += 1;
+                                    last_assistant_id = message.id.clone();
+                                }
+                                // Only stop once the tool results are in, so no tool call is left unanswered
+                                if let Some(max_turns) = self.max_turns.filter(|max| assistant_turns >= *max) {
+                                    if between_tool_turns(&self.messages) {
+                                        tracing::info!("Stopping the reply after reaching the limit of {} turns", max_turns);
+                                        let stop_message = Message::assistant().with_text("Maximum turns reached, stopping.");
+                                        push_message(&mut self.messages, stop_message.clone());
+                                        if let Some(session_file) = &self.session_file {
+                                            let working_dir = std::env::current_dir().ok();
+                                            session::persist_messages_with_schedule_id(
+                                                session_file,
+                                                &self.messages,
+                                                None,
+                                                self.scheduled_job_id.clone(),
+                                                working_dir,
+                                            )
+                                            .await?;
+                                        }
+                                        output::render_message(&stop_message, self.debug);
+                                        cancel_token_clone.cancel();
+                                        drop(stream);
+                                        break;
+                                    }
+                                }
+
+                                // Hand injected messages to the model before its next step
+                                if injection_requested && between_tool_turns(&self.messages) {
+                                    injection_requested = false;
+                                    if injector.has_pending() {
+                                        drop(stream);
+                                        self.apply_injected_messages().await?;
+                                        stream = self
+                                            .agent
+                                            .reply(&self.messages, session_config.clone(), Some(cancel_token_clone.clone()))
+                                            .await?;
+                                    }
+                                }
                             }
                         }
                         Some(Ok(AgentEvent::McpNotification((_id, message)))) => {
@@ -1052,11 +1259,7 @@ impl Session {
                                                     }
                                                     "response_generated" => {
                                                         // Check verbosity setting for subagent response content
-                                                        let config = Config::global();
-                                                        let min_priority = config
-                                                            .get_param::<f32>("GOOSE_CLI_MIN_PRIORITY")
-                                                            .ok()
-                                                            .unwrap_or(0.5);
+                                                        let min_priority = output::min_priority();
 
                                                         if min_priority > 0.1 && !self.debug {
                                                             // High/Medium verbosity: show truncated response
@@ -1157,7 +1360,22 @@ impl Session {
                             );
                             break;
                         }
-                        None => break,
+                        None => {
+                            // Messages injected during the final step still get a reply
+                            if injector.has_pending() {
+                                drop(stream);
+                                self.apply_injected_messages().await?;
+                                if self.messages.last().is_some_and(|m| m.role == rmcp::model::Role::User) {
+                                    injection_requested = false;
+                                    stream = self
+                                        .agent
+                                        .reply(&self.messages, session_config.clone(), Some(cancel_token_clone.clone()))
+                                        .await?;
+                                    continue;
+                                }
+                            }
+                            break;
+                        }
                     }
                 }
                 _ = tokio::signal::ctrl_c() => {
@@ -1299,8 +1517,18 @@ impl Session {
     }
 
     /// Update the completion cache with fresh data
-    /// This should be called before the interactive session starts
+    /// Does nothing while the cache is younger than GOOSE_COMPLETION_CACHE_TTL_SECS and has not
+    /// been invalidated, so it is cheap to call before every prompt
     pub async fn update_completion_cache(&mut self) -> Result<()> {
+        if self
+            .completion_cache
+            .read()
+            .unwrap()
+            .is_fresh(self.cache_ttl)
+        {
+            return Ok(());
+        }
+
         // Get fresh data
         let prompts = self.agent.list_extension_prompts().await;
 
@@ -1326,7 +1554,7 @@ impl Session {
             }
         }
 
-        cache.last_updated = Instant::now();
+        cache.last_updated = Some(Instant::now());
         Ok(())
     }
 
@@ -1336,7 +1564,7 @@ impl Session {
         let mut cache = self.completion_cache.write().unwrap();
         cache.prompts.clear();
         cache.prompt_info.clear();
-        cache.last_updated = Instant::now();
+        cache.last_updated = None;
     }
 
     pub fn message_history(&self) -> Vec<Message> {
@@ -1376,6 +1604,12 @@ impl Session {
         session::read_metadata(self.session_file.as_ref().unwrap())
     }
 
+    /// Usage metrics for this session, token counts come from the session file if there is one
+    pub fn statistics(&self) -> SessionStatistics {
+        let metadata = self.get_metadata().ok();
+        SessionStatistics::from_messages(&self.messages, metadata.as_ref())
+    }
+
     // Get the session's total token usage
     pub fn get_total_token_usage(&self) -> Result<Option<i32>> {
         let metadata = self.get_metadata()?;
@@ -1492,6 +1726,67 @@ impl Session {
         Ok(())
     }
 
+    /// Replace the session's tags, returning them without duplicates. Later saves through
+    /// `persist_messages_with_schedule_id` keep them, as it reads the existing metadata.
+    fn set_tags(&self, tags: Vec<String>) -> Result<Vec<String>> {
+        let session_file = self
+            .session_file
+            .as_ref()
+            .context("Tags are not available when the session is not saved")?;
+
+        let mut unique_tags: Vec<String> = Vec::new();
+        for tag in tags {
+            if !unique_tags.contains(&tag) {
+                unique_tags.push(tag);
+            }
+        }
+
+        let mut metadata = if session_file.exists() {
+            session::read_metadata(session_file)?
+        } else {
+            session::SessionMetadata::new(std::env::current_dir()?)
+        };
+        metadata.tags = unique_tags.clone();
+        session::storage::save_messages_with_metadata(session_file, &metadata, &self.messages)?;
+
+        Ok(unique_tags)
+    }
+
+    /// Switch the agent to another provider and model mid-session, keeping the conversation.
+    /// The switch is recorded in the session metadata when the session is saved.
+    pub async fn set_provider(&mut self, provider_name: &str, model_name: &str) -> Result<()> {
+        use goose::model::ModelConfig;
+        use goose::providers::create;
+
+        // Keep the temperature the session was started with
+        let temperature = self
+            .agent
+            .provider()
+            .await
+            .ok()
+            .and_then(|provider| provider.get_model_config().temperature);
+        let model_config = ModelConfig::new(model_name.to_string()).with_temperature(temperature);
+        let provider = create(provider_name, model_config)?;
+        self.agent.update_provider(provider).await?;
+
+        if let Some(session_file) = &self.session_file {
+            let mut metadata = if session_file.exists() {
+                session::read_metadata(session_file)?
+            } else {
+                session::SessionMetadata::new(std::env::current_dir()?)
+            };
+            metadata.provider = Some(provider_name.to_string());
+            metadata.model = Some(model_name.to_string());
+            session::storage::save_messages_with_metadata(session_file, &metadata, &self.messages)?;
+        }
+
+        println!(
+            "{}",
+            console::style(format!("Switched to {} ({})", model_name, provider_name)).green()
+        );
+        Ok(())
+    }
+
     /// Save a recipe to a file
     ///
     /// # Arguments
@@ -1538,6 +1833,45 @@ impl Session {
     fn push_message(&mut self, message: Message) {
         push_message(&mut self.messages, message);
     }
+
+    /// Replace tool results larger than `max_content_bytes` with a note naming the tool and
+    /// return how many were pruned
+    pub fn prune_tool_results(&mut self, max_content_bytes: usize) -> usize {
+        truncate::prune_tool_results(&mut self.messages, max_content_bytes)
+    }
+
+    /// Move queued injected messages into the session and persist them
+    async fn apply_injected_messages(&mut self) -> Result<()> {
+        for message in self.injector.take() {
+            output::render_message(&message, self.debug);
+            self.push_message(message);
+        }
+
+        if let Some(session_file) = &self.session_file {
+            let working_dir = std::env::current_dir().ok();
+            session::persist_messages_with_schedule_id(
+                session_file,
+                &self.messages,
+                None,
+                self.scheduled_job_id.clone(),
+                working_dir,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// A handle other tasks can use to inject messages while the session is running
+    pub fn message_injector(&self) -> MessageInjector {
+        self.injector.clone()
+    }
+
+    /// Add a message to the session and persist it. If a reply is in progress, use
+    /// `message_injector` instead, the agent loop picks the message up between tool calls.
+    pub async fn inject_message(&mut self, role: rmcp::model::Role, text: String) -> Result<()> {
+        self.injector.inject(role, text);
+        self.apply_injected_messages().await
+    }
 }
 
 fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
@@ -1,12 +1,13 @@
 use bat::WrappingMode;
 use console::{style, Color};
 use goose::config::Config;
-use goose::message::{Message, MessageContent, ToolRequest, ToolResponse};
+use goose::message::{ContextSizeReport, Message, MessageContent, ToolRequest, ToolResponse};
+use goose::providers::base::PartialMessage;
 use goose::providers::pricing::get_model_pricing;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use mcp_core::tool::ToolCall;
 use regex::Regex;
-use rmcp::model::PromptArgument;
+use rmcp::model::{PromptArgument, Role};
 use serde_json::Value;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -116,6 +117,44 @@ impl ThinkingIndicator {
     }
 }
 
+/// How long each of a recipe's activities is shown before moving to the next
+const ACTIVITY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Spinner that cycles through a recipe's activities while the session is loading,
+/// cleared when dropped
+pub struct ActivityTicker {
+    spinner: cliclack::ProgressBar,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl ActivityTicker {
+    pub fn start(activities: Vec<String>) -> Option<Self> {
+        let first = activities.first()?;
+        let spinner = cliclack::spinner();
+        spinner.start(first);
+
+        let ticking = spinner.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ACTIVITY_INTERVAL);
+            // The first tick completes immediately and the first activity is already showing
+            interval.tick().await;
+            for activity in activities.iter().cycle().skip(1) {
+                interval.tick().await;
+                ticking.set_message(activity);
+            }
+        });
+
+        Some(Self { spinner, task })
+    }
+}
+
+impl Drop for ActivityTicker {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.spinner.clear();
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PromptInfo {
     pub name: String,
@@ -146,16 +185,44 @@ pub fn set_thinking_message(s: &String) {
     });
 }
 
+/// Content with a priority below GOOSE_CLI_MIN_PRIORITY is not shown to the user
+pub fn min_priority() -> f32 {
+    Config::global()
+        .get_param::<f32>("GOOSE_CLI_MIN_PRIORITY")
+        .ok()
+        .unwrap_or(0.5)
+}
+
+/// Whether annotated content is meant for the assistant only or too low priority to show.
+/// Content without annotations is shown.
+fn hidden_from_user(
+    audience: Option<&Vec<Role>>,
+    priority: Option<f32>,
+    min_priority: f32,
+) -> bool {
+    audience.is_some_and(|audience| !audience.contains(&Role::User))
+        || priority.is_some_and(|priority| priority < min_priority)
+}
+
 pub fn render_message(message: &Message, debug: bool) {
     let theme = get_theme();
+    let min_priority = min_priority();
 
     for content in &message.content {
         match content {
-            MessageContent::Text(text) => print_markdown(&text.text, theme),
+            MessageContent::Text(text) => {
+                if !hidden_from_user(text.audience(), text.priority(), min_priority) {
+                    print_markdown(&text.text, theme)
+                }
+            }
             MessageContent::ToolRequest(req) => render_tool_request(req, theme, debug),
-            MessageContent::ToolResponse(resp) => render_tool_response(resp, theme, debug),
+            MessageContent::ToolResponse(resp) => {
+                render_tool_response(resp, theme, debug, min_priority)
+            }
             MessageContent::Image(image) => {
-                println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
+                if !hidden_from_user(image.audience(), image.priority(), min_priority) {
+                    println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
+                }
             }
             MessageContent::Thinking(thinking) => {
                 if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok() {
@@ -177,6 +244,30 @@ pub fn render_message(message: &Message, debug: bool) {
     let _ = std::io::stdout().flush();
 }
 
+/// The text of a message that is one piece of a streamed assistant response, None for any
+/// other message. Streamed pieces share the id of their response and hold only text. Text
+/// hidden from the user is left to render_message, which filters it out.
+pub fn streamed_text(message: &Message) -> Option<String> {
+    if message.role != Role::Assistant || message.id.is_none() {
+        return None;
+    }
+    let min_priority = min_priority();
+    if message.content.iter().any(|content| {
+        matches!(content, MessageContent::Text(text)
+            if hidden_from_user(text.audience(), text.priority(), min_priority))
+    }) {
+        return None;
+    }
+    let partial = PartialMessage::from_message(message.clone(), None);
+    partial.content.is_empty().then_some(partial.text_delta)
+}
+
+/// Print a piece of streamed response text as soon as it arrives
+pub fn stream_text(text: &str) {
+    print!("{}", text);
+    let _ = std::io::stdout().flush();
+}
+
 pub fn render_text(text: &str, color: Option<Color>, dim: bool) {
     render_text_no_newlines(format!("\n{}\n\n", text).as_str(), color, dim);
 }
@@ -233,26 +324,12 @@ fn render_tool_request(req: &ToolRequest, theme: Theme, debug: bool) {
     }
 }
 
-fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
-    let config = Config::global();
-
+fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool, min_priority: f32) {
     match &resp.tool_result {
         Ok(contents) => {
             for content in contents {
-                if let Some(audience) = content.audience() {
-                    if !audience.contains(&rmcp::model::Role::User) {
-                        continue;
-                    }
-                }
-
-                let min_priority = config
-                    .get_param::<f32>("GOOSE_CLI_MIN_PRIORITY")
-                    .ok()
-                    .unwrap_or(0.5);
-
-                if content
-                    .priority()
-                    .is_some_and(|priority| priority < min_priority)
+                // Tool output without a priority is only shown when debugging
+                if hidden_from_user(content.audience(), content.priority(), min_priority)
                     || (content.priority().is_none() && !debug)
                 {
                     continue;
@@ -710,6 +787,28 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     );
 }
 
+/// Show which messages take up the most of an overflowing context
+pub fn render_context_size_report(report: &ContextSizeReport) {
+    println!(
+        "\n{} {} tokens",
+        style("Context size:").yellow().bold(),
+        report.total_tokens
+    );
+    if report.largest_messages.is_empty() {
+        return;
+    }
+    println!("{}", style("Largest messages:").dim());
+    for (index, tokens, preview) in &report.largest_messages {
+        println!(
+            "  {} {} {}",
+            style(format!("#{:<4}", index)).cyan(),
+            style(format!("{:>8} tokens", tokens)).yellow(),
+            style(preview).dim()
+        );
+    }
+    println!();
+}
+
 fn normalize_model_name(model: &str) -> String {
     let mut result = model.to_string();
 
@@ -841,6 +940,50 @@ mod tests {
     use super::*;
     use std::env;
 
+    #[test]
+    fn test_hidden_from_user() {
+        let user = vec![Role::User];
+        let assistant = vec![Role::Assistant];
+
+        assert!(!hidden_from_user(None, None, 0.5));
+        assert!(!hidden_from_user(Some(&user), Some(0.5), 0.5));
+        assert!(hidden_from_user(Some(&user), Some(0.2), 0.5));
+        assert!(hidden_from_user(Some(&assistant), Some(1.0), 0.5));
+        assert!(!hidden_from_user(None, Some(0.0), 0.0));
+    }
+
+    #[test]
+    fn test_streamed_text() {
+        let mut delta = Message::assistant().with_text("Hel");
+        delta.id = Some("msg_1".to_string());
+        assert_eq!(streamed_text(&delta), Some("Hel".to_string()));
+
+        // Whole responses from providers that don't stream have no id
+        assert_eq!(
+            streamed_text(&Message::assistant().with_text("Hello")),
+            None
+        );
+
+        let mut tool_call = Message::assistant().with_tool_request(
+            "call_1",
+            Ok(ToolCall::new("developer__shell", serde_json::json!({}))),
+        );
+        tool_call.id = Some("msg_1".to_string());
+        assert_eq!(streamed_text(&tool_call), None);
+
+        let mut user = Message::user().with_text("Hi");
+        user.id = Some("msg_2".to_string());
+        assert_eq!(streamed_text(&user), None);
+
+        let mut hidden = Message::assistant().with_content(
+            rmcp::model::Content::text("For the assistant")
+                .with_audience(vec![Role::Assistant])
+                .into(),
+        );
+        hidden.id = Some("msg_1".to_string());
+        assert_eq!(streamed_text(&hidden), None);
+    }
+
     #[test]
     fn test_short_paths_unchanged() {
         assert_eq!(shorten_path("/usr/bin", false), "/usr/bin");
@@ -0,0 +1,149 @@
+use goose::message::{Message, MessageContent};
+use goose::session::SessionMetadata;
+use std::fmt;
+
+/// Usage metrics for a session, computed from its messages and stored metadata
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStatistics {
+    pub total_messages: usize,
+    pub user_messages: usize,
+    pub assistant_messages: usize,
+    pub total_tokens: Option<i32>,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub tool_calls: usize,
+    /// Tool calls that could not be parsed plus tool responses that returned an error
+    pub errors: usize,
+    /// Seconds between the first and the last message
+    pub elapsed_seconds: i64,
+}
+
+impl SessionStatistics {
+    pub fn from_messages(messages: &[Message], metadata: Option<&SessionMetadata>) -> Self {
+        let mut stats = SessionStatistics {
+            total_messages: messages.len(),
+            ..Default::default()
+        };
+
+        for message in messages {
+            match message.role {
+                rmcp::model::Role::User => stats.user_messages += 1,
+                rmcp::model::Role::Assistant => stats.assistant_messages += 1,
+            }
+            for content in &message.content {
+                match content {
+                    MessageContent::ToolRequest(request) => {
+                        stats.tool_calls += 1;
+                        if request.tool_call.is_err() {
+                            stats.errors += 1;
+                        }
+                    }
+                    MessageContent::ToolResponse(response) if response.tool_result.is_err() => {
+                        stats.errors += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let (Some(first), Some(last)) = (messages.first(), messages.last()) {
+            stats.elapsed_seconds = (last.created - first.created).max(0);
+        }
+
+        if let Some(metadata) = metadata {
+            stats.total_tokens = metadata.total_tokens;
+            stats.input_tokens = metadata.input_tokens;
+            stats.output_tokens = metadata.output_tokens;
+        }
+
+        stats
+    }
+}
+
+fn format_tokens(tokens: Option<i32>) -> String {
+    tokens.map_or_else(|| "unknown".to_string(), |t| t.to_string())
+}
+
+fn format_elapsed(seconds: i64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+impl fmt::Display for SessionStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Messages:     {} ({} user, {} assistant)",
+            self.total_messages, self.user_messages, self.assistant_messages
+        )?;
+        writeln!(
+            f,
+            "Tokens:       {} ({} input, {} output)",
+            format_tokens(self.total_tokens),
+            format_tokens(self.input_tokens),
+            format_tokens(self.output_tokens)
+        )?;
+        writeln!(f, "Tool calls:   {}", self.tool_calls)?;
+        writeln!(f, "Errors:       {}", self.errors)?;
+        write!(f, "Elapsed time: {}", format_elapsed(self.elapsed_seconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::handler::ToolError;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::{Content, Role};
+    use serde_json::json;
+
+    #[test]
+    fn test_statistics_from_messages() {
+        let messages = vec![
+            Message::new(Role::User, 1_000, vec![]).with_text("list files"),
+            Message::new(Role::Assistant, 1_010, vec![])
+                .with_tool_request("1", Ok(ToolCall::new("shell", json!({"command": "ls"}))))
+                .with_tool_request("2", Ok(ToolCall::new("shell", json!({"command": "cat"})))),
+            Message::new(Role::User, 1_020, vec![])
+                .with_tool_response("1", Ok(vec![Content::text("a.txt")]))
+                .with_tool_response(
+                    "2",
+                    Err(ToolError::ExecutionError("missing file".to_string())),
+                ),
+            Message::new(Role::Assistant, 1_125, vec![]).with_text("done"),
+        ];
+
+        let metadata = SessionMetadata {
+            total_tokens: Some(300),
+            input_tokens: Some(200),
+            output_tokens: Some(100),
+            ..Default::default()
+        };
+
+        let stats = SessionStatistics::from_messages(&messages, Some(&metadata));
+        assert_eq!(stats.total_messages, 4);
+        assert_eq!(stats.user_messages, 2);
+        assert_eq!(stats.assistant_messages, 2);
+        assert_eq!(stats.tool_calls, 2);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.elapsed_seconds, 125);
+        assert_eq!(stats.total_tokens, Some(300));
+
+        let summary = stats.to_string();
+        assert!(summary.contains("Elapsed time: 2m 5s"));
+        assert!(summary.contains("300 (200 input, 100 output)"));
+    }
+
+    #[test]
+    fn test_statistics_empty_session() {
+        let stats = SessionStatistics::from_messages(&[], None);
+        assert_eq!(stats, SessionStatistics::default());
+        assert!(stats.to_string().contains("Tokens:       unknown"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use super::shell::get_shell_config;
+
+/// Runs of the command when the call does not say how many
+pub const DEFAULT_RUNS: u32 = 5;
+/// Most runs a single call may ask for
+pub const MAX_RUNS: u32 = 100;
+
+/// Wall time of each run of a command and statistics over them, all in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkResult {
+    pub command: String,
+    pub runs: u32,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    /// Sample standard deviation, 0 for a single run
+    pub stddev_ms: f64,
+    pub timings_ms: Vec<f64>,
+}
+
+fn round(ms: f64) -> f64 {
+    (ms * 1000.0).round() / 1000.0
+}
+
+impl BenchmarkResult {
+    pub fn from_timings(command: &str, timings: &[Duration]) -> Self {
+        let timings_ms: Vec<f64> = timings.iter().map(|t| t.as_secs_f64() * 1000.0).collect();
+        let count = timings_ms.len().max(1) as f64;
+        let mean = timings_ms.iter().sum::<f64>() / count;
+        let variance = match timings_ms.len() {
+            0 | 1 => 0.0,
+            n => timings_ms.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1) as f64,
+        };
+        BenchmarkResult {
+            command: command.to_string(),
+            runs: timings_ms.len() as u32,
+            mean_ms: round(mean),
+            min_ms: round(timings_ms.iter().copied().fold(f64::INFINITY, f64::min)),
+            max_ms: round(timings_ms.iter().copied().fold(0.0, f64::max)),
+            stddev_ms: round(variance.sqrt()),
+            timings_ms: timings_ms.into_iter().map(round).collect(),
+        }
+    }
+
+    /// One line for the user, like `12.3 ms ± 0.4 ms (min 11.9 ms, max 13.0 ms) over 5 runs`
+    pub fn summary(&self) -> String {
+        format!(
+            "'{}': {:.1} ms ± {:.1} ms (min {:.1} ms, max {:.1} ms) over {} runs",
+            self.command, self.mean_ms, self.stddev_ms, self.min_ms, self.max_ms, self.runs
+        )
+    }
+}
+
+/// Run `command` in the shell `runs` times one after another and time each run. A run that
+/// fails, takes longer than `timeout` or is cancelled stops the benchmark, since timings of a
+/// failing command say little.
+pub async fn run(
+    command: &str,
+    runs: u32,
+    timeout: Option<Duration>,
+    cancel: &CancellationToken,
+) -> Result<BenchmarkResult, String> {
+    let shell_config = get_shell_config();
+    let mut timings = Vec::with_capacity(runs as usize);
+    for run in 1..=runs {
+        let start = Instant::now();
+        let child = Command::new(&shell_config.executable)
+            .args(&shell_config.args)
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+
+        let deadline = async {
+            match timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        let output = tokio::select! {
+            output = child.wait_with_output() => output
+                .map_err(|e| format!("Failed to run '{}': {}", command, e))?,
+            _ = deadline => {
+                return Err(format!(
+                    "Run {} of '{}' timed out after {} seconds",
+                    run,
+                    command,
+                    timeout.unwrap_or_default().as_secs()
+                ))
+            }
+            _ = cancel.cancelled() => return Err(format!("Benchmark of '{}' was cancelled", command)),
+        };
+        let elapsed = start.elapsed();
+        if !output.status.success() {
+            return Err(format!(
+                "Run {} of '{}' exited with {}: {}",
+                run,
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        timings.push(elapsed);
+    }
+    Ok(BenchmarkResult::from_timings(command, &timings))
+}
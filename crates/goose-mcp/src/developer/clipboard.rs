@@ -0,0 +1,30 @@
+use std::sync::{Mutex, OnceLock};
+
+use arboard::Clipboard;
+
+// On Linux the copied text is served by the process that set it, so the clipboard is kept open
+// for as long as the extension runs instead of being dropped after each call
+static CLIPBOARD: OnceLock<Mutex<Clipboard>> = OnceLock::new();
+
+fn with_clipboard<T>(
+    f: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>,
+) -> Result<T, String> {
+    let clipboard = match CLIPBOARD.get() {
+        Some(clipboard) => clipboard,
+        None => {
+            let clipboard =
+                Clipboard::new().map_err(|e| format!("Failed to open the clipboard: {}", e))?;
+            CLIPBOARD.get_or_init(|| Mutex::new(clipboard))
+        }
+    };
+    let mut clipboard = clipboard.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut clipboard).map_err(|e| e.to_string())
+}
+
+pub fn read_text() -> Result<String, String> {
+    with_clipboard(|clipboard| clipboard.get_text())
+}
+
+pub fn write_text(text: &str) -> Result<(), String> {
+    with_clipboard(|clipboard| clipboard.set_text(text))
+}
@@ -0,0 +1,44 @@
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// A unique name for the container, so it can be stopped when the call times out or is cancelled
+pub fn container_name() -> String {
+    format!("goose-run-{}", Uuid::new_v4().simple())
+}
+
+/// Arguments to `docker` running `command` with `sh -c` in a throwaway container of `image`.
+/// Environment variables are passed by name only, docker reads their values from its own
+/// environment so they do not show up in the process list.
+pub fn run_args(
+    name: &str,
+    image: &str,
+    command: &str,
+    volumes: &[String],
+    env_names: &[String],
+) -> Vec<String> {
+    let mut args: Vec<String> = ["run", "--rm", "--name", name]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    for volume in volumes {
+        args.extend(["--volume".to_string(), volume.clone()]);
+    }
+    for env_name in env_names {
+        args.extend(["--env".to_string(), env_name.clone()]);
+    }
+    args.extend([image, "sh", "-c", command].map(String::from));
+    args
+}
+
+/// Stop the container, killing the `docker run` client alone leaves it running
+pub async fn kill(name: &str) {
+    let result = Command::new("docker")
+        .args(["kill", name])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to stop container {}: {}", name, e);
+    }
+}
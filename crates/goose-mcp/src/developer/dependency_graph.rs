@@ -0,0 +1,195 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use cargo_metadata::{DependencyKind, MetadataCommand};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectKind {
+    Cargo,
+    Npm,
+    Python,
+}
+
+/// Packages of the project and what they depend on. The project's own packages are kept apart
+/// so they stand out from third party ones.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    pub packages: BTreeSet<String>,
+    pub edges: BTreeSet<(String, String)>,
+}
+
+impl ProjectKind {
+    /// The kind of project rooted at `dir`, going by its manifest
+    pub fn detect(dir: &Path) -> Option<Self> {
+        if dir.join("Cargo.toml").is_file() {
+            Some(ProjectKind::Cargo)
+        } else if dir.join("package.json").is_file() {
+            Some(ProjectKind::Npm)
+        } else if dir.join("requirements.txt").is_file() {
+            Some(ProjectKind::Python)
+        } else {
+            None
+        }
+    }
+
+    /// The direct dependencies of the project's packages, dev dependencies only when asked for
+    pub fn graph(&self, dir: &Path, include_dev: bool) -> Result<DependencyGraph, String> {
+        match self {
+            ProjectKind::Cargo => cargo_graph(dir, include_dev),
+            ProjectKind::Npm => npm_graph(dir, include_dev),
+            ProjectKind::Python => python_graph(dir),
+        }
+    }
+}
+
+// Every workspace member with the crates its manifest names, read without resolving the lock
+// file so it works offline
+fn cargo_graph(dir: &Path, include_dev: bool) -> Result<DependencyGraph, String> {
+    let metadata = MetadataCommand::new()
+        .manifest_path(dir.join("Cargo.toml"))
+        .no_deps()
+        .exec()
+        .map_err(|e| format!("cargo metadata failed: {}", e))?;
+
+    let mut graph = DependencyGraph::default();
+    for package in metadata.workspace_packages() {
+        graph.packages.insert(package.name.clone());
+        for dependency in &package.dependencies {
+            let wanted = match dependency.kind {
+                DependencyKind::Development => include_dev,
+                _ => true,
+            };
+            if wanted {
+                let name = dependency.rename.as_ref().unwrap_or(&dependency.name);
+                graph.edges.insert((package.name.clone(), name.clone()));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn npm_graph(dir: &Path, include_dev: bool) -> Result<DependencyGraph, String> {
+    let manifest = std::fs::read_to_string(dir.join("package.json"))
+        .map_err(|e| format!("Failed to read package.json: {}", e))?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest)
+        .map_err(|e| format!("Failed to parse package.json: {}", e))?;
+    let name = manifest["name"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| directory_name(dir));
+
+    let mut graph = DependencyGraph::default();
+    graph.packages.insert(name.clone());
+    let mut sections = vec!["dependencies", "peerDependencies", "optionalDependencies"];
+    if include_dev {
+        sections.push("devDependencies");
+    }
+    for section in sections {
+        if let Some(dependencies) = manifest[section].as_object() {
+            for dependency in dependencies.keys() {
+                graph.edges.insert((name.clone(), dependency.clone()));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+// requirements.txt lists one requirement per line like `requests>=2.0 ; python_version < "3.8"`,
+// lines starting with a dash are pip options such as `-r other.txt`
+fn python_graph(dir: &Path) -> Result<DependencyGraph, String> {
+    let requirements = std::fs::read_to_string(dir.join("requirements.txt"))
+        .map_err(|e| format!("Failed to read requirements.txt: {}", e))?;
+    let name = directory_name(dir);
+
+    let mut graph = DependencyGraph::default();
+    graph.packages.insert(name.clone());
+    for line in requirements.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('-') {
+            continue;
+        }
+        let end = line
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            .unwrap_or(line.len());
+        if end > 0 {
+            graph
+                .edges
+                .insert((name.clone(), line[..end].to_lowercase()));
+        }
+    }
+    Ok(graph)
+}
+
+fn directory_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "project".to_string())
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl DependencyGraph {
+    /// The graph in Graphviz DOT format, the project's own packages filled in
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n");
+        for package in &self.packages {
+            dot.push_str(&format!(
+                "    {} [style=filled, fillcolor=lightblue];\n",
+                quote(package)
+            ));
+        }
+        for (from, to) in &self.edges {
+            dot.push_str(&format!("    {} -> {};\n", quote(from), quote(to)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Render `dot` to an SVG file in the temp directory with Graphviz, None when it is not installed
+pub async fn render_svg(dot: &str, name: &str) -> Result<Option<PathBuf>, String> {
+    let Ok(program) = which::which("dot") else {
+        return Ok(None);
+    };
+    let file_name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let path = std::env::temp_dir().join(format!("goose-dependencies-{}.svg", file_name));
+
+    let mut child = tokio::process::Command::new(program)
+        .arg("-Tsvg")
+        .arg("-o")
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start Graphviz: {}", e))?;
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(dot.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to send the graph to Graphviz: {}", e))?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Graphviz failed: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Graphviz failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(Some(path))
+}
@@ -1,14 +1,36 @@
+mod benchmark;
+mod clipboard;
+mod container;
+mod dependency_graph;
+mod diff;
 mod editor_models;
 mod lang;
+mod network;
+mod notebook;
+mod ocr;
+mod plugin;
+mod processes;
+mod profile;
+mod recipe_lint;
+mod rename;
+mod semantic_search;
 mod shell;
+mod spell;
+mod sql;
+mod terminal;
+mod test_runner;
+
+pub use editor_models::EditorModel;
+pub use plugin::ToolPlugin;
+pub use semantic_search::EmbeddingClient;
 
 use anyhow::Result;
 use base64::Engine;
 use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     future::Future,
     io::Cursor,
     path::{Path, PathBuf},
@@ -19,6 +41,7 @@ use tokio::{
     process::Command,
     sync::mpsc,
 };
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
 use include_dir::{include_dir, Dir};
@@ -36,18 +59,54 @@ use rmcp::model::{
 };
 use rmcp::object;
 
-use self::editor_models::{create_editor_model, EditorModel};
-use self::shell::{expand_path, get_shell_config, is_absolute_path, normalize_line_endings};
+use self::editor_models::create_editor_model;
+use self::semantic_search::{chunk_file, cosine_similarity, CodeChunk};
+use self::shell::{
+    expand_path, get_shell_config, is_absolute_path, is_idempotent_command, normalize_line_endings,
+};
 use indoc::indoc;
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use xcap::{Monitor, Window};
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
 
 // Embeds the prompts directory to the build
 static PROMPTS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/developer/prompts");
 
+/// How long `watch_file` waits for a change when no timeout is given
+const DEFAULT_WATCH_TIMEOUT_SECS: u64 = 60;
+
+/// How much of a file `hex_dump` shows
+const HEX_DUMP_LIMIT: u64 = 4096;
+
+/// Largest image file `image_processor` and `image_ocr` read
+const MAX_IMAGE_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Largest file `encode_base64` embeds, the encoded text is a third bigger
+const MAX_BASE64_SOURCE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Line length of wrapped base64, as in PEM and MIME
+const BASE64_LINE_WIDTH: usize = 76;
+
+fn check_image_file_size(path: &Path) -> Result<(), ToolError> {
+    let file_size = std::fs::metadata(path)
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+        .len();
+
+    if file_size > MAX_IMAGE_FILE_SIZE {
+        return Err(ToolError::ExecutionError(format!(
+            "File '{}' is too large ({:.2}MB). Maximum size is 10MB.",
+            path.display(),
+            file_size as f64 / (1024.0 * 1024.0)
+        )));
+    }
+    Ok(())
+}
+
 /// Loads prompt files from the embedded PROMPTS_DIR and returns a HashMap of prompts.
 /// Ensures that each prompt name is unique.
 pub fn load_prompt_files() -> HashMap<String, Prompt> {
@@ -91,13 +150,275 @@ pub fn load_prompt_files() -> HashMap<String, Prompt> {
     prompts
 }
 
+// choose_app_strategy().config_dir()
+// - macOS/Linux: ~/.config/goose/
+// - Windows:     ~\AppData\Roaming\Block\goose\config\
+// keep previous behavior of expanding ~/.config in case this fails
+fn global_hints_path() -> PathBuf {
+    choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.in_config_dir(".goosehints"))
+        .unwrap_or_else(|_| {
+            PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints").to_string())
+        })
+}
+
+/// Write through a sibling temp file that is renamed into place, so a crash leaves either the
+/// old or the new content and never a partial file. Falls back to a direct write when the
+/// temp file cannot be created or renamed, e.g. in a read-only directory. A symlink is
+/// followed so the file it points to is replaced rather than the link itself, and the
+/// replaced file keeps its permissions.
+fn write_file_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = target.as_path();
+    let dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let existing_permissions = std::fs::metadata(path).ok().map(|m| m.permissions());
+
+    let attempt = (|| -> std::io::Result<()> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(".goose-write-");
+        // New files get the same mode a direct write would give them
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            builder.permissions(std::fs::Permissions::from_mode(0o666));
+        }
+        let mut temp = builder.tempfile_in(dir)?;
+        temp.write_all(contents.as_bytes())?;
+        if let Some(permissions) = &existing_permissions {
+            temp.as_file().set_permissions(permissions.clone())?;
+        }
+        temp.as_file().sync_all()?;
+        temp.persist(path).map_err(|e| e.error)?;
+        Ok(())
+    })();
+
+    if let Err(e) = attempt {
+        tracing::warn!(
+            "Atomic write to {} failed ({}), writing the file directly",
+            path.display(),
+            e
+        );
+        std::fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+/// Convert scraped HTML to Markdown. Scripts, styles and comments are removed first since
+/// their text would otherwise end up in the output.
+fn html_to_markdown(html: &str) -> String {
+    let stripped =
+        regex::Regex::new(r"(?is)<script\b.*?</script\s*>|<style\b.*?</style\s*>|<!--.*?-->")
+            .unwrap()
+            .replace_all(html, "");
+    html2md::parse_html(&stripped).trim().to_string() + "\n"
+}
+
+/// Format bytes like `hexdump -C`: the offset, sixteen bytes in hex and the printable ASCII
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for (i, byte) in chunk.iter().enumerate() {
+            if i == 8 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{:02x} ", byte));
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        output.push_str(&format!("{:08x}  {:<49} |{}|\n", row * 16, hex, ascii));
+    }
+    output
+}
+
+/// Encode bytes as standard base64, optionally broken into lines of 76 characters
+fn encode_base64(bytes: &[u8], wrap_lines: bool) -> String {
+    let encoded = base64::prelude::BASE64_STANDARD.encode(bytes);
+    if !wrap_lines {
+        return encoded;
+    }
+    // Base64 is ASCII, so splitting the bytes keeps every line valid UTF-8
+    encoded
+        .as_bytes()
+        .chunks(BASE64_LINE_WIDTH)
+        .map(|line| std::str::from_utf8(line).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether an opt-in tool is turned on by its environment variable
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+/// Limits and feature toggles for the developer router. `Default` reads nothing from the
+/// environment, `from_env` gives the settings `DeveloperRouter::new()` uses.
+#[derive(Debug, Clone)]
+pub struct DeveloperRouterConfig {
+    /// Largest file the text editor will load into memory
+    pub max_file_size_bytes: u64,
+    /// Shell output longer than this is rejected rather than returned to the model
+    pub max_shell_output_chars: usize,
+    /// Kill shell commands that run longer than this, no limit when None
+    pub shell_timeout_secs: Option<u64>,
+    /// Expose the list_windows and screen_capture tools
+    pub enable_screen_capture: bool,
+    /// Expose the image_processor tool
+    pub enable_image_processor: bool,
+    /// Expose the clipboard_read and clipboard_write tools, off unless
+    /// GOOSE_ENABLE_CLIPBOARD_TOOLS is true since the clipboard often holds private data
+    pub enable_clipboard_tools: bool,
+    /// Expose the run_in_container tool, off unless GOOSE_ENABLE_CONTAINER_TOOL is true since it
+    /// needs a Docker daemon and can mount host directories
+    pub enable_container_tool: bool,
+    /// Stop run_in_container commands that run longer than this
+    pub container_timeout_secs: u64,
+    /// Expose the terminal_record and terminal_replay tools, off unless
+    /// GOOSE_ENABLE_TERMINAL_RECORDING is true since they need asciinema installed
+    pub enable_terminal_recording: bool,
+    /// Expose the lint_recipe tool, off unless GOOSE_ENABLE_LINT_RECIPE is true
+    pub enable_lint_recipe: bool,
+    /// Expose the watch_directory tool, off unless GOOSE_ENABLE_WATCH_DIRECTORY is true since it
+    /// keeps a watcher and notifications running for the length of the call
+    pub enable_watch_directory: bool,
+    /// Expose the profile and benchmark tools, off unless GOOSE_ENABLE_PROFILING_TOOLS is true
+    /// since they run commands repeatedly
+    pub enable_profiling_tools: bool,
+    /// Expose the sql_query and csv_to_sql tools, off unless GOOSE_ENABLE_SQL_TOOLS is true since
+    /// they open and write SQLite databases
+    pub enable_sql_tools: bool,
+    /// Expose the run_tests tool, off unless GOOSE_ENABLE_RUN_TESTS is true
+    pub enable_run_tests: bool,
+    /// Expose the generate_uuid tool, off unless GOOSE_ENABLE_GENERATE_UUID is true
+    pub enable_generate_uuid: bool,
+    /// Expose the env_inspect, network_inspect and process_list tools, off unless
+    /// GOOSE_ENABLE_SYSTEM_INSPECTION is true since they report on the host beyond the project
+    pub enable_system_inspection: bool,
+    /// Expose the dependency_graph tool, off unless GOOSE_ENABLE_DEPENDENCY_GRAPH is true since
+    /// it needs the project's build tool and Graphviz
+    pub enable_dependency_graph: bool,
+    /// Hint files appended to the instructions, in order, missing files are skipped
+    pub hints_paths: Vec<PathBuf>,
+    /// How long output of known read-only shell commands is reused, caching is off when 0,
+    /// which is the default unless GOOSE_SHELL_CACHE_TTL_SECS is set
+    pub shell_cache_ttl_secs: u64,
+    /// Number of chunks semantic_search returns when the call does not set `limit`
+    pub semantic_search_top_k: usize,
+    /// How long a notebook_execute cell may run before its kernel is shut down
+    pub notebook_timeout_secs: u64,
+    /// Edits kept per file for undo_edit, the oldest are dropped first
+    pub max_history_depth: usize,
+    /// Globs matched case-insensitively against names of environment variables env_inspect hides
+    pub sensitive_env_patterns: Vec<String>,
+    /// Environment variables env_inspect shows even when they match a sensitive pattern
+    pub env_allowlist: Vec<String>,
+    /// Model applying str_replace edits, plain string replacement when None
+    pub editor_model: Option<EditorModel>,
+    /// Embeddings endpoint for semantic_search, which is only offered when one is set
+    pub embedding_client: Option<EmbeddingClient>,
+}
+
+impl Default for DeveloperRouterConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size_bytes: 400 * 1024, // 400KB in bytes
+            max_shell_output_chars: 400_000, // 409600 chars = 400KB
+            shell_timeout_secs: None,
+            enable_screen_capture: true,
+            enable_image_processor: true,
+            enable_clipboard_tools: false,
+            enable_container_tool: false,
+            container_timeout_secs: 600,
+            enable_terminal_recording: false,
+            enable_lint_recipe: false,
+            enable_watch_directory: false,
+            enable_profiling_tools: false,
+            enable_sql_tools: false,
+            enable_run_tests: false,
+            enable_generate_uuid: false,
+            enable_system_inspection: false,
+            enable_dependency_graph: false,
+            hints_paths: Vec::new(),
+            shell_cache_ttl_secs: 0,
+            semantic_search_top_k: 5,
+            notebook_timeout_secs: 120,
+            max_history_depth: 50,
+            sensitive_env_patterns: ["*TOKEN*", "*SECRET*", "*PASSWORD*", "*KEY*", "*CREDENTIAL*"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            env_allowlist: Vec::new(),
+            editor_model: None,
+            embedding_client: None,
+        }
+    }
+}
+
+impl DeveloperRouterConfig {
+    /// The defaults with the opt-in tools, shell cache, env_inspect allowlist, editor model and
+    /// embeddings endpoint set from environment variables, reading the global and working
+    /// directory `.goosehints`
+    pub fn from_env() -> Self {
+        let cwd = std::env::current_dir().expect("should have a current working dir");
+        Self {
+            enable_clipboard_tools: env_flag("GOOSE_ENABLE_CLIPBOARD_TOOLS"),
+            enable_container_tool: env_flag("GOOSE_ENABLE_CONTAINER_TOOL"),
+            enable_terminal_recording: env_flag("GOOSE_ENABLE_TERMINAL_RECORDING"),
+            enable_lint_recipe: env_flag("GOOSE_ENABLE_LINT_RECIPE"),
+            enable_watch_directory: env_flag("GOOSE_ENABLE_WATCH_DIRECTORY"),
+            enable_profiling_tools: env_flag("GOOSE_ENABLE_PROFILING_TOOLS"),
+            enable_sql_tools: env_flag("GOOSE_ENABLE_SQL_TOOLS"),
+            enable_run_tests: env_flag("GOOSE_ENABLE_RUN_TESTS"),
+            enable_generate_uuid: env_flag("GOOSE_ENABLE_GENERATE_UUID"),
+            enable_system_inspection: env_flag("GOOSE_ENABLE_SYSTEM_INSPECTION"),
+            enable_dependency_graph: env_flag("GOOSE_ENABLE_DEPENDENCY_GRAPH"),
+            hints_paths: vec![global_hints_path(), cwd.join(".goosehints")],
+            shell_cache_ttl_secs: std::env::var("GOOSE_SHELL_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            env_allowlist: std::env::var("GOOSE_ENV_ALLOWLIST")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            editor_model: create_editor_model(),
+            embedding_client: EmbeddingClient::from_env(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Output of read-only shell commands keyed by the directory they ran in and the command
+type OutputCache = HashMap<(PathBuf, String), (Instant, String)>;
+
 pub struct DeveloperRouter {
     tools: Vec<Tool>,
     prompts: Arc<HashMap<String, Prompt>>,
     instructions: String,
     file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
     ignore_patterns: Arc<Gitignore>,
-    editor_model: Option<EditorModel>,
+    config: DeveloperRouterConfig,
+    output_cache: Arc<Mutex<OutputCache>>,
+    plugins: HashMap<String, Arc<dyn ToolPlugin + Send + Sync>>,
+    recording: Arc<Mutex<Option<terminal::Recording>>>,
 }
 
 impl Default for DeveloperRouter {
@@ -108,6 +429,32 @@ impl Default for DeveloperRouter {
 
 impl DeveloperRouter {
     pub fn new() -> Self {
+        Self::new_with_config(DeveloperRouterConfig::from_env())
+    }
+
+    /// A router configured from the environment that reads the given hint files, in order,
+    /// instead of the global and working directory `.goosehints`
+    pub fn with_hint_paths(paths: Vec<PathBuf>) -> Self {
+        Self::new_with_config(DeveloperRouterConfig {
+            hints_paths: paths,
+            ..DeveloperRouterConfig::from_env()
+        })
+    }
+
+    /// The `.goosehints` files in `start` and up to `max_levels` of its parent directories,
+    /// outermost first so that hints closer to `start` come later and can refine them
+    pub fn discover_hints(start: PathBuf, max_levels: usize) -> Vec<PathBuf> {
+        let mut hints_paths: Vec<PathBuf> = start
+            .ancestors()
+            .take(max_levels + 1)
+            .map(|dir| dir.join(".goosehints"))
+            .filter(|path| path.is_file())
+            .collect();
+        hints_paths.reverse();
+        hints_paths
+    }
+
+    pub fn new_with_config(config: DeveloperRouterConfig) -> Self {
         // TODO consider rust native search tools, we could use
         // https://docs.rs/ignore/latest/ignore/
 
@@ -116,7 +463,7 @@ impl DeveloperRouter {
         //
         // when there is an editor model, the prompts are slightly changed as it takes
         // a load off the main LLM making the tool calls and you get faster more correct applies
-        let editor_model = create_editor_model();
+        let editor_model = config.editor_model.as_ref();
 
         // Get OS-specific shell tool description
         let shell_tool_desc = match std::env::consts::OS {
@@ -255,17 +602,33 @@ impl DeveloperRouter {
         });
 
         // Create text editor tool with different descriptions based on editor API configuration
-        let (text_editor_desc, str_replace_command) = if let Some(ref editor) = editor_model {
+        let (text_editor_desc, str_replace_command) = if let Some(editor) = editor_model {
             (
                 formatdoc! {r#"
                 Perform text editing operations on files.
 
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file, or list the entries of a directory.
                 - `write`: Create or overwrite a file with the given content
                 - `edit_file`: Edit the file with the new content.
                 - `insert`: Insert text at a specific line location in the file.
                 - `undo_edit`: Undo the last edit made to a file.
+                - `json_query`: Run a jq-style `query` against a JSON file and return the result.
+                - `yaml_query`: Run a JSONPath `query` against a YAML file and return the result.
+                - `render_markdown`: Render a markdown file to HTML next to it and return the output path.
+                - `bulk_edit`: Apply several replacements to a file at once, or none if any of them fails.
+                - `read_csv`: Parse a CSV file with a header row and return its rows as JSON objects.
+                - `write_lines`: Replace a range of lines in the file with new content.
+                - `watch_file`: Wait for the file to be changed by another process and return its new content.
+                - `html_to_markdown`: Convert an HTML file to Markdown, returning it or writing it to `destination`.
+                - `git_diff`: Show the unstaged changes to a file relative to git.
+                - `hex_dump`: Show the first 4 KB of a binary file as a hex dump.
+                - `symbol_rename`: Rename an identifier in every source file under `path`.
+                - `spell_check`: List the misspelled words in a Markdown or text file with suggested corrections.
+                - `sort_lines`: Sort the lines of a file, or of a range of its lines.
+                - `encode_base64`: Write a file base64 encoded to the text file `destination`.
+                - `validate_schema`: Check a JSON or YAML file against the JSON Schema at `schema_path`.
+                - `history_depth`: Show how many edits to a file `undo_edit` can still undo.
 
                 To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
                 existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
@@ -274,19 +637,85 @@ impl DeveloperRouter {
 
                 To use the insert command, you must specify both `insert_line` (the line number after which to insert, 0 for beginning) 
                 and `new_str` (the text to insert).
-            "#, editor.get_str_replace_description()},
+
+                To use the json_query command, you must specify `query` (a jq filter such as `.dependencies | keys`).
+                Set `compact` to true to return single-line JSON instead of pretty-printed output.
+
+                To use the yaml_query command, you must specify `query` (a JSONPath expression such as
+                `$.spec.template.spec.containers[*].image`). The result is YAML unless `output_format` is `json`.
+
+                The render_markdown command writes `<name>.html` in the same directory as the markdown file.
+
+                To use the bulk_edit command, you must specify `edits`, a list of `{{"old_str": ..., "new_str": ...}}` objects. Each
+                `old_str` must appear exactly once in the file and must not overlap another edit. The file is only changed if every
+                edit is valid.
+
+                The read_csv command returns at most 1000 rows, and notes when the file has more.
+
+                To use the write_lines command, you must specify `start_line` and `end_line` (1-indexed, inclusive) and
+                `new_content`, which replaces those lines. An empty `new_content` deletes them.
+
+                The watch_file command waits up to `timeout_secs` (default 60) for the file to change, e.g. after
+                starting a build or a code generator. Prefer it over polling the file with the shell.
+
+                The html_to_markdown command drops scripts, styles and comments and keeps headings, links and code
+                blocks. Set `destination` to write the Markdown to a file instead of returning it.
+
+                The git_diff command runs `git diff` on the file. Outside a git repository it compares the file with
+                its content before the first edit made to it in this session.
+
+                Use the hex_dump command for files that `view` cannot read as text.
+
+                To use the symbol_rename command, you must specify `symbol` and `new_name`. `path` is the file or
+                directory to rename in. Every identifier spelled `symbol` is renamed, whatever scope it is in, while
+                strings and comments are left alone. Nothing is changed if a file does not parse or already uses
+                `new_name`.
+
+                The spell_check command needs aspell or hunspell to be installed. Code blocks, inline code and links in
+                Markdown files are skipped.
+
+                The sort_lines command sorts the whole file unless `start_line` and/or `end_line` (1-indexed, inclusive)
+                are given. Set `reverse` to sort in descending order and `ignore_case` to compare lines case-insensitively.
+                Use it to order imports, configuration keys or word lists instead of running `sort` in the shell.
+
+                To use the encode_base64 command, you must specify `destination`. `path` can be any file, such as an
+                image or a certificate. Set `wrap_lines` to break the output into lines of 76 characters.
+
+                To use the validate_schema command, you must specify `schema_path`. The result lists every
+                validation error with the JSON Pointer of the value it concerns, so all of them can be fixed at once.
+
+                Use the history_depth command before undoing several edits in a row. Only the last {depth} edits to a
+                file are kept.
+            "#, editor.get_str_replace_description(), depth=config.max_history_depth},
                 "edit_file",
             )
         } else {
-            (indoc! {r#"
+            (
+                formatdoc! {r#"
                 Perform text editing operations on files.
 
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file, or list the entries of a directory.
                 - `write`: Create or overwrite a file with the given content
                 - `str_replace`: Replace a string in a file with a new string.
                 - `insert`: Insert text at a specific line location in the file.
                 - `undo_edit`: Undo the last edit made to a file.
+                - `json_query`: Run a jq-style `query` against a JSON file and return the result.
+                - `yaml_query`: Run a JSONPath `query` against a YAML file and return the result.
+                - `render_markdown`: Render a markdown file to HTML next to it and return the output path.
+                - `bulk_edit`: Apply several replacements to a file at once, or none if any of them fails.
+                - `read_csv`: Parse a CSV file with a header row and return its rows as JSON objects.
+                - `write_lines`: Replace a range of lines in the file with new content.
+                - `watch_file`: Wait for the file to be changed by another process and return its new content.
+                - `html_to_markdown`: Convert an HTML file to Markdown, returning it or writing it to `destination`.
+                - `git_diff`: Show the unstaged changes to a file relative to git.
+                - `hex_dump`: Show the first 4 KB of a binary file as a hex dump.
+                - `symbol_rename`: Rename an identifier in every source file under `path`.
+                - `spell_check`: List the misspelled words in a Markdown or text file with suggested corrections.
+                - `sort_lines`: Sort the lines of a file, or of a range of its lines.
+                - `encode_base64`: Write a file base64 encoded to the text file `destination`.
+                - `validate_schema`: Check a JSON or YAML file against the JSON Schema at `schema_path`.
+                - `history_depth`: Show how many edits to a file `undo_edit` can still undo.
 
                 To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
                 existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
@@ -297,7 +726,58 @@ impl DeveloperRouter {
 
                 To use the insert command, you must specify both `insert_line` (the line number after which to insert, 0 for beginning) 
                 and `new_str` (the text to insert).
-            "#}.to_string(), "str_replace")
+
+                To use the json_query command, you must specify `query` (a jq filter such as `.dependencies | keys`).
+                Set `compact` to true to return single-line JSON instead of pretty-printed output.
+
+                To use the yaml_query command, you must specify `query` (a JSONPath expression such as
+                `$.spec.template.spec.containers[*].image`). The result is YAML unless `output_format` is `json`.
+
+                The render_markdown command writes `<name>.html` in the same directory as the markdown file.
+
+                To use the bulk_edit command, you must specify `edits`, a list of `{{"old_str": ..., "new_str": ...}}` objects. Each
+                `old_str` must appear exactly once in the file and must not overlap another edit. The file is only changed if every
+                edit is valid.
+
+                The read_csv command returns at most 1000 rows, and notes when the file has more.
+
+                To use the write_lines command, you must specify `start_line` and `end_line` (1-indexed, inclusive) and
+                `new_content`, which replaces those lines. An empty `new_content` deletes them.
+
+                The watch_file command waits up to `timeout_secs` (default 60) for the file to change, e.g. after
+                starting a build or a code generator. Prefer it over polling the file with the shell.
+
+                The html_to_markdown command drops scripts, styles and comments and keeps headings, links and code
+                blocks. Set `destination` to write the Markdown to a file instead of returning it.
+
+                The git_diff command runs `git diff` on the file. Outside a git repository it compares the file with
+                its content before the first edit made to it in this session.
+
+                Use the hex_dump command for files that `view` cannot read as text.
+
+                To use the symbol_rename command, you must specify `symbol` and `new_name`. `path` is the file or
+                directory to rename in. Every identifier spelled `symbol` is renamed, whatever scope it is in, while
+                strings and comments are left alone. Nothing is changed if a file does not parse or already uses
+                `new_name`.
+
+                The spell_check command needs aspell or hunspell to be installed. Code blocks, inline code and links in
+                Markdown files are skipped.
+
+                The sort_lines command sorts the whole file unless `start_line` and/or `end_line` (1-indexed, inclusive)
+                are given. Set `reverse` to sort in descending order and `ignore_case` to compare lines case-insensitively.
+                Use it to order imports, configuration keys or word lists instead of running `sort` in the shell.
+
+                To use the encode_base64 command, you must specify `destination`. `path` can be any file, such as an
+                image or a certificate. Set `wrap_lines` to break the output into lines of 76 characters.
+
+                To use the validate_schema command, you must specify `schema_path`. The result lists every
+                validation error with the JSON Pointer of the value it concerns, so all of them can be fixed at once.
+
+                Use the history_depth command before undoing several edits in a row. Only the last {depth} edits to a
+                file are kept.
+            "#, depth=config.max_history_depth},
+                "str_replace",
+            )
         };
 
         let text_editor_tool = Tool::new(
@@ -313,8 +793,8 @@ impl DeveloperRouter {
                     },
                     "command": {
                         "type": "string",
-                        "enum": ["view", "write", str_replace_command, "insert", "undo_edit"],
-                        "description": format!("Allowed options are: `view`, `write`, `{}`, `insert`, `undo_edit`.", str_replace_command)
+                        "enum": ["view", "write", str_replace_command, "insert", "undo_edit", "json_query", "yaml_query", "render_markdown", "bulk_edit", "read_csv", "write_lines", "watch_file", "html_to_markdown", "git_diff", "hex_dump", "symbol_rename", "spell_check", "sort_lines", "encode_base64", "validate_schema", "history_depth"],
+                        "description": format!("Allowed options are: `view`, `write`, `{}`, `insert`, `undo_edit`, `json_query`, `yaml_query`, `render_markdown`, `bulk_edit`, `read_csv`, `write_lines`, `watch_file`, `html_to_markdown`, `git_diff`, `hex_dump`, `symbol_rename`, `spell_check`, `sort_lines`, `encode_base64`, `validate_schema`, `history_depth`.", str_replace_command)
                     },
                     "view_range": {
                         "type": "array",
@@ -327,13 +807,121 @@ impl DeveloperRouter {
                         "type": "integer",
                         "description": "The line number after which to insert the text (0 for beginning of file). This parameter is required when using the insert command."
                     },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "First line to replace, 1-indexed. This parameter is required when using the write_lines command, and sets the first line to sort for sort_lines."
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "Last line to replace, inclusive. This parameter is required when using the write_lines command, and sets the last line to sort for sort_lines."
+                    },
+                    "new_content": {
+                        "type": "string",
+                        "description": "The text that replaces lines `start_line` to `end_line`. This parameter is required when using the write_lines command."
+                    },
+                    "reverse": {
+                        "type": "boolean",
+                        "description": "Sort in descending order with the sort_lines command."
+                    },
+                    "ignore_case": {
+                        "type": "boolean",
+                        "description": "Compare lines case-insensitively with the sort_lines command."
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "How long the watch_file command waits for a change before giving up, defaults to 60."
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "Path to write the html_to_markdown result to. When omitted the Markdown is returned instead. This parameter is required when using the encode_base64 command."
+                    },
+                    "wrap_lines": {
+                        "type": "boolean",
+                        "description": "Break the encode_base64 output into lines of 76 characters."
+                    },
+                    "schema_path": {
+                        "type": "string",
+                        "description": "Path to the JSON Schema file to validate against. This parameter is required when using the validate_schema command."
+                    },
+                    "symbol": {
+                        "type": "string",
+                        "description": "The identifier to rename. This parameter is required when using the symbol_rename command."
+                    },
+                    "new_name": {
+                        "type": "string",
+                        "description": "The name `symbol` is renamed to. This parameter is required when using the symbol_rename command."
+                    },
                     "old_str": {"type": "string"},
                     "new_str": {"type": "string"},
-                    "file_text": {"type": "string"}
+                    "file_text": {"type": "string"},
+                    "query": {
+                        "type": "string",
+                        "description": "A jq-style filter for json_query, or a JSONPath expression for yaml_query. This parameter is required when using either command."
+                    },
+                    "output_format": {
+                        "type": "string",
+                        "enum": ["yaml", "json"],
+                        "description": "Format of the yaml_query result, defaults to yaml."
+                    },
+                    "compact": {
+                        "type": "boolean",
+                        "description": "Return compact single-line JSON from the json_query command instead of pretty-printed output."
+                    },
+                    "edits": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["old_str", "new_str"],
+                            "properties": {
+                                "old_str": {"type": "string"},
+                                "new_str": {"type": "string"}
+                            }
+                        },
+                        "description": "The replacements to apply. This parameter is required when using the bulk_edit command."
+                    }
                 }
             }),
         );
 
+        let semantic_search_tool = Tool::new(
+            "semantic_search".to_string(),
+            indoc! {r#"
+                Search source code by meaning rather than exact text.
+
+                Source files under `path` are split into functions, embedded, and ranked by how similar
+                they are to `query`. Use this to find code when you know what it does but not what it is
+                called, e.g. "where are retries with backoff implemented". Use grep for exact matches.
+
+                Returns the best matching chunks with their file paths and line ranges.
+            "#}
+            .to_string(),
+            object!({
+                "type": "object",
+                "required": ["query"],
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "A natural language description of the code to find."
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path of the directory to search, defaults to the current directory."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of chunks to return."
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Search code by meaning".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(true),
+        });
+
         let list_windows_tool = Tool::new(
             "list_windows",
             indoc! {r#"
@@ -355,6 +943,49 @@ impl DeveloperRouter {
             open_world_hint: Some(false),
         });
 
+        let clipboard_read_tool = Tool::new(
+            "clipboard_read",
+            indoc! {r#"
+                Read the text currently on the system clipboard.
+            "#},
+            object!({
+                "type": "object",
+                "required": [],
+                "properties": {}
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Read the clipboard".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
+
+        let clipboard_write_tool = Tool::new(
+            "clipboard_write",
+            indoc! {r#"
+                Put text on the system clipboard, replacing what was there.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["text"],
+                "properties": {
+                    "text": {
+                        "type": "string",
+                        "description": "The text to copy to the clipboard"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Write to the clipboard".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         let screen_capture_tool = Tool::new(
             "screen_capture",
             indoc! {r#"
@@ -418,1431 +1049,6205 @@ impl DeveloperRouter {
             open_world_hint: Some(false),
         });
 
-        // Get base instructions and working directory
-        let cwd = std::env::current_dir().expect("should have a current working dir");
-        let os = std::env::consts::OS;
+        let image_ocr_tool = Tool::new(
+            "image_ocr",
+            indoc! {r#"
+                Extract the text from an image file, such as a screenshot or a scanned document, with Tesseract.
 
-        let base_instructions = match os {
-            "windows" => formatdoc! {r#"
-                The developer extension gives you the capabilities to edit code files and run shell commands,
-                and can be used to solve a wide range of problems.
+                Returns the recognized text. Set `lang` to the Tesseract language of the text, e.g. `deu`, or
+                several joined with `+` like `eng+fra`; the language data has to be installed.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path to the image file"
+                    },
+                    "lang": {
+                        "type": "string",
+                        "description": "Tesseract language code, defaults to eng"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Extract text from an image".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-                You can use the shell tool to run Windows commands (PowerShell or CMD).
-                When using paths, you can use either backslashes or forward slashes.
+        let notebook_execute_tool = Tool::new(
+            "notebook_execute",
+            formatdoc! {r#"
+                Run Python code as a Jupyter notebook cell and return its output.
 
-                Use the shell tool as needed to locate files or interact with the project.
+                The cell runs in a fresh kernel, so state does not carry over between calls. Stream output,
+                results and tracebacks are returned the way a notebook shows them. Cells running longer than
+                {timeout} seconds are stopped.
+            "#, timeout=config.notebook_timeout_secs},
+            object!({
+                "type": "object",
+                "required": ["cell_source"],
+                "properties": {
+                    "cell_source": {
+                        "type": "string",
+                        "description": "The code of the cell to run"
+                    },
+                    "kernel_name": {
+                        "type": "string",
+                        "description": "Jupyter kernel to run the cell with, defaults to python3"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run a notebook cell".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            // Whether the cell reaches the network depends on its code
+            open_world_hint: None,
+        });
 
-                Your windows/screen tools can be used for visual debugging. You should not use these tools unless
-                prompted to, but you can mention they are available if they are relevant.
+        let lint_recipe_tool = Tool::new(
+            "lint_recipe",
+            indoc! {r#"
+                Check a goose recipe file (YAML or JSON) for problems before running it.
 
-                operating system: {os}
-                current directory: {cwd}
+                Reports errors that would stop the recipe from loading, like missing required keys,
+                undeclared template variables or invalid extensions, and warnings for likely mistakes
+                such as unknown keys or unused parameters. Each problem comes with its line number
+                and a suggested fix. Run it after creating or editing a recipe.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path to the recipe file"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Lint a recipe".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-                "#,
-                os=os,
-                cwd=cwd.to_string_lossy(),
-            },
-            _ => formatdoc! {r#"
-                The developer extension gives you the capabilities to edit code files and run shell commands,
-                and can be used to solve a wide range of problems.
-
-            You can use the shell tool to run any command that would work on the relevant operating system.
-            Use the shell tool as needed to locate files or interact with the project.
-
-            Your windows/screen tools can be used for visual debugging. You should not use these tools unless
-            prompted to, but you can mention they are available if they are relevant.
-
-            operating system: {os}
-            current directory: {cwd}
+        let profile_tool = Tool::new(
+            "profile",
+            indoc! {r#"
+                Run a shell command once and measure it, to find out where a program spends its time.
 
-                "#,
-                os=os,
-                cwd=cwd.to_string_lossy(),
-            },
-        };
+                Reports the wall time, CPU time (user and system) and peak memory of the command and
+                everything it starts, followed by the end of its output. On Linux the command runs under
+                `perf stat` when perf is installed and its counters are included; on macOS it is recorded
+                with the Instruments Time Profiler and the path of the trace is returned.
+                Use the shell tool for commands whose output you need in full.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["command"],
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The shell command to profile"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Profile a command".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            // Like shell commands, it depends on the command
+            open_world_hint: None,
+        });
 
-        // choose_app_strategy().config_dir()
-        // - macOS/Linux: ~/.config/goose/
-        // - Windows:     ~\AppData\Roaming\Block\goose\config\
-        // keep previous behavior of expanding ~/.config in case this fails
-        let global_hints_path = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_config_dir(".goosehints"))
-            .unwrap_or_else(|_| {
-                PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints").to_string())
-            });
+        let benchmark_tool = Tool::new(
+            "benchmark",
+            formatdoc! {r#"
+                Run a shell command several times in a row and report how long it takes.
+
+                Each run is timed from start to exit, and the mean, minimum, maximum and standard deviation
+                of the wall time are returned in milliseconds along with every run's time. Runs {default}
+                times unless `runs` is given, at most {max}. Output of the command is discarded and a run
+                that fails stops the benchmark. Use this rather than `time` in the shell tool to compare
+                the speed of commands, and profile to find out where a single run spends its time.
+            "#, default=benchmark::DEFAULT_RUNS, max=benchmark::MAX_RUNS},
+            object!({
+                "type": "object",
+                "required": ["command"],
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The shell command to time"
+                    },
+                    "runs": {
+                        "type": "integer",
+                        "description": "How many times to run the command"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Benchmark a command".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            // Like shell commands, it depends on the command
+            open_world_hint: None,
+        });
 
-        // Create the directory if it doesn't exist
-        let _ = std::fs::create_dir_all(global_hints_path.parent().unwrap());
+        let sql_query_tool = Tool::new(
+            "sql_query",
+            indoc! {r#"
+                Query a local SQLite database.
 
-        // Check for local hints in current directory
-        let local_hints_path = cwd.join(".goosehints");
+                The database is opened read-only, so only statements that read data (SELECT, WITH,
+                read-only PRAGMAs) succeed. Use `SELECT name, sql FROM sqlite_master` to list its tables.
+                Returns at most `limit` rows as a JSON array of objects keyed by column name.
+                Databases larger than 10 MB are refused.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["db_path", "query"],
+                "properties": {
+                    "db_path": {
+                        "type": "string",
+                        "description": "Absolute path to the SQLite database file"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "A single SQL statement to run"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of rows to return, defaults to 100"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Query a SQLite database".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-        // Read global hints if they exist
-        let mut hints = String::new();
-        if global_hints_path.is_file() {
-            if let Ok(global_hints) = std::fs::read_to_string(&global_hints_path) {
-                hints.push_str("\n### Global Hints\nThe developer extension includes some global hints that apply to all projects & directories.\n");
-                hints.push_str(&global_hints);
-            }
-        }
+        let csv_to_sql_tool = Tool::new(
+            "csv_to_sql",
+            indoc! {r#"
+                Import a CSV file into a table of a SQLite database, so it can be queried with sql_query.
 
-        // Read local hints if they exist
-        if local_hints_path.is_file() {
-            if let Ok(local_hints) = std::fs::read_to_string(&local_hints_path) {
-                if !hints.is_empty() {
-                    hints.push_str("\n\n");
+                The first row of the file names the columns. Each column gets the INTEGER, REAL or TEXT type
+                that fits all of its values, and empty cells become NULL. The table is named after the
+                file unless `table_name` is given, and must not exist yet. Without `db_path` a new
+                database is created in the temp directory. Returns the database path, the schema of the
+                new table and the number of rows imported.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["csv_path"],
+                "properties": {
+                    "csv_path": {
+                        "type": "string",
+                        "description": "Absolute path to the CSV file"
+                    },
+                    "db_path": {
+                        "type": "string",
+                        "description": "Absolute path to the SQLite database, created if it does not exist"
+                    },
+                    "table_name": {
+                        "type": "string",
+                        "description": "Name of the new table"
+                    }
                 }
-                hints.push_str("### Project Hints\nThe developer extension includes some hints for working on the project in this directory.\n");
-                hints.push_str(&local_hints);
-            }
-        }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Import CSV into SQLite".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
 
-        // Return base instructions directly when no hints are found
-        let instructions = if hints.is_empty() {
-            base_instructions
-        } else {
-            format!("{base_instructions}\n{hints}")
-        };
+        let run_tests_tool = Tool::new(
+            "run_tests",
+            indoc! {r#"
+                Run the test suite of a project and return a summary of the results as JSON.
 
-        let mut builder = GitignoreBuilder::new(cwd.clone());
-        let mut has_ignore_file = false;
-        // Initialize ignore patterns
-        // - macOS/Linux: ~/.config/goose/
-        // - Windows:     ~\AppData\Roaming\Block\goose\config\
-        let global_ignore_path = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_config_dir(".gooseignore"))
-            .unwrap_or_else(|_| {
-                PathBuf::from(shellexpand::tilde("~/.config/goose/.gooseignore").to_string())
-            });
+                The test runner is picked from the files at the root of `path`: `cargo test` for a
+                Cargo.toml, `npm test` for a package.json and `pytest` for a pytest.ini or a pyproject.toml
+                with pytest settings. The report has the total, passed and failed counts and the name and
+                message of every failed test. Use `filter` to only run tests whose name matches it, e.g.
+                after fixing a single failure.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path to the project directory, defaults to the working directory"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Only run the tests matching this name or pattern"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run the project's tests".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-        // Create the directory if it doesn't exist
-        let _ = std::fs::create_dir_all(global_ignore_path.parent().unwrap());
+        let generate_uuid_tool = Tool::new(
+            "generate_uuid",
+            indoc! {r#"
+                Generate one or more random UUIDs, returned one per line.
 
-        // Read global ignores if they exist
-        if global_ignore_path.is_file() {
-            let _ = builder.add(global_ignore_path);
-            has_ignore_file = true;
-        }
+                Use this instead of `uuidgen` when writing configuration, test data or database
+                records. Version `v4` is fully random, `v7` starts with a timestamp so the UUIDs sort by
+                creation time, which suits database keys.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "count": {
+                        "type": "integer",
+                        "description": "Number of UUIDs to generate, defaults to 1"
+                    },
+                    "version": {
+                        "type": "string",
+                        "enum": ["v4", "v7"],
+                        "description": "UUID version, defaults to v4"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Generate UUIDs".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
 
-        // Check for local ignores in current directory
-        let local_ignore_path = cwd.join(".gooseignore");
+        let env_inspect_tool = Tool::new(
+            "env_inspect",
+            indoc! {r#"
+                List the environment variables of the developer extension as a JSON object.
 
-        // Read local ignores if they exist
-        if local_ignore_path.is_file() {
-            let _ = builder.add(local_ignore_path);
-            has_ignore_file = true;
-        } else {
-            // If no .gooseignore exists, check for .gitignore as fallback
-            let gitignore_path = cwd.join(".gitignore");
-            if gitignore_path.is_file() {
-                tracing::debug!(
-                    "No .gooseignore found, using .gitignore as fallback for ignore patterns"
-                );
-                let _ = builder.add(gitignore_path);
-                has_ignore_file = true;
-            }
-        }
+                Use this rather than `env` in the shell when debugging configuration problems: variables
+                that look like secrets, such as names containing TOKEN, SECRET, PASSWORD, KEY or
+                CREDENTIAL, are left out unless they are listed in GOOSE_ENV_ALLOWLIST.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {}
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Inspect environment variables".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-        // Only use default patterns if no .gooseignore files were found
-        // AND no .gitignore was used as fallback
-        if !has_ignore_file {
-            // Add some sensible defaults
-            let _ = builder.add_line(None, "**/.env");
-            let _ = builder.add_line(None, "**/.env.*");
-            let _ = builder.add_line(None, "**/secrets.*");
-        }
+        let network_inspect_tool = Tool::new(
+            "network_inspect",
+            indoc! {r#"
+                Diagnose network connectivity, returning the result and its latency as JSON.
 
-        let ignore_patterns = builder.build().expect("Failed to build ignore patterns");
+                Commands:
+                - `dns_lookup`: resolve `hostname` to its addresses with the system resolver
+                - `port_check`: check whether a TCP connection to `host` on `port` is accepted, e.g. to
+                  see if a server came up
+                - `http_head`: send a HEAD request to `url` and report the status without following
+                  redirects
 
-        Self {
-            tools: vec![
-                bash_tool,
-                glob_tool,
-                grep_tool,
-                text_editor_tool,
-                list_windows_tool,
-                screen_capture_tool,
-                image_processor_tool,
-            ],
-            prompts: Arc::new(load_prompt_files()),
-            instructions,
-            file_history: Arc::new(Mutex::new(HashMap::new())),
-            ignore_patterns: Arc::new(ignore_patterns),
-            editor_model,
-        }
-    }
+                Each check gives up after `timeout_ms`. A failed check is reported in the result rather
+                than as an error.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["command"],
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "enum": ["dns_lookup", "port_check", "http_head"],
+                        "description": "The check to run"
+                    },
+                    "hostname": {
+                        "type": "string",
+                        "description": "Host name to resolve, for dns_lookup"
+                    },
+                    "host": {
+                        "type": "string",
+                        "description": "Host name or IP address to connect to, for port_check"
+                    },
+                    "port": {
+                        "type": "integer",
+                        "description": "TCP port to connect to, for port_check"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "http or https URL to request, for http_head"
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "How long to wait in milliseconds, defaults to 3000"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Inspect network connectivity".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(true),
+        });
 
-    // Helper method to check if a path should be ignored
-    fn is_ignored(&self, path: &Path) -> bool {
-        self.ignore_patterns.matched(path, false).is_ignore()
-    }
+        let dependency_graph_tool = Tool::new(
+            "dependency_graph",
+            indoc! {r#"
+                Show what the packages of a project depend on as a Graphviz DOT graph.
 
-    // Helper method to resolve a path relative to cwd with platform-specific handling
-    fn resolve_path(&self, path_str: &str) -> Result<PathBuf, ToolError> {
-        let cwd = std::env::current_dir().expect("should have a current working dir");
-        let expanded = expand_path(path_str);
-        let path = Path::new(&expanded);
+                The manifest at the root of `path` decides how dependencies are read: the workspace members
+                of a Cargo.toml, a package.json, or a requirements.txt. The project's own packages are
+                filled in, which shows how crates of a workspace depend on each other before refactoring
+                across them. Only direct dependencies are listed. When Graphviz is installed the graph is
+                also rendered to an SVG file and its path returned.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path to the project directory, defaults to the working directory"
+                    },
+                    "include_dev": {
+                        "type": "boolean",
+                        "description": "Include dev dependencies, defaults to false"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Graph the project's dependencies".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-        let suggestion = cwd.join(path);
+        let process_list_tool = Tool::new(
+            "process_list",
+            indoc! {r#"
+                List running processes as a JSON array of `{pid, name, cpu_percent, memory_mb, command}`,
+                busiest first and at most 50.
 
-        match is_absolute_path(&expanded) {
-            true => Ok(path.to_path_buf()),
-            false => Err(ToolError::InvalidParameters(format!(
-                "The path {} is not an absolute path, did you possibly mean {}?",
-                path_str,
-                suggestion.to_string_lossy(),
-            ))),
-        }
-    }
+                Use this rather than `ps` in the shell, e.g. to find a server that is still running or what
+                is using the CPU. `name_contains` keeps only processes whose name contains the text,
+                ignoring case.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "name_contains": {
+                        "type": "string",
+                        "description": "Only list processes whose name contains this text"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("List processes".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
 
-    // Shell command execution with platform-specific handling
-    async fn bash(
-        &self,
-        params: Value,
-        notifier: mpsc::Sender<JsonRpcMessage>,
-    ) -> Result<Vec<Content>, ToolError> {
-        let command =
-            params
-                .get("command")
-                .and_then(|v| v.as_str())
-                .ok_or(ToolError::InvalidParameters(
-                    "The command string is required".to_string(),
-                ))?;
+        let run_in_container_tool = Tool::new(
+            "run_in_container",
+            formatdoc! {r#"
+                Run a shell command inside a throwaway Docker container, removed when the command ends.
 
-        // Check if command might access ignored files and return early if it does
-        let cmd_parts: Vec<&str> = command.split_whitespace().collect();
-        for arg in &cmd_parts[1..] {
-            // Skip command flags
-            if arg.starts_with('-') {
-                continue;
-            }
-            // Skip invalid paths
-            let path = Path::new(arg);
-            if !path.exists() {
-                continue;
-            }
+                Use this rather than the shell tool for untrusted code, such as scripts downloaded from the
+                internet or code under review, so it cannot touch the host. The command runs with `sh -c`,
+                so the image needs a shell. Nothing is shared with the host unless it is mounted with
+                `volumes`, each written like `/host/path:/container/path` or with `:ro` at the end to mount
+                it read-only.
 
-            if self.is_ignored(path) {
-                return Err(ToolError::ExecutionError(format!(
-                    "The command attempts to access '{}' which is restricted by .gooseignore",
-                    arg
-                )));
-            }
-        }
+                Commands still running after {timeout} seconds are stopped along with their container.
+            "#, timeout=config.container_timeout_secs},
+            object!({
+                "type": "object",
+                "required": ["image", "command"],
+                "properties": {
+                    "image": {
+                        "type": "string",
+                        "description": "The image to run, e.g. `python:3.12-slim`"
+                    },
+                    "command": {
+                        "type": "string",
+                        "description": "The shell command to run in the container"
+                    },
+                    "volumes": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Host directories to mount, as `host:container[:ro]`"
+                    },
+                    "env": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Environment variables to set in the container"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run in a container".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(true),
+        });
 
-        // Get platform-specific shell configuration
-        let shell_config = get_shell_config();
+        let terminal_record_tool = Tool::new(
+            "terminal_record",
+            indoc! {r#"
+                Record a terminal session with asciinema, to show how a workflow is done.
 
-        // Execute the command using platform-specific shell
-        let mut child = Command::new(&shell_config.executable)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .stdin(Stdio::null())
-            .kill_on_drop(true)
-            .args(&shell_config.args)
-            .arg(command)
-            .spawn()
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                With `action` `start` (the default) the recording begins in the background and the path of
+                the `.cast` file is returned right away. It records `command` when one is given and ends
+                when that command exits, otherwise it records a shell until it is stopped. Call again with
+                `action` `stop` to end the recording and save the file. Only one recording runs at a time.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["start", "stop"],
+                        "description": "Start a new recording or stop the one in progress"
+                    },
+                    "command": {
+                        "type": "string",
+                        "description": "The command to record, a shell when omitted"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "Title stored in the recording"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Record the terminal".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
 
-        let stdout = child.stdout.take().unwrap();
-        let stderr = child.stderr.take().unwrap();
+        let terminal_replay_tool = Tool::new(
+            "terminal_replay",
+            indoc! {r#"
+                Play back an asciinema recording (`.cast` file), such as one made with terminal_record.
 
-        let mut stdout_reader = BufReader::new(stdout);
-        let mut stderr_reader = BufReader::new(stderr);
+                The output is streamed to the user as it plays, in real time unless `speed` is set. Long
+                pauses in the recording can be shortened with `idle_time_limit`.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the `.cast` file"
+                    },
+                    "speed": {
+                        "type": "number",
+                        "description": "Playback speed, 2 plays twice as fast"
+                    },
+                    "idle_time_limit": {
+                        "type": "number",
+                        "description": "Longest pause in seconds, longer ones are cut short"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Replay a terminal recording".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
 
-        let output_task = tokio::spawn(async move {
-            let mut combined_output = String::new();
+        let watch_directory_tool = Tool::new(
+            "watch_directory",
+            indoc! {r#"
+                Watch a directory and its subdirectories for files being created, modified or removed.
 
-            let mut stdout_buf = Vec::new();
-            let mut stderr_buf = Vec::new();
+                Waits for the full `timeout_secs` and then returns a summary of every file that changed,
+                e.g. to see which artifacts a build produced. Use `pattern` to only report files matching a
+                glob relative to the directory, like `*.o` or `dist/**/*.js`.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path", "timeout_secs"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path to the directory to watch"
+                    },
+                    "pattern": {
+                        "type": "string",
+                        "description": "Optional glob the changed file's path, relative to `path`, has to match"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "How long to watch for changes, in seconds"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Watch a directory".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
 
-            let mut stdout_done = false;
-            let mut stderr_done = false;
+        // Get base instructions and working directory
+        let cwd = std::env::current_dir().expect("should have a current working dir");
+        let os = std::env::consts::OS;
 
-            loop {
-                tokio::select! {
-                    n = stdout_reader.read_until(b'\n', &mut stdout_buf), if !stdout_done => {
-                        if n? == 0 {
-                            stdout_done = true;
-                        } else {
-                            let line = String::from_utf8_lossy(&stdout_buf);
+        let base_instructions = match os {
+            "windows" => formatdoc! {r#"
+                The developer extension gives you the capabilities to edit code files and run shell commands,
+                and can be used to solve a wide range of problems.
 
-                            notifier.try_send(JsonRpcMessage::Notification(JsonRpcNotification {
-                                jsonrpc: JsonRpcVersion2_0,
-                                notification: Notification {
-                                    method: "notifications/message".to_string(),
-                                    params: object!({
-                                        "level": "info",
-                                        "data": {
-                                            "type": "shell",
-                                            "stream": "stdout",
-                                            "output": line.to_string(),
-                                        }
-                                    }),
-                                    extensions: Default::default(),
-                                }
-                            })).ok();
+                You can use the shell tool to run Windows commands (PowerShell or CMD).
+                When using paths, you can use either backslashes or forward slashes.
 
-                            combined_output.push_str(&line);
-                            stdout_buf.clear();
-                        }
-                    }
+                Use the shell tool as needed to locate files or interact with the project.
 
-                    n = stderr_reader.read_until(b'\n', &mut stderr_buf), if !stderr_done => {
-                        if n? == 0 {
-                            stderr_done = true;
-                        } else {
-                            let line = String::from_utf8_lossy(&stderr_buf);
+                Your windows/screen tools can be used for visual debugging. You should not use these tools unless
+                prompted to, but you can mention they are available if they are relevant.
 
-                            notifier.try_send(JsonRpcMessage::Notification(JsonRpcNotification {
-                                jsonrpc: JsonRpcVersion2_0,
-                                notification: Notification {
-                                    method: "notifications/message".to_string(),
-                                    params: object!({
-                                        "level": "info",
-                                        "data": {
-                                            "type": "shell",
-                                            "stream": "stderr",
-                                            "output": line.to_string(),
-                                        }
-                                    }),
-                                    extensions: Default::default(),
-                                }
-                            })).ok();
+                operating system: {os}
+                current directory: {cwd}
 
-                            combined_output.push_str(&line);
-                            stderr_buf.clear();
-                        }
-                    }
+                "#,
+                os=os,
+                cwd=cwd.to_string_lossy(),
+            },
+            _ => formatdoc! {r#"
+                The developer extension gives you the capabilities to edit code files and run shell commands,
+                and can be used to solve a wide range of problems.
 
-                    else => break,
-                }
+            You can use the shell tool to run any command that would work on the relevant operating system.
+            Use the shell tool as needed to locate files or interact with the project.
 
-                if stdout_done && stderr_done {
-                    break;
-                }
-            }
-            Ok::<_, std::io::Error>(combined_output)
-        });
+            Your windows/screen tools can be used for visual debugging. You should not use these tools unless
+            prompted to, but you can mention they are available if they are relevant.
 
-        // Wait for the command to complete and get output
-        child
-            .wait()
-            .await
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            operating system: {os}
+            current directory: {cwd}
 
-        let output_str = match output_task.await {
-            Ok(result) => result.map_err(|e| ToolError::ExecutionError(e.to_string()))?,
-            Err(e) => return Err(ToolError::ExecutionError(e.to_string())),
+                "#,
+                os=os,
+                cwd=cwd.to_string_lossy(),
+            },
         };
 
-        // Check the character count of the output
-        const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
-        let char_count = output_str.chars().count();
-        if char_count > MAX_CHAR_COUNT {
-            return Err(ToolError::ExecutionError(format!(
-                    "Shell output from command '{}' has too many characters ({}). Maximum character count is {}.",
-                    command,
-                    char_count,
-                    MAX_CHAR_COUNT
-                )));
-        }
-
-        Ok(vec![
-            Content::text(output_str.clone()).with_audience(vec![Role::Assistant]),
-            Content::text(output_str)
-                .with_audience(vec![Role::User])
-                .with_priority(0.0),
-        ])
-    }
+        let global_hints_path = global_hints_path();
 
-    async fn glob(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let pattern =
-            params
-                .get("pattern")
-                .and_then(|v| v.as_str())
-                .ok_or(ToolError::InvalidParameters(
-                    "The pattern string is required".to_string(),
-                ))?;
+        // Create the directory if it doesn't exist
+        let _ = std::fs::create_dir_all(global_hints_path.parent().unwrap());
 
-        let search_path = params.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+        // Read hints if they exist, the global hints file keeps its own heading and hints from
+        // other directories name the directory they apply to
+        let mut hints = String::new();
+        for hints_path in &config.hints_paths {
+            if !hints_path.is_file() {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(hints_path) {
+                if *hints_path == global_hints_path {
+                    hints.push_str("\n### Global Hints\nThe developer extension includes some global hints that apply to all projects & directories.\n");
+                } else {
+                    if !hints.is_empty() {
+                        hints.push_str("\n\n");
+                    }
+                    match hints_path.parent() {
+                        Some(dir) if dir != cwd => hints.push_str(&format!(
+                            "### Project Hints ({})\nThe developer extension includes some hints for working on files under {}.\n",
+                            hints_path.display(),
+                            dir.display()
+                        )),
+                        _ => hints.push_str("### Project Hints\nThe developer extension includes some hints for working on the project in this directory.\n"),
+                    }
+                }
+                hints.push_str(&content);
+            }
+        }
 
-        let full_pattern = if search_path == "." {
-            pattern.to_string()
+        // Return base instructions directly when no hints are found
+        let instructions = if hints.is_empty() {
+            base_instructions
         } else {
-            format!("{}/{}", search_path.trim_end_matches('/'), pattern)
+            format!("{base_instructions}\n{hints}")
         };
 
-        let glob_result = glob::glob(&full_pattern)
-            .map_err(|e| ToolError::InvalidParameters(format!("Invalid glob pattern: {}", e)))?;
+        let mut builder = GitignoreBuilder::new(cwd.clone());
+        let mut has_ignore_file = false;
+        // Initialize ignore patterns
+        // - macOS/Linux: ~/.config/goose/
+        // - Windows:     ~\AppData\Roaming\Block\goose\config\
+        let global_ignore_path = choose_app_strategy(crate::APP_STRATEGY.clone())
+            .map(|strategy| strategy.in_config_dir(".gooseignore"))
+            .unwrap_or_else(|_| {
+                PathBuf::from(shellexpand::tilde("~/.config/goose/.gooseignore").to_string())
+            });
 
-        let mut file_paths_with_metadata = Vec::new();
+        // Create the directory if it doesn't exist
+        let _ = std::fs::create_dir_all(global_ignore_path.parent().unwrap());
 
-        for entry in glob_result {
-            match entry {
-                Ok(path) => {
-                    // Check if the path should be ignored
-                    if !self.is_ignored(&path) {
-                        // Get file metadata for sorting by modification time
-                        if let Ok(metadata) = std::fs::metadata(&path) {
-                            if metadata.is_file() {
-                                let modified = metadata
-                                    .modified()
-                                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-                                file_paths_with_metadata.push((path, modified));
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("Error reading glob entry: {}", e);
-                }
-            }
+        // Read global ignores if they exist
+        if global_ignore_path.is_file() {
+            let _ = builder.add(global_ignore_path);
+            has_ignore_file = true;
         }
 
-        // Sort by modification time (newest first)
-        file_paths_with_metadata.sort_by(|a, b| b.1.cmp(&a.1));
-
-        // Extract just the file paths
-        let file_paths: Vec<String> = file_paths_with_metadata
-            .into_iter()
-            .map(|(path, _)| path.to_string_lossy().to_string())
-            .collect();
+        // Check for local ignores in current directory
+        let local_ignore_path = cwd.join(".gooseignore");
 
-        let result = file_paths.join("\n");
+        // Read local ignores if they exist
+        if local_ignore_path.is_file() {
+            let _ = builder.add(local_ignore_path);
+            has_ignore_file = true;
+        } else {
+            // If no .gooseignore exists, check for .gitignore as fallback
+            let gitignore_path = cwd.join(".gitignore");
+            if gitignore_path.is_file() {
+                tracing::debug!(
+                    "No .gooseignore found, using .gitignore as fallback for ignore patterns"
+                );
+                let _ = builder.add(gitignore_path);
+                has_ignore_file = true;
+            }
+        }
 
-        Ok(vec![
-            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
-            Content::text(result)
-                .with_audience(vec![Role::User])
-                .with_priority(0.0),
-        ])
-    }
+        // Only use default patterns if no .gooseignore files were found
+        // AND no .gitignore was used as fallback
+        if !has_ignore_file {
+            // Add some sensible defaults
+            let _ = builder.add_line(None, "**/.env");
+            let _ = builder.add_line(None, "**/.env.*");
+            let _ = builder.add_line(None, "**/secrets.*");
+        }
 
-    async fn text_editor(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let command = params
-            .get("command")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                ToolError::InvalidParameters("Missing 'command' parameter".to_string())
-            })?;
+        let ignore_patterns = builder.build().expect("Failed to build ignore patterns");
 
-        let path_str = params
-            .get("path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let mut tools = vec![bash_tool, glob_tool, grep_tool, text_editor_tool];
+        if config.enable_screen_capture {
+            tools.push(list_windows_tool);
+            tools.push(screen_capture_tool);
+        }
+        if config.enable_image_processor {
+            tools.push(image_processor_tool);
+        }
+        if config.enable_clipboard_tools {
+            tools.push(clipboard_read_tool);
+            tools.push(clipboard_write_tool);
+        }
+        if config.enable_container_tool {
+            tools.push(run_in_container_tool);
+        }
+        if config.enable_terminal_recording {
+            tools.push(terminal_record_tool);
+            tools.push(terminal_replay_tool);
+        }
+        if config.enable_lint_recipe {
+            tools.push(lint_recipe_tool);
+        }
+        if config.enable_watch_directory {
+            tools.push(watch_directory_tool);
+        }
+        if config.enable_profiling_tools {
+            tools.push(profile_tool);
+            tools.push(benchmark_tool);
+        }
+        if config.enable_sql_tools {
+            tools.push(sql_query_tool);
+            tools.push(csv_to_sql_tool);
+        }
+        if config.enable_run_tests {
+            tools.push(run_tests_tool);
+        }
+        if config.enable_generate_uuid {
+            tools.push(generate_uuid_tool);
+        }
+        if config.enable_system_inspection {
+            tools.push(env_inspect_tool);
+            tools.push(network_inspect_tool);
+            tools.push(process_list_tool);
+        }
+        if config.enable_dependency_graph {
+            tools.push(dependency_graph_tool);
+        }
+        if cfg!(feature = "ocr") {
+            tools.push(image_ocr_tool);
+        }
+        if config.embedding_client.is_some() {
+            tools.push(semantic_search_tool);
+        }
+        if notebook::jupyter_available() {
+            tools.push(notebook_execute_tool);
+        }
 
-        let path = self.resolve_path(path_str)?;
+        Self {
+            tools,
+            prompts: Arc::new(load_prompt_files()),
+            instructions,
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(ignore_patterns),
+            config,
+            output_cache: Arc::new(Mutex::new(HashMap::new())),
+            plugins: HashMap::new(),
+            recording: Arc::new(Mutex::new(None)),
+        }
+    }
 
-        // Check if file is ignored before proceeding with any text editor operation
-        if self.is_ignored(&path) {
-            return Err(ToolError::ExecutionError(format!(
-                "Access to '{}' is restricted by .gooseignore",
-                path.display()
-            )));
+    /// Add a custom tool, it is listed after the built-in tools and calls to it are
+    /// handed to the plugin. Fails if a tool with the same name is already registered.
+    pub fn register_plugin(&mut self, plugin: Box<dyn ToolPlugin + Send + Sync>) -> Result<()> {
+        let tool = plugin.tool_definition();
+        if self.tools.iter().any(|t| t.name == tool.name) {
+            return Err(anyhow::anyhow!(
+                "A tool named '{}' is already registered",
+                tool.name
+            ));
         }
+        self.plugins
+            .insert(tool.name.to_string(), Arc::from(plugin));
+        self.tools.push(tool);
+        Ok(())
+    }
 
-        match command {
-            "view" => {
-                let view_range = params
-                    .get("view_range")
-                    .and_then(|v| v.as_array())
-                    .and_then(|arr| {
-                        if arr.len() == 2 {
-                            let start = arr[0].as_i64().unwrap_or(1) as usize;
-                            let end = arr[1].as_i64().unwrap_or(-1);
-                            Some((start, end))
-                        } else {
-                            None
-                        }
-                    });
-                self.text_editor_view(&path, view_range).await
-            }
-            "write" => {
-                let file_text = params
-                    .get("file_text")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'file_text' parameter".into())
-                    })?;
+    // Helper method to check if a path should be ignored
+    fn is_ignored(&self, path: &Path) -> bool {
+        self.ignore_patterns.matched(path, false).is_ignore()
+    }
 
-                self.text_editor_write(&path, file_text).await
-            }
-            "str_replace" | "edit_file" => {
-                let old_str = params
-                    .get("old_str")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'old_str' parameter".into())
-                    })?;
-                let new_str = params
-                    .get("new_str")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'new_str' parameter".into())
-                    })?;
+    // Helper method to resolve a path relative to cwd with platform-specific handling
+    fn resolve_path(&self, path_str: &str) -> Result<PathBuf, ToolError> {
+        let cwd = std::env::current_dir().expect("should have a current working dir");
+        let expanded = expand_path(path_str);
+        let path = Path::new(&expanded);
 
-                self.text_editor_replace(&path, old_str, new_str).await
-            }
-            "insert" => {
-                let insert_line = params
-                    .get("insert_line")
-                    .and_then(|v| v.as_i64())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'insert_line' parameter".into())
-                    })? as usize;
-                let new_str = params
-                    .get("new_str")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'new_str' parameter".into())
-                    })?;
+        let suggestion = cwd.join(path);
 
-                self.text_editor_insert(&path, insert_line, new_str).await
-            }
-            "undo_edit" => self.text_editor_undo(&path).await,
-            _ => Err(ToolError::InvalidParameters(format!(
-                "Unknown command '{}'",
-                command
+        match is_absolute_path(&expanded) {
+            true => Ok(path.to_path_buf()),
+            false => Err(ToolError::InvalidParameters(format!(
+                "The path {} is not an absolute path, did you possibly mean {}?",
+                path_str,
+                suggestion.to_string_lossy(),
             ))),
         }
     }
 
-    async fn text_editor_view(
+    // Shell command execution with platform-specific handling
+    async fn bash(
         &self,
-        path: &PathBuf,
-        view_range: Option<(usize, i64)>,
+        params: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+        cancel_token: CancellationToken,
     ) -> Result<Vec<Content>, ToolError> {
-        if path.is_file() {
-            // Check file size first (400KB limit)
-            const MAX_FILE_SIZE: u64 = 400 * 1024; // 400KB in bytes
-            const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
+        let command =
+            params
+                .get("command")
+                .and_then(|v| v.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The command string is required".to_string(),
+                ))?;
 
-            let file_size = std::fs::metadata(path)
-                .map_err(|e| {
-                    ToolError::ExecutionError(format!("Failed to get file metadata: {}", e))
-                })?
-                .len();
+        // Check if command might access ignored files and return early if it does
+        let cmd_parts: Vec<&str> = command.split_whitespace().collect();
+        for arg in &cmd_parts[1..] {
+            // Skip command flags
+            if arg.starts_with('-') {
+                continue;
+            }
+            // Skip invalid paths
+            let path = Path::new(arg);
+            if !path.exists() {
+                continue;
+            }
 
-            if file_size > MAX_FILE_SIZE {
+            if self.is_ignored(path) {
                 return Err(ToolError::ExecutionError(format!(
-                    "File '{}' is too large ({:.2}KB). Maximum size is 400KB to prevent memory issues.",
-                    path.display(),
-                    file_size as f64 / 1024.0
+                    "The command attempts to access '{}' which is restricted by .gooseignore",
+                    arg
                 )));
             }
+        }
 
-            let uri = Url::from_file_path(path)
-                .map_err(|_| ToolError::ExecutionError("Invalid file path".into()))?
-                .to_string();
+        // Reuse recent output of read-only commands, any other command may change what they
+        // report so it drops everything cached so far
+        let cache_ttl = Duration::from_secs(self.config.shell_cache_ttl_secs);
+        let cacheable = !cache_ttl.is_zero() && is_idempotent_command(command);
+        let cache_key = (
+            std::env::current_dir().unwrap_or_default(),
+            command.to_string(),
+        );
+        if cacheable {
+            if let Some((cached_at, output)) = self.output_cache.lock().unwrap().get(&cache_key) {
+                if cached_at.elapsed() < cache_ttl {
+                    return Ok(vec![
+                        Content::text(output.clone()).with_audience(vec![Role::Assistant]),
+                        Content::text(output.clone())
+                            .with_audience(vec![Role::User])
+                            .with_priority(0.0),
+                    ]);
+                }
+            }
+        } else {
+            self.output_cache.lock().unwrap().clear();
+        }
 
-            let content = std::fs::read_to_string(path)
-                .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        // Get platform-specific shell configuration
+        let shell_config = get_shell_config();
 
-            let char_count = content.chars().count();
-            if char_count > MAX_CHAR_COUNT {
-                return Err(ToolError::ExecutionError(format!(
-                    "File '{}' has too many characters ({}). Maximum character count is {}.",
-                    path.display(),
-                    char_count,
-                    MAX_CHAR_COUNT
-                )));
-            }
+        // Execute the command using platform-specific shell
+        let mut child = Command::new(&shell_config.executable)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .args(&shell_config.args)
+            .arg(command)
+            .spawn()
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
 
-            let lines: Vec<&str> = content.lines().collect();
-            let total_lines = lines.len();
+        let stdout = child.stdout.take().unwrap();
+        let stderr = child.stderr.take().unwrap();
 
-            // Handle view_range if provided, otherwise show all lines
-            let (start_idx, end_idx) = if let Some((start_line, end_line)) = view_range {
-                // Convert 1-indexed line numbers to 0-indexed
-                let start_idx = if start_line > 0 { start_line - 1 } else { 0 };
-                let end_idx = if end_line == -1 {
-                    total_lines
-                } else {
-                    std::cmp::min(end_line as usize, total_lines)
-                };
+        let mut stdout_reader = BufReader::new(stdout);
+        let mut stderr_reader = BufReader::new(stderr);
 
-                if start_idx >= total_lines {
-                    return Err(ToolError::InvalidParameters(format!(
-                        "Start line {} is beyond the end of the file (total lines: {})",
-                        start_line, total_lines
-                    )));
-                }
+        let output_task = tokio::spawn(async move {
+            let mut combined_output = String::new();
 
-                if start_idx >= end_idx {
-                    return Err(ToolError::InvalidParameters(format!(
-                        "Start line {} must be less than end line {}",
-                        start_line, end_line
-                    )));
-                }
+            let mut stdout_buf = Vec::new();
+            let mut stderr_buf = Vec::new();
 
-                (start_idx, end_idx)
-            } else {
-                (0, total_lines)
-            };
+            let mut stdout_done = false;
+            let mut stderr_done = false;
 
-            // Always format lines with line numbers for better usability
-            let display_content = if total_lines == 0 {
-                String::new()
-            } else {
-                let selected_lines: Vec<String> = lines[start_idx..end_idx]
-                    .iter()
-                    .enumerate()
-                    .map(|(i, line)| format!("{}: {}", start_idx + i + 1, line))
-                    .collect();
+            loop {
+                tokio::select! {
+                    n = stdout_reader.read_until(b'\n', &mut stdout_buf), if !stdout_done => {
+                        if n? == 0 {
+                            stdout_done = true;
+                        } else {
+                            let line = String::from_utf8_lossy(&stdout_buf);
 
-                selected_lines.join("\n")
-            };
+                            notifier.try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                                jsonrpc: JsonRpcVersion2_0,
+                                notification: Notification {
+                                    method: "notifications/message".to_string(),
+                                    params: object!({
+                                        "level": "info",
+                                        "data": {
+                                            "type": "shell",
+                                            "stream": "stdout",
+                                            "output": line.to_string(),
+                                        }
+                                    }),
+                                    extensions: Default::default(),
+                                }
+                            })).ok();
 
-            let language = lang::get_language_identifier(path);
-            let formatted = if view_range.is_some() {
-                formatdoc! {"
-                    ### {path} (lines {start}-{end})
-                    ```{language}
-                    {content}
-                    ```
-                    ",
-                    path=path.display(),
-                    start=view_range.unwrap().0,
-                    end=if view_range.unwrap().1 == -1 { "end".to_string() } else { view_range.unwrap().1.to_string() },
-                    language=language,
-                    content=display_content,
+                            combined_output.push_str(&line);
+                            stdout_buf.clear();
+                        }
+                    }
+
+                    n = stderr_reader.read_until(b'\n', &mut stderr_buf), if !stderr_done => {
+                        if n? == 0 {
+                            stderr_done = true;
+                        } else {
+                            let line = String::from_utf8_lossy(&stderr_buf);
+
+                            notifier.try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                                jsonrpc: JsonRpcVersion2_0,
+                                notification: Notification {
+                                    method: "notifications/message".to_string(),
+                                    params: object!({
+                                        "level": "info",
+                                        "data": {
+                                            "type": "shell",
+                                            "stream": "stderr",
+                                            "output": line.to_string(),
+                                        }
+                                    }),
+                                    extensions: Default::default(),
+                                }
+                            })).ok();
+
+                            combined_output.push_str(&line);
+                            stderr_buf.clear();
+                        }
+                    }
+
+                    else => break,
                 }
-            } else {
-                formatdoc! {"
-                    ### {path}
-                    ```{language}
-                    {content}
-                    ```
-                    ",
-                    path=path.display(),
-                    language=language,
-                    content=display_content,
+
+                if stdout_done && stderr_done {
+                    break;
                 }
-            };
+            }
+            Ok::<_, std::io::Error>(combined_output)
+        });
 
-            // The LLM gets just a quick update as we expect the file to view in the status
-            // but we send a low priority message for the human
-            Ok(vec![
-                Content::embedded_text(uri, content).with_audience(vec![Role::Assistant]),
-                Content::text(formatted)
-                    .with_audience(vec![Role::User])
-                    .with_priority(0.0),
-            ])
-        } else {
-            Err(ToolError::ExecutionError(format!(
-                "The path '{}' does not exist or is not a file.",
-                path.display()
-            )))
+        // Wait for the command to complete and get output, the child is killed on drop
+        // if it runs past the configured timeout
+        let wait = async {
+            match self.config.shell_timeout_secs {
+                Some(secs) => tokio::time::timeout(Duration::from_secs(secs), child.wait())
+                    .await
+                    .map_err(|_| {
+                        ToolError::ExecutionError(format!(
+                            "Command '{}' timed out after {} seconds",
+                            command, secs
+                        ))
+                    })?,
+                None => child.wait().await,
+            }
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))
+        };
+        tokio::select! {
+            status = wait => {
+                status?;
+            }
+            _ = cancel_token.cancelled() => {
+                child.kill().await.ok();
+                output_task.abort();
+                return Err(ToolError::ExecutionError(format!(
+                    "Command '{}' was cancelled",
+                    command
+                )));
+            }
         }
-    }
 
-    async fn text_editor_write(
-        &self,
-        path: &PathBuf,
-        file_text: &str,
-    ) -> Result<Vec<Content>, ToolError> {
-        // Normalize line endings based on platform
-        let mut normalized_text = normalize_line_endings(file_text); // Make mutable
+        let output_str = match output_task.await {
+            Ok(result) => result.map_err(|e| ToolError::ExecutionError(e.to_string()))?,
+            Err(e) => return Err(ToolError::ExecutionError(e.to_string())),
+        };
 
-        // Ensure the text ends with a newline
-        if !normalized_text.ends_with('\n') {
-            normalized_text.push('\n');
+        // Check the character count of the output
+        let max_char_count = self.config.max_shell_output_chars;
+        let char_count = output_str.chars().count();
+        if char_count > max_char_count {
+            return Err(ToolError::ExecutionError(format!(
+                    "Shell output from command '{}' has too many characters ({}). Maximum character count is {}.",
+                    command,
+                    char_count,
+                    max_char_count
+                )));
         }
 
-        // Write to the file
-        std::fs::write(path, &normalized_text) // Write the potentially modified text
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
-
-        // Try to detect the language from the file extension
-        let language = lang::get_language_identifier(path);
+        if cacheable {
+            self.output_cache
+                .lock()
+                .unwrap()
+                .insert(cache_key, (Instant::now(), output_str.clone()));
+        }
 
-        // The assistant output does not show the file again because the content is already in the tool request
-        // but we do show it to the user here, using the final written content
         Ok(vec![
-            Content::text(format!("Successfully wrote to {}", path.display()))
-                .with_audience(vec![Role::Assistant]),
-            Content::text(formatdoc! {
-                r#"
-                ### {path}
-                ```{language}
-                {content}
-                ```
-                "#,
-                path=path.display(),
-                language=language,
-                content=&normalized_text // Use the final normalized_text for user feedback
-            })
-            .with_audience(vec![Role::User])
-            .with_priority(0.2),
+            Content::text(output_str.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(output_str)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
         ])
     }
 
-    async fn text_editor_replace(
+    // Measure a command with the platform profiler, stopped like shell commands when it runs past
+    // the configured timeout or the call is cancelled
+    async fn profile(
         &self,
-        path: &PathBuf,
-        old_str: &str,
-        new_str: &str,
+        params: Value,
+        cancel_token: CancellationToken,
     ) -> Result<Vec<Content>, ToolError> {
-        // Check if file exists and is active
-        if !path.exists() {
+        let command = params
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("The command string is required".to_string())
+            })?;
+
+        let timeout = self.config.shell_timeout_secs.map(Duration::from_secs);
+        let report = profile::profile(command, timeout, &cancel_token)
+            .await
+            .map_err(ToolError::ExecutionError)?
+            .format(command);
+
+        Ok(vec![
+            Content::text(report.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(report)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn benchmark(
+        &self,
+        params: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<Vec<Content>, ToolError> {
+        let command = params
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("The command string is required".to_string())
+            })?;
+        let runs = match params.get("runs") {
+            None | Some(Value::Null) => benchmark::DEFAULT_RUNS,
+            Some(value) => match value.as_u64() {
+                Some(runs) if (1..=benchmark::MAX_RUNS as u64).contains(&runs) => runs as u32,
+                _ => {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "'runs' must be a whole number from 1 to {}",
+                        benchmark::MAX_RUNS
+                    )))
+                }
+            },
+        };
+
+        let timeout = self.config.shell_timeout_secs.map(Duration::from_secs);
+        let result = benchmark::run(command, runs, timeout, &cancel_token)
+            .await
+            .map_err(ToolError::ExecutionError)?;
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        Ok(vec![
+            Content::text(json).with_audience(vec![Role::Assistant]),
+            Content::text(result.summary())
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn image_ocr(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let lang = params
+            .get("lang")
+            .and_then(|v| v.as_str())
+            .unwrap_or("eng")
+            .to_string();
+        if !ocr::is_valid_language(&lang) {
             return Err(ToolError::InvalidParameters(format!(
-                "File '{}' does not exist, you can write a new file with the `write` command",
+                "Invalid language '{}', expected Tesseract language codes like 'eng' or 'eng+deu'",
+                lang
+            )));
+        }
+        let path = self.resolve_path(path_str)?;
+
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+        if !path.exists() {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' does not exist",
                 path.display()
             )));
         }
+        check_image_file_size(&path)?;
 
-        // Read content
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let image = image::open(&path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to open image file: {}", e)))?;
+        let text = tokio::task::spawn_blocking(move || ocr::recognize(&ocr::prepare(image), &lang))
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+            .map_err(ToolError::ExecutionError)?;
 
-        // Check if Editor API is configured and use it as the primary path
-        if let Some(ref editor) = self.editor_model {
-            // Editor API path - save history then call API directly
-            self.save_file_history(path)?;
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(vec![Content::text(format!(
+                "No text was found in {}",
+                path.display()
+            ))]);
+        }
+        Ok(vec![Content::text(text.to_string())])
+    }
 
-            match editor.edit_code(&content, old_str, new_str).await {
-                Ok(updated_content) => {
-                    // Write the updated content directly
-                    let normalized_content = normalize_line_endings(&updated_content);
-                    std::fs::write(path, &normalized_content).map_err(|e| {
-                        ToolError::ExecutionError(format!("Failed to write file: {}", e))
-                    })?;
+    // Detect the project's test runner and run it, stopped like shell commands when it runs past
+    // the configured timeout or the call is cancelled
+    async fn run_tests(
+        &self,
+        params: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<Vec<Content>, ToolError> {
+        let dir = match params.get("path").and_then(|v| v.as_str()) {
+            Some(path) => self.resolve_path(path)?,
+            None => std::env::current_dir().map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to get the working directory: {}", e))
+            })?,
+        };
+        let filter = params.get("filter").and_then(|v| v.as_str());
+        if !dir.is_dir() {
+            return Err(ToolError::InvalidParameters(format!(
+                "'{}' is not a directory",
+                dir.display()
+            )));
+        }
+        let runner = test_runner::TestRunner::detect(&dir).ok_or_else(|| {
+            ToolError::ExecutionError(format!(
+                "No Cargo.toml, package.json, pytest.ini or pytest configuration in pyproject.toml found in '{}', run the tests with the shell tool instead",
+                dir.display()
+            ))
+        })?;
 
-                    // Simple success message for Editor API
-                    return Ok(vec![
-                        Content::text(format!("Successfully edited {}", path.display()))
-                            .with_audience(vec![Role::Assistant]),
-                        Content::text(format!("File {} has been edited", path.display()))
-                            .with_audience(vec![Role::User])
-                            .with_priority(0.2),
-                    ]);
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Editor API call failed: {}, falling back to string replacement",
-                        e
-                    );
-                    // Fall through to traditional path below
-                }
+        let timeout = self.config.shell_timeout_secs.map(Duration::from_secs);
+        let report = test_runner::run(runner, &dir, filter, timeout, &cancel_token)
+            .await
+            .map_err(ToolError::ExecutionError)?;
+        let summary = format!(
+            "{} passed, {} failed ({} total) with `{}`",
+            report.passed, report.failed, report.total, report.command
+        );
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        Ok(vec![
+            Content::text(json).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn generate_uuid(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        const MAX_COUNT: u64 = 1000;
+
+        let count = params.get("count").and_then(|v| v.as_u64()).unwrap_or(1);
+        if count == 0 || count > MAX_COUNT {
+            return Err(ToolError::InvalidParameters(format!(
+                "'count' must be between 1 and {}",
+                MAX_COUNT
+            )));
+        }
+        let generate = match params.get("version").and_then(|v| v.as_str()) {
+            None | Some("v4") => uuid::Uuid::new_v4,
+            Some("v7") => uuid::Uuid::now_v7,
+            Some(other) => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Unsupported UUID version '{}', use 'v4' or 'v7'",
+                    other
+                )))
+            }
+        };
+
+        let uuids: Vec<String> = (0..count).map(|_| generate().to_string()).collect();
+        Ok(vec![Content::text(uuids.join("\n"))])
+    }
+
+    async fn env_inspect(&self) -> Result<Vec<Content>, ToolError> {
+        let patterns = self
+            .config
+            .sensitive_env_patterns
+            .iter()
+            .map(|pattern| glob::Pattern::new(&pattern.to_uppercase()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ToolError::ExecutionError(format!("Invalid sensitive pattern: {}", e)))?;
+
+        let mut variables = serde_json::Map::new();
+        let mut hidden = 0;
+        for (name, value) in std::env::vars_os() {
+            let name = name.to_string_lossy().into_owned();
+            let allowed = self.config.env_allowlist.contains(&name);
+            let upper = name.to_uppercase();
+            if !allowed && patterns.iter().any(|pattern| pattern.matches(&upper)) {
+                hidden += 1;
+                continue;
             }
+            variables.insert(name, Value::String(value.to_string_lossy().into_owned()));
         }
 
-        // Traditional string replacement path (original logic)
-        // Ensure 'old_str' appears exactly once
-        if content.matches(old_str).count() > 1 {
-            return Err(ToolError::InvalidParameters(
-                "'old_str' must appear exactly once in the file, but it appears multiple times"
-                    .into(),
+        let mut text = serde_json::to_string_pretty(&variables)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        if hidden > 0 {
+            text.push_str(&format!(
+                "\n\n{} variable{} hidden because the name looks sensitive",
+                hidden,
+                if hidden == 1 { " was" } else { "s were" }
             ));
         }
-        if content.matches(old_str).count() == 0 {
-            return Err(ToolError::InvalidParameters(
-                "'old_str' must appear exactly once in the file, but it does not appear in the file. Make sure the string exactly matches existing file content, including whitespace!".into(),
-            ));
+        Ok(vec![Content::text(text)])
+    }
+
+    async fn network_inspect(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        const DEFAULT_TIMEOUT_MS: u64 = 3000;
+
+        let string_param = |name: &str| {
+            params.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+                ToolError::InvalidParameters(format!("Missing '{}' parameter", name))
+            })
+        };
+        let command = string_param("command")?;
+        let timeout = Duration::from_millis(
+            params
+                .get("timeout_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_TIMEOUT_MS),
+        );
+
+        let result = match command {
+            "dns_lookup" => network::dns_lookup(string_param("hostname")?, timeout).await,
+            "port_check" => {
+                let port = params
+                    .get("port")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|p| u16::try_from(p).ok())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters(
+                            "'port' must be a number between 0 and 65535".into(),
+                        )
+                    })?;
+                network::port_check(string_param("host")?, port, timeout).await
+            }
+            "http_head" => {
+                let url = string_param("url")?;
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "'{}' is not an http or https URL",
+                        url
+                    )));
+                }
+                network::http_head(url, timeout)
+                    .await
+                    .map_err(ToolError::ExecutionError)?
+            }
+            other => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Unknown command '{}', use dns_lookup, port_check or http_head",
+                    other
+                )))
+            }
+        };
+
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        Ok(vec![Content::text(json)])
+    }
+
+    async fn process_list(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let name_contains = params
+            .get("name_contains")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let processes =
+            tokio::task::spawn_blocking(move || processes::list(name_contains.as_deref()))
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let text = serde_json::to_string_pretty(&processes)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        Ok(vec![Content::text(text)])
+    }
+
+    async fn dependency_graph(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let dir = match params.get("path").and_then(|v| v.as_str()) {
+            Some(path) => self.resolve_path(path)?,
+            None => std::env::current_dir().map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to get the working directory: {}", e))
+            })?,
+        };
+        let include_dev = params
+            .get("include_dev")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !dir.is_dir() {
+            return Err(ToolError::InvalidParameters(format!(
+                "'{}' is not a directory",
+                dir.display()
+            )));
         }
+        if self.is_ignored(&dir) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                dir.display()
+            )));
+        }
+        let kind = dependency_graph::ProjectKind::detect(&dir).ok_or_else(|| {
+            ToolError::ExecutionError(format!(
+                "No Cargo.toml, package.json or requirements.txt found in '{}'",
+                dir.display()
+            ))
+        })?;
 
-        // Save history for undo (original behavior - after validation)
-        self.save_file_history(path)?;
+        // cargo metadata runs cargo and waits for it
+        let graph_dir = dir.clone();
+        let graph = tokio::task::spawn_blocking(move || kind.graph(&graph_dir, include_dev))
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+            .map_err(ToolError::ExecutionError)?;
+        let dot = graph.to_dot();
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let svg = dependency_graph::render_svg(&dot, &name)
+            .await
+            .map_err(ToolError::ExecutionError)?;
+
+        let summary = format!(
+            "{} package{} with {} direct dependenc{}{}",
+            graph.packages.len(),
+            if graph.packages.len() == 1 { "" } else { "s" },
+            graph.edges.len(),
+            if graph.edges.len() == 1 { "y" } else { "ies" },
+            svg.as_ref()
+                .map(|path| format!(", rendered to {}", path.display()))
+                .unwrap_or_default()
+        );
+        Ok(vec![
+            Content::text(format!("{}\n\n{}", summary, dot)).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
 
-        let new_content = content.replace(old_str, new_str);
-        let normalized_content = normalize_line_endings(&new_content);
-        std::fs::write(path, &normalized_content)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+    // Runs the command in a container removed afterwards, output is streamed as it arrives and the
+    // container is stopped when it runs past the configured timeout or the call is cancelled
+    async fn run_in_container(
+        &self,
+        params: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+        cancel_token: CancellationToken,
+    ) -> Result<Vec<Content>, ToolError> {
+        let string_param = |name: &str| {
+            params.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+                ToolError::InvalidParameters(format!("Missing '{}' parameter", name))
+            })
+        };
+        let image = string_param("image")?;
+        let command = string_param("command")?;
+        // Anything starting with a dash would be read by docker as one of its own options
+        if image.is_empty() || image.starts_with('-') {
+            return Err(ToolError::InvalidParameters(format!(
+                "'{}' is not a valid image name",
+                image
+            )));
+        }
 
-        // Try to detect the language from the file extension
-        let language = lang::get_language_identifier(path);
+        let volumes: Vec<String> = match params.get("volumes") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+                ToolError::InvalidParameters("'volumes' must be an array of strings".into())
+            })?,
+        };
+        for volume in &volumes {
+            let host = volume.split(':').next().unwrap_or_default();
+            if self.is_ignored(Path::new(host)) {
+                return Err(ToolError::ExecutionError(format!(
+                    "Mounting '{}' is restricted by .gooseignore",
+                    host
+                )));
+            }
+        }
+        let env: BTreeMap<String, String> = match params.get("env") {
+            None | Some(Value::Null) => BTreeMap::new(),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+                ToolError::InvalidParameters("'env' must map names to string values".into())
+            })?,
+        };
+        if let Some(name) = env
+            .keys()
+            .find(|name| name.is_empty() || name.contains('='))
+        {
+            return Err(ToolError::InvalidParameters(format!(
+                "'{}' is not a valid environment variable name",
+                name
+            )));
+        }
+
+        let name = container::container_name();
+        let env_names: Vec<String> = env.keys().cloned().collect();
+        let mut child = Command::new("docker")
+            .args(container::run_args(
+                &name, image, command, &volumes, &env_names,
+            ))
+            .envs(&env)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to start docker, is it installed? {}", e))
+            })?;
+
+        let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+        let mut stderr = BufReader::new(child.stderr.take().unwrap()).lines();
+        let output_task = tokio::spawn(async move {
+            let mut output = String::new();
+            let (mut stdout_done, mut stderr_done) = (false, false);
+            while !(stdout_done && stderr_done) {
+                let (stream, line) = tokio::select! {
+                    line = stdout.next_line(), if !stdout_done => ("stdout", line?),
+                    line = stderr.next_line(), if !stderr_done => ("stderr", line?),
+                };
+                let Some(line) = line else {
+                    match stream {
+                        "stdout" => stdout_done = true,
+                        _ => stderr_done = true,
+                    }
+                    continue;
+                };
+                notifier
+                    .try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                        jsonrpc: JsonRpcVersion2_0,
+                        notification: Notification {
+                            method: "notifications/message".to_string(),
+                            params: object!({
+                                "level": "info",
+                                "data": {
+                                    "type": "container",
+                                    "stream": stream,
+                                    "output": format!("{}\n", line),
+                                }
+                            }),
+                            extensions: Default::default(),
+                        },
+                    }))
+                    .ok();
+                output.push_str(&line);
+                output.push('\n');
+            }
+            Ok::<_, std::io::Error>(output)
+        });
+
+        let timeout_secs = self.config.container_timeout_secs;
+        let stopped = tokio::select! {
+            status = child.wait() => match status {
+                Ok(status) => Ok(status),
+                Err(e) => Err(e.to_string()),
+            },
+            _ = tokio::time::sleep(Duration::from_secs(timeout_secs)) => {
+                Err(format!("timed out after {} seconds", timeout_secs))
+            }
+            _ = cancel_token.cancelled() => Err("was cancelled".to_string()),
+        };
+        let status = match stopped {
+            Ok(status) => status,
+            Err(reason) => {
+                container::kill(&name).await;
+                child.kill().await.ok();
+                output_task.abort();
+                return Err(ToolError::ExecutionError(format!(
+                    "Command '{}' in {} {}",
+                    command, image, reason
+                )));
+            }
+        };
+
+        let mut output = match output_task.await {
+            Ok(result) => result.map_err(|e| ToolError::ExecutionError(e.to_string()))?,
+            Err(e) => return Err(ToolError::ExecutionError(e.to_string())),
+        };
+        let max_char_count = self.config.max_shell_output_chars;
+        let char_count = output.chars().count();
+        if char_count > max_char_count {
+            return Err(ToolError::ExecutionError(format!(
+                "Output from command '{}' has too many characters ({}). Maximum character count is {}.",
+                command, char_count, max_char_count
+            )));
+        }
+        if !status.success() {
+            output.push_str(&format!("\nExited with {}", status));
+        }
+
+        Ok(vec![
+            Content::text(output.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    // Starts asciinema in the background and returns right away, the recording runs until its
+    // command exits or it is stopped with the `stop` action
+    async fn terminal_record(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let action = params.get("action").and_then(|v| v.as_str());
+        match action.unwrap_or("start") {
+            "start" => {}
+            "stop" => {
+                let recording = self.recording.lock().unwrap().take().ok_or_else(|| {
+                    ToolError::ExecutionError("No terminal recording is in progress".into())
+                })?;
+                let path = terminal::stop(recording).await;
+                return Ok(vec![Content::text(format!(
+                    "Saved the recording to {}",
+                    path.display()
+                ))]);
+            }
+            other => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Unknown action '{}', expected 'start' or 'stop'",
+                    other
+                )))
+            }
+        }
+        let command = params.get("command").and_then(|v| v.as_str());
+        let title = params.get("title").and_then(|v| v.as_str());
+        let dir = std::env::current_dir().map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to get the working directory: {}", e))
+        })?;
+
+        let mut recording = self.recording.lock().unwrap();
+        if let Some(current) = recording.as_mut() {
+            if matches!(current.child.try_wait(), Ok(None)) {
+                return Err(ToolError::ExecutionError(format!(
+                    "Already recording to {}, stop that recording first",
+                    current.path.display()
+                )));
+            }
+        }
+        let path = terminal::recording_path(&dir);
+        let child = Command::new("asciinema")
+            .args(terminal::record_args(&path, command, title))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to start asciinema, is it installed? {}",
+                    e
+                ))
+            })?;
+        *recording = Some(terminal::Recording {
+            child,
+            path: path.clone(),
+        });
+
+        let ends = match command {
+            Some(command) => format!("It ends when '{}' exits.", command),
+            None => "Call terminal_record with action 'stop' to end it.".to_string(),
+        };
+        Ok(vec![Content::text(format!(
+            "Recording the terminal to {}. {}",
+            path.display(),
+            ends
+        ))])
+    }
+
+    // Output is streamed as it plays rather than returned, it is mostly terminal escape codes
+    async fn terminal_replay(
+        &self,
+        params: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+        cancel_token: CancellationToken,
+    ) -> Result<Vec<Content>, ToolError> {
+        use tokio::io::AsyncReadExt;
+
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let path = self.resolve_path(path)?;
+        if path.extension().and_then(|e| e.to_str()) != Some("cast") {
+            return Err(ToolError::InvalidParameters(format!(
+                "'{}' is not an asciinema recording (.cast)",
+                path.display()
+            )));
+        }
+        if !path.is_file() {
+            return Err(ToolError::InvalidParameters(format!(
+                "'{}' does not exist",
+                path.display()
+            )));
+        }
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+        let positive_param = |name: &str| match params.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => match value.as_f64() {
+                Some(number) if number > 0.0 => Ok(Some(number)),
+                _ => Err(ToolError::InvalidParameters(format!(
+                    "'{}' must be a positive number",
+                    name
+                ))),
+            },
+        };
+        let speed = positive_param("speed")?;
+        let idle_time_limit = positive_param("idle_time_limit")?;
+
+        let mut child = Command::new("asciinema")
+            .args(terminal::play_args(&path, speed, idle_time_limit))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to start asciinema, is it installed? {}",
+                    e
+                ))
+            })?;
+
+        let mut stdout = child.stdout.take().unwrap();
+        let playback = async {
+            let mut buffer = [0u8; 4096];
+            let mut pending = Vec::new();
+            loop {
+                let read = stdout.read(&mut buffer).await?;
+                if read == 0 {
+                    return Ok::<_, std::io::Error>(());
+                }
+                // Hold back a character split across reads so it is not sent as two broken halves
+                pending.extend_from_slice(&buffer[..read]);
+                let complete = match std::str::from_utf8(&pending) {
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    _ => pending.len(),
+                };
+                let chunk: Vec<u8> = pending.drain(..complete).collect();
+                notifier
+                    .try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                        jsonrpc: JsonRpcVersion2_0,
+                        notification: Notification {
+                            method: "notifications/message".to_string(),
+                            params: object!({
+                                "level": "info",
+                                "data": {
+                                    "type": "terminal_replay",
+                                    "output": String::from_utf8_lossy(&chunk),
+                                }
+                            }),
+                            extensions: Default::default(),
+                        },
+                    }))
+                    .ok();
+            }
+        };
+        tokio::select! {
+            result = playback => result.map_err(|e| ToolError::ExecutionError(e.to_string()))?,
+            _ = cancel_token.cancelled() => {
+                child.kill().await.ok();
+                return Err(ToolError::ExecutionError(format!(
+                    "Playback of '{}' was cancelled",
+                    path.display()
+                )));
+            }
+        }
+
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        if !output.status.success() {
+            return Err(ToolError::ExecutionError(format!(
+                "asciinema failed to play '{}': {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(vec![Content::text(format!(
+            "Finished playing back {}",
+            path.display()
+        ))])
+    }
+
+    async fn sql_query(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        const DEFAULT_LIMIT: usize = 100;
+
+        let path_str = params
+            .get("db_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'db_path' parameter".into()))?;
+        let query = params
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'query' parameter".into()))?
+            .to_string();
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_LIMIT, |l| l as usize);
+        let path = self.resolve_path(path_str)?;
+
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+
+        let size = std::fs::metadata(&path)
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to read '{}': {}", path.display(), e))
+            })?
+            .len();
+        if size > sql::MAX_DATABASE_BYTES {
+            return Err(ToolError::ExecutionError(format!(
+                "'{}' is {} bytes, larger than the {} byte limit for sql_query",
+                path.display(),
+                size,
+                sql::MAX_DATABASE_BYTES
+            )));
+        }
+
+        let result = tokio::task::spawn_blocking(move || sql::query(&path, &query, limit))
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+            .map_err(ToolError::ExecutionError)?;
+
+        let mut json = serde_json::to_string_pretty(&result.to_json())
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        if result.truncated {
+            json.push_str(&format!("\n\nOnly the first {} rows are shown", limit));
+        }
+        Ok(vec![
+            Content::text(json).with_audience(vec![Role::Assistant]),
+            Content::text(result.format_table())
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn csv_to_sql(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let csv_path = params
+            .get("csv_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'csv_path' parameter".into()))?;
+        let csv_path = self.resolve_path(csv_path)?;
+        let table = match params.get("table_name").and_then(|v| v.as_str()) {
+            Some(name) if !name.trim().is_empty() => sql::table_name(name.trim()),
+            _ => sql::table_name(
+                &csv_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "data".to_string()),
+            ),
+        };
+        let db_path = match params.get("db_path").and_then(|v| v.as_str()) {
+            Some(path) => Some(self.resolve_path(path)?),
+            None => None,
+        };
+
+        for path in std::iter::once(&csv_path).chain(&db_path) {
+            if self.is_ignored(path) {
+                return Err(ToolError::ExecutionError(format!(
+                    "Access to '{}' is restricted by .gooseignore",
+                    path.display()
+                )));
+            }
+        }
+        let size = std::fs::metadata(&csv_path)
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to read '{}': {}", csv_path.display(), e))
+            })?
+            .len();
+        // The database would be too large for sql_query to open
+        if size > sql::MAX_DATABASE_BYTES {
+            return Err(ToolError::ExecutionError(format!(
+                "'{}' is {} bytes, larger than the {} byte limit for sql_query",
+                csv_path.display(),
+                size,
+                sql::MAX_DATABASE_BYTES
+            )));
+        }
+        let db_path = match db_path {
+            Some(db_path) => db_path,
+            None => tempfile::Builder::new()
+                .prefix(&format!("goose-{}-", table))
+                .suffix(".db")
+                .tempfile()
+                .and_then(|file| file.into_temp_path().keep().map_err(|e| e.error))
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to create a database file: {}", e))
+                })?,
+        };
+
+        let report = {
+            let (csv_path, db_path, table) = (csv_path.clone(), db_path.clone(), table.clone());
+            tokio::task::spawn_blocking(move || sql::import_csv(&csv_path, &db_path, &table))
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+                .map_err(ToolError::ExecutionError)?
+        };
+
+        let summary = format!(
+            "Imported {} rows from {} into table {} of {}\n\n{}",
+            report.rows,
+            csv_path.display(),
+            report.table,
+            db_path.display(),
+            report.schema
+        );
+        Ok(vec![
+            Content::text(summary.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn semantic_search(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        // Bounds the number of embeddings requested for a single search
+        const MAX_CHUNKS: usize = 2000;
+
+        let Some(client) = &self.config.embedding_client else {
+            return Err(ToolError::ExecutionError(
+                "Semantic search requires an embeddings endpoint to be configured".into(),
+            ));
+        };
+        let query = params
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("The query string is required".into()))?;
+        let root = match params.get("path").and_then(|v| v.as_str()) {
+            Some(path) => self.resolve_path(path)?,
+            None => std::env::current_dir().expect("should have a current working dir"),
+        };
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|l| l as usize)
+            .unwrap_or(self.config.semantic_search_top_k);
+
+        // Chunk every readable source file, skipping ignored and oversized files
+        let mut chunks: Vec<CodeChunk> = Vec::new();
+        for entry in ignore::WalkBuilder::new(&root).build().flatten() {
+            let path = entry.path();
+            if !path.is_file()
+                || self.is_ignored(path)
+                || lang::get_language_identifier(path).is_empty()
+            {
+                continue;
+            }
+            let too_large = std::fs::metadata(path)
+                .map(|m| m.len() > self.config.max_file_size_bytes)
+                .unwrap_or(true);
+            if too_large {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(path) {
+                chunks.extend(chunk_file(path, &content));
+            }
+            if chunks.len() >= MAX_CHUNKS {
+                chunks.truncate(MAX_CHUNKS);
+                break;
+            }
+        }
+
+        if chunks.is_empty() {
+            return Ok(vec![Content::text(format!(
+                "No source files found under {}",
+                root.display()
+            ))]);
+        }
+
+        let mut texts = vec![query.to_string()];
+        texts.extend(chunks.iter().map(|c| c.text.clone()));
+        let embeddings = client.embed(&texts).await.map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to generate embeddings: {}", e))
+        })?;
+        let (query_embedding, chunk_embeddings) = embeddings
+            .split_first()
+            .ok_or_else(|| ToolError::ExecutionError("No embeddings returned".into()))?;
+
+        let mut ranked: Vec<(f32, &CodeChunk)> = chunk_embeddings
+            .iter()
+            .map(|embedding| cosine_similarity(query_embedding, embedding))
+            .zip(&chunks)
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranked.truncate(limit);
+
+        let mut output = String::new();
+        let mut locations = Vec::new();
+        for (score, chunk) in &ranked {
+            let location = format!(
+                "{}:{}-{}",
+                chunk.path.display(),
+                chunk.start_line,
+                chunk.end_line
+            );
+            output.push_str(&formatdoc! {r#"
+                ### {location} (score {score:.3})
+                ```{language}
+                {text}
+                ```
+
+                "#,
+                location=location,
+                score=score,
+                language=lang::get_language_identifier(&chunk.path),
+                text=chunk.text,
+            });
+            locations.push(location);
+        }
+
+        Ok(vec![
+            Content::text(output).with_audience(vec![Role::Assistant]),
+            Content::text(locations.join("\n"))
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn glob(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let pattern =
+            params
+                .get("pattern")
+                .and_then(|v| v.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The pattern string is required".to_string(),
+                ))?;
+
+        let search_path = params.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+
+        let full_pattern = if search_path == "." {
+            pattern.to_string()
+        } else {
+            format!("{}/{}", search_path.trim_end_matches('/'), pattern)
+        };
+
+        let glob_result = glob::glob(&full_pattern)
+            .map_err(|e| ToolError::InvalidParameters(format!("Invalid glob pattern: {}", e)))?;
+
+        let mut file_paths_with_metadata = Vec::new();
+
+        for entry in glob_result {
+            match entry {
+                Ok(path) => {
+                    // Check if the path should be ignored
+                    if !self.is_ignored(&path) {
+                        // Get file metadata for sorting by modification time
+                        if let Ok(metadata) = std::fs::metadata(&path) {
+                            if metadata.is_file() {
+                                let modified = metadata
+                                    .modified()
+                                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+                                file_paths_with_metadata.push((path, modified));
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Error reading glob entry: {}", e);
+                }
+            }
+        }
+
+        // Sort by modification time (newest first)
+        file_paths_with_metadata.sort_by(|a, b| b.1.cmp(&a.1));
+
+        // Extract just the file paths
+        let file_paths: Vec<String> = file_paths_with_metadata
+            .into_iter()
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect();
+
+        let result = file_paths.join("\n");
+
+        Ok(vec![
+            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(result)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor(
+        &self,
+        params: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let command = params
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'command' parameter".to_string())
+            })?;
+
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+
+        let path = self.resolve_path(path_str)?;
+
+        // Check if file is ignored before proceeding with any text editor operation
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+
+        // Cached shell output may describe files as they were before this edit
+        if !matches!(
+            command,
+            "view"
+                | "json_query"
+                | "yaml_query"
+                | "render_markdown"
+                | "read_csv"
+                | "watch_file"
+                | "git_diff"
+                | "hex_dump"
+                | "spell_check"
+                | "validate_schema"
+                | "history_depth"
+        ) {
+            self.output_cache.lock().unwrap().clear();
+        }
+
+        match command {
+            "view" => {
+                let view_range = params
+                    .get("view_range")
+                    .and_then(|v| v.as_array())
+                    .and_then(|arr| {
+                        if arr.len() == 2 {
+                            let start = arr[0].as_i64().unwrap_or(1) as usize;
+                            let end = arr[1].as_i64().unwrap_or(-1);
+                            Some((start, end))
+                        } else {
+                            None
+                        }
+                    });
+                self.text_editor_view(&path, view_range).await
+            }
+            "write" => {
+                let file_text = params
+                    .get("file_text")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'file_text' parameter".into())
+                    })?;
+
+                self.text_editor_write(&path, file_text).await
+            }
+            "str_replace" | "edit_file" => {
+                let old_str = params
+                    .get("old_str")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'old_str' parameter".into())
+                    })?;
+                let new_str = params
+                    .get("new_str")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'new_str' parameter".into())
+                    })?;
+
+                self.text_editor_replace(&path, old_str, new_str).await
+            }
+            "insert" => {
+                let insert_line = params
+                    .get("insert_line")
+                    .and_then(|v| v.as_i64())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'insert_line' parameter".into())
+                    })? as usize;
+                let new_str = params
+                    .get("new_str")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'new_str' parameter".into())
+                    })?;
+
+                self.text_editor_insert(&path, insert_line, new_str).await
+            }
+            "write_lines" => {
+                let line_param = |name: &str| {
+                    params
+                        .get(name)
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as usize)
+                        .ok_or_else(|| {
+                            ToolError::InvalidParameters(format!("Missing '{}' parameter", name))
+                        })
+                };
+                let start_line = line_param("start_line")?;
+                let end_line = line_param("end_line")?;
+                let new_content = params
+                    .get("new_content")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'new_content' parameter".into())
+                    })?;
+
+                self.text_editor_write_lines(&path, start_line, end_line, new_content)
+                    .await
+            }
+            "watch_file" => {
+                let timeout_secs = params
+                    .get("timeout_secs")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(DEFAULT_WATCH_TIMEOUT_SECS);
+
+                self.text_editor_watch_file(&path, Duration::from_secs(timeout_secs), notifier)
+                    .await
+            }
+            "undo_edit" => self.text_editor_undo(&path).await,
+            "history_depth" => self.text_editor_history_depth(&path).await,
+            "json_query" => {
+                let query = params
+                    .get("query")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'query' parameter".into())
+                    })?;
+                let compact = params
+                    .get("compact")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                self.text_editor_json_query(&path, query, compact).await
+            }
+            "yaml_query" => {
+                let query = params
+                    .get("query")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'query' parameter".into())
+                    })?;
+                let as_json = match params.get("output_format").and_then(|v| v.as_str()) {
+                    None | Some("yaml") => false,
+                    Some("json") => true,
+                    Some(other) => {
+                        return Err(ToolError::InvalidParameters(format!(
+                            "Unknown output_format '{}', expected 'yaml' or 'json'",
+                            other
+                        )))
+                    }
+                };
+
+                self.text_editor_yaml_query(&path, query, as_json).await
+            }
+            "render_markdown" => self.text_editor_render_markdown(&path).await,
+            "read_csv" => self.text_editor_read_csv(&path).await,
+            "html_to_markdown" => {
+                let destination = params
+                    .get("destination")
+                    .and_then(|v| v.as_str())
+                    .map(|destination| self.resolve_path(destination))
+                    .transpose()?;
+
+                self.text_editor_html_to_markdown(&path, destination.as_ref())
+                    .await
+            }
+            "git_diff" => self.text_editor_git_diff(&path).await,
+            "hex_dump" => self.text_editor_hex_dump(&path).await,
+            "spell_check" => self.text_editor_spell_check(&path).await,
+            "encode_base64" => {
+                let destination = params
+                    .get("destination")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'destination' parameter".into())
+                    })?;
+                let destination = self.resolve_path(destination)?;
+                let wrap_lines = params
+                    .get("wrap_lines")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                self.text_editor_encode_base64(&path, &destination, wrap_lines)
+                    .await
+            }
+            "validate_schema" => {
+                let schema_path = params
+                    .get("schema_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'schema_path' parameter".into())
+                    })?;
+                let schema_path = self.resolve_path(schema_path)?;
+
+                self.text_editor_validate_schema(&path, &schema_path).await
+            }
+            "sort_lines" => {
+                let line_param = |name: &str| {
+                    params
+                        .get(name)
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as usize)
+                };
+                let flag = |name: &str| params.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+
+                self.text_editor_sort_lines(
+                    &path,
+                    line_param("start_line"),
+                    line_param("end_line"),
+                    flag("reverse"),
+                    flag("ignore_case"),
+                )
+                .await
+            }
+            "symbol_rename" => {
+                let param = |name: &str| {
+                    params.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+                        ToolError::InvalidParameters(format!("Missing '{}' parameter", name))
+                    })
+                };
+
+                self.text_editor_symbol_rename(&path, param("symbol")?, param("new_name")?)
+                    .await
+            }
+            "bulk_edit" => {
+                let edits = params
+                    .get("edits")
+                    .and_then(|v| v.as_array())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'edits' parameter".into())
+                    })?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, edit)| {
+                        let field = |name: &str| {
+                            edit.get(name)
+                                .and_then(|v| v.as_str())
+                                .map(str::to_string)
+                                .ok_or_else(|| {
+                                    ToolError::InvalidParameters(format!(
+                                        "Edit {} is missing '{}'",
+                                        i + 1,
+                                        name
+                                    ))
+                                })
+                        };
+                        Ok((field("old_str")?, field("new_str")?))
+                    })
+                    .collect::<Result<Vec<_>, ToolError>>()?;
+
+                self.text_editor_bulk_edit(&path, &edits).await
+            }
+            _ => Err(ToolError::InvalidParameters(format!(
+                "Unknown command '{}'",
+                command
+            ))),
+        }
+    }
+
+    async fn text_editor_view(
+        &self,
+        path: &PathBuf,
+        view_range: Option<(usize, i64)>,
+    ) -> Result<Vec<Content>, ToolError> {
+        if path.is_file() {
+            // Check file size first
+            let max_file_size = self.config.max_file_size_bytes;
+            const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
+
+            let file_size = std::fs::metadata(path)
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to get file metadata: {}", e))
+                })?
+                .len();
+
+            if file_size > max_file_size {
+                return Err(ToolError::ExecutionError(format!(
+                    "File '{}' is too large ({:.2}KB). Maximum size is {}KB to prevent memory issues.",
+                    path.display(),
+                    file_size as f64 / 1024.0,
+                    max_file_size / 1024
+                )));
+            }
+
+            let uri = Url::from_file_path(path)
+                .map_err(|_| ToolError::ExecutionError("Invalid file path".into()))?
+                .to_string();
+
+            let content = std::fs::read_to_string(path).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::InvalidData {
+                    ToolError::ExecutionError(format!(
+                        "'{}' is not a text file, use the hex_dump command to inspect it",
+                        path.display()
+                    ))
+                } else {
+                    ToolError::ExecutionError(format!("Failed to read file: {}", e))
+                }
+            })?;
+
+            let char_count = content.chars().count();
+            if char_count > MAX_CHAR_COUNT {
+                return Err(ToolError::ExecutionError(format!(
+                    "File '{}' has too many characters ({}). Maximum character count is {}.",
+                    path.display(),
+                    char_count,
+                    MAX_CHAR_COUNT
+                )));
+            }
+
+            let lines: Vec<&str> = content.lines().collect();
+            let total_lines = lines.len();
+
+            // Handle view_range if provided, otherwise show all lines
+            let (start_idx, end_idx) = if let Some((start_line, end_line)) = view_range {
+                // Convert 1-indexed line numbers to 0-indexed
+                let start_idx = if start_line > 0 { start_line - 1 } else { 0 };
+                let end_idx = if end_line == -1 {
+                    total_lines
+                } else {
+                    std::cmp::min(end_line as usize, total_lines)
+                };
+
+                if start_idx >= total_lines {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "Start line {} is beyond the end of the file (total lines: {})",
+                        start_line, total_lines
+                    )));
+                }
+
+                if start_idx >= end_idx {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "Start line {} must be less than end line {}",
+                        start_line, end_line
+                    )));
+                }
+
+                (start_idx, end_idx)
+            } else {
+                (0, total_lines)
+            };
+
+            // Always format lines with line numbers for better usability
+            let display_content = if total_lines == 0 {
+                String::new()
+            } else {
+                let selected_lines: Vec<String> = lines[start_idx..end_idx]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| format!("{}: {}", start_idx + i + 1, line))
+                    .collect();
+
+                selected_lines.join("\n")
+            };
+
+            let language = lang::get_language_identifier(path);
+            let formatted = if view_range.is_some() {
+                formatdoc! {"
+                    ### {path} (lines {start}-{end})
+                    ```{language}
+                    {content}
+                    ```
+                    ",
+                    path=path.display(),
+                    start=view_range.unwrap().0,
+                    end=if view_range.unwrap().1 == -1 { "end".to_string() } else { view_range.unwrap().1.to_string() },
+                    language=language,
+                    content=display_content,
+                }
+            } else {
+                formatdoc! {"
+                    ### {path}
+                    ```{language}
+                    {content}
+                    ```
+                    ",
+                    path=path.display(),
+                    language=language,
+                    content=display_content,
+                }
+            };
+
+            // The LLM gets just a quick update as we expect the file to view in the status
+            // but we send a low priority message for the human
+            Ok(vec![
+                Content::embedded_text(uri, content).with_audience(vec![Role::Assistant]),
+                Content::text(formatted)
+                    .with_audience(vec![Role::User])
+                    .with_priority(0.0),
+            ])
+        } else if path.is_dir() {
+            self.text_editor_view_directory(path)
+        } else {
+            Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist.",
+                path.display()
+            )))
+        }
+    }
+
+    /// List the entries of a directory, without descending into subdirectories
+    fn text_editor_view_directory(&self, path: &Path) -> Result<Vec<Content>, ToolError> {
+        const MAX_DIRECTORY_ENTRIES: usize = 500;
+
+        let read_dir = std::fs::read_dir(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read directory: {}", e)))?;
+
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let entry = entry.map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to read directory: {}", e))
+            })?;
+            let entry_path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if self
+                .ignore_patterns
+                .matched(&entry_path, metadata.is_dir())
+                .is_ignore()
+            {
+                continue;
+            }
+            entries.push((
+                entry.file_name().to_string_lossy().into_owned(),
+                entry_path,
+                metadata,
+            ));
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let total = entries.len();
+        let truncated = total > MAX_DIRECTORY_ENTRIES;
+        entries.truncate(MAX_DIRECTORY_ENTRIES);
+
+        let listing: Vec<Value> = entries
+            .iter()
+            .map(|(name, entry_path, metadata)| {
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339());
+                if metadata.is_dir() {
+                    json!({
+                        "name": name,
+                        "type": "directory",
+                        "modified": modified,
+                    })
+                } else {
+                    json!({
+                        "name": name,
+                        "type": "file",
+                        "size": metadata.len(),
+                        "modified": modified,
+                        "language": lang::get_language_identifier(entry_path),
+                    })
+                }
+            })
+            .collect();
+
+        let result = serde_json::to_string_pretty(&json!({
+            "path": path.display().to_string(),
+            "entries": listing,
+            "total": total,
+            "truncated": truncated,
+        }))
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to format result: {}", e)))?;
+
+        // Markdown table for the user
+        let mut table =
+            String::from("| Name | Size | Modified | Language |\n| --- | --- | --- | --- |\n");
+        for entry in &listing {
+            let name = entry["name"]
+                .as_str()
+                .unwrap_or_default()
+                .replace('|', "\\|");
+            let is_dir = entry["type"] == "directory";
+            table.push_str(&format!(
+                "| {}{} | {} | {} | {} |\n",
+                name,
+                if is_dir { "/" } else { "" },
+                entry["size"]
+                    .as_u64()
+                    .map(|size| size.to_string())
+                    .unwrap_or_default(),
+                entry["modified"].as_str().unwrap_or_default(),
+                entry["language"].as_str().unwrap_or_default(),
+            ));
+        }
+        if truncated {
+            table.push_str(&format!(
+                "\nOnly the first {} of {} entries are listed.\n",
+                MAX_DIRECTORY_ENTRIES, total
+            ));
+        }
+
+        Ok(vec![
+            Content::text(result).with_audience(vec![Role::Assistant]),
+            Content::text(formatdoc! {"
+                ### {path}
+                {table}",
+                path=path.display(),
+                table=table,
+            })
+            .with_audience(vec![Role::User])
+            .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_write(
+        &self,
+        path: &Path,
+        file_text: &str,
+    ) -> Result<Vec<Content>, ToolError> {
+        // Normalize line endings based on platform
+        let mut normalized_text = normalize_line_endings(file_text); // Make mutable
+
+        // Ensure the text ends with a newline
+        if !normalized_text.ends_with('\n') {
+            normalized_text.push('\n');
+        }
+
+        // Write to the file
+        write_file_atomic(path, &normalized_text) // Write the potentially modified text
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        // Try to detect the language from the file extension
+        let language = lang::get_language_identifier(path);
+
+        // The assistant output does not show the file again because the content is already in the tool request
+        // but we do show it to the user here, using the final written content
+        Ok(vec![
+            Content::text(format!("Successfully wrote to {}", path.display()))
+                .with_audience(vec![Role::Assistant]),
+            Content::text(formatdoc! {
+                r#"
+                ### {path}
+                ```{language}
+                {content}
+                ```
+                "#,
+                path=path.display(),
+                language=language,
+                content=&normalized_text // Use the final normalized_text for user feedback
+            })
+            .with_audience(vec![Role::User])
+            .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_replace(
+        &self,
+        path: &PathBuf,
+        old_str: &str,
+        new_str: &str,
+    ) -> Result<Vec<Content>, ToolError> {
+        // Check if file exists and is active
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "File '{}' does not exist, you can write a new file with the `write` command",
+                path.display()
+            )));
+        }
+
+        // Read content
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+
+        // Check if Editor API is configured and use it as the primary path
+        if let Some(ref editor) = self.config.editor_model {
+            // Editor API path - save history then call API directly
+            self.save_file_history(path)?;
+
+            match editor.edit_code(&content, old_str, new_str).await {
+                Ok(updated_content) => {
+                    // Write the updated content directly
+                    let normalized_content = normalize_line_endings(&updated_content);
+                    write_file_atomic(path, &normalized_content).map_err(|e| {
+                        ToolError::ExecutionError(format!("Failed to write file: {}", e))
+                    })?;
+
+                    // Simple success message for Editor API
+                    return Ok(vec![
+                        Content::text(format!("Successfully edited {}", path.display()))
+                            .with_audience(vec![Role::Assistant]),
+                        Content::text(format!("File {} has been edited", path.display()))
+                            .with_audience(vec![Role::User])
+                            .with_priority(0.2),
+                    ]);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Editor API call failed: {}, falling back to string replacement",
+                        e
+                    );
+                    // Fall through to traditional path below
+                }
+            }
+        }
+
+        // Traditional string replacement path (original logic)
+        // Ensure 'old_str' appears exactly once
+        if content.matches(old_str).count() > 1 {
+            return Err(ToolError::InvalidParameters(
+                "'old_str' must appear exactly once in the file, but it appears multiple times"
+                    .into(),
+            ));
+        }
+        if content.matches(old_str).count() == 0 {
+            return Err(ToolError::InvalidParameters(
+                "'old_str' must appear exactly once in the file, but it does not appear in the file. Make sure the string exactly matches existing file content, including whitespace!".into(),
+            ));
+        }
+
+        // Save history for undo (original behavior - after validation)
+        self.save_file_history(path)?;
+
+        let new_content = content.replace(old_str, new_str);
+        let normalized_content = normalize_line_endings(&new_content);
+        write_file_atomic(path, &normalized_content)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        // Try to detect the language from the file extension
+        let language = lang::get_language_identifier(path);
+
+        // Show a snippet of the changed content with context
+        const SNIPPET_LINES: usize = 4;
+
+        // Count newlines before the replacement to find the line number
+        let replacement_line = content
+            .split(old_str)
+            .next()
+            .expect("should split on already matched content")
+            .matches('\n')
+            .count();
+
+        // Calculate start and end lines for the snippet
+        let start_line = replacement_line.saturating_sub(SNIPPET_LINES);
+        let end_line = replacement_line + SNIPPET_LINES + new_content.matches('\n').count();
+
+        // Get the relevant lines for our snippet
+        let lines: Vec<&str> = new_content.lines().collect();
+        let snippet = lines
+            .iter()
+            .skip(start_line)
+            .take(end_line - start_line + 1)
+            .cloned()
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        let output = formatdoc! {r#"
+            ```{language}
+            {snippet}
+            ```
+            "#,
+            language=language,
+            snippet=snippet
+        };
+
+        let success_message = formatdoc! {r#"
+            The file {} has been edited, and the section now reads:
+            {}
+            Review the changes above for errors. Undo and edit the file again if necessary!
+            "#,
+            path.display(),
+            output
+        };
+
+        Ok(vec![
+            Content::text(success_message).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_bulk_edit(
+        &self,
+        path: &PathBuf,
+        edits: &[(String, String)],
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "File '{}' does not exist, you can write a new file with the `write` command",
+                path.display()
+            )));
+        }
+        if edits.is_empty() {
+            return Err(ToolError::InvalidParameters(
+                "'edits' must contain at least one edit".into(),
+            ));
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+
+        // Validate every edit against the original content before touching the file
+        let mut failures = Vec::new();
+        let mut ranges = Vec::new();
+        for (i, (old_str, _)) in edits.iter().enumerate() {
+            if old_str.is_empty() {
+                failures.push(format!("edit {}: 'old_str' must not be empty", i + 1));
+                continue;
+            }
+            match content.matches(old_str.as_str()).count() {
+                1 => {
+                    let start = content.find(old_str.as_str()).expect("matched above");
+                    ranges.push((start, start + old_str.len(), i));
+                }
+                0 => failures.push(format!(
+                    "edit {}: 'old_str' does not appear in the file",
+                    i + 1
+                )),
+                n => failures.push(format!(
+                    "edit {}: 'old_str' must appear exactly once, but it appears {} times",
+                    i + 1,
+                    n
+                )),
+            }
+        }
+
+        ranges.sort_by_key(|&(start, _, _)| start);
+        for pair in ranges.windows(2) {
+            let ((_, prev_end, prev), (next_start, _, next)) = (pair[0], pair[1]);
+            if next_start < prev_end {
+                failures.push(format!(
+                    "edit {}: 'old_str' overlaps with edit {}",
+                    next.max(prev) + 1,
+                    next.min(prev) + 1
+                ));
+            }
+        }
+
+        if !failures.is_empty() {
+            return Err(ToolError::InvalidParameters(format!(
+                "No changes were made to {}, the following edits are invalid:\n{}",
+                path.display(),
+                failures.join("\n")
+            )));
+        }
+
+        self.save_file_history(path)?;
+
+        // Apply from the end of the file so earlier byte offsets stay valid
+        let mut new_content = content;
+        for &(start, end, i) in ranges.iter().rev() {
+            new_content.replace_range(start..end, &edits[i].1);
+        }
+
+        let normalized_content = normalize_line_endings(&new_content);
+        write_file_atomic(path, &normalized_content)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        Ok(vec![
+            Content::text(format!(
+                "Applied {} edits to {}. Review the file for errors and undo if necessary!",
+                edits.len(),
+                path.display()
+            ))
+            .with_audience(vec![Role::Assistant]),
+            Content::text(format!(
+                "File {} has been edited ({} replacements)",
+                path.display(),
+                edits.len()
+            ))
+            .with_audience(vec![Role::User])
+            .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_write_lines(
+        &self,
+        path: &PathBuf,
+        start_line: usize,
+        end_line: usize,
+        new_content: &str,
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "File '{}' does not exist, you can write a new file with the `write` command",
+                path.display()
+            )));
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let total_lines = lines.len();
+
+        if start_line == 0 || start_line > end_line {
+            return Err(ToolError::InvalidParameters(format!(
+                "Invalid line range {}-{}, lines are 1-indexed and start_line must not be after end_line",
+                start_line, end_line
+            )));
+        }
+        if end_line > total_lines {
+            return Err(ToolError::InvalidParameters(format!(
+                "End line {} is beyond the end of the file (total lines: {})",
+                end_line, total_lines
+            )));
+        }
+
+        self.save_file_history(path)?;
+
+        let removed = &lines[start_line - 1..end_line];
+        let added: Vec<&str> = new_content.lines().collect();
+
+        let mut new_lines = Vec::with_capacity(total_lines - removed.len() + added.len());
+        new_lines.extend_from_slice(&lines[..start_line - 1]);
+        new_lines.extend_from_slice(&added);
+        new_lines.extend_from_slice(&lines[end_line..]);
+
+        let mut new_file = new_lines.join("\n");
+        if !new_file.is_empty() {
+            new_file.push('\n');
+        }
+        let normalized_content = normalize_line_endings(&new_file);
+        write_file_atomic(path, &normalized_content)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        let diff = std::iter::once(format!(
+            "@@ -{},{} +{},{} @@",
+            start_line,
+            removed.len(),
+            start_line,
+            added.len()
+        ))
+        .chain(removed.iter().map(|line| format!("-{}", line)))
+        .chain(added.iter().map(|line| format!("+{}", line)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let output = formatdoc! {r#"
+            ```diff
+            {diff}
+            ```
+            "#,
+            diff=diff
+        };
+
+        let success_message = formatdoc! {r#"
+            Lines {}-{} of {} have been replaced:
+            {}
+            Review the changes above for errors. Undo and edit the file again if necessary!
+            "#,
+            start_line,
+            end_line,
+            path.display(),
+            output
+        };
+
+        Ok(vec![
+            Content::text(success_message).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_sort_lines(
+        &self,
+        path: &PathBuf,
+        start_line: Option<usize>,
+        end_line: Option<usize>,
+        reverse: bool,
+        ignore_case: bool,
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "File '{}' does not exist",
+                path.display()
+            )));
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let mut lines: Vec<&str> = content.lines().collect();
+        let total_lines = lines.len();
+        if total_lines == 0 {
+            return Ok(vec![Content::text(format!(
+                "{} is empty, the file was not changed",
+                path.display()
+            ))]);
+        }
+        let start_line = start_line.unwrap_or(1);
+        let end_line = end_line.unwrap_or(total_lines);
+
+        if start_line == 0 || start_line > end_line {
+            return Err(ToolError::InvalidParameters(format!(
+                "Invalid line range {}-{}, lines are 1-indexed and start_line must not be after end_line",
+                start_line, end_line
+            )));
+        }
+        if end_line > total_lines {
+            return Err(ToolError::InvalidParameters(format!(
+                "End line {} is beyond the end of the file (total lines: {})",
+                end_line, total_lines
+            )));
+        }
+
+        // A stable sort, so lines that compare equal keep their order
+        let range = &mut lines[start_line - 1..end_line];
+        if ignore_case {
+            range.sort_by_cached_key(|line| line.to_lowercase());
+        } else {
+            range.sort();
+        }
+        if reverse {
+            range.reverse();
+        }
+
+        let mut sorted = lines.join("\n");
+        if content.ends_with('\n') {
+            sorted.push('\n');
+        }
+        let label = path.display().to_string();
+        let diff = diff::unified_diff(&content, &sorted, &label, &label);
+        if diff.is_empty() {
+            return Ok(vec![Content::text(format!(
+                "Lines {}-{} of {} are already sorted, the file was not changed",
+                start_line,
+                end_line,
+                path.display()
+            ))]);
+        }
+
+        self.save_file_history(path)?;
+        let normalized_content = normalize_line_endings(&sorted);
+        write_file_atomic(path, &normalized_content)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        let output = format!("```diff\n{}```\n", diff);
+        Ok(vec![
+            Content::text(format!(
+                "Sorted lines {}-{} of {}:\n{}",
+                start_line,
+                end_line,
+                path.display(),
+                output
+            ))
+            .with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_watch_file(
+        &self,
+        path: &PathBuf,
+        timeout: Duration,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::InvalidParameters(format!(
+                "The path '{}' is not a file",
+                path.display()
+            )));
+        }
+        let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+            return Err(ToolError::InvalidParameters(format!(
+                "Cannot watch '{}'",
+                path.display()
+            )));
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to create file watcher: {}", e)))?;
+        // Watch the directory rather than the file, generators and editors often replace the
+        // file with a new one instead of writing to it
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to watch file: {}", e)))?;
+
+        let original = std::fs::read(path).ok();
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    let event: notify::Event = match event {
+                        Some(Ok(event)) => event,
+                        Some(Err(e)) => {
+                            return Err(ToolError::ExecutionError(format!(
+                                "Failed to watch file: {}",
+                                e
+                            )))
+                        }
+                        None => {
+                            return Err(ToolError::ExecutionError(
+                                "File watcher stopped unexpectedly".into(),
+                            ))
+                        }
+                    };
+                    let is_change = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+                    if !is_change || !event.paths.iter().any(|p| p.file_name() == Some(file_name)) {
+                        continue;
+                    }
+                    // Skip touches and the moment between a file being removed and recreated
+                    match std::fs::read(path) {
+                        Ok(content) if Some(&content) != original.as_ref() => {}
+                        _ => continue,
+                    }
+
+                    notifier
+                        .try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                            jsonrpc: JsonRpcVersion2_0,
+                            notification: Notification {
+                                method: "notifications/message".to_string(),
+                                params: object!({
+                                    "level": "info",
+                                    "data": {
+                                        "type": "watch_file",
+                                        "path": path.display().to_string(),
+                                        "output": format!("{} changed", path.display()),
+                                    }
+                                }),
+                                extensions: Default::default(),
+                            },
+                        }))
+                        .ok();
+
+                    let mut result = vec![Content::text(format!(
+                        "{} was changed, its new content is:",
+                        path.display()
+                    ))
+                    .with_audience(vec![Role::Assistant])];
+                    result.extend(self.text_editor_view(path, None).await?);
+                    return Ok(result);
+                }
+                _ = &mut deadline => {
+                    return Err(ToolError::ExecutionError(format!(
+                        "Timed out after {}s waiting for '{}' to change",
+                        timeout.as_secs(),
+                        path.display()
+                    )));
+                }
+            }
+        }
+    }
+
+    async fn text_editor_insert(
+        &self,
+        path: &PathBuf,
+        insert_line: usize,
+        new_str: &str,
+    ) -> Result<Vec<Content>, ToolError> {
+        // Check if file exists
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "File '{}' does not exist, you can write a new file with the `write` command",
+                path.display()
+            )));
+        }
+
+        // Read content
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+
+        // Save history for undo
+        self.save_file_history(path)?;
+
+        let lines: Vec<&str> = content.lines().collect();
+        let total_lines = lines.len();
+
+        // Validate insert_line parameter
+        if insert_line > total_lines {
+            return Err(ToolError::InvalidParameters(format!(
+                "Insert line {} is beyond the end of the file (total lines: {}). Use 0 to insert at the beginning or {} to insert at the end.",
+                insert_line, total_lines, total_lines
+            )));
+        }
+
+        // Create new content with inserted text
+        let mut new_lines = Vec::new();
+
+        // Add lines before the insertion point
+        for (i, line) in lines.iter().enumerate() {
+            if i == insert_line {
+                // Insert the new text at this position
+                new_lines.push(new_str.to_string());
+            }
+            new_lines.push(line.to_string());
+        }
+
+        // If inserting at the end (after all existing lines)
+        if insert_line == total_lines {
+            new_lines.push(new_str.to_string());
+        }
+
+        let new_content = new_lines.join("\n");
+        let normalized_content = normalize_line_endings(&new_content);
+
+        // Ensure the file ends with a newline
+        let final_content = if !normalized_content.ends_with('\n') {
+            format!("{}\n", normalized_content)
+        } else {
+            normalized_content
+        };
+
+        write_file_atomic(path, &final_content)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        // Try to detect the language from the file extension
+        let language = lang::get_language_identifier(path);
+
+        // Show a snippet of the inserted content with context
+        const SNIPPET_LINES: usize = 4;
+        let insertion_line = insert_line + 1; // Convert to 1-indexed for display
+
+        // Calculate start and end lines for the snippet
+        let start_line = insertion_line.saturating_sub(SNIPPET_LINES);
+        let end_line = std::cmp::min(insertion_line + SNIPPET_LINES, new_lines.len());
+
+        // Get the relevant lines for our snippet with line numbers
+        let snippet_lines: Vec<String> = new_lines[start_line.saturating_sub(1)..end_line]
+            .iter()
+            .enumerate()
+            .map(|(i, line)| format!("{}: {}", start_line + i, line))
+            .collect();
+
+        let snippet = snippet_lines.join("\n");
+
+        let output = formatdoc! {r#"
+            ```{language}
+            {snippet}
+            ```
+            "#,
+            language=language,
+            snippet=snippet
+        };
+
+        let success_message = formatdoc! {r#"
+            Text has been inserted at line {} in {}. The section now reads:
+            {}
+            Review the changes above for errors. Undo and edit the file again if necessary!
+            "#,
+            insertion_line,
+            path.display(),
+            output
+        };
+
+        Ok(vec![
+            Content::text(success_message).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_undo(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        let mut history = self.file_history.lock().unwrap();
+        if let Some(contents) = history.get_mut(path) {
+            if let Some(previous_content) = contents.pop() {
+                // Write previous content back to file
+                write_file_atomic(path, &previous_content).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to write file: {}", e))
+                })?;
+                Ok(vec![Content::text("Undid the last edit")])
+            } else {
+                Err(ToolError::InvalidParameters(
+                    "No edit history available to undo".into(),
+                ))
+            }
+        } else {
+            Err(ToolError::InvalidParameters(
+                "No edit history available to undo".into(),
+            ))
+        }
+    }
+
+    async fn text_editor_history_depth(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        let depth = self
+            .file_history
+            .lock()
+            .unwrap()
+            .get(path)
+            .map_or(0, Vec::len);
+        Ok(vec![Content::text(format!(
+            "{} edit{} to {} can be undone",
+            depth,
+            if depth == 1 { "" } else { "s" },
+            path.display()
+        ))])
+    }
+
+    async fn text_editor_json_query(
+        &self,
+        path: &PathBuf,
+        query: &str,
+        compact: bool,
+    ) -> Result<Vec<Content>, ToolError> {
+        use jaq_core::load::{Arena, File, Loader};
+        use jaq_core::{Compiler, Ctx, RcIter};
+        use jaq_json::Val;
+
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        // Same limit as view, the whole document is loaded into memory to run the query
+        let max_file_size = self.config.max_file_size_bytes;
+        let file_size = std::fs::metadata(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
+        if file_size > max_file_size {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' is too large ({:.2}KB). Maximum size is {}KB to prevent memory issues.",
+                path.display(),
+                file_size as f64 / 1024.0,
+                max_file_size / 1024
+            )));
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let input: Value = serde_json::from_str(&content).map_err(|e| {
+            ToolError::ExecutionError(format!(
+                "File '{}' is not valid JSON: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        // Parse and compile the filter with the jq standard library available
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let arena = Arena::default();
+        let program = File {
+            code: query,
+            path: (),
+        };
+        let modules = loader.load(&arena, program).map_err(|errs| {
+            ToolError::InvalidParameters(format!(
+                "Invalid query '{}': {:?}",
+                query,
+                errs.into_iter().map(|(_, e)| e).collect::<Vec<_>>()
+            ))
+        })?;
+        let filter = Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errs| {
+                ToolError::InvalidParameters(format!(
+                    "Invalid query '{}': {:?}",
+                    query,
+                    errs.into_iter().map(|(_, e)| e).collect::<Vec<_>>()
+                ))
+            })?;
+
+        let inputs = RcIter::new(core::iter::empty());
+        let mut results = Vec::new();
+        for output in filter.run((Ctx::new([], &inputs), Val::from(input))) {
+            let value = output.map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to evaluate query '{}': {}", query, e))
+            })?;
+            let value = Value::from(value);
+            let rendered = if compact {
+                serde_json::to_string(&value)
+            } else {
+                serde_json::to_string_pretty(&value)
+            }
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to format result: {}", e)))?;
+            results.push(rendered);
+        }
+
+        let result = results.join("\n");
+
+        Ok(vec![
+            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(formatdoc! {r#"
+                ### {path} | `{query}`
+                ```json
+                {result}
+                ```
+                "#,
+                path=path.display(),
+                query=query,
+                result=result,
+            })
+            .with_audience(vec![Role::User])
+            .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_yaml_query(
+        &self,
+        path: &PathBuf,
+        query: &str,
+        as_json: bool,
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        let max_file_size = self.config.max_file_size_bytes;
+        let file_size = std::fs::metadata(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
+        if file_size > max_file_size {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' is too large ({:.2}KB). Maximum size is {}KB to prevent memory issues.",
+                path.display(),
+                file_size as f64 / 1024.0,
+                max_file_size / 1024
+            )));
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let input: Value = serde_yaml::from_str(&content).map_err(|e| {
+            ToolError::ExecutionError(format!(
+                "File '{}' is not valid YAML: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let matches = jsonpath_lib::select(&input, query).map_err(|e| {
+            ToolError::InvalidParameters(format!("Invalid query '{}': {}", query, e))
+        })?;
+        // A single match is returned as is, several as a list
+        let value = match matches.as_slice() {
+            [single] => (*single).clone(),
+            _ => Value::Array(matches.into_iter().cloned().collect()),
+        };
+
+        let (result, language) = if as_json {
+            (
+                serde_json::to_string_pretty(&value).map_err(|e| e.to_string()),
+                "json",
+            )
+        } else {
+            (
+                serde_yaml::to_string(&value).map_err(|e| e.to_string()),
+                "yaml",
+            )
+        };
+        let result = result
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to format result: {}", e)))?;
+
+        Ok(vec![
+            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(formatdoc! {r#"
+                ### {path} | `{query}`
+                ```{language}
+                {result}
+                ```
+                "#,
+                path=path.display(),
+                query=query,
+                language=language,
+                result=result.trim_end(),
+            })
+            .with_audience(vec![Role::User])
+            .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_read_csv(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        const MAX_CSV_ROWS: usize = 1000;
+        const PREVIEW_ROWS: usize = 10;
+
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        let mut reader = csv::Reader::from_path(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to open CSV file: {}", e)))?;
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read CSV headers: {}", e)))?
+            .iter()
+            .map(str::to_string)
+            .collect();
+
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        for record in reader.records() {
+            if rows.len() == MAX_CSV_ROWS {
+                truncated = true;
+                break;
+            }
+            let record = record.map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to parse CSV file: {}", e))
+            })?;
+            rows.push(
+                headers
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(header, field)| (header.clone(), Value::String(field.to_string())))
+                    .collect::<serde_json::Map<_, _>>(),
+            );
+        }
+
+        let mut result = serde_json::to_string_pretty(&rows)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to format result: {}", e)))?;
+        if truncated {
+            result.push_str(&format!(
+                "\n\nOnly the first {} rows are included, the file has more.",
+                MAX_CSV_ROWS
+            ));
+        }
+
+        // Markdown table of the first rows for the user
+        let escape = |field: &str| field.replace('|', "\\|").replace('\n', " ");
+        let mut preview = format!(
+            "| {} |\n|{}\n",
+            headers
+                .iter()
+                .map(|h| escape(h))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            " --- |".repeat(headers.len())
+        );
+        for row in rows.iter().take(PREVIEW_ROWS) {
+            let fields: Vec<String> = headers
+                .iter()
+                .map(|h| escape(row.get(h).and_then(Value::as_str).unwrap_or_default()))
+                .collect();
+            preview.push_str(&format!("| {} |\n", fields.join(" | ")));
+        }
+
+        Ok(vec![
+            Content::text(result).with_audience(vec![Role::Assistant]),
+            Content::text(formatdoc! {r#"
+                ### {path} ({count} rows)
+                {preview}
+                "#,
+                path=path.display(),
+                count=if truncated { format!("{}+", MAX_CSV_ROWS) } else { rows.len().to_string() },
+                preview=preview,
+            })
+            .with_audience(vec![Role::User])
+            .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_html_to_markdown(
+        &self,
+        path: &PathBuf,
+        destination: Option<&PathBuf>,
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        // Same limit as view, the whole page is converted in memory
+        let max_file_size = self.config.max_file_size_bytes;
+        let file_size = std::fs::metadata(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
+        if file_size > max_file_size {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' is too large ({:.2}KB). Maximum size is {}KB to prevent memory issues.",
+                path.display(),
+                file_size as f64 / 1024.0,
+                max_file_size / 1024
+            )));
+        }
+
+        let html = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let markdown = html_to_markdown(&html);
+
+        let Some(destination) = destination else {
+            return Ok(vec![
+                Content::text(markdown.clone()).with_audience(vec![Role::Assistant]),
+                Content::text(markdown)
+                    .with_audience(vec![Role::User])
+                    .with_priority(0.0),
+            ]);
+        };
+
+        if self.is_ignored(destination) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                destination.display()
+            )));
+        }
+
+        self.save_file_history(destination)?;
+        write_file_atomic(destination, &markdown)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        Ok(vec![Content::text(format!(
+            "Converted {} to Markdown in {} ({} lines)",
+            path.display(),
+            destination.display(),
+            markdown.lines().count()
+        ))])
+    }
+
+    async fn text_editor_encode_base64(
+        &self,
+        path: &PathBuf,
+        destination: &PathBuf,
+        wrap_lines: bool,
+    ) -> Result<Vec<Content>, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+        if self.is_ignored(destination) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                destination.display()
+            )));
+        }
+
+        let file_size = std::fs::metadata(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
+        if file_size > MAX_BASE64_SOURCE_SIZE {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' is too large ({:.2}MB). Maximum size is 10MB.",
+                path.display(),
+                file_size as f64 / (1024.0 * 1024.0)
+            )));
+        }
+
+        let bytes = std::fs::read(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let encoded = encode_base64(&bytes, wrap_lines);
+        let line_count = encoded.lines().count();
+        let encoded_len = encoded.len() - (line_count.max(1) - 1);
+
+        self.save_file_history(destination)?;
+        write_file_atomic(destination, &format!("{}\n", encoded))
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        Ok(vec![Content::text(format!(
+            "Encoded {} ({} bytes) as base64 in {}: {} characters on {} line{}",
+            path.display(),
+            bytes.len(),
+            destination.display(),
+            encoded_len,
+            line_count,
+            if line_count == 1 { "" } else { "s" }
+        ))])
+    }
+
+    /// Parse a JSON file, or a YAML one going by its extension
+    fn read_json_or_yaml(&self, path: &Path) -> Result<Value, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+        if self.is_ignored(path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+
+        let max_file_size = self.config.max_file_size_bytes;
+        let file_size = std::fs::metadata(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
+        if file_size > max_file_size {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' is too large ({:.2}KB). Maximum size is {}KB to prevent memory issues.",
+                path.display(),
+                file_size as f64 / 1024.0,
+                max_file_size / 1024
+            )));
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+        if is_yaml {
+            serde_yaml::from_str(&content).map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "File '{}' is not valid YAML: {}",
+                    path.display(),
+                    e
+                ))
+            })
+        } else {
+            serde_json::from_str(&content).map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "File '{}' is not valid JSON: {}",
+                    path.display(),
+                    e
+                ))
+            })
+        }
+    }
+
+    async fn text_editor_validate_schema(
+        &self,
+        path: &Path,
+        schema_path: &Path,
+    ) -> Result<Vec<Content>, ToolError> {
+        let instance = self.read_json_or_yaml(path)?;
+        let schema = self.read_json_or_yaml(schema_path)?;
+
+        let validator = jsonschema::validator_for(&schema).map_err(|e| {
+            ToolError::ExecutionError(format!(
+                "'{}' is not a valid JSON Schema: {}",
+                schema_path.display(),
+                e
+            ))
+        })?;
+        let errors: Vec<Value> = validator
+            .iter_errors(&instance)
+            .map(|error| {
+                json!({
+                    "instance_path": error.instance_path.to_string(),
+                    "schema_path": error.schema_path.to_string(),
+                    "message": error.to_string(),
+                })
+            })
+            .collect();
+
+        let summary = if errors.is_empty() {
+            format!(
+                "{} is valid against {}",
+                path.display(),
+                schema_path.display()
+            )
+        } else {
+            format!(
+                "{} has {} validation error{} against {}",
+                path.display(),
+                errors.len(),
+                if errors.len() == 1 { "" } else { "s" },
+                schema_path.display()
+            )
+        };
+        let report = json!({
+            "valid": errors.is_empty(),
+            "error_count": errors.len(),
+            "errors": errors,
+        });
+        let report = serde_json::to_string_pretty(&report)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        Ok(vec![
+            Content::text(report).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_git_diff(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        let diff = match diff::git_diff(path).await {
+            Some(diff) => diff,
+            None => {
+                // Without git the best baseline is the file before this session first touched it
+                let original = self
+                    .file_history
+                    .lock()
+                    .unwrap()
+                    .get(path)
+                    .and_then(|contents| contents.first().cloned())
+                    .ok_or_else(|| {
+                        ToolError::ExecutionError(format!(
+                            "Cannot diff '{}' without git, and it has not been edited in this session",
+                            path.display()
+                        ))
+                    })?;
+                let current = std::fs::read_to_string(path).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to read file: {}", e))
+                })?;
+                let label = path.display().to_string();
+                diff::unified_diff(
+                    &original,
+                    &current,
+                    &format!("{} (before edits)", label),
+                    &label,
+                )
+            }
+        };
+
+        if diff.trim().is_empty() {
+            return Ok(vec![Content::text(format!(
+                "No changes in {}",
+                path.display()
+            ))]);
+        }
+
+        let formatted = format!("```diff\n{}```", diff);
+        Ok(vec![
+            Content::text(formatted.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(formatted)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_hex_dump(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        use std::io::Read;
+
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        let file = std::fs::File::open(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to open file: {}", e)))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
+        let mut bytes = Vec::new();
+        file.take(HEX_DUMP_LIMIT)
+            .read_to_end(&mut bytes)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+
+        let shown = if file_size > HEX_DUMP_LIMIT {
+            format!("first {} of {} bytes", bytes.len(), file_size)
+        } else {
+            format!("{} bytes", file_size)
+        };
+        let formatted = formatdoc! {r#"
+            ### {path} ({shown})
+            ```
+            {dump}```
+            "#,
+            path=path.display(),
+            shown=shown,
+            dump=hex_dump(&bytes),
+        };
+
+        Ok(vec![
+            Content::text(formatted.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(formatted)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_symbol_rename(
+        &self,
+        scope: &PathBuf,
+        symbol: &str,
+        new_name: &str,
+    ) -> Result<Vec<Content>, ToolError> {
+        for name in [symbol, new_name] {
+            if !rename::is_identifier(name) {
+                return Err(ToolError::InvalidParameters(format!(
+                    "'{}' is not a valid identifier",
+                    name
+                )));
+            }
+        }
+        if symbol == new_name {
+            return Err(ToolError::InvalidParameters(
+                "'new_name' must differ from 'symbol'".into(),
+            ));
+        }
+
+        let files: Vec<PathBuf> = if scope.is_file() {
+            vec![scope.clone()]
+        } else {
+            ignore::WalkBuilder::new(scope)
+                .build()
+                .flatten()
+                .map(|entry| entry.into_path())
+                .filter(|path| path.is_file() && !self.is_ignored(path))
+                .collect()
+        };
+
+        // Work out every change before writing anything
+        let mut renames = Vec::new();
+        let mut failures = Vec::new();
+        for path in files {
+            let too_large = std::fs::metadata(&path)
+                .map(|m| m.len() > self.config.max_file_size_bytes)
+                .unwrap_or(true);
+            if too_large {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match rename::rename_in_file(&path, &content, symbol, new_name) {
+                Ok(Some(rename)) => renames.push(rename),
+                Ok(None) => {}
+                Err(e) => failures.push(e),
+            }
+        }
+
+        if !failures.is_empty() {
+            return Err(ToolError::InvalidParameters(format!(
+                "No files were changed, renaming '{}' to '{}' is not safe:\n{}",
+                symbol,
+                new_name,
+                failures.join("\n")
+            )));
+        }
+        if renames.is_empty() {
+            return Err(ToolError::InvalidParameters(format!(
+                "No identifier named '{}' was found in {}",
+                symbol,
+                scope.display()
+            )));
+        }
+
+        for (i, rename) in renames.iter().enumerate() {
+            self.save_file_history(&rename.path)?;
+            if let Err(e) = write_file_atomic(&rename.path, &rename.renamed) {
+                // Put back the files already written so the rename is all or nothing
+                let mut history = self.file_history.lock().unwrap();
+                for done in &renames[..=i] {
+                    let _ = write_file_atomic(&done.path, &done.original);
+                    history
+                        .get_mut(&done.path)
+                        .and_then(|contents| contents.pop());
+                }
+                return Err(ToolError::ExecutionError(format!(
+                    "Failed to write {}, no files were changed: {}",
+                    rename.path.display(),
+                    e
+                )));
+            }
+        }
+
+        let occurrences: usize = renames.iter().map(|r| r.lines.len()).sum();
+        let mut summary = format!(
+            "Renamed '{}' to '{}' ({} occurrences in {} files):",
+            symbol,
+            new_name,
+            occurrences,
+            renames.len()
+        );
+        for rename in &renames {
+            summary.push_str(&format!(
+                "\n- {}: lines {}",
+                rename.path.display(),
+                rename::format_line_ranges(&rename.lines)
+            ));
+        }
+
+        Ok(vec![
+            Content::text(summary.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
+    async fn text_editor_spell_check(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+        let checker = spell::find_checker().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "spell_check needs aspell or hunspell, neither was found on the PATH".into(),
+            )
+        })?;
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let is_markdown = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("md" | "markdown" | "mdx")
+        );
+        let lines = if is_markdown {
+            spell::mask_markdown(&content)
+        } else {
+            content.lines().map(str::to_string).collect()
+        };
+
+        let misspellings = spell::check(&checker, &lines)
+            .await
+            .map_err(ToolError::ExecutionError)?;
+        if misspellings.is_empty() {
+            return Ok(vec![Content::text(format!(
+                "No spelling mistakes found in {}",
+                path.display()
+            ))]);
+        }
+
+        let result = json!({
+            "path": path.display().to_string(),
+            "misspellings": misspellings,
+        });
+        Ok(vec![
+            Content::text(serde_json::to_string_pretty(&result).unwrap_or_default())
+                .with_audience(vec![Role::Assistant]),
+            Content::text(formatdoc! {r#"
+                ### {path} ({count} misspelled)
+                {table}
+                "#,
+                path=path.display(),
+                count=misspellings.len(),
+                table=spell::format_table(&misspellings),
+            })
+            .with_audience(vec![Role::User])
+            .with_priority(0.0),
+        ])
+    }
+
+    async fn text_editor_render_markdown(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        use pulldown_cmark::{html, Options, Parser};
+
+        if !path.is_file() {
+            return Err(ToolError::ExecutionError(format!(
+                "The path '{}' does not exist or is not a file.",
+                path.display()
+            )));
+        }
+
+        let output_path = path.with_extension("html");
+        if self.is_ignored(&output_path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                output_path.display()
+            )));
+        }
+
+        let markdown = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+
+        let mut body = String::new();
+        html::push_html(&mut body, Parser::new_ext(&markdown, Options::all()));
+
+        let title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        let document = formatdoc! {r#"
+            <!DOCTYPE html>
+            <html>
+            <head>
+            <meta charset="utf-8">
+            <title>{title}</title>
+            </head>
+            <body>
+            {body}
+            </body>
+            </html>
+            "#,
+            title=title,
+            body=body,
+        };
+
+        // Keep the previous render around so it can be restored with undo_edit
+        self.save_file_history(&output_path)?;
+        std::fs::write(&output_path, document)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        // Only try to open a browser when there is a display to show it on
+        if std::env::var_os("DISPLAY").is_some() {
+            if let Err(e) = webbrowser::open(&output_path.to_string_lossy()) {
+                tracing::warn!(
+                    "Failed to open {} in a browser: {}",
+                    output_path.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(vec![
+            Content::text(format!(
+                "Rendered {} to {}",
+                path.display(),
+                output_path.display()
+            ))
+            .with_audience(vec![Role::Assistant]),
+            Content::text(format!(
+                "Rendered HTML written to {}",
+                output_path.display()
+            ))
+            .with_audience(vec![Role::User])
+            .with_priority(0.0),
+        ])
+    }
+
+    fn save_file_history(&self, path: &PathBuf) -> Result<(), ToolError> {
+        let mut history = self.file_history.lock().unwrap();
+        let content = if path.exists() {
+            std::fs::read_to_string(path)
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?
+        } else {
+            String::new()
+        };
+        let entries = history.entry(path.clone()).or_default();
+        entries.push(content);
+        let excess = entries.len().saturating_sub(self.config.max_history_depth);
+        entries.drain(..excess);
+        Ok(())
+    }
+
+    async fn list_windows(&self, _params: Value) -> Result<Vec<Content>, ToolError> {
+        let windows = Window::all()
+            .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
+
+        let window_titles: Vec<String> =
+            windows.into_iter().map(|w| w.title().to_string()).collect();
+
+        Ok(vec![
+            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
+                .with_audience(vec![Role::Assistant]),
+            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn clipboard_read(&self) -> Result<Vec<Content>, ToolError> {
+        let text = tokio::task::spawn_blocking(clipboard::read_text)
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+            .map_err(ToolError::ExecutionError)?;
+        Ok(vec![Content::text(text)])
+    }
+
+    async fn clipboard_write(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let text = params
+            .get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'text' parameter".into()))?
+            .to_string();
+        let chars = text.chars().count();
+        tokio::task::spawn_blocking(move || clipboard::write_text(&text))
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+            .map_err(ToolError::ExecutionError)?;
+        Ok(vec![Content::text(format!(
+            "Copied {} character{} to the clipboard",
+            chars,
+            if chars == 1 { "" } else { "s" }
+        ))])
+    }
+
+    // Helper function to handle Mac screenshot filenames that contain U+202F (narrow no-break space)
+    fn normalize_mac_screenshot_path(&self, path: &Path) -> PathBuf {
+        // Only process if the path has a filename
+        if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
+            // Check if this matches Mac screenshot pattern:
+            // "Screenshot YYYY-MM-DD at H.MM.SS AM/PM.png"
+            if let Some(captures) = regex::Regex::new(r"^Screenshot \d{4}-\d{2}-\d{2} at \d{1,2}\.\d{2}\.\d{2} (AM|PM|am|pm)(?: \(\d+\))?\.png$")
+                .ok()
+                .and_then(|re| re.captures(filename))
+            {
+
+                // Get the AM/PM part
+                let meridian = captures.get(1).unwrap().as_str();
+
+                // Find the last space before AM/PM and replace it with U+202F
+                let space_pos = filename.rfind(meridian)
+                    .map(|pos| filename[..pos].trim_end().len())
+                    .unwrap_or(0);
+
+                if space_pos > 0 {
+                    let parent = path.parent().unwrap_or(Path::new(""));
+                    let new_filename = format!(
+                        "{}{}{}",
+                        &filename[..space_pos],
+                        '\u{202F}',
+                        &filename[space_pos+1..]
+                    );
+                    let new_path = parent.join(new_filename);
+
+                    return new_path;
+                }
+            }
+        }
+        path.to_path_buf()
+    }
+
+    async fn notebook_execute(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let cell_source = params
+            .get("cell_source")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'cell_source' parameter".into())
+            })?;
+        let kernel_name = params
+            .get("kernel_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(notebook::DEFAULT_KERNEL);
+
+        let temp_dir = tempfile::tempdir().map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let notebook_path = temp_dir.path().join("cell.ipynb");
+        let notebook = notebook::single_cell_notebook(cell_source, kernel_name);
+        std::fs::write(&notebook_path, notebook.to_string())
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let executed = notebook::execute_notebook(
+            &notebook_path,
+            kernel_name,
+            Duration::from_secs(self.config.notebook_timeout_secs),
+        )
+        .await
+        .map_err(ToolError::ExecutionError)?;
+
+        let mut output = notebook::format_outputs(&executed);
+        if output.trim().is_empty() {
+            output = "The cell ran without output".to_string();
+        }
+
+        Ok(vec![
+            Content::text(output.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn lint_recipe(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let path = self.resolve_path(path_str)?;
+
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to read '{}': {}", path.display(), e))
+        })?;
+        let report = recipe_lint::format_report(&recipe_lint::lint(&content));
+
+        Ok(vec![
+            Content::text(report.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(report)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn watch_directory(
+        &self,
+        params: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let timeout_secs = params
+            .get("timeout_secs")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'timeout_secs' parameter".into())
+            })?;
+        let pattern = params
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| ToolError::InvalidParameters(format!("Invalid pattern: {}", e)))?;
+
+        let dir = self.resolve_path(path_str)?;
+        if !dir.is_dir() {
+            return Err(ToolError::InvalidParameters(format!(
+                "The path '{}' is not a directory",
+                dir.display()
+            )));
+        }
+        if self.is_ignored(&dir) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                dir.display()
+            )));
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to create file watcher: {}", e)))?;
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to watch directory: {}", e)))?;
+
+        // The last change seen for each file, a file that was created and then written to
+        // still shows as created
+        let mut changes: BTreeMap<PathBuf, &str> = BTreeMap::new();
+        let deadline = tokio::time::sleep(Duration::from_secs(timeout_secs));
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    let event: notify::Event = match event {
+                        Some(Ok(event)) => event,
+                        Some(Err(e)) => {
+                            return Err(ToolError::ExecutionError(format!(
+                                "Failed to watch directory: {}",
+                                e
+                            )))
+                        }
+                        None => break,
+                    };
+                    for path in &event.paths {
+                        let change = match event.kind {
+                            EventKind::Create(_) => "created",
+                            EventKind::Modify(ModifyKind::Metadata(_)) => continue,
+                            // Renames report both names, the one that still exists was created
+                            EventKind::Modify(ModifyKind::Name(_)) if path.exists() => "created",
+                            EventKind::Modify(ModifyKind::Name(_)) => "removed",
+                            EventKind::Modify(_) => "modified",
+                            EventKind::Remove(_) => "removed",
+                            _ => continue,
+                        };
+                        let relative = path.strip_prefix(&dir).unwrap_or(path);
+                        if path.is_dir()
+                            || self.is_ignored(path)
+                            || pattern.as_ref().is_some_and(|p| !p.matches_path(relative))
+                        {
+                            continue;
+                        }
+
+                        let previous = changes.get(relative).copied();
+                        let change = match (previous, change) {
+                            (Some("created"), "modified") => "created",
+                            _ => change,
+                        };
+                        if previous == Some(change) {
+                            continue;
+                        }
+                        changes.insert(relative.to_path_buf(), change);
+
+                        notifier
+                            .try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                                jsonrpc: JsonRpcVersion2_0,
+                                notification: Notification {
+                                    method: "notifications/message".to_string(),
+                                    params: object!({
+                                        "level": "info",
+                                        "data": {
+                                            "type": "watch_directory",
+                                            "path": path.display().to_string(),
+                                            "output": format!("{} {}", relative.display(), change),
+                                        }
+                                    }),
+                                    extensions: Default::default(),
+                                },
+                            }))
+                            .ok();
+                    }
+                }
+                _ = &mut deadline => break,
+            }
+        }
+
+        let summary = if changes.is_empty() {
+            format!("No changes in {} within {}s", dir.display(), timeout_secs)
+        } else {
+            let lines: Vec<String> = changes
+                .iter()
+                .map(|(path, change)| format!("{} {}", change, path.display()))
+                .collect();
+            format!(
+                "Changes in {} within {}s:\n{}",
+                dir.display(),
+                timeout_secs,
+                lines.join("\n")
+            )
+        };
+
+        Ok(vec![
+            Content::text(summary.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn image_processor(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+
+        let path = {
+            let p = self.resolve_path(path_str)?;
+            if cfg!(target_os = "macos") {
+                self.normalize_mac_screenshot_path(&p)
+            } else {
+                p
+            }
+        };
+
+        // Check if file is ignored before proceeding
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+
+        // Check if file exists
+        if !path.exists() {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' does not exist",
+                path.display()
+            )));
+        }
+
+        check_image_file_size(&path)?;
+
+        // Open and decode the image
+        let image = xcap::image::open(&path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to open image file: {}", e)))?;
+
+        // Resize if necessary (same logic as screen_capture)
+        let mut processed_image = image;
+        let max_width = 768;
+        if processed_image.width() > max_width {
+            let scale = max_width as f32 / processed_image.width() as f32;
+            let new_height = (processed_image.height() as f32 * scale) as u32;
+            processed_image = xcap::image::DynamicImage::ImageRgba8(xcap::image::imageops::resize(
+                &processed_image,
+                max_width,
+                new_height,
+                xcap::image::imageops::FilterType::Lanczos3,
+            ));
+        }
+
+        // Convert to PNG and encode as base64
+        let mut bytes: Vec<u8> = Vec::new();
+        processed_image
+            .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to write image buffer: {}", e))
+            })?;
+
+        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+
+        Ok(vec![
+            Content::text(format!(
+                "Successfully processed image from {}",
+                path.display()
+            ))
+            .with_audience(vec![Role::Assistant]),
+            Content::image(data, "image/png").with_priority(0.0),
+        ])
+    }
+
+    async fn screen_capture(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let mut image = if let Some(window_title) =
+            params.get("window_title").and_then(|v| v.as_str())
+        {
+            // Try to find and capture the specified window
+            let windows = Window::all()
+                .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
+
+            let window = windows
+                .into_iter()
+                .find(|w| w.title() == window_title)
+                .ok_or_else(|| {
+                    ToolError::ExecutionError(format!(
+                        "No window found with title '{}'",
+                        window_title
+                    ))
+                })?;
+
+            window.capture_image().map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to capture window '{}': {}",
+                    window_title, e
+                ))
+            })?
+        } else {
+            // Default to display capture if no window title is specified
+            let display = params.get("display").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+
+            let monitors = Monitor::all()
+                .map_err(|_| ToolError::ExecutionError("Failed to access monitors".into()))?;
+            let monitor = monitors.get(display).ok_or_else(|| {
+                ToolError::ExecutionError(format!(
+                    "{} was not an available monitor, {} found.",
+                    display,
+                    monitors.len()
+                ))
+            })?;
+
+            monitor.capture_image().map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to capture display {}: {}", display, e))
+            })?
+        };
+
+        // Resize the image to a reasonable width while maintaining aspect ratio
+        let max_width = 768;
+        if image.width() > max_width {
+            let scale = max_width as f32 / image.width() as f32;
+            let new_height = (image.height() as f32 * scale) as u32;
+            image = xcap::image::imageops::resize(
+                &image,
+                max_width,
+                new_height,
+                xcap::image::imageops::FilterType::Lanczos3,
+            )
+        };
+
+        let mut bytes: Vec<u8> = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to write image buffer {}", e))
+            })?;
+
+        // Convert to base64
+        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+
+        Ok(vec![
+            Content::text("Screenshot captured").with_audience(vec![Role::Assistant]),
+            Content::image(data, "image/png").with_priority(0.0),
+        ])
+    }
+}
+
+impl Router for DeveloperRouter {
+    fn name(&self) -> String {
+        "developer".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new()
+            .with_tools(false)
+            .with_prompts(false)
+            .build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "shell" => {
+                    this.bash(arguments, notifier, CancellationToken::new())
+                        .await
+                }
+                "glob" => this.glob(arguments).await,
+                "grep" => {
+                    this.bash(arguments, notifier, CancellationToken::new())
+                        .await
+                }
+                "text_editor" => this.text_editor(arguments, notifier).await,
+                "list_windows" if this.config.enable_screen_capture => {
+                    this.list_windows(arguments).await
+                }
+                "screen_capture" if this.config.enable_screen_capture => {
+                    this.screen_capture(arguments).await
+                }
+                "image_processor" if this.config.enable_image_processor => {
+                    this.image_processor(arguments).await
+                }
+                "clipboard_read" if this.config.enable_clipboard_tools => {
+                    this.clipboard_read().await
+                }
+                "clipboard_write" if this.config.enable_clipboard_tools => {
+                    this.clipboard_write(arguments).await
+                }
+                "image_ocr" => this.image_ocr(arguments).await,
+                "notebook_execute" => this.notebook_execute(arguments).await,
+                "lint_recipe" if this.config.enable_lint_recipe => {
+                    this.lint_recipe(arguments).await
+                }
+                "watch_directory" if this.config.enable_watch_directory => {
+                    this.watch_directory(arguments, notifier).await
+                }
+                "profile" if this.config.enable_profiling_tools => {
+                    this.profile(arguments, CancellationToken::new()).await
+                }
+                "benchmark" if this.config.enable_profiling_tools => {
+                    this.benchmark(arguments, CancellationToken::new()).await
+                }
+                "sql_query" if this.config.enable_sql_tools => this.sql_query(arguments).await,
+                "csv_to_sql" if this.config.enable_sql_tools => this.csv_to_sql(arguments).await,
+                "run_tests" if this.config.enable_run_tests => {
+                    this.run_tests(arguments, CancellationToken::new()).await
+                }
+                "generate_uuid" if this.config.enable_generate_uuid => {
+                    this.generate_uuid(arguments).await
+                }
+                "env_inspect" if this.config.enable_system_inspection => this.env_inspect().await,
+                "network_inspect" if this.config.enable_system_inspection => {
+                    this.network_inspect(arguments).await
+                }
+                "process_list" if this.config.enable_system_inspection => {
+                    this.process_list(arguments).await
+                }
+                "dependency_graph" if this.config.enable_dependency_graph => {
+                    this.dependency_graph(arguments).await
+                }
+                "run_in_container" if this.config.enable_container_tool => {
+                    this.run_in_container(arguments, notifier, CancellationToken::new())
+                        .await
+                }
+                "terminal_record" if this.config.enable_terminal_recording => {
+                    this.terminal_record(arguments).await
+                }
+                "terminal_replay" if this.config.enable_terminal_recording => {
+                    this.terminal_replay(arguments, notifier, CancellationToken::new())
+                        .await
+                }
+                "semantic_search" if this.config.embedding_client.is_some() => {
+                    this.semantic_search(arguments).await
+                }
+                name => match this.plugins.get(name) {
+                    Some(plugin) => plugin.call(arguments).await,
+                    None => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+                },
+            }
+        })
+    }
+
+    fn call_tool_with_cancellation(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+        cancel_token: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        // Only shell commands run long enough to be worth stopping early
+        match tool_name {
+            "shell" | "grep" => {
+                let this = self.clone();
+                Box::pin(async move { this.bash(arguments, notifier, cancel_token).await })
+            }
+            "profile" if self.config.enable_profiling_tools => {
+                let this = self.clone();
+                Box::pin(async move { this.profile(arguments, cancel_token).await })
+            }
+            "benchmark" if self.config.enable_profiling_tools => {
+                let this = self.clone();
+                Box::pin(async move { this.benchmark(arguments, cancel_token).await })
+            }
+            "run_tests" if self.config.enable_run_tests => {
+                let this = self.clone();
+                Box::pin(async move { this.run_tests(arguments, cancel_token).await })
+            }
+            "run_in_container" if self.config.enable_container_tool => {
+                let this = self.clone();
+                Box::pin(async move {
+                    this.run_in_container(arguments, notifier, cancel_token)
+                        .await
+                })
+            }
+            "terminal_replay" if self.config.enable_terminal_recording => {
+                let this = self.clone();
+                Box::pin(async move {
+                    this.terminal_replay(arguments, notifier, cancel_token)
+                        .await
+                })
+            }
+            _ => self.call_tool(tool_name, arguments, notifier),
+        }
+    }
+
+    // TODO see if we can make it easy to skip implementing these
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        _uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        Box::pin(async move { Ok("".to_string()) })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        self.prompts.values().cloned().collect()
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.trim().to_owned();
+
+        // Validate prompt name is not empty
+        if prompt_name.is_empty() {
+            return Box::pin(async move {
+                Err(PromptError::InvalidParameters(
+                    "Prompt name cannot be empty".to_string(),
+                ))
+            });
+        }
+
+        let prompts = Arc::clone(&self.prompts);
+
+        Box::pin(async move {
+            match prompts.get(&prompt_name) {
+                Some(prompt) => Ok(prompt.description.clone().unwrap_or_default()),
+                None => Err(PromptError::NotFound(format!(
+                    "Prompt '{prompt_name}' not found"
+                ))),
+            }
+        })
+    }
+}
+
+impl Clone for DeveloperRouter {
+    fn clone(&self) -> Self {
+        Self {
+            tools: self.tools.clone(),
+            prompts: Arc::clone(&self.prompts),
+            instructions: self.instructions.clone(),
+            file_history: Arc::clone(&self.file_history),
+            ignore_patterns: Arc::clone(&self.ignore_patterns),
+            config: self.config.clone(),
+            output_cache: Arc::clone(&self.output_cache),
+            plugins: self.plugins.clone(),
+            recording: Arc::clone(&self.recording),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use serial_test::serial;
+    use std::fs;
+    use tempfile::TempDir;
+    use tokio::sync::OnceCell;
+
+    #[test]
+    #[serial]
+    fn test_global_goosehints() {
+        // if ~/.config/goose/.goosehints exists, it should be included in the instructions
+        // copy the existing global hints file to a .bak file
+        let global_hints_path =
+            PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints").to_string());
+        let global_hints_bak_path =
+            PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints.bak").to_string());
+        let mut globalhints_existed = false;
+
+        if global_hints_path.is_file() {
+            globalhints_existed = true;
+            fs::copy(&global_hints_path, &global_hints_bak_path).unwrap();
+        }
+
+        fs::write(&global_hints_path, "These are my global goose hints.").unwrap();
+
+        let dir = TempDir::new().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+
+        let router = DeveloperRouter::new();
+        let instructions = router.instructions();
+
+        assert!(instructions.contains("### Global Hints"));
+        assert!(instructions.contains("my global goose hints."));
+
+        // restore backup if globalhints previously existed
+        if globalhints_existed {
+            fs::copy(&global_hints_bak_path, &global_hints_path).unwrap();
+            fs::remove_file(&global_hints_bak_path).unwrap();
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_goosehints_when_present() {
+        let dir = TempDir::new().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+
+        fs::write(".goosehints", "Test hint content").unwrap();
+        let router = DeveloperRouter::new();
+        let instructions = router.instructions();
+
+        assert!(instructions.contains("Test hint content"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_goosehints_when_missing() {
+        let dir = TempDir::new().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+
+        let router = DeveloperRouter::new();
+        let instructions = router.instructions();
+
+        assert!(!instructions.contains("Project Hints"));
+    }
+
+    static DEV_ROUTER: OnceCell<DeveloperRouter> = OnceCell::const_new();
+
+    async fn get_router() -> &'static DeveloperRouter {
+        DEV_ROUTER
+            .get_or_init(|| async {
+                DeveloperRouter::new_with_config(DeveloperRouterConfig {
+                    enable_lint_recipe: true,
+                    enable_watch_directory: true,
+                    enable_profiling_tools: true,
+                    enable_sql_tools: true,
+                    enable_run_tests: true,
+                    enable_generate_uuid: true,
+                    enable_system_inspection: true,
+                    enable_dependency_graph: true,
+                    ..Default::default()
+                })
+            })
+            .await
+    }
+
+    fn dummy_sender() -> mpsc::Sender<JsonRpcMessage> {
+        mpsc::channel(1).0
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_shell_missing_parameters() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = get_router().await;
+        let result = router.call_tool("shell", json!({}), dummy_sender()).await;
+
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    #[cfg(not(windows))]
+    async fn test_shell_cancellation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = get_router().await;
+        let cancel_token = CancellationToken::new();
+        let call = router.call_tool_with_cancellation(
+            "shell",
+            json!({"command": "sleep 30"}),
+            dummy_sender(),
+            cancel_token.clone(),
+        );
+        cancel_token.cancel();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), call)
+            .await
+            .expect("cancelled command should stop promptly");
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("was cancelled"));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    #[cfg(windows)]
+    async fn test_windows_specific_commands() {
+        let router = get_router().await;
+
+        // Test PowerShell command
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": "Get-ChildItem"
+                }),
+            )
+            .await;
+        assert!(result.is_ok());
+
+        // Test Windows path handling
+        let result = router.resolve_path("C:\\Windows\\System32");
+        assert!(result.is_ok());
+
+        // Test UNC path handling
+        let result = router.resolve_path("\\\\server\\share");
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_size_limits() {
+        // Create temp directory first so it stays in scope for the whole test
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Get router after setting current directory
+        let router = get_router().await;
+
+        // Test file size limit
+        {
+            let large_file_path = temp_dir.path().join("large.txt");
+            let large_file_str = large_file_path.to_str().unwrap();
+
+            // Create a file larger than 2MB
+            let content = "x".repeat(3 * 1024 * 1024); // 3MB
+            std::fs::write(&large_file_path, content).unwrap();
+
+            let result = router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "view",
+                        "path": large_file_str
+                    }),
+                    dummy_sender(),
+                )
+                .await;
+
+            assert!(result.is_err());
+            let err = result.err().unwrap();
+            assert!(matches!(err, ToolError::ExecutionError(_)));
+            assert!(err.to_string().contains("too large"));
+        }
+
+        // Test character count limit
+        {
+            let many_chars_path = temp_dir.path().join("many_chars.txt");
+            let many_chars_str = many_chars_path.to_str().unwrap();
+
+            // Create a file with more than 400K characters but less than 400KB
+            let content = "x".repeat(405_000);
+            std::fs::write(&many_chars_path, content).unwrap();
+
+            let result = router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "view",
+                        "path": many_chars_str
+                    }),
+                    dummy_sender(),
+                )
+                .await;
+
+            assert!(result.is_err());
+            let err = result.err().unwrap();
+            assert!(matches!(err, ToolError::ExecutionError(_)));
+            assert!(err.to_string().contains("too many characters"));
+        }
+
+        // Let temp_dir drop naturally at end of scope
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_write_and_view_file() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a new file
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": "Hello, world!"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // View the file
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        assert!(!view_result.is_empty());
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        assert!(text.text.contains("Hello, world!"));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_str_replace() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a new file
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": "Hello, world!"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // Replace string
+        let replace_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "str_replace",
+                    "path": file_path_str,
+                    "old_str": "world",
+                    "new_str": "Rust"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let text = replace_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::Assistant))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+
+        assert!(text
+            .text
+            .contains("has been edited, and the section now reads"));
+
+        // View the file to verify the change
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+
+        // Check that the file has been modified and contains some form of "Rust"
+        // The Editor API might transform the content differently than simple string replacement
+        assert!(
+            text.text.contains("Rust") || text.text.contains("Hello, Rust!"),
+            "Expected content to contain 'Rust', but got: {}",
+            text.text
+        );
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_history_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            max_history_depth: 2,
+            ..Default::default()
+        });
+        let file_path = temp_dir.path().join("notes.txt");
+        let path = file_path.to_str().unwrap();
+        let edit = |args: Value| {
+            let router = &router;
+            async move {
+                router
+                    .call_tool("text_editor", args, dummy_sender())
+                    .await
+                    .map(|result| result[0].as_text().unwrap().text.clone())
+            }
+        };
+
+        let depth = edit(json!({"command": "history_depth", "path": path}))
+            .await
+            .unwrap();
+        assert!(depth.starts_with("0 edits"));
+
+        edit(json!({"command": "write", "path": path, "file_text": "one"}))
+            .await
+            .unwrap();
+        for (old, new) in [("one", "two"), ("two", "three"), ("three", "four")] {
+            edit(json!({"command": "str_replace", "path": path, "old_str": old, "new_str": new}))
+                .await
+                .unwrap();
+        }
+        let depth = edit(json!({"command": "history_depth", "path": path}))
+            .await
+            .unwrap();
+        assert!(depth.starts_with("2 edits"));
+
+        // Only the two most recent edits can be undone
+        edit(json!({"command": "undo_edit", "path": path}))
+            .await
+            .unwrap();
+        edit(json!({"command": "undo_edit", "path": path}))
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap().trim_end(), "two");
+        assert!(edit(json!({"command": "undo_edit", "path": path}))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_undo_edit() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a new file
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": "First line"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // Replace string
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "str_replace",
+                    "path": file_path_str,
+                    "old_str": "First line",
+                    "new_str": "Second line"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // Undo the edit
+        let undo_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "undo_edit",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let text = undo_result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("Undid the last edit"));
+
+        // View the file to verify the undo
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        assert!(text.text.contains("First line"));
+
+        temp_dir.close().unwrap();
+    }
+
+    // Test GooseIgnore pattern matching
+    #[tokio::test]
+    #[serial]
+    async fn test_goose_ignore_basic_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a DeveloperRouter with custom ignore patterns
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "secret.txt").unwrap();
+        builder.add_line(None, "*.env").unwrap();
+        let ignore_patterns = builder.build().unwrap();
+
+        let router = DeveloperRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(ignore_patterns),
+            config: DeveloperRouterConfig::default(),
+            output_cache: Arc::new(Mutex::new(HashMap::new())),
+            plugins: HashMap::new(),
+            recording: Arc::new(Mutex::new(None)),
+        };
+
+        // Test basic file matching
+        assert!(
+            router.is_ignored(Path::new("secret.txt")),
+            "secret.txt should be ignored"
+        );
+        assert!(
+            router.is_ignored(Path::new("./secret.txt")),
+            "./secret.txt should be ignored"
+        );
+        assert!(
+            !router.is_ignored(Path::new("not_secret.txt")),
+            "not_secret.txt should not be ignored"
+        );
+
+        // Test pattern matching
+        assert!(
+            router.is_ignored(Path::new("test.env")),
+            "*.env pattern should match test.env"
+        );
+        assert!(
+            router.is_ignored(Path::new("./test.env")),
+            "*.env pattern should match ./test.env"
+        );
+        assert!(
+            !router.is_ignored(Path::new("test.txt")),
+            "*.env pattern should not match test.txt"
+        );
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_respects_ignore_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a DeveloperRouter with custom ignore patterns
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "secret.txt").unwrap();
+        let ignore_patterns = builder.build().unwrap();
+
+        let router = DeveloperRouter {
+            tools: DeveloperRouter::new().tools, // Reuse default tools
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(ignore_patterns),
+            config: DeveloperRouterConfig::default(),
+            output_cache: Arc::new(Mutex::new(HashMap::new())),
+            plugins: HashMap::new(),
+            recording: Arc::new(Mutex::new(None)),
+        };
+
+        // Try to write to an ignored file
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": temp_dir.path().join("secret.txt").to_str().unwrap(),
+                    "file_text": "test content"
+                }),
+                dummy_sender(),
+            )
+            .await;
+
+        assert!(
+            result.is_err(),
+            "Should not be able to write to ignored file"
+        );
+        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
+
+        // Try to write to a non-ignored file
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": temp_dir.path().join("allowed.txt").to_str().unwrap(),
+                    "file_text": "test content"
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-        // Show a snippet of the changed content with context
-        const SNIPPET_LINES: usize = 4;
+        assert!(
+            result.is_ok(),
+            "Should be able to write to non-ignored file"
+        );
 
-        // Count newlines before the replacement to find the line number
-        let replacement_line = content
-            .split(old_str)
-            .next()
-            .expect("should split on already matched content")
-            .matches('\n')
-            .count();
+        temp_dir.close().unwrap();
+    }
 
-        // Calculate start and end lines for the snippet
-        let start_line = replacement_line.saturating_sub(SNIPPET_LINES);
-        let end_line = replacement_line + SNIPPET_LINES + new_content.matches('\n').count();
+    #[tokio::test]
+    #[serial]
+    async fn test_bash_respects_ignore_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Get the relevant lines for our snippet
-        let lines: Vec<&str> = new_content.lines().collect();
-        let snippet = lines
-            .iter()
-            .skip(start_line)
-            .take(end_line - start_line + 1)
-            .cloned()
-            .collect::<Vec<&str>>()
-            .join("\n");
+        // Create a DeveloperRouter with custom ignore patterns
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "secret.txt").unwrap();
+        let ignore_patterns = builder.build().unwrap();
 
-        let output = formatdoc! {r#"
-            ```{language}
-            {snippet}
-            ```
-            "#,
-            language=language,
-            snippet=snippet
+        let router = DeveloperRouter {
+            tools: DeveloperRouter::new().tools, // Reuse default tools
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(ignore_patterns),
+            config: DeveloperRouterConfig::default(),
+            output_cache: Arc::new(Mutex::new(HashMap::new())),
+            plugins: HashMap::new(),
+            recording: Arc::new(Mutex::new(None)),
         };
 
-        let success_message = formatdoc! {r#"
-            The file {} has been edited, and the section now reads:
-            {}
-            Review the changes above for errors. Undo and edit the file again if necessary!
-            "#,
-            path.display(),
-            output
-        };
+        // Create an ignored file
+        let secret_file_path = temp_dir.path().join("secret.txt");
+        std::fs::write(&secret_file_path, "secret content").unwrap();
 
-        Ok(vec![
-            Content::text(success_message).with_audience(vec![Role::Assistant]),
-            Content::text(output)
-                .with_audience(vec![Role::User])
-                .with_priority(0.2),
-        ])
-    }
+        // Try to cat the ignored file
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": format!("cat {}", secret_file_path.to_str().unwrap())
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-    async fn text_editor_insert(
-        &self,
-        path: &PathBuf,
-        insert_line: usize,
-        new_str: &str,
-    ) -> Result<Vec<Content>, ToolError> {
-        // Check if file exists
-        if !path.exists() {
-            return Err(ToolError::InvalidParameters(format!(
-                "File '{}' does not exist, you can write a new file with the `write` command",
-                path.display()
-            )));
-        }
+        assert!(result.is_err(), "Should not be able to cat ignored file");
+        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
 
-        // Read content
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        // Try to cat a non-ignored file
+        let allowed_file_path = temp_dir.path().join("allowed.txt");
+        std::fs::write(&allowed_file_path, "allowed content").unwrap();
 
-        // Save history for undo
-        self.save_file_history(path)?;
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": format!("cat {}", allowed_file_path.to_str().unwrap())
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-        let lines: Vec<&str> = content.lines().collect();
-        let total_lines = lines.len();
+        assert!(result.is_ok(), "Should be able to cat non-ignored file");
 
-        // Validate insert_line parameter
-        if insert_line > total_lines {
-            return Err(ToolError::InvalidParameters(format!(
-                "Insert line {} is beyond the end of the file (total lines: {}). Use 0 to insert at the beginning or {} to insert at the end.",
-                insert_line, total_lines, total_lines
-            )));
-        }
+        temp_dir.close().unwrap();
+    }
 
-        // Create new content with inserted text
-        let mut new_lines = Vec::new();
+    #[tokio::test]
+    #[serial]
+    async fn test_gitignore_fallback_when_no_gooseignore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Add lines before the insertion point
-        for (i, line) in lines.iter().enumerate() {
-            if i == insert_line {
-                // Insert the new text at this position
-                new_lines.push(new_str.to_string());
-            }
-            new_lines.push(line.to_string());
-        }
+        // Create a .gitignore file but no .gooseignore
+        std::fs::write(temp_dir.path().join(".gitignore"), "*.log\n*.tmp\n.env").unwrap();
 
-        // If inserting at the end (after all existing lines)
-        if insert_line == total_lines {
-            new_lines.push(new_str.to_string());
-        }
+        let router = DeveloperRouter::new();
 
-        let new_content = new_lines.join("\n");
-        let normalized_content = normalize_line_endings(&new_content);
+        // Test that gitignore patterns are respected
+        assert!(
+            router.is_ignored(Path::new("test.log")),
+            "*.log pattern from .gitignore should be ignored"
+        );
+        assert!(
+            router.is_ignored(Path::new("build.tmp")),
+            "*.tmp pattern from .gitignore should be ignored"
+        );
+        assert!(
+            router.is_ignored(Path::new(".env")),
+            ".env pattern from .gitignore should be ignored"
+        );
+        assert!(
+            !router.is_ignored(Path::new("test.txt")),
+            "test.txt should not be ignored"
+        );
 
-        // Ensure the file ends with a newline
-        let final_content = if !normalized_content.ends_with('\n') {
-            format!("{}\n", normalized_content)
-        } else {
-            normalized_content
-        };
+        temp_dir.close().unwrap();
+    }
 
-        std::fs::write(path, &final_content)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+    #[tokio::test]
+    #[serial]
+    async fn test_gooseignore_takes_precedence_over_gitignore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Try to detect the language from the file extension
-        let language = lang::get_language_identifier(path);
+        // Create both .gooseignore and .gitignore files with different patterns
+        std::fs::write(temp_dir.path().join(".gooseignore"), "*.secret").unwrap();
+        std::fs::write(temp_dir.path().join(".gitignore"), "*.log\ntarget/").unwrap();
 
-        // Show a snippet of the inserted content with context
-        const SNIPPET_LINES: usize = 4;
-        let insertion_line = insert_line + 1; // Convert to 1-indexed for display
+        let router = DeveloperRouter::new();
 
-        // Calculate start and end lines for the snippet
-        let start_line = insertion_line.saturating_sub(SNIPPET_LINES);
-        let end_line = std::cmp::min(insertion_line + SNIPPET_LINES, new_lines.len());
+        // .gooseignore patterns should be used
+        assert!(
+            router.is_ignored(Path::new("test.secret")),
+            "*.secret pattern from .gooseignore should be ignored"
+        );
 
-        // Get the relevant lines for our snippet with line numbers
-        let snippet_lines: Vec<String> = new_lines[start_line.saturating_sub(1)..end_line]
-            .iter()
-            .enumerate()
-            .map(|(i, line)| format!("{}: {}", start_line + i, line))
-            .collect();
+        // .gitignore patterns should NOT be used when .gooseignore exists
+        assert!(
+            !router.is_ignored(Path::new("test.log")),
+            "*.log pattern from .gitignore should NOT be ignored when .gooseignore exists"
+        );
+        assert!(
+            !router.is_ignored(Path::new("build.tmp")),
+            "*.tmp pattern from .gitignore should NOT be ignored when .gooseignore exists"
+        );
 
-        let snippet = snippet_lines.join("\n");
+        temp_dir.close().unwrap();
+    }
 
-        let output = formatdoc! {r#"
-            ```{language}
-            {snippet}
-            ```
-            "#,
-            language=language,
-            snippet=snippet
-        };
+    #[tokio::test]
+    #[serial]
+    async fn test_default_patterns_when_no_ignore_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-        let success_message = formatdoc! {r#"
-            Text has been inserted at line {} in {}. The section now reads:
-            {}
-            Review the changes above for errors. Undo and edit the file again if necessary!
-            "#,
-            insertion_line,
-            path.display(),
-            output
-        };
+        // Don't create any ignore files
+        let router = DeveloperRouter::new();
 
-        Ok(vec![
-            Content::text(success_message).with_audience(vec![Role::Assistant]),
-            Content::text(output)
-                .with_audience(vec![Role::User])
-                .with_priority(0.2),
-        ])
-    }
+        // Default patterns should be used
+        assert!(
+            router.is_ignored(Path::new(".env")),
+            ".env should be ignored by default patterns"
+        );
+        assert!(
+            router.is_ignored(Path::new(".env.local")),
+            ".env.local should be ignored by default patterns"
+        );
+        assert!(
+            router.is_ignored(Path::new("secrets.txt")),
+            "secrets.txt should be ignored by default patterns"
+        );
+        assert!(
+            !router.is_ignored(Path::new("normal.txt")),
+            "normal.txt should not be ignored"
+        );
 
-    async fn text_editor_undo(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
-        let mut history = self.file_history.lock().unwrap();
-        if let Some(contents) = history.get_mut(path) {
-            if let Some(previous_content) = contents.pop() {
-                // Write previous content back to file
-                std::fs::write(path, previous_content).map_err(|e| {
-                    ToolError::ExecutionError(format!("Failed to write file: {}", e))
-                })?;
-                Ok(vec![Content::text("Undid the last edit")])
-            } else {
-                Err(ToolError::InvalidParameters(
-                    "No edit history available to undo".into(),
-                ))
-            }
-        } else {
-            Err(ToolError::InvalidParameters(
-                "No edit history available to undo".into(),
-            ))
-        }
+        temp_dir.close().unwrap();
     }
 
-    fn save_file_history(&self, path: &PathBuf) -> Result<(), ToolError> {
-        let mut history = self.file_history.lock().unwrap();
-        let content = if path.exists() {
-            std::fs::read_to_string(path)
-                .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?
-        } else {
-            String::new()
-        };
-        history.entry(path.clone()).or_default().push(content);
-        Ok(())
-    }
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_descriptions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-    async fn list_windows(&self, _params: Value) -> Result<Vec<Content>, ToolError> {
-        let windows = Window::all()
-            .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
+        // Test without editor API configured (should be the case in tests due to cfg!(test))
+        let router = DeveloperRouter::new();
+        let tools = router.list_tools();
+        let text_editor_tool = tools.iter().find(|t| t.name == "text_editor").unwrap();
 
-        let window_titles: Vec<String> =
-            windows.into_iter().map(|w| w.title().to_string()).collect();
+        // Should use traditional description with str_replace command
+        assert!(text_editor_tool
+            .description
+            .as_ref()
+            .map_or(false, |desc| desc
+                .contains("Replace a string in a file with a new string")));
+        assert!(text_editor_tool
+            .description
+            .as_ref()
+            .map_or(false, |desc| desc
+                .contains("the `old_str` needs to exactly match one")));
+        assert!(text_editor_tool
+            .description
+            .as_ref()
+            .map_or(false, |desc| desc.contains("str_replace")));
 
-        Ok(vec![
-            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
-                .with_audience(vec![Role::Assistant]),
-            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
-                .with_audience(vec![Role::User])
-                .with_priority(0.0),
-        ])
+        // Should not contain editor API description or edit_file command
+        assert!(!text_editor_tool
+            .description
+            .as_ref()
+            .map_or(false, |desc| desc
+                .contains("Edit the file with the new content")));
+        assert!(!text_editor_tool
+            .description
+            .as_ref()
+            .map_or(false, |desc| desc.contains("edit_file")));
+        assert!(!text_editor_tool
+            .description
+            .as_ref()
+            .map_or(false, |desc| desc
+                .contains("work out how to place old_str with it intelligently")));
+
+        temp_dir.close().unwrap();
     }
 
-    // Helper function to handle Mac screenshot filenames that contain U+202F (narrow no-break space)
-    fn normalize_mac_screenshot_path(&self, path: &Path) -> PathBuf {
-        // Only process if the path has a filename
-        if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
-            // Check if this matches Mac screenshot pattern:
-            // "Screenshot YYYY-MM-DD at H.MM.SS AM/PM.png"
-            if let Some(captures) = regex::Regex::new(r"^Screenshot \d{4}-\d{2}-\d{2} at \d{1,2}\.\d{2}\.\d{2} (AM|PM|am|pm)(?: \(\d+\))?\.png$")
-                .ok()
-                .and_then(|re| re.captures(filename))
-            {
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_respects_gitignore_fallback() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-                // Get the AM/PM part
-                let meridian = captures.get(1).unwrap().as_str();
+        // Create a .gitignore file but no .gooseignore
+        std::fs::write(temp_dir.path().join(".gitignore"), "*.log").unwrap();
 
-                // Find the last space before AM/PM and replace it with U+202F
-                let space_pos = filename.rfind(meridian)
-                    .map(|pos| filename[..pos].trim_end().len())
-                    .unwrap_or(0);
+        let router = DeveloperRouter::new();
 
-                if space_pos > 0 {
-                    let parent = path.parent().unwrap_or(Path::new(""));
-                    let new_filename = format!(
-                        "{}{}{}",
-                        &filename[..space_pos],
-                        '\u{202F}',
-                        &filename[space_pos+1..]
-                    );
-                    let new_path = parent.join(new_filename);
+        // Try to write to a file ignored by .gitignore
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": temp_dir.path().join("test.log").to_str().unwrap(),
+                    "file_text": "test content"
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-                    return new_path;
-                }
-            }
-        }
-        path.to_path_buf()
-    }
+        assert!(
+            result.is_err(),
+            "Should not be able to write to file ignored by .gitignore fallback"
+        );
+        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
 
-    async fn image_processor(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let path_str = params
-            .get("path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        // Try to write to a non-ignored file
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": temp_dir.path().join("allowed.txt").to_str().unwrap(),
+                    "file_text": "test content"
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-        let path = {
-            let p = self.resolve_path(path_str)?;
-            if cfg!(target_os = "macos") {
-                self.normalize_mac_screenshot_path(&p)
-            } else {
-                p
-            }
-        };
+        assert!(
+            result.is_ok(),
+            "Should be able to write to non-ignored file"
+        );
 
-        // Check if file is ignored before proceeding
-        if self.is_ignored(&path) {
-            return Err(ToolError::ExecutionError(format!(
-                "Access to '{}' is restricted by .gooseignore",
-                path.display()
-            )));
-        }
+        temp_dir.close().unwrap();
+    }
 
-        // Check if file exists
-        if !path.exists() {
-            return Err(ToolError::ExecutionError(format!(
-                "File '{}' does not exist",
-                path.display()
-            )));
-        }
+    #[tokio::test]
+    #[serial]
+    async fn test_bash_respects_gitignore_fallback() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Check file size (10MB limit for image files)
-        const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB in bytes
-        let file_size = std::fs::metadata(&path)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
-            .len();
+        // Create a .gitignore file but no .gooseignore
+        std::fs::write(temp_dir.path().join(".gitignore"), "*.log").unwrap();
 
-        if file_size > MAX_FILE_SIZE {
-            return Err(ToolError::ExecutionError(format!(
-                "File '{}' is too large ({:.2}MB). Maximum size is 10MB.",
-                path.display(),
-                file_size as f64 / (1024.0 * 1024.0)
-            )));
-        }
+        let router = DeveloperRouter::new();
 
-        // Open and decode the image
-        let image = xcap::image::open(&path)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to open image file: {}", e)))?;
+        // Create a file that would be ignored by .gitignore
+        let log_file_path = temp_dir.path().join("test.log");
+        std::fs::write(&log_file_path, "log content").unwrap();
 
-        // Resize if necessary (same logic as screen_capture)
-        let mut processed_image = image;
-        let max_width = 768;
-        if processed_image.width() > max_width {
-            let scale = max_width as f32 / processed_image.width() as f32;
-            let new_height = (processed_image.height() as f32 * scale) as u32;
-            processed_image = xcap::image::DynamicImage::ImageRgba8(xcap::image::imageops::resize(
-                &processed_image,
-                max_width,
-                new_height,
-                xcap::image::imageops::FilterType::Lanczos3,
-            ));
-        }
+        // Try to cat the ignored file
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": format!("cat {}", log_file_path.to_str().unwrap())
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-        // Convert to PNG and encode as base64
-        let mut bytes: Vec<u8> = Vec::new();
-        processed_image
-            .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
-            .map_err(|e| {
-                ToolError::ExecutionError(format!("Failed to write image buffer: {}", e))
-            })?;
+        assert!(
+            result.is_err(),
+            "Should not be able to cat file ignored by .gitignore fallback"
+        );
+        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
+
+        // Try to cat a non-ignored file
+        let allowed_file_path = temp_dir.path().join("allowed.txt");
+        std::fs::write(&allowed_file_path, "allowed content").unwrap();
+
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": format!("cat {}", allowed_file_path.to_str().unwrap())
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+        assert!(result.is_ok(), "Should be able to cat non-ignored file");
 
-        Ok(vec![
-            Content::text(format!(
-                "Successfully processed image from {}",
-                path.display()
-            ))
-            .with_audience(vec![Role::Assistant]),
-            Content::image(data, "image/png").with_priority(0.0),
-        ])
+        temp_dir.close().unwrap();
     }
 
-    async fn screen_capture(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let mut image = if let Some(window_title) =
-            params.get("window_title").and_then(|v| v.as_str())
-        {
-            // Try to find and capture the specified window
-            let windows = Window::all()
-                .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
-
-            let window = windows
-                .into_iter()
-                .find(|w| w.title() == window_title)
-                .ok_or_else(|| {
-                    ToolError::ExecutionError(format!(
-                        "No window found with title '{}'",
-                        window_title
-                    ))
-                })?;
-
-            window.capture_image().map_err(|e| {
-                ToolError::ExecutionError(format!(
-                    "Failed to capture window '{}': {}",
-                    window_title, e
-                ))
-            })?
-        } else {
-            // Default to display capture if no window title is specified
-            let display = params.get("display").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    // Tests for view_range functionality
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_range() {
+        let router = get_router().await;
 
-            let monitors = Monitor::all()
-                .map_err(|_| ToolError::ExecutionError("Failed to access monitors".into()))?;
-            let monitor = monitors.get(display).ok_or_else(|| {
-                ToolError::ExecutionError(format!(
-                    "{} was not an available monitor, {} found.",
-                    display,
-                    monitors.len()
-                ))
-            })?;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-            monitor.capture_image().map_err(|e| {
-                ToolError::ExecutionError(format!("Failed to capture display {}: {}", display, e))
-            })?
-        };
+        // Create a multi-line file
+        let content =
+            "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6\nLine 7\nLine 8\nLine 9\nLine 10";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        // Resize the image to a reasonable width while maintaining aspect ratio
-        let max_width = 768;
-        if image.width() > max_width {
-            let scale = max_width as f32 / image.width() as f32;
-            let new_height = (image.height() as f32 * scale) as u32;
-            image = xcap::image::imageops::resize(
-                &image,
-                max_width,
-                new_height,
-                xcap::image::imageops::FilterType::Lanczos3,
+        // Test viewing specific range
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str,
+                    "view_range": [3, 6]
+                }),
+                dummy_sender(),
             )
-        };
+            .await
+            .unwrap();
 
-        let mut bytes: Vec<u8> = Vec::new();
-        image
-            .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
-            .map_err(|e| {
-                ToolError::ExecutionError(format!("Failed to write image buffer {}", e))
-            })?;
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
 
-        // Convert to base64
-        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+        // Should contain lines 3-6 with line numbers
+        assert!(text.text.contains("3: Line 3"));
+        assert!(text.text.contains("4: Line 4"));
+        assert!(text.text.contains("5: Line 5"));
+        assert!(text.text.contains("6: Line 6"));
+        assert!(text.text.contains("(lines 3-6)"));
+        // Should not contain other lines
+        assert!(!text.text.contains("1: Line 1"));
+        assert!(!text.text.contains("7: Line 7"));
 
-        Ok(vec![
-            Content::text("Screenshot captured").with_audience(vec![Role::Assistant]),
-            Content::image(data, "image/png").with_priority(0.0),
-        ])
+        temp_dir.close().unwrap();
     }
-}
 
-impl Router for DeveloperRouter {
-    fn name(&self) -> String {
-        "developer".to_string()
-    }
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_range_to_end() {
+        let router = get_router().await;
 
-    fn instructions(&self) -> String {
-        self.instructions.clone()
-    }
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-    fn capabilities(&self) -> ServerCapabilities {
-        CapabilitiesBuilder::new()
-            .with_tools(false)
-            .with_prompts(false)
-            .build()
-    }
+        // Create a multi-line file
+        let content = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-    fn list_tools(&self) -> Vec<Tool> {
-        self.tools.clone()
-    }
+        // Test viewing from line 3 to end using -1
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str,
+                    "view_range": [3, -1]
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-    fn call_tool(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-        notifier: mpsc::Sender<JsonRpcMessage>,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
-        let this = self.clone();
-        let tool_name = tool_name.to_string();
-        Box::pin(async move {
-            match tool_name.as_str() {
-                "shell" => this.bash(arguments, notifier).await,
-                "glob" => this.glob(arguments).await,
-                "grep" => this.bash(arguments, notifier).await,
-                "text_editor" => this.text_editor(arguments).await,
-                "list_windows" => this.list_windows(arguments).await,
-                "screen_capture" => this.screen_capture(arguments).await,
-                "image_processor" => this.image_processor(arguments).await,
-                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
-            }
-        })
-    }
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
 
-    // TODO see if we can make it easy to skip implementing these
-    fn list_resources(&self) -> Vec<Resource> {
-        Vec::new()
-    }
+        // Should contain lines 3 to end
+        assert!(text.text.contains("3: Line 3"));
+        assert!(text.text.contains("4: Line 4"));
+        assert!(text.text.contains("5: Line 5"));
+        assert!(text.text.contains("(lines 3-end)"));
+        // Should not contain earlier lines
+        assert!(!text.text.contains("1: Line 1"));
+        assert!(!text.text.contains("2: Line 2"));
 
-    fn read_resource(
-        &self,
-        _uri: &str,
-    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
-        Box::pin(async move { Ok("".to_string()) })
+        temp_dir.close().unwrap();
     }
 
-    fn list_prompts(&self) -> Vec<Prompt> {
-        self.prompts.values().cloned().collect()
-    }
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_range_invalid() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a small file
+        let content = "Line 1\nLine 2\nLine 3";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // Test invalid range - start beyond end of file
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str,
+                    "view_range": [10, 15]
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-    fn get_prompt(
-        &self,
-        prompt_name: &str,
-    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
-        let prompt_name = prompt_name.trim().to_owned();
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(err.to_string().contains("beyond the end of the file"));
 
-        // Validate prompt name is not empty
-        if prompt_name.is_empty() {
-            return Box::pin(async move {
-                Err(PromptError::InvalidParameters(
-                    "Prompt name cannot be empty".to_string(),
-                ))
-            });
-        }
+        // Test invalid range - start >= end
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str,
+                    "view_range": [3, 2]
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-        let prompts = Arc::clone(&self.prompts);
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(err.to_string().contains("must be less than end line"));
 
-        Box::pin(async move {
-            match prompts.get(&prompt_name) {
-                Some(prompt) => Ok(prompt.description.clone().unwrap_or_default()),
-                None => Err(PromptError::NotFound(format!(
-                    "Prompt '{prompt_name}' not found"
-                ))),
-            }
-        })
+        temp_dir.close().unwrap();
     }
-}
 
-impl Clone for DeveloperRouter {
-    fn clone(&self) -> Self {
-        Self {
-            tools: self.tools.clone(),
-            prompts: Arc::clone(&self.prompts),
-            instructions: self.instructions.clone(),
-            file_history: Arc::clone(&self.file_history),
-            ignore_patterns: Arc::clone(&self.ignore_patterns),
-            editor_model: create_editor_model(), // Recreate the editor model since it's not Clone
-        }
-    }
-}
+    // Tests for insert functionality
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_insert_at_beginning() {
+        let router = get_router().await;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
-    use serial_test::serial;
-    use std::fs;
-    use tempfile::TempDir;
-    use tokio::sync::OnceCell;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-    #[test]
-    #[serial]
-    fn test_global_goosehints() {
-        // if ~/.config/goose/.goosehints exists, it should be included in the instructions
-        // copy the existing global hints file to a .bak file
-        let global_hints_path =
-            PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints").to_string());
-        let global_hints_bak_path =
-            PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints.bak").to_string());
-        let mut globalhints_existed = false;
+        // Create a file with some content
+        let content = "Line 2\nLine 3\nLine 4";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        if global_hints_path.is_file() {
-            globalhints_existed = true;
-            fs::copy(&global_hints_path, &global_hints_bak_path).unwrap();
-        }
+        // Insert at the beginning (line 0)
+        let insert_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "insert",
+                    "path": file_path_str,
+                    "insert_line": 0,
+                    "new_str": "Line 1"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        fs::write(&global_hints_path, "These are my global goose hints.").unwrap();
+        let text = insert_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::Assistant))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
 
-        let dir = TempDir::new().unwrap();
-        std::env::set_current_dir(dir.path()).unwrap();
+        assert!(text.text.contains("Text has been inserted at line 1"));
 
-        let router = DeveloperRouter::new();
-        let instructions = router.instructions();
+        // Verify the file content
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        assert!(instructions.contains("### Global Hints"));
-        assert!(instructions.contains("my global goose hints."));
+        let view_text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
 
-        // restore backup if globalhints previously existed
-        if globalhints_existed {
-            fs::copy(&global_hints_bak_path, &global_hints_path).unwrap();
-            fs::remove_file(&global_hints_bak_path).unwrap();
-        }
+        assert!(view_text.text.contains("1: Line 1"));
+        assert!(view_text.text.contains("2: Line 2"));
+        assert!(view_text.text.contains("3: Line 3"));
+        assert!(view_text.text.contains("4: Line 4"));
+
+        temp_dir.close().unwrap();
     }
 
-    #[test]
+    #[tokio::test]
     #[serial]
-    fn test_goosehints_when_present() {
-        let dir = TempDir::new().unwrap();
-        std::env::set_current_dir(dir.path()).unwrap();
+    async fn test_text_editor_insert_in_middle() {
+        let router = get_router().await;
 
-        fs::write(".goosehints", "Test hint content").unwrap();
-        let router = DeveloperRouter::new();
-        let instructions = router.instructions();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-        assert!(instructions.contains("Test hint content"));
-    }
+        // Create a file with some content
+        let content = "Line 1\nLine 2\nLine 4\nLine 5";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-    #[test]
-    #[serial]
-    fn test_goosehints_when_missing() {
-        let dir = TempDir::new().unwrap();
-        std::env::set_current_dir(dir.path()).unwrap();
+        // Insert after line 2
+        let insert_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "insert",
+                    "path": file_path_str,
+                    "insert_line": 2,
+                    "new_str": "Line 3"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        let router = DeveloperRouter::new();
-        let instructions = router.instructions();
+        let text = insert_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::Assistant))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
 
-        assert!(!instructions.contains("Project Hints"));
-    }
+        assert!(text.text.contains("Text has been inserted at line 3"));
 
-    static DEV_ROUTER: OnceCell<DeveloperRouter> = OnceCell::const_new();
+        // Verify the file content
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let view_text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
 
-    async fn get_router() -> &'static DeveloperRouter {
-        DEV_ROUTER
-            .get_or_init(|| async { DeveloperRouter::new() })
-            .await
-    }
+        assert!(view_text.text.contains("1: Line 1"));
+        assert!(view_text.text.contains("2: Line 2"));
+        assert!(view_text.text.contains("3: Line 3"));
+        assert!(view_text.text.contains("4: Line 4"));
+        assert!(view_text.text.contains("5: Line 5"));
 
-    fn dummy_sender() -> mpsc::Sender<JsonRpcMessage> {
-        mpsc::channel(1).0
+        temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_shell_missing_parameters() {
+    async fn test_text_editor_insert_at_end() {
+        let router = get_router().await;
+
         let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        let router = get_router().await;
-        let result = router.call_tool("shell", json!({}), dummy_sender()).await;
+        // Create a file with some content
+        let content = "Line 1\nLine 2\nLine 3";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        // Insert at the end (after line 3)
+        let insert_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "insert",
+                    "path": file_path_str,
+                    "insert_line": 3,
+                    "new_str": "Line 4"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let text = insert_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::Assistant))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+
+        assert!(text.text.contains("Text has been inserted at line 4"));
+
+        // Verify the file content
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let view_text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+
+        assert!(view_text.text.contains("1: Line 1"));
+        assert!(view_text.text.contains("2: Line 2"));
+        assert!(view_text.text.contains("3: Line 3"));
+        assert!(view_text.text.contains("4: Line 4"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    #[cfg(windows)]
-    async fn test_windows_specific_commands() {
+    async fn test_text_editor_insert_invalid_line() {
         let router = get_router().await;
 
-        // Test PowerShell command
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Create a file with some content
+        let content = "Line 1\nLine 2\nLine 3";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // Try to insert beyond the end of the file
         let result = router
             .call_tool(
-                "shell",
+                "text_editor",
                 json!({
-                    "command": "Get-ChildItem"
+                    "command": "insert",
+                    "path": file_path_str,
+                    "insert_line": 10,
+                    "new_str": "Line 11"
                 }),
+                dummy_sender(),
             )
             .await;
-        assert!(result.is_ok());
 
-        // Test Windows path handling
-        let result = router.resolve_path("C:\\Windows\\System32");
-        assert!(result.is_ok());
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(err.to_string().contains("beyond the end of the file"));
 
-        // Test UNC path handling
-        let result = router.resolve_path("\\\\server\\share");
-        assert!(result.is_ok());
+        temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_size_limits() {
-        // Create temp directory first so it stays in scope for the whole test
-        let temp_dir = tempfile::tempdir().unwrap();
-        std::env::set_current_dir(&temp_dir).unwrap();
-
-        // Get router after setting current directory
+    async fn test_text_editor_insert_missing_parameters() {
         let router = get_router().await;
 
-        // Test file size limit
-        {
-            let large_file_path = temp_dir.path().join("large.txt");
-            let large_file_str = large_file_path.to_str().unwrap();
-
-            // Create a file larger than 2MB
-            let content = "x".repeat(3 * 1024 * 1024); // 3MB
-            std::fs::write(&large_file_path, content).unwrap();
-
-            let result = router
-                .call_tool(
-                    "text_editor",
-                    json!({
-                        "command": "view",
-                        "path": large_file_str
-                    }),
-                    dummy_sender(),
-                )
-                .await;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
 
-            assert!(result.is_err());
-            let err = result.err().unwrap();
-            assert!(matches!(err, ToolError::ExecutionError(_)));
-            assert!(err.to_string().contains("too large"));
-        }
+        // Create a file
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": "Test content"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
 
-        // Test character count limit
-        {
-            let many_chars_path = temp_dir.path().join("many_chars.txt");
-            let many_chars_str = many_chars_path.to_str().unwrap();
+        // Try insert without insert_line parameter
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "insert",
+                    "path": file_path_str,
+                    "new_str": "New line"
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-            // Create a file with more than 400K characters but less than 400KB
-            let content = "x".repeat(405_000);
-            std::fs::write(&many_chars_path, content).unwrap();
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(err.to_string().contains("Missing 'insert_line' parameter"));
 
-            let result = router
-                .call_tool(
-                    "text_editor",
-                    json!({
-                        "command": "view",
-                        "path": many_chars_str
-                    }),
-                    dummy_sender(),
-                )
-                .await;
+        // Try insert without new_str parameter
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "insert",
+                    "path": file_path_str,
+                    "insert_line": 1
+                }),
+                dummy_sender(),
+            )
+            .await;
 
-            assert!(result.is_err());
-            let err = result.err().unwrap();
-            assert!(matches!(err, ToolError::ExecutionError(_)));
-            assert!(err.to_string().contains("too many characters"));
-        }
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(err.to_string().contains("Missing 'new_str' parameter"));
 
-        // Let temp_dir drop naturally at end of scope
+        temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_write_and_view_file() {
+    async fn test_text_editor_insert_with_undo() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1850,21 +7255,53 @@ mod tests {
         let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a new file
+        // Create a file with some content
+        let content = "Line 1\nLine 2";
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": file_path_str,
+                    "file_text": content
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        // Insert a line
         router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
+                    "command": "insert",
                     "path": file_path_str,
-                    "file_text": "Hello, world!"
+                    "insert_line": 1,
+                    "new_str": "Inserted Line"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
 
-        // View the file
+        // Undo the insert
+        let undo_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "undo_edit",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+
+        let text = undo_result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("Undid the last edit"));
+
+        // Verify the file is back to original content
         let view_result = router
             .call_tool(
                 "text_editor",
@@ -1877,8 +7314,7 @@ mod tests {
             .await
             .unwrap();
 
-        assert!(!view_result.is_empty());
-        let text = view_result
+        let view_text = view_result
             .iter()
             .find(|c| {
                 c.audience()
@@ -1887,51 +7323,77 @@ mod tests {
             .unwrap()
             .as_text()
             .unwrap();
-        assert!(text.text.contains("Hello, world!"));
+
+        assert!(view_text.text.contains("1: Line 1"));
+        assert!(view_text.text.contains("2: Line 2"));
+        assert!(!view_text.text.contains("Inserted Line"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_str_replace() {
+    async fn test_text_editor_insert_nonexistent_file() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
+        let file_path = temp_dir.path().join("nonexistent.txt");
         let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a new file
-        router
+        // Try to insert into a nonexistent file
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
+                    "command": "insert",
                     "path": file_path_str,
-                    "file_text": "Hello, world!"
+                    "insert_line": 0,
+                    "new_str": "New line"
                 }),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+            .await;
 
-        // Replace string
-        let replace_result = router
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(err.to_string().contains("does not exist"));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_json_query() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("package.json");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        std::fs::write(
+            &file_path,
+            r#"{"name": "demo", "dependencies": {"left-pad": "1.0.0", "react": "18.2.0"}}"#,
+        )
+        .unwrap();
+
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "str_replace",
+                    "command": "json_query",
                     "path": file_path_str,
-                    "old_str": "world",
-                    "new_str": "Rust"
+                    "query": ".dependencies | keys",
+                    "compact": true
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
 
-        let text = replace_result
+        let text = result
             .iter()
             .find(|c| {
                 c.audience()
@@ -1940,769 +7402,1524 @@ mod tests {
             .unwrap()
             .as_text()
             .unwrap();
+        assert_eq!(text.text, r#"["left-pad","react"]"#);
 
-        assert!(text
-            .text
-            .contains("has been edited, and the section now reads"));
-
-        // View the file to verify the change
-        let view_result = router
+        // Invalid filters are reported as parameter errors
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
-                    "path": file_path_str
+                    "command": "json_query",
+                    "path": file_path_str,
+                    "query": ".dependencies | ["
                 }),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
 
-        let text = view_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::User))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
+        temp_dir.close().unwrap();
+    }
 
-        // Check that the file has been modified and contains some form of "Rust"
-        // The Editor API might transform the content differently than simple string replacement
-        assert!(
-            text.text.contains("Rust") || text.text.contains("Hello, Rust!"),
-            "Expected content to contain 'Rust', but got: {}",
-            text.text
+    #[test]
+    #[serial]
+    fn test_with_hint_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let service_dir = temp_dir.path().join("services").join("billing");
+        fs::create_dir_all(&service_dir).unwrap();
+        std::env::set_current_dir(&service_dir).unwrap();
+
+        fs::write(temp_dir.path().join(".goosehints"), "Use pnpm, not npm.").unwrap();
+        fs::write(service_dir.join(".goosehints"), "Amounts are in cents.").unwrap();
+
+        let hints_paths = DeveloperRouter::discover_hints(service_dir.clone(), 2);
+        assert_eq!(
+            hints_paths,
+            vec![
+                temp_dir.path().join(".goosehints"),
+                service_dir.join(".goosehints")
+            ]
+        );
+        // The repository root is two levels up
+        assert_eq!(
+            DeveloperRouter::discover_hints(service_dir.clone(), 1),
+            vec![service_dir.join(".goosehints")]
         );
 
+        let router = DeveloperRouter::with_hint_paths(hints_paths);
+        let instructions = router.instructions();
+        let root = instructions.find("Use pnpm, not npm.").unwrap();
+        let service = instructions.find("Amounts are in cents.").unwrap();
+        assert!(root < service);
+        assert!(instructions.contains(&format!(
+            "### Project Hints ({})",
+            temp_dir.path().join(".goosehints").display()
+        )));
+        assert!(!instructions.contains("Global Hints"));
+
         temp_dir.close().unwrap();
     }
 
-    #[tokio::test]
+    #[test]
     #[serial]
-    async fn test_text_editor_undo_edit() {
-        let router = get_router().await;
+    fn test_config_from_env() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        std::env::set_var("GOOSE_ENABLE_SQL_TOOLS", "true");
+        std::env::set_var("GOOSE_SHELL_CACHE_TTL_SECS", "30");
+        std::env::set_var("GOOSE_ENV_ALLOWLIST", "HOME, PATH");
+
+        let config = DeveloperRouterConfig::default();
+        assert!(!config.enable_sql_tools);
+        assert_eq!(config.shell_cache_ttl_secs, 0);
+        assert!(config.env_allowlist.is_empty());
+        assert!(config.hints_paths.is_empty());
+
+        let config = DeveloperRouterConfig::from_env();
+        assert!(config.enable_sql_tools);
+        assert_eq!(config.shell_cache_ttl_secs, 30);
+        assert_eq!(config.env_allowlist, vec!["HOME", "PATH"]);
+        assert_eq!(config.hints_paths.len(), 2);
+
+        for name in [
+            "GOOSE_ENABLE_SQL_TOOLS",
+            "GOOSE_SHELL_CACHE_TTL_SECS",
+            "GOOSE_ENV_ALLOWLIST",
+        ] {
+            std::env::remove_var(name);
+        }
+    }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_new_with_config() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a new file
-        router
+        let hints_path = temp_dir.path().join("team-hints.md");
+        fs::write(&hints_path, "Always run the linter.").unwrap();
+        let file_path = temp_dir.path().join("small.txt");
+        fs::write(&file_path, "x".repeat(2048)).unwrap();
+
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            max_file_size_bytes: 1024,
+            max_shell_output_chars: 10,
+            shell_timeout_secs: Some(1),
+            enable_screen_capture: false,
+            enable_image_processor: false,
+            enable_clipboard_tools: false,
+            enable_container_tool: false,
+            container_timeout_secs: 600,
+            enable_terminal_recording: false,
+            enable_lint_recipe: false,
+            enable_watch_directory: false,
+            enable_profiling_tools: false,
+            enable_sql_tools: false,
+            enable_run_tests: false,
+            enable_generate_uuid: false,
+            enable_system_inspection: false,
+            enable_dependency_graph: false,
+            hints_paths: vec![hints_path],
+            shell_cache_ttl_secs: 0,
+            semantic_search_top_k: 5,
+            notebook_timeout_secs: 120,
+            max_history_depth: 50,
+            sensitive_env_patterns: Vec::new(),
+            env_allowlist: Vec::new(),
+            editor_model: None,
+            embedding_client: None,
+        });
+
+        assert!(router.instructions().contains("Always run the linter."));
+        let tool_names: Vec<_> = router.list_tools().into_iter().map(|t| t.name).collect();
+        assert!(!tool_names.iter().any(|n| n == "screen_capture"));
+        assert!(!tool_names.iter().any(|n| n == "image_processor"));
+        assert!(!tool_names.iter().any(|n| n == "clipboard_read"));
+        assert!(!tool_names.iter().any(|n| n == "run_in_container"));
+        assert!(!tool_names.iter().any(|n| n == "terminal_record"));
+        for name in [
+            "lint_recipe",
+            "watch_directory",
+            "profile",
+            "benchmark",
+            "sql_query",
+            "csv_to_sql",
+            "run_tests",
+            "generate_uuid",
+            "env_inspect",
+            "network_inspect",
+            "process_list",
+            "dependency_graph",
+        ] {
+            assert!(!tool_names.iter().any(|n| n == name), "{} is listed", name);
+            let result = router.call_tool(name, json!({}), dummy_sender()).await;
+            assert!(matches!(result, Err(ToolError::NotFound(_))), "{}", name);
+        }
+
+        let result = router
+            .call_tool("screen_capture", json!({"display": 0}), dummy_sender())
+            .await;
+        assert!(matches!(result, Err(ToolError::NotFound(_))));
+        let result = router
+            .call_tool("clipboard_read", json!({}), dummy_sender())
+            .await;
+        assert!(matches!(result, Err(ToolError::NotFound(_))));
+        let result = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": "First line"
-                }),
+                "run_in_container",
+                json!({"image": "alpine", "command": "true"}),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+            .await;
+        assert!(matches!(result, Err(ToolError::NotFound(_))));
 
-        // Replace string
-        router
+        let result = router
             .call_tool(
                 "text_editor",
-                json!({
-                    "command": "str_replace",
-                    "path": file_path_str,
-                    "old_str": "First line",
-                    "new_str": "Second line"
-                }),
+                json!({"command": "view", "path": file_path.to_str().unwrap()}),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Maximum size is 1KB"));
 
-        // Undo the edit
-        let undo_result = router
+        let result = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "undo_edit",
-                    "path": file_path_str
-                }),
+                "shell",
+                json!({"command": "echo 'this is more than ten characters'"}),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("too many characters"));
 
-        let text = undo_result.first().unwrap().as_text().unwrap();
-        assert!(text.text.contains("Undid the last edit"));
+        #[cfg(not(windows))]
+        {
+            let result = router
+                .call_tool("shell", json!({"command": "sleep 5"}), dummy_sender())
+                .await;
+            assert!(result.unwrap_err().to_string().contains("timed out"));
+        }
 
-        // View the file to verify the undo
-        let view_result = router
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    #[cfg(not(windows))]
+    async fn test_profile() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            enable_profiling_tools: true,
+            shell_timeout_secs: Some(1),
+            ..Default::default()
+        });
+
+        let result = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "view",
-                    "path": file_path_str
-                }),
+                "profile",
+                json!({"command": "echo profiled; exit 3"}),
                 dummy_sender(),
             )
             .await
             .unwrap();
-
-        let text = view_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::User))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
-        assert!(text.text.contains("First line"));
+        let report = &result[0].as_text().unwrap().text;
+        assert!(report.contains("| Exit status | 3 |"));
+        assert!(report.contains("| Wall time |"));
+        assert!(report.contains("| CPU time |"));
+        assert!(report.contains("| Peak memory |"));
+        assert!(report.contains("profiled"));
+
+        let start = Instant::now();
+        let result = router
+            .call_tool("profile", json!({"command": "sleep 30"}), dummy_sender())
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("timed out after 1 seconds"));
+        assert!(start.elapsed() < Duration::from_secs(10));
 
         temp_dir.close().unwrap();
     }
 
-    // Test GooseIgnore pattern matching
     #[tokio::test]
     #[serial]
-    async fn test_goose_ignore_basic_patterns() {
+    #[cfg(not(windows))]
+    async fn test_benchmark() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a DeveloperRouter with custom ignore patterns
-        let mut builder = GitignoreBuilder::new(temp_dir.path());
-        builder.add_line(None, "secret.txt").unwrap();
-        builder.add_line(None, "*.env").unwrap();
-        let ignore_patterns = builder.build().unwrap();
-
-        let router = DeveloperRouter {
-            tools: vec![],
-            prompts: Arc::new(HashMap::new()),
-            instructions: String::new(),
-            file_history: Arc::new(Mutex::new(HashMap::new())),
-            ignore_patterns: Arc::new(ignore_patterns),
-            editor_model: None,
-        };
-
-        // Test basic file matching
-        assert!(
-            router.is_ignored(Path::new("secret.txt")),
-            "secret.txt should be ignored"
-        );
-        assert!(
-            router.is_ignored(Path::new("./secret.txt")),
-            "./secret.txt should be ignored"
-        );
-        assert!(
-            !router.is_ignored(Path::new("not_secret.txt")),
-            "not_secret.txt should not be ignored"
+        let stats = benchmark::BenchmarkResult::from_timings(
+            "true",
+            &[10, 20, 30].map(Duration::from_millis),
         );
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.mean_ms, 20.0);
+        assert_eq!(stats.min_ms, 10.0);
+        assert_eq!(stats.max_ms, 30.0);
+        assert_eq!(stats.stddev_ms, 10.0);
+        assert_eq!(stats.timings_ms, vec![10.0, 20.0, 30.0]);
+
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            enable_profiling_tools: true,
+            ..Default::default()
+        });
+        let result = router
+            .call_tool(
+                "benchmark",
+                json!({"command": "echo timed", "runs": 3}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let report: Value = serde_json::from_str(&result[0].as_text().unwrap().text).unwrap();
+        assert_eq!(report["runs"], 3);
+        assert_eq!(report["timings_ms"].as_array().unwrap().len(), 3);
+        assert!(report["min_ms"].as_f64().unwrap() <= report["mean_ms"].as_f64().unwrap());
 
-        // Test pattern matching
-        assert!(
-            router.is_ignored(Path::new("test.env")),
-            "*.env pattern should match test.env"
-        );
-        assert!(
-            router.is_ignored(Path::new("./test.env")),
-            "*.env pattern should match ./test.env"
-        );
-        assert!(
-            !router.is_ignored(Path::new("test.txt")),
-            "*.env pattern should not match test.txt"
-        );
+        let err = router
+            .call_tool(
+                "benchmark",
+                json!({"command": "true", "runs": 0}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        let err = router
+            .call_tool("benchmark", json!({"command": "exit 2"}), dummy_sender())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Run 1 of 'exit 2' exited with"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_respects_ignore_patterns() {
+    async fn test_sql_query() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE users (id INTEGER, name TEXT, score REAL);
+                 INSERT INTO users VALUES (1, 'ada', 9.5), (2, 'grace', NULL), (3, 'linus', 7.0);",
+            )
+            .unwrap();
+        }
 
-        // Create a DeveloperRouter with custom ignore patterns
-        let mut builder = GitignoreBuilder::new(temp_dir.path());
-        builder.add_line(None, "secret.txt").unwrap();
-        let ignore_patterns = builder.build().unwrap();
-
-        let router = DeveloperRouter {
-            tools: DeveloperRouter::new().tools, // Reuse default tools
-            prompts: Arc::new(HashMap::new()),
-            instructions: String::new(),
-            file_history: Arc::new(Mutex::new(HashMap::new())),
-            ignore_patterns: Arc::new(ignore_patterns),
-            editor_model: None,
-        };
-
-        // Try to write to an ignored file
+        let router = get_router().await;
+        let db_path = db_path.to_str().unwrap();
         let result = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "write",
-                    "path": temp_dir.path().join("secret.txt").to_str().unwrap(),
-                    "file_text": "test content"
-                }),
+                "sql_query",
+                json!({"db_path": db_path, "query": "SELECT * FROM users ORDER BY id", "limit": 2}),
                 dummy_sender(),
             )
-            .await;
-
-        assert!(
-            result.is_err(),
-            "Should not be able to write to ignored file"
+            .await
+            .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        let (rows, note) = text.split_once("\n\n").unwrap();
+        let rows: Value = serde_json::from_str(rows).unwrap();
+        assert_eq!(
+            rows,
+            json!([
+                {"id": 1, "name": "ada", "score": 9.5},
+                {"id": 2, "name": "grace", "score": null}
+            ])
         );
-        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
+        assert!(note.contains("first 2 rows"));
+        let table = &result[1].as_text().unwrap().text;
+        assert!(table.starts_with("| id | name | score |\n|---|---|---|\n| 1 | ada | 9.5 |"));
+        assert!(table.contains("| 2 | grace | NULL |"));
 
-        // Try to write to a non-ignored file
+        // The database is read-only
         let result = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "write",
-                    "path": temp_dir.path().join("allowed.txt").to_str().unwrap(),
-                    "file_text": "test content"
-                }),
+                "sql_query",
+                json!({"db_path": db_path, "query": "DELETE FROM users"}),
                 dummy_sender(),
             )
             .await;
-
-        assert!(
-            result.is_ok(),
-            "Should be able to write to non-ignored file"
-        );
+        assert!(result.is_err());
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_bash_respects_ignore_patterns() {
+    async fn test_csv_to_sql() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        let csv_path = temp_dir.path().join("2024 sales.csv");
+        fs::write(
+            &csv_path,
+            "id,region,amount,,region\n1,north,9.5,a,x\n2,south,,b,y\n3,,12,c,z\n",
+        )
+        .unwrap();
+        let db_path = temp_dir.path().join("sales.db");
 
-        // Create a DeveloperRouter with custom ignore patterns
-        let mut builder = GitignoreBuilder::new(temp_dir.path());
-        builder.add_line(None, "secret.txt").unwrap();
-        let ignore_patterns = builder.build().unwrap();
-
-        let router = DeveloperRouter {
-            tools: DeveloperRouter::new().tools, // Reuse default tools
-            prompts: Arc::new(HashMap::new()),
-            instructions: String::new(),
-            file_history: Arc::new(Mutex::new(HashMap::new())),
-            ignore_patterns: Arc::new(ignore_patterns),
-            editor_model: None,
-        };
-
-        // Create an ignored file
-        let secret_file_path = temp_dir.path().join("secret.txt");
-        std::fs::write(&secret_file_path, "secret content").unwrap();
-
-        // Try to cat the ignored file
+        let router = get_router().await;
         let result = router
             .call_tool(
-                "shell",
+                "csv_to_sql",
                 json!({
-                    "command": format!("cat {}", secret_file_path.to_str().unwrap())
+                    "csv_path": csv_path.to_str().unwrap(),
+                    "db_path": db_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
-            .await;
-
-        assert!(result.is_err(), "Should not be able to cat ignored file");
-        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
+            .await
+            .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.starts_with("Imported 3 rows"));
+        assert!(text.contains(
+            r#"CREATE TABLE "_2024_sales" ("id" INTEGER, "region" TEXT, "amount" REAL, "column_4" TEXT, "region_2" TEXT)"#
+        ));
 
-        // Try to cat a non-ignored file
-        let allowed_file_path = temp_dir.path().join("allowed.txt");
-        std::fs::write(&allowed_file_path, "allowed content").unwrap();
+        let result = router
+            .call_tool(
+                "sql_query",
+                json!({
+                    "db_path": db_path.to_str().unwrap(),
+                    "query": "SELECT id, region, amount FROM _2024_sales ORDER BY id"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let rows: Value = serde_json::from_str(&result[0].as_text().unwrap().text).unwrap();
+        assert_eq!(
+            rows,
+            json!([
+                {"id": 1, "region": "north", "amount": 9.5},
+                {"id": 2, "region": "south", "amount": null},
+                {"id": 3, "region": null, "amount": 12.0}
+            ])
+        );
 
+        // An existing table is never replaced
         let result = router
             .call_tool(
-                "shell",
+                "csv_to_sql",
                 json!({
-                    "command": format!("cat {}", allowed_file_path.to_str().unwrap())
+                    "csv_path": csv_path.to_str().unwrap(),
+                    "db_path": db_path.to_str().unwrap(),
+                    "table_name": "_2024_sales"
                 }),
                 dummy_sender(),
             )
             .await;
-
-        assert!(result.is_ok(), "Should be able to cat non-ignored file");
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+
+        fs::write(temp_dir.path().join(".gooseignore"), "secret.csv").unwrap();
+        let secret = temp_dir.path().join("secret.csv");
+        fs::write(&secret, "a\n1\n").unwrap();
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            enable_sql_tools: true,
+            ..Default::default()
+        });
+        let result = router
+            .call_tool(
+                "csv_to_sql",
+                json!({"csv_path": secret.to_str().unwrap()}),
+                dummy_sender(),
+            )
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("restricted by .gooseignore"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_gitignore_fallback_when_no_gooseignore() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        std::env::set_current_dir(&temp_dir).unwrap();
+    async fn test_run_tests() {
+        use test_runner::TestRunner;
+
+        let cargo_output = indoc! {"
+            running 3 tests
+            test math::adds ... ok
+            test math::divides ... FAILED
+            test math::panics_on_zero - should panic ... FAILED
+
+            failures:
+
+            ---- math::divides stdout ----
+            thread 'math::divides' panicked at src/math.rs:12:9:
+            assertion `left == right` failed
+              left: 2
+             right: 3
+
+            ---- math::panics_on_zero stdout ----
+            note: test did not panic as expected
+
+            failures:
+                math::divides
+                math::panics_on_zero
+
+            test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+            running 1 test
+            test it_works ... ok
+
+            test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+        "};
+        let report = TestRunner::Cargo.parse("cargo test", cargo_output).unwrap();
+        assert_eq!((report.total, report.passed, report.failed), (4, 2, 2));
+        assert_eq!(report.failures[0].name, "math::divides");
+        assert!(report.failures[0]
+            .message
+            .starts_with("thread 'math::divides' panicked"));
+        assert!(report.failures[0].message.ends_with("right: 3"));
+        assert_eq!(report.failures[1].name, "math::panics_on_zero");
+
+        let jest_output = indoc! {"
+            FAIL src/sum.test.js
+              ● sum › adds negative numbers
+
+                expect(received).toBe(expected)
+
+                Expected: -3
+                Received: 3
+
+            Test Suites: 1 failed, 1 total
+            Tests:       1 failed, 4 passed, 5 total
+        "};
+        let report = TestRunner::Npm.parse("npm test", jest_output).unwrap();
+        assert_eq!((report.total, report.passed, report.failed), (5, 4, 1));
+        assert_eq!(report.failures[0].name, "sum › adds negative numbers");
+        assert!(report.failures[0].message.contains("Expected: -3"));
+
+        let pytest_output = indoc! {"
+            ..F.                                                     [100%]
+            =========================== short test summary info ============================
+            FAILED tests/test_parse.py::test_dates - AssertionError: assert 1 == 2
+            1 failed, 3 passed in 0.05s
+        "};
+        let report = TestRunner::Pytest.parse("pytest", pytest_output).unwrap();
+        assert_eq!((report.total, report.passed, report.failed), (4, 3, 1));
+        assert_eq!(
+            report.failures,
+            vec![test_runner::TestFailure {
+                name: "tests/test_parse.py::test_dates".to_string(),
+                message: "AssertionError: assert 1 == 2".to_string(),
+            }]
+        );
 
-        // Create a .gitignore file but no .gooseignore
-        std::fs::write(temp_dir.path().join(".gitignore"), "*.log\n*.tmp\n.env").unwrap();
+        // A compile error has no summary to read
+        assert!(TestRunner::Cargo
+            .parse("cargo test", "error[E0425]: cannot find value `x`")
+            .is_none());
 
-        let router = DeveloperRouter::new();
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        assert_eq!(TestRunner::detect(temp_dir.path()), None);
+        let router = get_router().await;
+        let err = router
+            .call_tool("run_tests", json!({}), dummy_sender())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No Cargo.toml"), "{}", err);
 
-        // Test that gitignore patterns are respected
-        assert!(
-            router.is_ignored(Path::new("test.log")),
-            "*.log pattern from .gitignore should be ignored"
-        );
-        assert!(
-            router.is_ignored(Path::new("build.tmp")),
-            "*.tmp pattern from .gitignore should be ignored"
-        );
-        assert!(
-            router.is_ignored(Path::new(".env")),
-            ".env pattern from .gitignore should be ignored"
-        );
-        assert!(
-            !router.is_ignored(Path::new("test.txt")),
-            "test.txt should not be ignored"
+        fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            "[tool.pytest.ini_options]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            TestRunner::detect(temp_dir.path()),
+            Some(TestRunner::Pytest)
         );
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        assert_eq!(TestRunner::detect(temp_dir.path()), Some(TestRunner::Cargo));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_gooseignore_takes_precedence_over_gitignore() {
+    async fn test_network_inspect() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-        // Create both .gooseignore and .gitignore files with different patterns
-        std::fs::write(temp_dir.path().join(".gooseignore"), "*.secret").unwrap();
-        std::fs::write(temp_dir.path().join(".gitignore"), "*.log\ntarget/").unwrap();
-
-        let router = DeveloperRouter::new();
+        let router = get_router().await;
+        let inspect = |args: Value| {
+            let router = &router;
+            async move {
+                let result = router
+                    .call_tool("network_inspect", args, dummy_sender())
+                    .await
+                    .unwrap();
+                serde_json::from_str::<Value>(&result[0].as_text().unwrap().text).unwrap()
+            }
+        };
 
-        // .gooseignore patterns should be used
-        assert!(
-            router.is_ignored(Path::new("test.secret")),
-            "*.secret pattern from .gooseignore should be ignored"
-        );
+        let result = inspect(json!({"command": "dns_lookup", "hostname": "localhost"})).await;
+        assert_eq!(result["resolved"], true);
+        assert!(!result["addresses"].as_array().unwrap().is_empty());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 204 No Content\r\nServer: test\r\n\r\n")
+                    .await;
+            }
+        });
 
-        // .gitignore patterns should NOT be used when .gooseignore exists
-        assert!(
-            !router.is_ignored(Path::new("test.log")),
-            "*.log pattern from .gitignore should NOT be ignored when .gooseignore exists"
-        );
-        assert!(
-            !router.is_ignored(Path::new("build.tmp")),
-            "*.tmp pattern from .gitignore should NOT be ignored when .gooseignore exists"
-        );
+        let result =
+            inspect(json!({"command": "port_check", "host": "127.0.0.1", "port": port})).await;
+        assert_eq!(result["open"], true);
+        assert!(result["latency_ms"].is_u64());
+
+        let url = format!("http://127.0.0.1:{}/health", port);
+        let result = inspect(json!({"command": "http_head", "url": url})).await;
+        assert_eq!(result["status"], 204);
+        assert_eq!(result["server"], "test");
+
+        // Nothing listens on a port once its listener is dropped
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let closed_port = closed.local_addr().unwrap().port();
+        drop(closed);
+        let result =
+            inspect(json!({"command": "port_check", "host": "127.0.0.1", "port": closed_port}))
+                .await;
+        assert_eq!(result["open"], false);
+        assert!(result["error"].is_string());
 
-        temp_dir.close().unwrap();
+        let err = router
+            .call_tool(
+                "network_inspect",
+                json!({"command": "http_head", "url": "ftp://example.com"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_default_patterns_when_no_ignore_files() {
+    async fn test_dependency_graph() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        let router = get_router().await;
 
-        // Don't create any ignore files
-        let router = DeveloperRouter::new();
+        let project = temp_dir.path().join("web");
+        fs::create_dir(&project).unwrap();
+        fs::write(
+            project.join("package.json"),
+            r#"{"name": "web", "dependencies": {"react": "^18"}, "devDependencies": {"jest": "^29"}}"#,
+        )
+        .unwrap();
+        let graph = |include_dev: bool| {
+            let router = &router;
+            let path = project.to_str().unwrap().to_string();
+            async move {
+                let result = router
+                    .call_tool(
+                        "dependency_graph",
+                        json!({"path": path, "include_dev": include_dev}),
+                        dummy_sender(),
+                    )
+                    .await
+                    .unwrap();
+                result[0].as_text().unwrap().text.clone()
+            }
+        };
 
-        // Default patterns should be used
-        assert!(
-            router.is_ignored(Path::new(".env")),
-            ".env should be ignored by default patterns"
-        );
-        assert!(
-            router.is_ignored(Path::new(".env.local")),
-            ".env.local should be ignored by default patterns"
-        );
-        assert!(
-            router.is_ignored(Path::new("secrets.txt")),
-            "secrets.txt should be ignored by default patterns"
-        );
-        assert!(
-            !router.is_ignored(Path::new("normal.txt")),
-            "normal.txt should not be ignored"
-        );
+        let text = graph(false).await;
+        assert!(text.contains("digraph dependencies {"));
+        assert!(text.contains("\"web\" [style=filled, fillcolor=lightblue];"));
+        assert!(text.contains("\"web\" -> \"react\";"));
+        assert!(!text.contains("jest"));
+        assert!(graph(true).await.contains("\"web\" -> \"jest\";"));
+
+        let project = temp_dir.path().join("engine");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("src/lib.rs"), "").unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"engine\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nserde = \"1\"\n",
+        )
+        .unwrap();
+        let result = router
+            .call_tool(
+                "dependency_graph",
+                json!({"path": project.to_str().unwrap()}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert!(result[0]
+            .as_text()
+            .unwrap()
+            .text
+            .contains("\"engine\" -> \"serde\";"));
 
-        temp_dir.close().unwrap();
+        let project = temp_dir.path().join("scripts");
+        fs::create_dir(&project).unwrap();
+        fs::write(
+            project.join("requirements.txt"),
+            "# tools\nRequests>=2.31 ; python_version >= \"3.8\"\n-r dev.txt\nnumpy[extra]==1.26\n",
+        )
+        .unwrap();
+        let result = router
+            .call_tool(
+                "dependency_graph",
+                json!({"path": project.to_str().unwrap()}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("\"scripts\" -> \"requests\";"));
+        assert!(text.contains("\"scripts\" -> \"numpy\";"));
+        assert!(text.starts_with("1 package with 2 direct dependencies"));
+
+        let err = router
+            .call_tool(
+                "dependency_graph",
+                json!({"path": temp_dir.path().to_str().unwrap()}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::ExecutionError(_)));
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_descriptions() {
+    async fn test_process_list() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Test without editor API configured (should be the case in tests due to cfg!(test))
-        let router = DeveloperRouter::new();
-        let tools = router.list_tools();
-        let text_editor_tool = tools.iter().find(|t| t.name == "text_editor").unwrap();
-
-        // Should use traditional description with str_replace command
-        assert!(text_editor_tool
-            .description
-            .as_ref()
-            .map_or(false, |desc| desc
-                .contains("Replace a string in a file with a new string")));
-        assert!(text_editor_tool
-            .description
-            .as_ref()
-            .map_or(false, |desc| desc
-                .contains("the `old_str` needs to exactly match one")));
-        assert!(text_editor_tool
-            .description
-            .as_ref()
-            .map_or(false, |desc| desc.contains("str_replace")));
+        let router = get_router().await;
+        let list = |args: Value| {
+            let router = &router;
+            async move {
+                let result = router
+                    .call_tool("process_list", args, dummy_sender())
+                    .await
+                    .unwrap();
+                serde_json::from_str::<Vec<Value>>(&result[0].as_text().unwrap().text).unwrap()
+            }
+        };
 
-        // Should not contain editor API description or edit_file command
-        assert!(!text_editor_tool
-            .description
-            .as_ref()
-            .map_or(false, |desc| desc
-                .contains("Edit the file with the new content")));
-        assert!(!text_editor_tool
-            .description
-            .as_ref()
-            .map_or(false, |desc| desc.contains("edit_file")));
-        assert!(!text_editor_tool
-            .description
-            .as_ref()
-            .map_or(false, |desc| desc
-                .contains("work out how to place old_str with it intelligently")));
+        // The test binary is named after the crate
+        let processes = list(json!({"name_contains": "GOOSE_MCP"})).await;
+        let this = processes
+            .iter()
+            .find(|p| p["pid"] == std::process::id())
+            .expect("the test process should be listed");
+        assert!(this["memory_mb"].as_f64().unwrap() > 0.0);
+        assert!(this["command"].as_str().unwrap().contains("goose_mcp"));
+
+        let processes = list(json!({})).await;
+        assert!(!processes.is_empty() && processes.len() <= processes::MAX_PROCESSES);
+        let cpu: Vec<f64> = processes
+            .iter()
+            .map(|p| p["cpu_percent"].as_f64().unwrap())
+            .collect();
+        assert!(cpu.windows(2).all(|pair| pair[0] >= pair[1]));
 
-        temp_dir.close().unwrap();
+        assert!(list(json!({"name_contains": "no-such-process-name"}))
+            .await
+            .is_empty());
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_respects_gitignore_fallback() {
+    async fn test_run_in_container_arguments() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a .gitignore file but no .gooseignore
-        std::fs::write(temp_dir.path().join(".gitignore"), "*.log").unwrap();
+        let args = container::run_args(
+            "goose-run-test",
+            "alpine:3",
+            "echo $GREETING",
+            &["/tmp/data:/data:ro".to_string()],
+            &["GREETING".to_string()],
+        );
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--rm",
+                "--name",
+                "goose-run-test",
+                "--volume",
+                "/tmp/data:/data:ro",
+                "--env",
+                "GREETING",
+                "alpine:3",
+                "sh",
+                "-c",
+                "echo $GREETING"
+            ]
+        );
 
-        let router = DeveloperRouter::new();
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            enable_container_tool: true,
+            ..Default::default()
+        });
+        assert!(router
+            .list_tools()
+            .iter()
+            .any(|tool| tool.name == "run_in_container"));
 
-        // Try to write to a file ignored by .gitignore
-        let result = router
+        // An image that looks like an option would be read as one by docker
+        let err = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "write",
-                    "path": temp_dir.path().join("test.log").to_str().unwrap(),
-                    "file_text": "test content"
-                }),
+                "run_in_container",
+                json!({"image": "--privileged", "command": "true"}),
                 dummy_sender(),
             )
-            .await;
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        let err = router
+            .call_tool(
+                "run_in_container",
+                json!({"image": "alpine", "command": "true", "env": {"A=B": "c"}}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+    }
 
-        assert!(
-            result.is_err(),
-            "Should not be able to write to file ignored by .gitignore fallback"
+    #[tokio::test]
+    #[serial]
+    async fn test_terminal_recording_arguments() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let path = terminal::recording_path(temp_dir.path());
+        assert_eq!(path.extension().unwrap(), "cast");
+        assert!(path.starts_with(temp_dir.path()));
+        assert_eq!(
+            terminal::record_args(Path::new("demo.cast"), Some("cargo build"), Some("Build")),
+            [
+                "rec",
+                "--quiet",
+                "--command",
+                "cargo build",
+                "--title",
+                "Build",
+                "demo.cast"
+            ]
+        );
+        assert_eq!(
+            terminal::play_args(Path::new("demo.cast"), Some(2.0), Some(1.5)),
+            [
+                "play",
+                "--speed",
+                "2",
+                "--idle-time-limit",
+                "1.5",
+                "demo.cast"
+            ]
         );
-        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
 
-        // Try to write to a non-ignored file
-        let result = router
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            enable_terminal_recording: true,
+            ..Default::default()
+        });
+        let tool_names: Vec<_> = router.list_tools().into_iter().map(|t| t.name).collect();
+        assert!(tool_names.iter().any(|n| n == "terminal_record"));
+        assert!(tool_names.iter().any(|n| n == "terminal_replay"));
+
+        let err = router
+            .call_tool("terminal_record", json!({"action": "stop"}), dummy_sender())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::ExecutionError(_)));
+
+        let notes = temp_dir.path().join("notes.txt");
+        fs::write(&notes, "not a recording").unwrap();
+        let err = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "write",
-                    "path": temp_dir.path().join("allowed.txt").to_str().unwrap(),
-                    "file_text": "test content"
-                }),
+                "terminal_replay",
+                json!({"path": notes.to_str().unwrap()}),
                 dummy_sender(),
             )
-            .await;
-
-        assert!(
-            result.is_ok(),
-            "Should be able to write to non-ignored file"
-        );
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        let cast = temp_dir.path().join("demo.cast");
+        fs::write(&cast, "{\"version\": 2, \"width\": 80, \"height\": 24}\n").unwrap();
+        let err = router
+            .call_tool(
+                "terminal_replay",
+                json!({"path": cast.to_str().unwrap(), "speed": 0}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+    }
 
-        temp_dir.close().unwrap();
+    #[tokio::test]
+    #[serial]
+    async fn test_env_inspect() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        std::env::set_var("ENV_INSPECT_VISIBLE", "shown");
+        std::env::set_var("ENV_INSPECT_API_KEY", "hidden");
+        std::env::set_var("env_inspect_password", "hidden");
+        std::env::set_var("ENV_INSPECT_TOKEN_LIMIT", "4096");
+
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            enable_system_inspection: true,
+            env_allowlist: vec!["ENV_INSPECT_TOKEN_LIMIT".to_string()],
+            ..Default::default()
+        });
+        let result = router
+            .call_tool("env_inspect", json!({}), dummy_sender())
+            .await
+            .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        let (json, note) = text.split_once("\n\n").unwrap();
+        let variables: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(variables["ENV_INSPECT_VISIBLE"], "shown");
+        assert_eq!(variables["ENV_INSPECT_TOKEN_LIMIT"], "4096");
+        assert!(variables.get("ENV_INSPECT_API_KEY").is_none());
+        assert!(variables.get("env_inspect_password").is_none());
+        assert!(note.contains("hidden"));
+
+        for name in [
+            "ENV_INSPECT_VISIBLE",
+            "ENV_INSPECT_API_KEY",
+            "env_inspect_password",
+            "ENV_INSPECT_TOKEN_LIMIT",
+        ] {
+            std::env::remove_var(name);
+        }
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_bash_respects_gitignore_fallback() {
+    async fn test_generate_uuid() {
         let temp_dir = tempfile::tempdir().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        let router = get_router().await;
 
-        // Create a .gitignore file but no .gooseignore
-        std::fs::write(temp_dir.path().join(".gitignore"), "*.log").unwrap();
+        let result = router
+            .call_tool("generate_uuid", json!({}), dummy_sender())
+            .await
+            .unwrap();
+        let uuid = uuid::Uuid::parse_str(&result[0].as_text().unwrap().text).unwrap();
+        assert_eq!(uuid.get_version_num(), 4);
 
-        let router = DeveloperRouter::new();
+        let result = router
+            .call_tool(
+                "generate_uuid",
+                json!({"count": 3, "version": "v7"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let uuids: Vec<uuid::Uuid> = result[0]
+            .as_text()
+            .unwrap()
+            .text
+            .lines()
+            .map(|line| uuid::Uuid::parse_str(line).unwrap())
+            .collect();
+        assert_eq!(uuids.len(), 3);
+        assert!(uuids.iter().all(|uuid| uuid.get_version_num() == 7));
+        assert_ne!(uuids[0], uuids[1]);
 
-        // Create a file that would be ignored by .gitignore
-        let log_file_path = temp_dir.path().join("test.log");
-        std::fs::write(&log_file_path, "log content").unwrap();
+        let err = router
+            .call_tool("generate_uuid", json!({"version": "v1"}), dummy_sender())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+    }
+
+    #[test]
+    fn test_ocr_prepare() {
+        assert!(ocr::is_valid_language("eng"));
+        assert!(ocr::is_valid_language("chi_sim+eng"));
+        assert!(!ocr::is_valid_language("eng+"));
+        assert!(!ocr::is_valid_language("../eng"));
+
+        let small = image::DynamicImage::new_rgb8(400, 100);
+        let prepared = ocr::prepare(small);
+        assert_eq!(prepared.dimensions(), (1600, 400));
+
+        let large = image::DynamicImage::new_rgb8(5000, 100);
+        assert_eq!(ocr::prepare(large).dimensions(), (4000, 80));
+    }
+
+    #[test]
+    fn test_notebook_format_outputs() {
+        let mut notebook = notebook::single_cell_notebook("print('hi')\n1 + 1\n1 / 0", "python3");
+        assert_eq!(notebook["cells"][0]["source"], "print('hi')\n1 + 1\n1 / 0");
+        assert_eq!(notebook["metadata"]["kernelspec"]["name"], "python3");
+
+        // Outputs as nbconvert writes them after executing the cell
+        notebook["cells"][0]["outputs"] = json!([
+            {"output_type": "stream", "name": "stdout", "text": ["hi\n"]},
+            {"output_type": "execute_result", "execution_count": 1, "metadata": {}, "data": {"text/plain": "2"}},
+            {"output_type": "display_data", "metadata": {}, "data": {"image/png": "iVBORw0KGgo="}},
+            {
+                "output_type": "error",
+                "ename": "ZeroDivisionError",
+                "evalue": "division by zero",
+                "traceback": ["\x1b[0;31mZeroDivisionError\x1b[0m: division by zero"]
+            }
+        ]);
+        assert_eq!(
+            notebook::format_outputs(&notebook),
+            "hi\n2\n[image/png output]\nZeroDivisionError: division by zero\n"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_html_to_markdown() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("page.html");
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        std::fs::write(
+            &file_path,
+            indoc! {r#"
+                <html>
+                <head><style>body { color: red; }</style><script>alert("hi")</script></head>
+                <body>
+                <!-- tracking pixel -->
+                <h1>Install</h1>
+                <p>See the <a href="https://example.com/docs">docs</a>.</p>
+                <pre><code>cargo install goose</code></pre>
+                </body>
+                </html>
+            "#},
+        )
+        .unwrap();
 
-        // Try to cat the ignored file
         let result = router
             .call_tool(
-                "shell",
+                "text_editor",
                 json!({
-                    "command": format!("cat {}", log_file_path.to_str().unwrap())
+                    "command": "html_to_markdown",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
-            .await;
-
-        assert!(
-            result.is_err(),
-            "Should not be able to cat file ignored by .gitignore fallback"
-        );
-        assert!(matches!(result.unwrap_err(), ToolError::ExecutionError(_)));
-
-        // Try to cat a non-ignored file
-        let allowed_file_path = temp_dir.path().join("allowed.txt");
-        std::fs::write(&allowed_file_path, "allowed content").unwrap();
+            .await
+            .unwrap();
+        let markdown = &result[0].as_text().unwrap().text;
+        assert!(markdown.contains("Install\n==="), "{}", markdown);
+        assert!(markdown.contains("[docs](https://example.com/docs)"));
+        assert!(markdown.contains("```\ncargo install goose\n```"));
+        assert!(!markdown.contains("color: red"));
+        assert!(!markdown.contains("alert"));
+        assert!(!markdown.contains("tracking pixel"));
+
+        let destination = temp_dir.path().join("page.md");
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "html_to_markdown",
+                    "path": file_path.to_str().unwrap(),
+                    "destination": destination.to_str().unwrap()
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(&std::fs::read_to_string(&destination).unwrap(), markdown);
 
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            max_file_size_bytes: 64,
+            ..Default::default()
+        });
         let result = router
             .call_tool(
-                "shell",
+                "text_editor",
                 json!({
-                    "command": format!("cat {}", allowed_file_path.to_str().unwrap())
+                    "command": "html_to_markdown",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
             .await;
-
-        assert!(result.is_ok(), "Should be able to cat non-ignored file");
+        assert!(result.unwrap_err().to_string().contains("too large"));
 
         temp_dir.close().unwrap();
     }
 
-    // Tests for view_range functionality
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_view_range() {
+    async fn test_text_editor_git_diff() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
+        let file_path = temp_dir.path().join("notes.txt");
         std::env::set_current_dir(&temp_dir).unwrap();
+        std::fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
 
-        // Create a multi-line file
-        let content =
-            "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6\nLine 7\nLine 8\nLine 9\nLine 10";
-        router
-            .call_tool(
+        let git_diff = || {
+            router.call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": content
+                    "command": "git_diff",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+        };
 
-        // Test viewing specific range
-        let view_result = router
+        // Not a repository and not edited yet, so there is nothing to compare with
+        assert!(git_diff().await.is_err());
+
+        router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
-                    "path": file_path_str,
-                    "view_range": [3, 6]
+                    "command": "str_replace",
+                    "path": file_path.to_str().unwrap(),
+                    "old_str": "two",
+                    "new_str": "2"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
 
-        let text = view_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::User))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
+        let result = git_diff().await.unwrap();
+        let diff = &result[0].as_text().unwrap().text;
+        assert!(diff.starts_with("```diff\n"), "{}", diff);
+        assert!(diff.contains("@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"));
+
+        // Inside a repository the diff comes from git
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=goose",
+                    "-c",
+                    "user.email=goose@example.com",
+                ])
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .unwrap()
+        };
+        git(&["init", "-q"]);
+        git(&["add", "notes.txt"]);
+        git(&["commit", "-q", "-m", "notes"]);
+
+        let result = git_diff().await.unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap().text,
+            format!("No changes in {}", file_path.display())
+        );
 
-        // Should contain lines 3-6 with line numbers
-        assert!(text.text.contains("3: Line 3"));
-        assert!(text.text.contains("4: Line 4"));
-        assert!(text.text.contains("5: Line 5"));
-        assert!(text.text.contains("6: Line 6"));
-        assert!(text.text.contains("(lines 3-6)"));
-        // Should not contain other lines
-        assert!(!text.text.contains("1: Line 1"));
-        assert!(!text.text.contains("7: Line 7"));
+        std::fs::write(&file_path, "one\n2\nthree\nfour\n").unwrap();
+        let result = git_diff().await.unwrap();
+        let diff = &result[0].as_text().unwrap().text;
+        assert!(
+            diff.contains("diff --git a/notes.txt b/notes.txt"),
+            "{}",
+            diff
+        );
+        assert!(diff.contains("+four"));
 
         temp_dir.close().unwrap();
     }
 
+    #[test]
+    fn test_load_prompt_files() {
+        let prompts = load_prompt_files();
+        assert!(prompts.contains_key("unit_test"));
+
+        let code_review = &prompts["code_review"];
+        let arguments = code_review.arguments.as_ref().unwrap();
+        let required: Vec<_> = arguments
+            .iter()
+            .map(|arg| (arg.name.as_str(), arg.required))
+            .collect();
+        assert_eq!(
+            required,
+            vec![("file_path", Some(true)), ("focus_areas", Some(false))]
+        );
+        assert!(code_review
+            .description
+            .as_deref()
+            .unwrap()
+            .contains("{focus_areas}"));
+    }
+
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_view_range_to_end() {
+    async fn test_text_editor_hex_dump() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
+        let file_path = temp_dir.path().join("image.bin");
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a multi-line file
-        let content = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5";
-        router
+        let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR".to_vec();
+        bytes.extend([0xff; 5000]);
+        std::fs::write(&file_path, &bytes).unwrap();
+
+        // Viewing it as text points at hex_dump instead of a bare UTF-8 error
+        let err = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": content
+                    "command": "view",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
             .await
-            .unwrap();
+            .unwrap_err();
+        assert!(err.to_string().contains("hex_dump"), "{}", err);
 
-        // Test viewing from line 3 to end using -1
-        let view_result = router
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
-                    "path": file_path_str,
-                    "view_range": [3, -1]
+                    "command": "hex_dump",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        let dump = &result[0].as_text().unwrap().text;
+        assert!(dump.contains("(first 4096 of 5016 bytes)"), "{}", dump);
+        assert!(dump.contains(
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n"
+        ));
+        assert!(dump.contains("00000ff0  ff ff"));
+        assert!(!dump.contains("00001000"));
 
-        let text = view_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::User))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
+        temp_dir.close().unwrap();
+    }
 
-        // Should contain lines 3 to end
-        assert!(text.text.contains("3: Line 3"));
-        assert!(text.text.contains("4: Line 4"));
-        assert!(text.text.contains("5: Line 5"));
-        assert!(text.text.contains("(lines 3-end)"));
-        // Should not contain earlier lines
-        assert!(!text.text.contains("1: Line 1"));
-        assert!(!text.text.contains("2: Line 2"));
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_validate_schema() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let schema_path = temp_dir.path().join("schema.json");
+        fs::write(
+            &schema_path,
+            json!({
+                "type": "object",
+                "required": ["name", "port"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "port": {"type": "integer"}
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let valid_path = temp_dir.path().join("valid.yaml");
+        fs::write(&valid_path, "name: api\nport: 8080\n").unwrap();
+        let invalid_path = temp_dir.path().join("invalid.json");
+        fs::write(&invalid_path, r#"{"port": "8080"}"#).unwrap();
+
+        let validate = |path: &Path| {
+            router.call_tool(
+                "text_editor",
+                json!({
+                    "command": "validate_schema",
+                    "path": path.to_str().unwrap(),
+                    "schema_path": schema_path.to_str().unwrap()
+                }),
+                dummy_sender(),
+            )
+        };
+
+        let result = validate(&valid_path).await.unwrap();
+        let report: Value = serde_json::from_str(&result[0].as_text().unwrap().text).unwrap();
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["errors"], json!([]));
+
+        let result = validate(&invalid_path).await.unwrap();
+        let report: Value = serde_json::from_str(&result[0].as_text().unwrap().text).unwrap();
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["error_count"], 2);
+        let mut paths: Vec<&str> = report["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["instance_path"].as_str().unwrap())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec!["", "/port"]);
+        assert!(result[1]
+            .as_text()
+            .unwrap()
+            .text
+            .contains("2 validation errors"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_view_range_invalid() {
+    async fn test_text_editor_encode_base64() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
+        let file_path = temp_dir.path().join("logo.png");
+        let destination = temp_dir.path().join("logo.b64");
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a small file
-        let content = "Line 1\nLine 2\nLine 3";
-        router
-            .call_tool(
+        let bytes: Vec<u8> = (0..=255).collect();
+        std::fs::write(&file_path, &bytes).unwrap();
+
+        let encode = |wrap_lines: bool| {
+            router.call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": content
+                    "command": "encode_base64",
+                    "path": file_path.to_str().unwrap(),
+                    "destination": destination.to_str().unwrap(),
+                    "wrap_lines": wrap_lines
                 }),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+        };
 
-        // Test invalid range - start beyond end of file
-        let result = router
+        let result = encode(false).await.unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("344 characters on 1 line"), "{}", text);
+        let written = std::fs::read_to_string(&destination).unwrap();
+        assert_eq!(
+            base64::prelude::BASE64_STANDARD
+                .decode(written.trim_end())
+                .unwrap(),
+            bytes
+        );
+
+        let result = encode(true).await.unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("344 characters on 5 lines"), "{}", text);
+        let written = std::fs::read_to_string(&destination).unwrap();
+        assert!(written.lines().all(|line| line.len() <= 76));
+        assert_eq!(written.lines().next().unwrap().len(), 76);
+        assert_eq!(
+            base64::prelude::BASE64_STANDARD
+                .decode(written.lines().collect::<String>())
+                .unwrap(),
+            bytes
+        );
+
+        let err = router
             .call_tool(
                 "text_editor",
-                json!({
-                    "command": "view",
-                    "path": file_path_str,
-                    "view_range": [10, 15]
-                }),
+                json!({"command": "encode_base64", "path": file_path.to_str().unwrap()}),
                 dummy_sender(),
             )
-            .await;
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("destination"), "{}", err);
 
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
-        assert!(err.to_string().contains("beyond the end of the file"));
+        temp_dir.close().unwrap();
+    }
 
-        // Test invalid range - start >= end
-        let result = router
-            .call_tool(
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_symbol_rename() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let lib_path = temp_dir.path().join("lib.rs");
+        let main_path = temp_dir.path().join("main.py");
+        std::fs::write(
+            &lib_path,
+            indoc! {r#"
+                /// Adds up the basket, see compute_total in the docs
+                pub fn compute_total(prices: &[u32]) -> u32 {
+                    prices.iter().sum()
+                }
+
+                fn checkout(prices: &[u32]) -> String {
+                    let total = compute_total(prices);
+                    format!("compute_total: {}", total)
+                }
+            "#},
+        )
+        .unwrap();
+        std::fs::write(
+            &main_path,
+            "from lib import compute_total\n\nprint(compute_total([1, 2]))\n",
+        )
+        .unwrap();
+
+        let rename = |new_name: &str| {
+            router.call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
-                    "path": file_path_str,
-                    "view_range": [3, 2]
+                    "command": "symbol_rename",
+                    "path": temp_dir.path().to_str().unwrap(),
+                    "symbol": "compute_total",
+                    "new_name": new_name
                 }),
                 dummy_sender(),
             )
-            .await;
+        };
 
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
-        assert!(err.to_string().contains("must be less than end line"));
+        // `total` is already a local in lib.rs, so nothing is renamed
+        let err = rename("total").await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("lib.rs:7: 'total' is already used"),
+            "{}",
+            err
+        );
+        assert!(std::fs::read_to_string(&main_path)
+            .unwrap()
+            .contains("compute_total"));
+
+        let result = rename("basket_total").await.unwrap();
+        let summary = &result[0].as_text().unwrap().text;
+        assert!(
+            summary.contains("(4 occurrences in 2 files)"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("lib.rs: lines 2, 7"));
+        assert!(summary.contains("main.py: lines 1, 3"));
+
+        let lib = std::fs::read_to_string(&lib_path).unwrap();
+        assert!(lib.contains("pub fn basket_total(prices"));
+        assert!(lib.contains("let total = basket_total(prices);"));
+        // Comments and strings keep the old name
+        assert!(lib.contains("see compute_total in the docs"));
+        assert!(lib.contains("\"compute_total: {}\""));
+        assert_eq!(
+            std::fs::read_to_string(&main_path).unwrap(),
+            "from lib import basket_total\n\nprint(basket_total([1, 2]))\n"
+        );
 
         temp_dir.close().unwrap();
     }
 
-    // Tests for insert functionality
+    #[test]
+    fn test_spell_check_parsing() {
+        let lines = spell::mask_markdown(indoc! {r#"
+            Ths is `a_vraible` in [the dcos](https://exmaple.com).
+            ```
+            let teh = 1;
+            ```
+            A sentense.
+        "#});
+        assert_eq!(
+            lines[0],
+            "Ths is             in [the dcos                      ."
+        );
+        assert_eq!(lines[2], "");
+
+        let output = indoc! {"
+            @(#) International Ispell Version 3.1.20 (but really Aspell 0.60.8)
+            & Ths 3 1: This, Thus, Th's
+            *
+            *
+            *
+            # dcos 29
+
+
+
+
+            *
+            & sentense 1 3: sentence, sentences
+
+        "};
+        let misspellings = spell::parse_pipe_output(output, &lines);
+        assert_eq!(
+            misspellings,
+            vec![
+                spell::Misspelling {
+                    word: "Ths".to_string(),
+                    line: 1,
+                    column: 1,
+                    suggestions: vec!["This".into(), "Thus".into(), "Th's".into()],
+                },
+                spell::Misspelling {
+                    word: "dcos".to_string(),
+                    line: 1,
+                    column: 28,
+                    suggestions: vec![],
+                },
+                spell::Misspelling {
+                    word: "sentense".to_string(),
+                    line: 5,
+                    column: 3,
+                    suggestions: vec!["sentence".into(), "sentences".into()],
+                },
+            ]
+        );
+        assert!(spell::format_table(&misspellings)
+            .contains("| 5 | 3 | sentense | sentence, sentences |"));
+    }
+
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_at_beginning() {
+    async fn test_text_editor_render_markdown() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
+        let file_path = temp_dir.path().join("README.md");
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a file with some content
-        let content = "Line 2\nLine 3\nLine 4";
-        router
-            .call_tool(
-                "text_editor",
-                json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": content
-                }),
-                dummy_sender(),
-            )
-            .await
-            .unwrap();
+        std::fs::write(
+            &file_path,
+            "# Title\n\nSome *emphasis* and a [link](https://example.com).\n",
+        )
+        .unwrap();
 
-        // Insert at the beginning (line 0)
-        let insert_result = router
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "insert",
-                    "path": file_path_str,
-                    "insert_line": 0,
-                    "new_str": "Line 1"
+                    "command": "render_markdown",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
 
-        let text = insert_result
+        let html_path = temp_dir.path().join("README.html");
+        let text = result
             .iter()
             .find(|c| {
                 c.audience()
@@ -2711,190 +8928,268 @@ mod tests {
             .unwrap()
             .as_text()
             .unwrap();
+        assert!(text.text.contains(html_path.to_str().unwrap()));
 
-        assert!(text.text.contains("Text has been inserted at line 1"));
+        let html = std::fs::read_to_string(&html_path).unwrap();
+        assert!(html.contains("<title>README</title>"));
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<em>emphasis</em>"));
+        assert!(html.contains("<a href=\"https://example.com\">link</a>"));
 
-        // Verify the file content
-        let view_result = router
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_write_is_atomic() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("script.sh");
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        std::fs::write(&file_path, "echo old\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
-                    "path": file_path_str
+                    "command": "write",
+                    "path": file_path.to_str().unwrap(),
+                    "file_text": "echo new"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
 
-        let view_text = view_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::User))
-            })
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "echo new\n");
+
+        // The temp file was renamed into place, nothing is left behind
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path())
             .unwrap()
-            .as_text()
-            .unwrap();
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("script.sh")]);
 
-        assert!(view_text.text.contains("1: Line 1"));
-        assert!(view_text.text.contains("2: Line 2"));
-        assert!(view_text.text.contains("3: Line 3"));
-        assert!(view_text.text.contains("4: Line 4"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&file_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
 
         temp_dir.close().unwrap();
     }
 
+    #[cfg(unix)]
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_in_middle() {
+    async fn test_text_editor_edits_keep_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        let target_path = temp_dir.path().join("dotfiles").join("bashrc");
+        std::fs::create_dir(target_path.parent().unwrap()).unwrap();
+        std::fs::write(&target_path, "alias ll='ls -l'\n").unwrap();
+        std::fs::set_permissions(&target_path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let link_path = temp_dir.path().join(".bashrc");
+        std::os::unix::fs::symlink(&target_path, &link_path).unwrap();
+        let link = link_path.to_str().unwrap();
+
+        let check = |expected: &str| {
+            assert!(std::fs::symlink_metadata(&link_path)
+                .unwrap()
+                .file_type()
+                .is_symlink());
+            assert_eq!(std::fs::read_to_string(&target_path).unwrap(), expected);
+            let mode = std::fs::metadata(&target_path)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        };
 
-        // Create a file with some content
-        let content = "Line 1\nLine 2\nLine 4\nLine 5";
         router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": content
+                    "command": "str_replace",
+                    "path": link,
+                    "old_str": "ls -l",
+                    "new_str": "ls -la"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        check("alias ll='ls -la'\n");
 
-        // Insert after line 2
-        let insert_result = router
+        router
             .call_tool(
                 "text_editor",
                 json!({
                     "command": "insert",
-                    "path": file_path_str,
-                    "insert_line": 2,
-                    "new_str": "Line 3"
+                    "path": link,
+                    "insert_line": 1,
+                    "new_str": "alias gs='git status'"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        check("alias ll='ls -la'\nalias gs='git status'\n");
 
-        let text = insert_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::Assistant))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
-
-        assert!(text.text.contains("Text has been inserted at line 3"));
-
-        // Verify the file content
-        let view_result = router
+        router
             .call_tool(
                 "text_editor",
-                json!({
-                    "command": "view",
-                    "path": file_path_str
-                }),
+                json!({"command": "undo_edit", "path": link}),
                 dummy_sender(),
             )
             .await
             .unwrap();
-
-        let view_text = view_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::User))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
-
-        assert!(view_text.text.contains("1: Line 1"));
-        assert!(view_text.text.contains("2: Line 2"));
-        assert!(view_text.text.contains("3: Line 3"));
-        assert!(view_text.text.contains("4: Line 4"));
-        assert!(view_text.text.contains("5: Line 5"));
+        check("alias ll='ls -la'\n");
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_at_end() {
+    async fn test_text_editor_bulk_edit() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
+        let file_path = temp_dir.path().join("config.toml");
         let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a file with some content
-        let content = "Line 1\nLine 2\nLine 3";
-        router
+        let original = "name = \"demo\"\nversion = \"0.1.0\"\nedition = \"2018\"\n";
+        std::fs::write(&file_path, original).unwrap();
+
+        // One missing and one overlapping edit, nothing should be written
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
+                    "command": "bulk_edit",
                     "path": file_path_str,
-                    "file_text": content
+                    "edits": [
+                        {"old_str": "version = \"0.1.0\"", "new_str": "version = \"0.2.0\""},
+                        {"old_str": "license", "new_str": "license = \"MIT\""},
+                        {"old_str": "\"0.1.0\"\nedition", "new_str": "\"0.1.0\"\n# edition"}
+                    ]
                 }),
                 dummy_sender(),
             )
-            .await
-            .unwrap();
+            .await;
+        let Err(ToolError::InvalidParameters(message)) = result else {
+            panic!("expected invalid parameters, got {:?}", result);
+        };
+        assert!(message.contains("edit 2: 'old_str' does not appear in the file"));
+        assert!(message.contains("edit 3: 'old_str' overlaps with edit 1"));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), original);
 
-        // Insert at the end (after line 3)
-        let insert_result = router
+        router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "insert",
+                    "command": "bulk_edit",
                     "path": file_path_str,
-                    "insert_line": 3,
-                    "new_str": "Line 4"
+                    "edits": [
+                        {"old_str": "edition = \"2018\"", "new_str": "edition = \"2021\""},
+                        {"old_str": "version = \"0.1.0\"", "new_str": "version = \"0.2.0\""}
+                    ]
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "name = \"demo\"\nversion = \"0.2.0\"\nedition = \"2021\"\n"
+        );
 
-        let text = insert_result
-            .iter()
-            .find(|c| {
-                c.audience()
-                    .is_some_and(|roles| roles.contains(&Role::Assistant))
-            })
-            .unwrap()
-            .as_text()
-            .unwrap();
-
-        assert!(text.text.contains("Text has been inserted at line 4"));
-
-        // Verify the file content
-        let view_result = router
+        // Both replacements are undone together
+        router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
+                    "command": "undo_edit",
                     "path": file_path_str
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), original);
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "*.env").unwrap();
+        let router = DeveloperRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(builder.build().unwrap()),
+            config: DeveloperRouterConfig::default(),
+            output_cache: Arc::new(Mutex::new(HashMap::new())),
+            plugins: HashMap::new(),
+            recording: Arc::new(Mutex::new(None)),
+        };
+
+        std::fs::write(temp_dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("README.md"), "# Readme\n").unwrap();
+        std::fs::write(temp_dir.path().join("secrets.env"), "TOKEN=1\n").unwrap();
+        std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+
+        let result = router
+            .text_editor_view(&temp_dir.path().to_path_buf(), None)
+            .await
+            .unwrap();
 
-        let view_text = view_result
+        let listing: Value = serde_json::from_str(
+            &result
+                .iter()
+                .find(|c| {
+                    c.audience()
+                        .is_some_and(|roles| roles.contains(&Role::Assistant))
+                })
+                .unwrap()
+                .as_text()
+                .unwrap()
+                .text,
+        )
+        .unwrap();
+        let entries = listing["entries"].as_array().unwrap();
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["README.md", "main.rs", "src"]);
+        assert_eq!(entries[1]["type"], "file");
+        assert_eq!(entries[1]["size"], 13);
+        assert_eq!(entries[1]["language"], "rust");
+        assert_eq!(entries[2]["type"], "directory");
+        assert_eq!(listing["truncated"], false);
+
+        let table = result
             .iter()
             .find(|c| {
                 c.audience()
@@ -2903,195 +9198,391 @@ mod tests {
             .unwrap()
             .as_text()
             .unwrap();
-
-        assert!(view_text.text.contains("1: Line 1"));
-        assert!(view_text.text.contains("2: Line 2"));
-        assert!(view_text.text.contains("3: Line 3"));
-        assert!(view_text.text.contains("4: Line 4"));
+        assert!(table.text.contains("| src/ |"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_invalid_line() {
+    async fn test_text_editor_write_lines() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
+        let file_path = temp_dir.path().join("lib.rs");
         let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a file with some content
-        let content = "Line 1\nLine 2\nLine 3";
-        router
+        let original = "fn add(a: i32, b: i32) -> i32 {\n    let sum = a + b;\n    sum\n}\n";
+        std::fs::write(&file_path, original).unwrap();
+
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "write",
+                    "command": "write_lines",
                     "path": file_path_str,
-                    "file_text": content
+                    "start_line": 2,
+                    "end_line": 3,
+                    "new_content": "    a + b"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n"
+        );
+
+        let diff = result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        assert!(diff
+            .text
+            .contains("@@ -2,2 +2,1 @@\n-    let sum = a + b;\n-    sum\n+    a + b"));
 
-        // Try to insert beyond the end of the file
         let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "insert",
+                    "command": "write_lines",
                     "path": file_path_str,
-                    "insert_line": 10,
-                    "new_str": "Line 11"
+                    "start_line": 3,
+                    "end_line": 5,
+                    "new_content": ""
                 }),
                 dummy_sender(),
             )
             .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
 
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
-        assert!(err.to_string().contains("beyond the end of the file"));
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "undo_edit",
+                    "path": file_path_str
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), original);
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_missing_parameters() {
+    async fn test_text_editor_sort_lines() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
+        let file_path = temp_dir.path().join("words.txt");
         let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Create a file
+        let original = "# words\ncherry\nBanana\napple\n";
+        std::fs::write(&file_path, original).unwrap();
+
+        let sort = |extra: Value| {
+            let mut params = json!({"command": "sort_lines", "path": file_path_str});
+            params
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            router.call_tool("text_editor", params, dummy_sender())
+        };
+
+        let result = sort(json!({"start_line": 2, "ignore_case": true}))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "# words\napple\nBanana\ncherry\n"
+        );
+        let diff = &result[1].as_text().unwrap().text;
+        assert!(diff.contains("-cherry") && diff.contains("+cherry"));
+
+        sort(json!({"reverse": true})).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "cherry\napple\nBanana\n# words\n"
+        );
+
+        let result = sort(json!({"reverse": true})).await.unwrap();
+        assert!(result[0].as_text().unwrap().text.contains("already sorted"));
+
+        let result = sort(json!({"start_line": 3, "end_line": 9})).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
         router
             .call_tool(
                 "text_editor",
-                json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": "Test content"
-                }),
+                json!({"command": "undo_edit", "path": file_path_str}),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "# words\napple\nBanana\ncherry\n"
+        );
+
+        // An empty file has nothing to sort
+        std::fs::write(&file_path, "").unwrap();
+        let result = sort(json!({})).await.unwrap();
+        assert!(result[0].as_text().unwrap().text.contains("is empty"));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "");
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_watch_file() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("build.log");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        std::fs::write(&file_path, "building...\n").unwrap();
 
-        // Try insert without insert_line parameter
         let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "insert",
+                    "command": "watch_file",
                     "path": file_path_str,
-                    "new_str": "New line"
+                    "timeout_secs": 0
                 }),
                 dummy_sender(),
             )
             .await;
+        let Err(ToolError::ExecutionError(message)) = result else {
+            panic!("expected a timeout, got {:?}", result);
+        };
+        assert!(message.contains("Timed out"));
 
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
-        assert!(err.to_string().contains("Missing 'insert_line' parameter"));
+        let writer_path = file_path.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            std::fs::write(writer_path, "build finished\n").unwrap();
+        });
 
-        // Try insert without new_str parameter
         let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "insert",
+                    "command": "watch_file",
                     "path": file_path_str,
-                    "insert_line": 1
+                    "timeout_secs": 10
                 }),
                 dummy_sender(),
             )
-            .await;
-
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
-        assert!(err.to_string().contains("Missing 'new_str' parameter"));
+            .await
+            .unwrap();
+        let text = result
+            .iter()
+            .filter_map(|c| c.as_text())
+            .map(|t| t.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains("build finished"));
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_with_undo() {
+    async fn test_watch_directory() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.txt");
-        let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
+        let build_dir = temp_dir.path().join("build");
+        std::fs::create_dir(&build_dir).unwrap();
+
+        let writer_dir = build_dir.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            std::fs::write(writer_dir.join("main.o"), "object").unwrap();
+            std::fs::write(writer_dir.join("build.log"), "done").unwrap();
+        });
 
-        // Create a file with some content
-        let content = "Line 1\nLine 2";
-        router
+        let result = router
             .call_tool(
-                "text_editor",
+                "watch_directory",
                 json!({
-                    "command": "write",
-                    "path": file_path_str,
-                    "file_text": content
+                    "path": build_dir.to_str().unwrap(),
+                    "pattern": "*.o",
+                    "timeout_secs": 1
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        let text = result[0].as_text().unwrap().text.as_str();
+        assert!(text.contains("created main.o"), "{}", text);
+        assert!(!text.contains("build.log"));
 
-        // Insert a line
-        router
+        let result = router
             .call_tool(
-                "text_editor",
-                json!({
-                    "command": "insert",
-                    "path": file_path_str,
-                    "insert_line": 1,
-                    "new_str": "Inserted Line"
-                }),
+                "watch_directory",
+                json!({"path": build_dir.to_str().unwrap(), "timeout_secs": 0}),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        assert!(result[0].as_text().unwrap().text.starts_with("No changes"));
 
-        // Undo the insert
-        let undo_result = router
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_shell_output_cache() {
+        assert!(is_idempotent_command("git log --oneline -5"));
+        assert!(is_idempotent_command("  cargo metadata --format-version 1"));
+        assert!(!is_idempotent_command("git logs"));
+        assert!(!is_idempotent_command("git log > out.txt"));
+        assert!(!is_idempotent_command("npm list && rm -rf node_modules"));
+        assert!(!is_idempotent_command("git commit -m 'log'"));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = DeveloperRouter::new_with_config(DeveloperRouterConfig {
+            shell_cache_ttl_secs: 60,
+            ..Default::default()
+        });
+        let shell_output = |result: Vec<Content>| {
+            result
+                .iter()
+                .find(|c| {
+                    c.audience()
+                        .is_some_and(|roles| roles.contains(&Role::Assistant))
+                })
+                .unwrap()
+                .as_text()
+                .unwrap()
+                .text
+                .clone()
+        };
+
+        // A fresh entry is returned without running the command
+        let cwd = std::env::current_dir().unwrap();
+        router.output_cache.lock().unwrap().insert(
+            (cwd.clone(), "git log -1".to_string()),
+            (Instant::now(), "cached log".to_string()),
+        );
+        let result = router
+            .call_tool("shell", json!({"command": "git log -1"}), dummy_sender())
+            .await
+            .unwrap();
+        assert_eq!(shell_output(result), "cached log");
+
+        // Output cached in another directory is not reused
+        let other_dir = temp_dir.path().join("other");
+        fs::create_dir(&other_dir).unwrap();
+        std::env::set_current_dir(&other_dir).unwrap();
+        let result = router
+            .call_tool("shell", json!({"command": "git log -1"}), dummy_sender())
+            .await;
+        if let Ok(result) = result {
+            assert_ne!(shell_output(result), "cached log");
+        }
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        // Edits through the text editor invalidate the cache
+        router.output_cache.lock().unwrap().insert(
+            (cwd, "cargo tree".to_string()),
+            (Instant::now(), "cached tree".to_string()),
+        );
+        let manifest = temp_dir.path().join("Cargo.toml");
+        router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "undo_edit",
-                    "path": file_path_str
+                    "command": "write",
+                    "path": manifest.to_str().unwrap(),
+                    "file_text": "[package]\nname = \"demo\"\n"
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
+        assert!(router.output_cache.lock().unwrap().is_empty());
+        router.output_cache.lock().unwrap().insert(
+            (std::env::current_dir().unwrap(), "git log -1".to_string()),
+            (Instant::now(), "cached log".to_string()),
+        );
 
-        let text = undo_result.first().unwrap().as_text().unwrap();
-        assert!(text.text.contains("Undid the last edit"));
+        // Any other command invalidates the cache
+        let result = router
+            .call_tool("shell", json!({"command": "echo hello"}), dummy_sender())
+            .await
+            .unwrap();
+        assert_eq!(shell_output(result).trim(), "hello");
+        assert!(router.output_cache.lock().unwrap().is_empty());
 
-        // Verify the file is back to original content
-        let view_result = router
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_read_csv() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("people.csv");
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        std::fs::write(
+            &file_path,
+            "name,role\nAlice,\"engineer, backend\"\nBob,designer\n",
+        )
+        .unwrap();
+
+        let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "view",
-                    "path": file_path_str
+                    "command": "read_csv",
+                    "path": file_path.to_str().unwrap()
                 }),
                 dummy_sender(),
             )
             .await
             .unwrap();
 
-        let view_text = view_result
+        let text = result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::Assistant))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        let rows: Value = serde_json::from_str(&text.text).unwrap();
+        assert_eq!(
+            rows,
+            json!([
+                {"name": "Alice", "role": "engineer, backend"},
+                {"name": "Bob", "role": "designer"}
+            ])
+        );
+
+        let preview = result
             .iter()
             .find(|c| {
                 c.audience()
@@ -3100,43 +9591,164 @@ mod tests {
             .unwrap()
             .as_text()
             .unwrap();
+        assert!(preview.text.contains("| name | role |"));
+        assert!(preview.text.contains("| Bob | designer |"));
 
-        assert!(view_text.text.contains("1: Line 1"));
-        assert!(view_text.text.contains("2: Line 2"));
-        assert!(!view_text.text.contains("Inserted Line"));
+        temp_dir.close().unwrap();
+    }
+
+    struct EchoPlugin;
+
+    #[async_trait::async_trait]
+    impl ToolPlugin for EchoPlugin {
+        fn tool_definition(&self) -> Tool {
+            Tool::new(
+                "echo",
+                "Echo the message back",
+                object!({
+                    "type": "object",
+                    "required": ["message"],
+                    "properties": {
+                        "message": {"type": "string"}
+                    }
+                }),
+            )
+        }
+
+        async fn call(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+            let message = params
+                .get("message")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| ToolError::InvalidParameters("Missing 'message'".into()))?;
+            Ok(vec![Content::text(message)])
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_register_plugin() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let mut router = DeveloperRouter::new();
+        router.register_plugin(Box::new(EchoPlugin)).unwrap();
+        assert!(router.list_tools().iter().any(|t| t.name == "echo"));
+
+        let result = router
+            .call_tool("echo", json!({"message": "hello"}), dummy_sender())
+            .await
+            .unwrap();
+        assert_eq!(result[0].as_text().unwrap().text, "hello");
+
+        // Names must be unique, including against the built-in tools
+        assert!(router.register_plugin(Box::new(EchoPlugin)).is_err());
 
         temp_dir.close().unwrap();
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_text_editor_insert_nonexistent_file() {
+    async fn test_text_editor_yaml_query() {
         let router = get_router().await;
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("nonexistent.txt");
+        let file_path = temp_dir.path().join("deployment.yaml");
         let file_path_str = file_path.to_str().unwrap();
         std::env::set_current_dir(&temp_dir).unwrap();
 
-        // Try to insert into a nonexistent file
+        std::fs::write(
+            &file_path,
+            indoc! {r#"
+                kind: Deployment
+                spec:
+                  replicas: 2
+                  containers:
+                    - name: web
+                      image: nginx:1.25
+                    - name: sidecar
+                      image: envoy:1.30
+            "#},
+        )
+        .unwrap();
+
+        let assistant_text = |result: Vec<Content>| {
+            result
+                .iter()
+                .find(|c| {
+                    c.audience()
+                        .is_some_and(|roles| roles.contains(&Role::Assistant))
+                })
+                .unwrap()
+                .as_text()
+                .unwrap()
+                .text
+                .clone()
+        };
+
         let result = router
             .call_tool(
                 "text_editor",
                 json!({
-                    "command": "insert",
+                    "command": "yaml_query",
                     "path": file_path_str,
-                    "insert_line": 0,
-                    "new_str": "New line"
+                    "query": "$.spec.containers[*].image"
                 }),
                 dummy_sender(),
             )
-            .await;
+            .await
+            .unwrap();
+        assert_eq!(assistant_text(result), "- nginx:1.25\n- envoy:1.30\n");
 
-        assert!(result.is_err());
-        let err = result.err().unwrap();
-        assert!(matches!(err, ToolError::InvalidParameters(_)));
-        assert!(err.to_string().contains("does not exist"));
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "yaml_query",
+                    "path": file_path_str,
+                    "query": "$.spec.replicas",
+                    "output_format": "json"
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(assistant_text(result), "2");
 
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn test_semantic_search_chunking() {
+        let source = indoc! {r#"
+            use std::time::Duration;
+
+            struct Client;
+
+            impl Client {
+                fn retry_with_backoff(&self) {
+                    let delay = Duration::from_millis(100);
+                    let _ = delay;
+                }
+            }
+
+            fn main() {
+                fn nested() {}
+                nested();
+            }
+        "#};
+
+        let chunks = chunk_file(Path::new("src/main.rs"), source);
+        let ranges: Vec<_> = chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(6, 9), (12, 15)]);
+        assert!(chunks[0].text.starts_with("fn retry_with_backoff"));
+
+        // Files without a grammar are split into fixed windows
+        let notes = "note\n".repeat(120);
+        let chunks = chunk_file(Path::new("notes.md"), &notes);
+        let ranges: Vec<_> = chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(1, 50), (51, 100), (101, 120)]);
+
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), 0.0);
+    }
 }
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Resolve `hostname` with the system resolver, so entries in /etc/hosts count the same way they
+/// do for the programs being debugged
+pub async fn dns_lookup(hostname: &str, timeout: Duration) -> Value {
+    let start = Instant::now();
+    // lookup_host wants a port, which does not affect the addresses returned
+    let result = tokio::time::timeout(timeout, tokio::net::lookup_host((hostname, 0))).await;
+    let latency_ms = elapsed_ms(start);
+    match result {
+        Ok(Ok(addresses)) => {
+            // Each address comes once per socket type, the first keeps the resolver's order
+            let mut seen = HashSet::new();
+            let addresses: Vec<String> = addresses
+                .map(|a| a.ip().to_string())
+                .filter(|a| seen.insert(a.clone()))
+                .collect();
+            json!({
+                "hostname": hostname,
+                "resolved": true,
+                "addresses": addresses,
+                "latency_ms": latency_ms,
+            })
+        }
+        Ok(Err(e)) => json!({
+            "hostname": hostname,
+            "resolved": false,
+            "error": e.to_string(),
+            "latency_ms": latency_ms,
+        }),
+        Err(_) => json!({
+            "hostname": hostname,
+            "resolved": false,
+            "error": format!("timed out after {} ms", timeout.as_millis()),
+            "latency_ms": latency_ms,
+        }),
+    }
+}
+
+/// Whether a TCP connection to `host:port` is accepted within `timeout`
+pub async fn port_check(host: &str, port: u16, timeout: Duration) -> Value {
+    let start = Instant::now();
+    let result = tokio::time::timeout(timeout, tokio::net::TcpStream::connect((host, port))).await;
+    let latency_ms = elapsed_ms(start);
+    let error = match result {
+        Ok(Ok(stream)) => {
+            return json!({
+                "host": host,
+                "port": port,
+                "open": true,
+                "address": stream.peer_addr().ok().map(|a| a.to_string()),
+                "latency_ms": latency_ms,
+            })
+        }
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => format!("timed out after {} ms", timeout.as_millis()),
+    };
+    json!({
+        "host": host,
+        "port": port,
+        "open": false,
+        "error": error,
+        "latency_ms": latency_ms,
+    })
+}
+
+/// Send a HEAD request to `url` without following redirects, so the response shows where the
+/// server points to
+pub async fn http_head(url: &str, timeout: Duration) -> Result<Value, String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let start = Instant::now();
+    let result = client.head(url).send().await;
+    let latency_ms = elapsed_ms(start);
+    Ok(match result {
+        Ok(response) => {
+            let header = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string)
+            };
+            json!({
+                "url": url,
+                "reachable": true,
+                "status": response.status().as_u16(),
+                "content_type": header("content-type"),
+                "location": header("location"),
+                "server": header("server"),
+                "latency_ms": latency_ms,
+            })
+        }
+        Err(e) => json!({
+            "url": url,
+            "reachable": false,
+            "error": if e.is_timeout() {
+                format!("timed out after {} ms", timeout.as_millis())
+            } else {
+                e.to_string()
+            },
+            "latency_ms": latency_ms,
+        }),
+    })
+}
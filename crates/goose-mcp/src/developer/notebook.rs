@@ -0,0 +1,141 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+pub const DEFAULT_KERNEL: &str = "python3";
+
+/// Extra time nbconvert gets past the cell timeout to shut the kernel down before it is killed
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(15);
+
+/// Whether `jupyter` is on the PATH, the notebook_execute tool is only offered when it is
+pub fn jupyter_available() -> bool {
+    which::which("jupyter").is_ok()
+}
+
+/// An nbformat 4 notebook holding one code cell
+pub fn single_cell_notebook(source: &str, kernel_name: &str) -> Value {
+    json!({
+        "cells": [{
+            "cell_type": "code",
+            "execution_count": null,
+            "metadata": {},
+            "outputs": [],
+            "source": source,
+        }],
+        "metadata": {
+            "kernelspec": {
+                "name": kernel_name,
+                "display_name": kernel_name,
+            }
+        },
+        "nbformat": 4,
+        "nbformat_minor": 5,
+    })
+}
+
+/// Execute the notebook at `path` in place and return it with its outputs. nbconvert stops the
+/// cell and shuts the kernel down once `timeout` passes, and is killed if it does not exit
+/// shortly after.
+pub async fn execute_notebook(
+    path: &Path,
+    kernel_name: &str,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let child = Command::new("jupyter")
+        .arg("nbconvert")
+        .args([
+            "--to",
+            "notebook",
+            "--execute",
+            "--inplace",
+            "--allow-errors",
+        ])
+        .arg(format!(
+            "--ExecutePreprocessor.timeout={}",
+            timeout.as_secs()
+        ))
+        .arg(format!("--ExecutePreprocessor.kernel_name={}", kernel_name))
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start jupyter nbconvert: {}", e))?;
+
+    let output = tokio::time::timeout(timeout + SHUTDOWN_GRACE, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            format!(
+                "Notebook execution timed out after {} seconds",
+                timeout.as_secs()
+            )
+        })?
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("CellTimeoutError") {
+            return Err(format!(
+                "The cell did not finish within {} seconds",
+                timeout.as_secs()
+            ));
+        }
+        return Err(format!("jupyter nbconvert failed:\n{}", stderr.trim()));
+    }
+
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid executed notebook: {}", e))
+}
+
+/// The outputs of every code cell as text, the way Jupyter shows them
+pub fn format_outputs(notebook: &Value) -> String {
+    let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+    let mut text = String::new();
+
+    let outputs = notebook["cells"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|cell| cell["outputs"].as_array())
+        .flatten();
+    for output in outputs {
+        match output["output_type"].as_str() {
+            Some("stream") => text.push_str(&multiline(&output["text"])),
+            Some("execute_result") | Some("display_data") => {
+                let data = &output["data"];
+                if data.get("text/plain").is_some() {
+                    text.push_str(&multiline(&data["text/plain"]));
+                    text.push('\n');
+                } else if let Some(mime) = data.as_object().and_then(|d| d.keys().next()) {
+                    text.push_str(&format!("[{} output]\n", mime));
+                }
+            }
+            Some("error") => {
+                let traceback = output["traceback"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                text.push_str(&ansi.replace_all(&traceback, ""));
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+// nbformat stores multiline text either as a string or as a list of lines
+fn multiline(value: &Value) -> String {
+    match value {
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        Value::String(text) => text.clone(),
+        _ => String::new(),
+    }
+}
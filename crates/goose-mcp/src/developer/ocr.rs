@@ -0,0 +1,40 @@
+use image::{imageops::FilterType, DynamicImage, GrayImage};
+
+/// Tesseract reads small text best when glyphs are around 30px tall, so narrow images such as
+/// cropped screenshots are scaled up to this width
+const MIN_OCR_WIDTH: u32 = 1600;
+/// Larger images only slow recognition down
+const MAX_OCR_WIDTH: u32 = 4000;
+
+/// Tesseract language codes, several joined with `+` like `eng+deu`
+pub fn is_valid_language(lang: &str) -> bool {
+    !lang.is_empty()
+        && lang.split('+').all(|code| {
+            !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// Convert to grayscale and scale the image into the width range Tesseract works best with
+pub fn prepare(image: DynamicImage) -> GrayImage {
+    let width = image.width().max(1);
+    let target = width.clamp(MIN_OCR_WIDTH, MAX_OCR_WIDTH);
+    let image = if target == width {
+        image
+    } else {
+        let height = ((image.height() as u64 * target as u64) / width as u64).max(1) as u32;
+        image.resize_exact(target, height, FilterType::Lanczos3)
+    };
+    image.to_luma8()
+}
+
+#[cfg(feature = "ocr")]
+pub fn recognize(image: &GrayImage, lang: &str) -> Result<String, String> {
+    let width = image.width() as i32;
+    tesseract::ocr_from_frame(image.as_raw(), width, image.height() as i32, 1, width, lang)
+        .map_err(|e| format!("Tesseract failed: {}", e))
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn recognize(_image: &GrayImage, _lang: &str) -> Result<String, String> {
+    Err("Text recognition is not available, goose-mcp was built without the `ocr` feature".into())
+}
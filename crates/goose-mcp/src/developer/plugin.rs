@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use mcp_core::handler::ToolError;
+use rmcp::model::{Content, Tool};
+use serde_json::Value;
+
+/// A tool added to the developer router at runtime, see `DeveloperRouter::register_plugin`.
+///
+/// Plugins let teams expose domain specific tools, like a `kubectl` wrapper or a database
+/// introspector, alongside the built-in developer tools.
+#[async_trait]
+pub trait ToolPlugin: Send + Sync {
+    /// The definition listed to the model, its name is what `call` is dispatched on
+    fn tool_definition(&self) -> Tool;
+
+    async fn call(&self, params: Value) -> Result<Vec<Content>, ToolError>;
+}
@@ -0,0 +1,66 @@
+use serde::Serialize;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// Most processes returned, the busiest ones are kept
+pub const MAX_PROCESSES: usize = 50;
+
+/// Longer command lines are cut short, some tools pass whole scripts as arguments
+const MAX_COMMAND_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_mb: f64,
+    pub command: String,
+}
+
+/// The running processes whose name contains `name_contains`, ignoring case, busiest first.
+/// Blocks for a moment since CPU usage is measured between two samples.
+pub fn list(name_contains: Option<&str>) -> Vec<ProcessInfo> {
+    let refresh = ProcessRefreshKind::new()
+        .with_cpu()
+        .with_memory()
+        .with_cmd(UpdateKind::OnlyIfNotSet);
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+
+    let filter = name_contains.map(str::to_lowercase);
+    let mut processes: Vec<ProcessInfo> = system
+        .processes()
+        .values()
+        .filter(|process| match &filter {
+            Some(filter) => process
+                .name()
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(filter),
+            None => true,
+        })
+        .map(|process| {
+            let command = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let command = match command.char_indices().nth(MAX_COMMAND_CHARS) {
+                Some((end, _)) => format!("{}...", &command[..end]),
+                None => command,
+            };
+            ProcessInfo {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                cpu_percent: (process.cpu_usage() * 10.0).round() / 10.0,
+                memory_mb: (process.memory() as f64 / (1024.0 * 1024.0) * 10.0).round() / 10.0,
+                command,
+            }
+        })
+        .collect();
+    processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+    processes.truncate(MAX_PROCESSES);
+    processes
+}
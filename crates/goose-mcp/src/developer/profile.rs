@@ -0,0 +1,282 @@
+use std::process::ExitStatus;
+use std::time::Duration;
+#[cfg(unix)]
+use std::{io::Read, path::PathBuf, process::Stdio, time::Instant};
+
+use tokio_util::sync::CancellationToken;
+
+/// Lines of the command's own output kept in the report, the measurements are what matter
+const OUTPUT_TAIL_LINES: usize = 50;
+
+/// Measurements of one run of a command
+#[derive(Debug)]
+pub struct ProfileReport {
+    pub status: ExitStatus,
+    pub wall_time: Duration,
+    pub user_time: Duration,
+    pub system_time: Duration,
+    pub peak_memory_bytes: u64,
+    /// Summary written by the platform profiler, when one was available
+    pub profiler: Option<(&'static str, String)>,
+    /// Combined stdout and stderr of the command
+    pub output: String,
+}
+
+#[cfg(unix)]
+enum Profiler {
+    /// `perf stat`, writing its summary to the file
+    PerfStat(PathBuf),
+    /// Instruments' Time Profiler, saving a trace to the path
+    Instruments(PathBuf),
+}
+
+#[cfg(unix)]
+impl Profiler {
+    fn find(scratch: &tempfile::TempDir) -> Option<Self> {
+        if cfg!(target_os = "linux") && which::which("perf").is_ok() {
+            return Some(Profiler::PerfStat(scratch.path().join("perf-stat.txt")));
+        }
+        if cfg!(target_os = "macos") && which::which("xcrun").is_ok() {
+            // The trace outlives the call so it can be opened in Instruments
+            let name = format!(
+                "goose-profile-{}.trace",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            );
+            return Some(Profiler::Instruments(std::env::temp_dir().join(name)));
+        }
+        None
+    }
+
+    fn command(&self, command: &str) -> std::process::Command {
+        let mut cmd = match self {
+            Profiler::PerfStat(output) => {
+                let mut cmd = std::process::Command::new("perf");
+                cmd.arg("stat").arg("-o").arg(output).arg("--");
+                cmd
+            }
+            Profiler::Instruments(trace) => {
+                let mut cmd = std::process::Command::new("xcrun");
+                cmd.args([
+                    "xctrace",
+                    "record",
+                    "--template",
+                    "Time Profiler",
+                    "--output",
+                ])
+                .arg(trace)
+                .args(["--launch", "--"]);
+                cmd
+            }
+        };
+        cmd.args(["/bin/sh", "-c", command]);
+        cmd
+    }
+
+    fn summary(&self) -> (&'static str, String) {
+        match self {
+            Profiler::PerfStat(output) => {
+                let summary = std::fs::read_to_string(output)
+                    .map(|s| {
+                        s.lines()
+                            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .unwrap_or_else(|e| format!("perf stat wrote no summary: {}", e));
+                ("perf stat", summary)
+            }
+            Profiler::Instruments(trace) => (
+                "Instruments",
+                format!(
+                    "Time Profiler trace saved to {}, open it with `open {}`",
+                    trace.display(),
+                    trace.display()
+                ),
+            ),
+        }
+    }
+}
+
+/// Run `command` through `/bin/sh` under the platform profiler, measuring wall time, CPU time
+/// and peak memory of the process tree. The command is killed when it runs past `timeout` or
+/// `cancel` fires.
+#[cfg(unix)]
+pub async fn profile(
+    command: &str,
+    timeout: Option<Duration>,
+    cancel: &CancellationToken,
+) -> Result<ProfileReport, String> {
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+    let scratch = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let profiler = Profiler::find(&scratch);
+    let mut cmd = match &profiler {
+        Some(profiler) => profiler.command(command),
+        None => {
+            let mut cmd = std::process::Command::new("/bin/sh");
+            cmd.args(["-c", command]);
+            cmd
+        }
+    };
+    // Its own process group, so a timeout stops everything the command started
+    cmd.process_group(0)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let start = Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+    let pid = child.id() as libc::pid_t;
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+    // Reaped by wait4 below, which also reports the resources it used
+    drop(child);
+
+    let mut waiter = tokio::task::spawn_blocking(move || wait_with_usage(pid));
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let stopped = tokio::select! {
+        result = &mut waiter => {
+            let (status, usage) = result
+                .map_err(|e| e.to_string())?
+                .map_err(|e| format!("Failed to wait for '{}': {}", command, e))?;
+            let output = [stdout, stderr]
+                .into_iter()
+                .filter_map(|reader| reader.join().ok())
+                .collect::<String>();
+            return Ok(ProfileReport {
+                status: ExitStatus::from_raw(status),
+                wall_time: start.elapsed(),
+                user_time: timeval_duration(usage.ru_utime),
+                system_time: timeval_duration(usage.ru_stime),
+                peak_memory_bytes: max_rss_bytes(usage.ru_maxrss),
+                profiler: profiler.map(|profiler| profiler.summary()),
+                output,
+            });
+        }
+        _ = deadline => format!(
+            "Command '{}' timed out after {} seconds",
+            command,
+            timeout.unwrap_or_default().as_secs()
+        ),
+        _ = cancel.cancelled() => format!("Command '{}' was cancelled", command),
+    };
+
+    // SAFETY: the process has not been reaped yet, so the group id is still ours
+    unsafe {
+        libc::kill(-pid, libc::SIGKILL);
+    }
+    let _ = waiter.await;
+    Err(stopped)
+}
+
+#[cfg(not(unix))]
+pub async fn profile(
+    _command: &str,
+    _timeout: Option<Duration>,
+    _cancel: &CancellationToken,
+) -> Result<ProfileReport, String> {
+    Err("Profiling is only supported on Linux and macOS".to_string())
+}
+
+#[cfg(unix)]
+fn read_to_end<R: Read + Send + 'static>(reader: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut reader) = reader {
+            let _ = reader.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+#[cfg(unix)]
+fn wait_with_usage(pid: libc::pid_t) -> std::io::Result<(i32, libc::rusage)> {
+    let mut status = 0;
+    // SAFETY: rusage is plain old data, and wait4 only writes to the two out pointers
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        if unsafe { libc::wait4(pid, &mut status, 0, &mut usage) } == pid {
+            return Ok((status, usage));
+        }
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn timeval_duration(time: libc::timeval) -> Duration {
+    Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+}
+
+// Linux reports the peak resident set size in kilobytes, macOS in bytes
+#[cfg(unix)]
+fn max_rss_bytes(max_rss: libc::c_long) -> u64 {
+    let max_rss = max_rss.max(0) as u64;
+    if cfg!(target_os = "macos") {
+        max_rss
+    } else {
+        max_rss * 1024
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+impl ProfileReport {
+    pub fn format(&self, command: &str) -> String {
+        let cpu_time = self.user_time + self.system_time;
+        let mut report = format!(
+            "## Profile of `{}`\n\n\
+            | Metric | Value |\n|---|---|\n\
+            | Exit status | {} |\n\
+            | Wall time | {:.3}s |\n\
+            | CPU time | {:.3}s (user {:.3}s, system {:.3}s) |\n\
+            | Peak memory | {} |\n",
+            command,
+            self.status
+                .code()
+                .map_or_else(|| "killed by a signal".to_string(), |code| code.to_string()),
+            self.wall_time.as_secs_f64(),
+            cpu_time.as_secs_f64(),
+            self.user_time.as_secs_f64(),
+            self.system_time.as_secs_f64(),
+            format_bytes(self.peak_memory_bytes),
+        );
+
+        if let Some((name, summary)) = &self.profiler {
+            report.push_str(&format!("\n### {}\n\n```\n{}\n```\n", name, summary));
+        }
+
+        let lines: Vec<&str> = self.output.lines().collect();
+        if !lines.is_empty() {
+            let skipped = lines.len().saturating_sub(OUTPUT_TAIL_LINES);
+            report.push_str("\n### Output\n\n");
+            if skipped > 0 {
+                report.push_str(&format!("({} earlier lines omitted)\n", skipped));
+            }
+            report.push_str(&format!("```\n{}\n```\n", lines[skipped..].join("\n")));
+        }
+        report
+    }
+}
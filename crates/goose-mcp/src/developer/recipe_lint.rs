@@ -0,0 +1,558 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// Keys a recipe reads, anything else is ignored when it is loaded
+const RECIPE_KEYS: &[&str] = &[
+    "version",
+    "title",
+    "description",
+    "instructions",
+    "prompt",
+    "extensions",
+    "context",
+    "settings",
+    "activities",
+    "author",
+    "parameters",
+    "response",
+    "sub_recipes",
+    "retry",
+    "dependencies",
+    "checksum",
+];
+const EXTENSION_TYPES: &[&str] = &["builtin", "stdio", "sse", "streamable_http", "frontend"];
+// Array parameters are written as `array: { item_type: <one of these> }`
+const INPUT_TYPES: &[&str] = &["string", "number", "boolean", "date", "file", "select"];
+const REQUIREMENTS: &[&str] = &["required", "optional", "user_prompt"];
+/// Template variables goose sets itself when rendering a recipe
+const BUILT_IN_PARAMS: &[&str] = &["recipe_dir", "dependency_session_ids"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in a recipe file, `line` is 1-based
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: Option<usize>,
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        write!(f, "\n  fix: {}", self.suggestion)
+    }
+}
+
+/// Check the content of a recipe file against the rules goose applies when it loads and
+/// renders recipes. Errors would stop the recipe from loading, warnings are likely mistakes.
+pub fn lint(content: &str) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        content,
+        diagnostics: Vec::new(),
+    };
+    linter.run();
+    linter.diagnostics
+}
+
+/// Errors and warnings as separate sections, the way the lint_recipe tool returns them
+pub fn format_report(diagnostics: &[Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return "No problems found".to_string();
+    }
+
+    let mut report = String::new();
+    for (severity, heading) in [(Severity::Error, "Errors"), (Severity::Warning, "Warnings")] {
+        let section: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .map(|d| format!("- {}", d))
+            .collect();
+        if !section.is_empty() {
+            report.push_str(&format!(
+                "{} ({}):\n{}\n\n",
+                heading,
+                section.len(),
+                section.join("\n")
+            ));
+        }
+    }
+    report.trim_end().to_string()
+}
+
+struct Linter<'a> {
+    content: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn error(&mut self, line: Option<usize>, message: String, suggestion: impl Into<String>) {
+        self.push(Severity::Error, line, message, suggestion.into());
+    }
+
+    fn warning(&mut self, line: Option<usize>, message: String, suggestion: impl Into<String>) {
+        self.push(Severity::Warning, line, message, suggestion.into());
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        line: Option<usize>,
+        message: String,
+        suggestion: String,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line,
+            message,
+            suggestion,
+        });
+    }
+
+    fn run(&mut self) {
+        // Recipes are rendered as templates before they are parsed, so template tags are
+        // blanked out first. Tags never span lines here, so line numbers still match.
+        let tags = Regex::new(r"\{\{[^\n]*?\}\}|\{%[^\n]*?%\}").unwrap();
+        let rendered = tags.replace_all(self.content, "template_value");
+
+        let root: Value = match serde_yaml::from_str(&rendered) {
+            Ok(root) => root,
+            Err(e) => {
+                let line = e.location().map(|location| location.line());
+                self.error(
+                    line,
+                    format!("The recipe is not valid YAML or JSON: {}", e),
+                    "Fix the syntax error, check indentation and quoting around the reported line",
+                );
+                return;
+            }
+        };
+
+        // Recipes can be wrapped in a top level `recipe` key, like deeplinks and exports
+        let recipe = match root.get("recipe") {
+            Some(nested) => nested,
+            None => &root,
+        };
+        let Some(recipe) = recipe.as_object() else {
+            self.error(
+                None,
+                "The recipe must be a mapping of keys to values".to_string(),
+                "Start the file with `title:`, `description:` and `instructions:` keys",
+            );
+            return;
+        };
+
+        self.check_keys(recipe);
+        self.check_required(recipe);
+        self.check_version(recipe);
+        self.check_extensions(recipe);
+        self.check_parameters(recipe);
+        self.check_response(recipe);
+        self.check_retry(recipe);
+    }
+
+    fn check_keys(&mut self, recipe: &Map<String, Value>) {
+        for key in recipe.keys() {
+            if !RECIPE_KEYS.contains(&key.as_str()) {
+                self.warning(
+                    self.key_line(key),
+                    format!("Unknown key `{}` is ignored", key),
+                    format!("Remove it or use one of: {}", RECIPE_KEYS.join(", ")),
+                );
+            }
+        }
+    }
+
+    fn check_required(&mut self, recipe: &Map<String, Value>) {
+        for key in ["title", "description"] {
+            match recipe.get(key) {
+                None => self.error(
+                    None,
+                    format!("Missing required key `{}`", key),
+                    format!("Add a `{}:` with a short text", key),
+                ),
+                Some(Value::String(text)) if text.trim().is_empty() => self.warning(
+                    self.key_line(key),
+                    format!("`{}` is empty", key),
+                    format!("Describe the recipe in `{}`", key),
+                ),
+                Some(Value::String(_)) => {}
+                Some(_) => self.error(
+                    self.key_line(key),
+                    format!("`{}` must be text", key),
+                    format!("Quote the value of `{}`", key),
+                ),
+            }
+        }
+
+        for key in ["instructions", "prompt"] {
+            if recipe.get(key).is_some_and(|value| !value.is_string()) {
+                self.error(
+                    self.key_line(key),
+                    format!("`{}` must be text", key),
+                    format!("Use a block scalar (`{}: |`) for multi-line text", key),
+                );
+            }
+        }
+        if !recipe.contains_key("instructions") && !recipe.contains_key("prompt") {
+            self.error(
+                None,
+                "The recipe has neither `instructions` nor `prompt`".to_string(),
+                "Add `instructions:` telling the agent how to behave, or a `prompt:` to start with",
+            );
+        }
+    }
+
+    fn check_version(&mut self, recipe: &Map<String, Value>) {
+        let semver = Regex::new(r"^\d+\.\d+\.\d+$").unwrap();
+        match recipe.get("version") {
+            Some(Value::String(version)) if semver.is_match(version) => {}
+            Some(version) => self.warning(
+                self.key_line("version"),
+                format!("`version` {} is not a semantic version", version),
+                "Use the recipe format version, for example `version: 1.0.0`",
+            ),
+            None => {}
+        }
+    }
+
+    fn check_extensions(&mut self, recipe: &Map<String, Value>) {
+        let Some(extensions) = recipe.get("extensions") else {
+            return;
+        };
+        let Some(extensions) = extensions.as_array() else {
+            self.error(
+                self.key_line("extensions"),
+                "`extensions` must be a list".to_string(),
+                "List each extension as an item starting with `- type:`",
+            );
+            return;
+        };
+
+        for (index, extension) in extensions.iter().enumerate() {
+            let name = extension.get("name").and_then(Value::as_str);
+            let line = name
+                .and_then(|name| self.value_line("name", name))
+                .or_else(|| self.key_line("extensions"));
+            let label = name
+                .map(|name| format!("Extension `{}`", name))
+                .unwrap_or_else(|| format!("Extension {}", index + 1));
+
+            if name.is_none() {
+                self.error(
+                    line,
+                    format!("{} has no `name`", label),
+                    "Add a `name:` identifying the extension",
+                );
+            }
+            match extension.get("type").and_then(Value::as_str) {
+                Some(kind) if EXTENSION_TYPES.contains(&kind) => {
+                    let field = match kind {
+                        "stdio" => Some("cmd"),
+                        "sse" | "streamable_http" => Some("uri"),
+                        _ => None,
+                    };
+                    if let Some(field) = field.filter(|field| extension.get(*field).is_none()) {
+                        self.error(
+                            line,
+                            format!("{} of type {} has no `{}`", label, kind, field),
+                            format!("Add the `{}:` to connect to", field),
+                        );
+                    }
+                }
+                Some(kind) => self.error(
+                    line,
+                    format!("{} has unknown type `{}`", label, kind),
+                    format!("Use one of: {}", EXTENSION_TYPES.join(", ")),
+                ),
+                None => self.error(
+                    line,
+                    format!("{} has no `type`", label),
+                    format!("Add a `type:`, one of: {}", EXTENSION_TYPES.join(", ")),
+                ),
+            }
+        }
+    }
+
+    fn check_parameters(&mut self, recipe: &Map<String, Value>) {
+        let parameters = match recipe.get("parameters") {
+            None => &Vec::new(),
+            Some(Value::Array(parameters)) => parameters,
+            Some(_) => {
+                self.error(
+                    self.key_line("parameters"),
+                    "`parameters` must be a list".to_string(),
+                    "List each parameter as an item starting with `- key:`",
+                );
+                return;
+            }
+        };
+
+        let mut declared = BTreeSet::new();
+        for parameter in parameters {
+            let Some(key) = parameter.get("key").and_then(Value::as_str) else {
+                self.error(
+                    self.key_line("parameters"),
+                    "A parameter has no `key`".to_string(),
+                    "Add a `key:` naming the template variable it fills in",
+                );
+                continue;
+            };
+            let line = self.value_line("key", key);
+            if !declared.insert(key.to_string()) {
+                self.error(
+                    line,
+                    format!("Parameter `{}` is declared twice", key),
+                    "Remove one of the declarations",
+                );
+            }
+
+            let input_type = parameter.get("input_type").map(item_type);
+            let fields = [
+                ("input_type", input_type, INPUT_TYPES),
+                ("requirement", parameter.get("requirement"), REQUIREMENTS),
+            ];
+            for (field, value, allowed) in fields {
+                match value {
+                    Some(Value::String(value)) if allowed.contains(&value.as_str()) => {}
+                    Some(value) => self.error(
+                        line,
+                        format!("Parameter `{}` has unknown {} {}", key, field, value),
+                        format!("Use one of: {}", allowed.join(", ")),
+                    ),
+                    None => self.error(
+                        line,
+                        format!("Parameter `{}` has no `{}`", key, field),
+                        format!("Add `{}:`, one of: {}", field, allowed.join(", ")),
+                    ),
+                }
+            }
+            if parameter.get("description").is_none() {
+                self.error(
+                    line,
+                    format!("Parameter `{}` has no `description`", key),
+                    "Add a `description:` shown when the value is asked for",
+                );
+            }
+            if parameter.get("requirement").and_then(Value::as_str) == Some("optional")
+                && parameter.get("default").is_none()
+            {
+                self.error(
+                    line,
+                    format!("Optional parameter `{}` has no default", key),
+                    "Add a `default:` or make the parameter required",
+                );
+            }
+            if input_type.and_then(Value::as_str) == Some("select")
+                && parameter.get("options").is_none()
+            {
+                self.warning(
+                    line,
+                    format!("Select parameter `{}` has no `options`", key),
+                    "Add the `options:` to choose from",
+                );
+            }
+        }
+
+        let used = template_variables(self.content);
+        for variable in used.difference(&declared) {
+            self.error(
+                self.template_line(variable),
+                format!("Template variable `{}` is not declared", variable),
+                format!(
+                    "Add a parameter with `key: {}` or remove the variable",
+                    variable
+                ),
+            );
+        }
+        for key in declared.difference(&used) {
+            self.warning(
+                self.value_line("key", key),
+                format!("Parameter `{}` is never used", key),
+                format!("Use it as `{{{{ {} }}}}` or remove the parameter", key),
+            );
+        }
+    }
+
+    fn check_response(&mut self, recipe: &Map<String, Value>) {
+        let Some(schema) = recipe.get("response").and_then(|r| r.get("json_schema")) else {
+            return;
+        };
+        if !schema.is_object() {
+            self.error(
+                self.key_line("json_schema"),
+                "`response.json_schema` must be a JSON schema object".to_string(),
+                "Give the schema as a mapping with `type: object` and `properties:`",
+            );
+        } else if schema.get("type").is_none() {
+            self.warning(
+                self.key_line("json_schema"),
+                "`response.json_schema` has no `type`".to_string(),
+                "Add `type: object` to the schema",
+            );
+        }
+    }
+
+    fn check_retry(&mut self, recipe: &Map<String, Value>) {
+        let Some(retry) = recipe.get("retry") else {
+            return;
+        };
+        if retry.get("max_retries").and_then(Value::as_u64) == Some(0) {
+            self.error(
+                self.key_line("max_retries"),
+                "`retry.max_retries` must be greater than 0".to_string(),
+                "Set `max_retries` to 1 or more, or remove the retry section",
+            );
+        }
+        for key in ["timeout_seconds", "on_failure_timeout_seconds"] {
+            if retry.get(key).and_then(Value::as_u64) == Some(0) {
+                self.error(
+                    self.key_line(key),
+                    format!("`retry.{}` must be greater than 0", key),
+                    format!("Set `{}` to 1 or more, or leave it out", key),
+                );
+            }
+        }
+        if retry.get("checks").is_none() {
+            self.error(
+                self.key_line("retry"),
+                "`retry` has no `checks`".to_string(),
+                "Add the `checks:` that decide whether the run succeeded",
+            );
+        }
+    }
+
+    /// The first line where `key` is used as a mapping key, in YAML or JSON
+    fn key_line(&self, key: &str) -> Option<usize> {
+        self.line_of(&format!(r#"(?m)^[ \t-]*"?{}"?[ \t]*:"#, regex::escape(key)))
+    }
+
+    /// The first line setting `key` to `value`
+    fn value_line(&self, key: &str, value: &str) -> Option<usize> {
+        self.line_of(&format!(
+            r#"(?m)^[ \t\-{{]*"?{}"?[ \t]*:[ \t]*["']?{}["']?[ \t]*,?"#,
+            regex::escape(key),
+            regex::escape(value)
+        ))
+    }
+
+    fn template_line(&self, variable: &str) -> Option<usize> {
+        self.line_of(&format!(r"\{{\{{\s*{}\b", regex::escape(variable)))
+    }
+
+    fn line_of(&self, pattern: &str) -> Option<usize> {
+        let found = Regex::new(pattern).ok()?.find(self.content)?;
+        Some(self.content[..found.start()].matches('\n').count() + 1)
+    }
+}
+
+/// The type of a parameter's values, looking through `array: { item_type: ... }`
+fn item_type(input_type: &Value) -> &Value {
+    match input_type.pointer("/array/item_type") {
+        Some(inner) => item_type(inner),
+        None => input_type,
+    }
+}
+
+/// Simple template variables like `{{ name }}` or `{{ name | upper }}`, without the built-ins
+fn template_variables(content: &str) -> BTreeSet<String> {
+    let variable = Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*(?:\||\}\})").unwrap();
+    variable
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .filter(|name| !BUILT_IN_PARAMS.contains(&name.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_recipe() {
+        let content = indoc::indoc! {r#"
+            version: 1.0.0
+            title: Release notes
+            description: Write release notes
+            instructions: Summarize the changes to {{ files }} in {{ repo }} since {{ since }}
+            extensions:
+              - type: stdio
+                name: github
+            parameters:
+              - key: repo
+                input_type: string
+                requirement: required
+                description: The repository
+              - key: branch
+                input_type: string
+                requirement: optional
+                description: Branch to read
+              - key: files
+                input_type:
+                  array:
+                    item_type: file
+                requirement: required
+                description: Files to include
+            colour: blue
+        "#};
+        let diagnostics = lint(content);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    Severity::Warning,
+                    Some(23),
+                    "Unknown key `colour` is ignored"
+                ),
+                (
+                    Severity::Error,
+                    Some(7),
+                    "Extension `github` of type stdio has no `cmd`"
+                ),
+                (
+                    Severity::Error,
+                    Some(13),
+                    "Optional parameter `branch` has no default"
+                ),
+                (
+                    Severity::Error,
+                    Some(4),
+                    "Template variable `since` is not declared"
+                ),
+                (
+                    Severity::Warning,
+                    Some(13),
+                    "Parameter `branch` is never used"
+                ),
+            ]
+        );
+
+        let report = format_report(&diagnostics);
+        assert!(report.starts_with("Errors (3):\n- line 7: Extension `github`"));
+        assert!(report.contains("Warnings (2):\n- line 23: Unknown key `colour` is ignored"));
+
+        let broken = lint("title: x\ndescription: [unclosed\n");
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].severity, Severity::Error);
+        assert!(broken[0].line.is_some());
+
+        let minimal =
+            "{\"recipe\": {\"title\": \"t\", \"description\": \"d\", \"prompt\": \"go\"}}";
+        assert_eq!(format_report(&lint(minimal)), "No problems found");
+    }
+}
@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use tree_sitter::{Node, Parser};
+
+use super::semantic_search::grammar_for;
+
+/// The occurrences of a symbol in one file and the file's content once they are renamed
+#[derive(Debug)]
+pub struct FileRename {
+    pub path: PathBuf,
+    pub original: String,
+    pub renamed: String,
+    /// 1-indexed line of every replaced occurrence, in order
+    pub lines: Vec<usize>,
+}
+
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Identifier nodes of every kind the grammars define (`identifier`, `type_identifier`,
+/// `field_identifier`, ...) so definitions, references, fields and methods are all found.
+/// Strings and comments are separate nodes and are left alone.
+fn collect_identifiers<'a>(node: Node<'a>, source: &str, name: &str, found: &mut Vec<Node<'a>>) {
+    if node.child_count() == 0 {
+        if node.kind().ends_with("identifier") && &source[node.byte_range()] == name {
+            found.push(node);
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_identifiers(child, source, name, found);
+    }
+}
+
+/// Rename every identifier spelled `symbol` in a file. Returns Ok(None) when the language has
+/// no grammar or the symbol does not appear, and an error when the rename is unsafe: the file
+/// does not parse, or `new_name` is already an identifier in it and would be captured.
+pub fn rename_in_file(
+    path: &Path,
+    content: &str,
+    symbol: &str,
+    new_name: &str,
+) -> Result<Option<FileRename>, String> {
+    let Some((language, _)) = grammar_for(path) else {
+        return Ok(None);
+    };
+    if !content.contains(symbol) {
+        return Ok(None);
+    }
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("{}: failed to parse", path.display()))?;
+    let root = tree.root_node();
+
+    let mut occurrences = Vec::new();
+    collect_identifiers(root, content, symbol, &mut occurrences);
+    if occurrences.is_empty() {
+        return Ok(None);
+    }
+    if root.has_error() {
+        return Err(format!(
+            "{}: the file has syntax errors, fix them before renaming",
+            path.display()
+        ));
+    }
+    let mut clashes = Vec::new();
+    collect_identifiers(root, content, new_name, &mut clashes);
+    if let Some(clash) = clashes.first() {
+        return Err(format!(
+            "{}:{}: '{}' is already used",
+            path.display(),
+            clash.start_position().row + 1,
+            new_name
+        ));
+    }
+
+    // Replace from the end so earlier byte offsets stay valid
+    let mut renamed = content.to_string();
+    for node in occurrences.iter().rev() {
+        renamed.replace_range(node.byte_range(), new_name);
+    }
+    Ok(Some(FileRename {
+        path: path.to_path_buf(),
+        original: content.to_string(),
+        renamed,
+        lines: occurrences
+            .iter()
+            .map(|node| node.start_position().row + 1)
+            .collect(),
+    }))
+}
+
+/// Compress sorted line numbers into ranges, e.g. `3, 10-12`
+pub fn format_line_ranges(lines: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &line in lines {
+        match ranges.last_mut() {
+            Some((_, end)) if line <= *end + 1 => *end = line.max(*end),
+            _ => ranges.push((line, line)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use reqwest::Client;
+use serde_json::{json, Value};
+use tree_sitter::{Language, Node, Parser};
+
+/// Lines per chunk for files without a grammar or without any functions
+const FALLBACK_CHUNK_LINES: usize = 50;
+/// Chunks longer than this are cut before embedding, most models cap input at ~8k tokens
+const MAX_CHUNK_CHARS: usize = 8000;
+/// Texts sent per embeddings request
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+/// A contiguous range of lines from a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeChunk {
+    pub path: PathBuf,
+    /// 1-indexed, inclusive
+    pub start_line: usize,
+    /// 1-indexed, inclusive
+    pub end_line: usize,
+    pub text: String,
+}
+
+/// Client for an OpenAI-compatible `/embeddings` endpoint
+#[derive(Debug, Clone)]
+pub struct EmbeddingClient {
+    api_key: String,
+    host: String,
+    model: String,
+}
+
+impl EmbeddingClient {
+    /// Configured with `GOOSE_EMBEDDING_API_KEY`, `GOOSE_EMBEDDING_HOST` and optionally
+    /// `GOOSE_EMBEDDING_MODEL`, semantic search is unavailable when these are not set
+    pub fn from_env() -> Option<Self> {
+        // Don't call out to an embeddings API during tests
+        if cfg!(test) {
+            return None;
+        }
+
+        let api_key = std::env::var("GOOSE_EMBEDDING_API_KEY").ok()?;
+        let host = std::env::var("GOOSE_EMBEDDING_HOST").ok()?;
+        let model = std::env::var("GOOSE_EMBEDDING_MODEL")
+            .unwrap_or_else(|_| "text-embedding-3-small".to_string());
+
+        if api_key.is_empty() || host.is_empty() || model.is_empty() {
+            return None;
+        }
+        Some(Self {
+            api_key,
+            host,
+            model,
+        })
+    }
+
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let url = if self.host.ends_with("/embeddings") {
+            self.host.clone()
+        } else {
+            format!("{}/embeddings", self.host.trim_end_matches('/'))
+        };
+        let client = Client::new();
+
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBEDDING_BATCH_SIZE) {
+            let response = client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&json!({
+                    "model": self.model,
+                    "input": batch,
+                }))
+                .send()
+                .await
+                .map_err(|e| format!("Request error: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(format!("API error: HTTP {}", response.status()));
+            }
+
+            let body: Value = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            let mut data: Vec<(u64, Vec<f32>)> = body
+                .get("data")
+                .and_then(Value::as_array)
+                .ok_or("Response is missing 'data'")?
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let index = item
+                        .get("index")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(i as u64);
+                    let embedding = item
+                        .get("embedding")
+                        .and_then(Value::as_array)
+                        .ok_or("Response item is missing 'embedding'")?
+                        .iter()
+                        .map(|v| v.as_f64().map(|f| f as f32))
+                        .collect::<Option<Vec<f32>>>()
+                        .ok_or("Embedding contains a non-numeric value")?;
+                    Ok((index, embedding))
+                })
+                .collect::<Result<_, String>>()?;
+
+            if data.len() != batch.len() {
+                return Err(format!(
+                    "Expected {} embeddings but received {}",
+                    batch.len(),
+                    data.len()
+                ));
+            }
+            data.sort_by_key(|(index, _)| *index);
+            embeddings.extend(data.into_iter().map(|(_, embedding)| embedding));
+        }
+        Ok(embeddings)
+    }
+}
+
+/// Grammar and the node kinds that start a new chunk, for the languages we can parse
+pub fn grammar_for(path: &Path) -> Option<(Language, &'static [&'static str])> {
+    let extension = path.extension()?.to_str()?;
+    let grammar = match extension {
+        "rs" => (tree_sitter_rust::LANGUAGE.into(), &["function_item"][..]),
+        "py" => (
+            tree_sitter_python::LANGUAGE.into(),
+            &["function_definition", "decorated_definition"][..],
+        ),
+        "js" | "jsx" | "mjs" | "cjs" => (
+            tree_sitter_javascript::LANGUAGE.into(),
+            &[
+                "function_declaration",
+                "generator_function_declaration",
+                "method_definition",
+            ][..],
+        ),
+        "ts" => (
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            &[
+                "function_declaration",
+                "generator_function_declaration",
+                "method_definition",
+            ][..],
+        ),
+        "tsx" => (
+            tree_sitter_typescript::LANGUAGE_TSX.into(),
+            &[
+                "function_declaration",
+                "generator_function_declaration",
+                "method_definition",
+            ][..],
+        ),
+        "go" => (
+            tree_sitter_go::LANGUAGE.into(),
+            &["function_declaration", "method_declaration"][..],
+        ),
+        _ => return None,
+    };
+    Some(grammar)
+}
+
+/// Collect the outermost nodes of the given kinds, functions nested in a function stay
+/// part of their parent's chunk
+fn collect_nodes<'a>(node: Node<'a>, kinds: &[&str], found: &mut Vec<Node<'a>>) {
+    if kinds.contains(&node.kind()) {
+        found.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_nodes(child, kinds, found);
+    }
+}
+
+fn truncate_chunk(text: &str) -> String {
+    match text.char_indices().nth(MAX_CHUNK_CHARS) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Split a source file into chunks at function boundaries, falling back to fixed size
+/// line windows when the language has no grammar or the file has no functions
+pub fn chunk_file(path: &Path, content: &str) -> Vec<CodeChunk> {
+    if let Some((language, kinds)) = grammar_for(path) {
+        let mut parser = Parser::new();
+        if parser.set_language(&language).is_ok() {
+            if let Some(tree) = parser.parse(content, None) {
+                let mut nodes = Vec::new();
+                collect_nodes(tree.root_node(), kinds, &mut nodes);
+                if !nodes.is_empty() {
+                    return nodes
+                        .into_iter()
+                        .map(|node| CodeChunk {
+                            path: path.to_path_buf(),
+                            start_line: node.start_position().row + 1,
+                            end_line: node.end_position().row + 1,
+                            text: truncate_chunk(&content[node.byte_range()]),
+                        })
+                        .collect();
+                }
+            }
+        }
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .chunks(FALLBACK_CHUNK_LINES)
+        .enumerate()
+        .filter(|(_, window)| window.iter().any(|line| !line.trim().is_empty()))
+        .map(|(i, window)| CodeChunk {
+            path: path.to_path_buf(),
+            start_line: i * FALLBACK_CHUNK_LINES + 1,
+            end_line: i * FALLBACK_CHUNK_LINES + window.len(),
+            text: truncate_chunk(&window.join("\n")),
+        })
+        .collect()
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
@@ -105,3 +105,29 @@ pub fn normalize_line_endings(text: &str) -> String {
         text.replace("\r\n", "\n")
     }
 }
+
+/// Read-only commands whose output only changes when the workspace does
+const IDEMPOTENT_COMMANDS: &[&str] = &[
+    "git log",
+    "git show",
+    "cargo metadata",
+    "cargo tree",
+    "npm list",
+    "npm ls",
+    "pip list",
+    "pip freeze",
+];
+
+/// Whether a shell command is a single known read-only command whose output can be cached.
+/// Anything using pipes, redirection, chaining or substitution is never considered idempotent.
+pub fn is_idempotent_command(command: &str) -> bool {
+    let command = command.trim();
+    if command.contains(['|', '&', ';', '>', '<', '`', '$', '\n']) {
+        return false;
+    }
+    let words: Vec<&str> = command.split_whitespace().collect();
+    IDEMPOTENT_COMMANDS.iter().any(|known| {
+        let known: Vec<&str> = known.split_whitespace().collect();
+        words.starts_with(&known)
+    })
+}
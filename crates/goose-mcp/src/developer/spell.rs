@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// A word the spell checker does not know
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Misspelling {
+    pub word: String,
+    /// 1-indexed
+    pub line: usize,
+    /// 1-indexed, in characters
+    pub column: usize,
+    pub suggestions: Vec<String>,
+}
+
+/// aspell, or hunspell when aspell is missing. Both speak the ispell pipe protocol.
+pub fn find_checker() -> Option<PathBuf> {
+    which::which("aspell")
+        .or_else(|_| which::which("hunspell"))
+        .ok()
+}
+
+/// Blank out fenced code blocks, inline code and links in Markdown so identifiers and urls are
+/// not reported, keeping every other character where it was so columns still match the file
+pub fn mask_markdown(content: &str) -> Vec<String> {
+    let inline = regex::Regex::new(r"`[^`]*`|\]\([^)]*\)|<?https?://\S+").unwrap();
+    let mut in_fence = false;
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                return String::new();
+            }
+            if in_fence {
+                return String::new();
+            }
+            inline
+                .replace_all(line, |caps: &regex::Captures| {
+                    " ".repeat(caps[0].chars().count())
+                })
+                .into_owned()
+        })
+        .collect()
+}
+
+/// Run the checker over the lines in pipe mode (`-a`)
+pub async fn check(checker: &Path, lines: &[String]) -> Result<Vec<Misspelling>, String> {
+    let mut command = Command::new(checker);
+    command.arg("-a");
+    if checker.file_stem().is_some_and(|stem| stem == "aspell") {
+        command.arg("--encoding=utf-8");
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", checker.display(), e))?;
+
+    // A leading ^ keeps a line from being read as a pipe command
+    let input: String = lines.iter().map(|line| format!("^{}\n", line)).collect();
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = async move {
+        let result = stdin.write_all(input.as_bytes()).await;
+        drop(stdin);
+        result
+    };
+    let (written, output) = tokio::join!(writer, child.wait_with_output());
+    let output = output.map_err(|e| e.to_string())?;
+    written.map_err(|e| format!("Failed to write to {}: {}", checker.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            checker.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(parse_pipe_output(
+        &String::from_utf8_lossy(&output.stdout),
+        lines,
+    ))
+}
+
+/// Parse ispell pipe output: a banner, then for every input line one result per misspelled
+/// word (`& word count offset: suggestions` or `# word offset`) and a blank line
+pub fn parse_pipe_output(output: &str, lines: &[String]) -> Vec<Misspelling> {
+    let mut misspellings = Vec::new();
+    let mut line_index = 0;
+    for result in output.lines().skip_while(|l| l.starts_with('@')) {
+        if result.is_empty() {
+            line_index += 1;
+            continue;
+        }
+        let (word, offset, suggestions) = match result.split_once(' ') {
+            Some(("&", rest)) => {
+                let (head, suggestions) = rest.split_once(": ").unwrap_or((rest, ""));
+                let mut parts = head.split(' ');
+                let word = parts.next().unwrap_or_default();
+                let offset = parts.nth(1).and_then(|o| o.parse::<usize>().ok());
+                let suggestions = suggestions
+                    .split(", ")
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                (word, offset, suggestions)
+            }
+            Some(("#", rest)) => {
+                let mut parts = rest.split(' ');
+                let word = parts.next().unwrap_or_default();
+                let offset = parts.next().and_then(|o| o.parse::<usize>().ok());
+                (word, offset, Vec::new())
+            }
+            _ => continue,
+        };
+        misspellings.push(Misspelling {
+            word: word.to_string(),
+            line: line_index + 1,
+            column: word_column(lines.get(line_index), word, offset),
+            suggestions,
+        });
+    }
+    misspellings
+}
+
+// Offsets count the leading ^, which makes them the 1-indexed column. Some checkers count
+// bytes rather than characters, so the word is looked up in the line when it is not there.
+fn word_column(line: Option<&String>, word: &str, offset: Option<usize>) -> usize {
+    let Some(line) = line else {
+        return offset.unwrap_or(1);
+    };
+    let chars: Vec<char> = line.chars().collect();
+    let word_chars: Vec<char> = word.chars().collect();
+    if let Some(offset) = offset.filter(|o| *o >= 1) {
+        if chars.get(offset - 1..offset - 1 + word_chars.len()) == Some(&word_chars[..]) {
+            return offset;
+        }
+    }
+    line.find(word)
+        .map(|byte| line[..byte].chars().count() + 1)
+        .or(offset)
+        .unwrap_or(1)
+}
+
+pub fn format_table(misspellings: &[Misspelling]) -> String {
+    let mut table = String::from("| Line | Column | Word | Suggestions |\n|---|---|---|---|\n");
+    for m in misspellings {
+        let suggestions = m
+            .suggestions
+            .iter()
+            .take(5)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        table.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            m.line, m.column, m.word, suggestions
+        ));
+    }
+    table
+}
@@ -0,0 +1,276 @@
+use std::path::Path;
+
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{Map, Value};
+
+/// Larger databases are better explored with the sqlite3 shell
+pub const MAX_DATABASE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Cells longer than this are cut short in the markdown table, the JSON keeps them whole
+const MAX_CELL_CHARS: usize = 80;
+
+/// The rows returned by a query, each an object keyed by column name
+#[derive(Debug)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+    /// Whether the query returned more rows than the limit
+    pub truncated: bool,
+}
+
+/// Run a single statement against the database without being able to change it: the file is
+/// opened read-only and `query_only` rejects writes through attached databases or pragmas too
+pub fn query(db_path: &Path, sql: &str, limit: usize) -> Result<QueryResult, String> {
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Failed to open '{}': {}", db_path.display(), e))?;
+    conn.pragma_update(None, "query_only", "ON")
+        .map_err(|e| e.to_string())?;
+
+    let mut statement = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    let mut results = statement.query([]).map_err(|e| e.to_string())?;
+    while let Some(row) = results.next().map_err(|e| e.to_string())? {
+        if rows.len() == limit {
+            truncated = true;
+            break;
+        }
+        let mut object = Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = row.get_ref(i).map_err(|e| e.to_string())?;
+            object.insert(column.clone(), to_json(value));
+        }
+        rows.push(object);
+    }
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+    })
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(blob) => Value::String(format!("<blob, {} bytes>", blob.len())),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => "NULL".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let mut text = text.replace('|', "\\|").replace('\n', " ");
+    if text.chars().count() > MAX_CELL_CHARS {
+        text = text.chars().take(MAX_CELL_CHARS).collect::<String>() + "…";
+    }
+    text
+}
+
+impl QueryResult {
+    pub fn to_json(&self) -> Value {
+        Value::Array(self.rows.iter().cloned().map(Value::Object).collect())
+    }
+
+    pub fn format_table(&self) -> String {
+        if self.columns.is_empty() {
+            return "The statement returned no columns".to_string();
+        }
+        let mut table = format!(
+            "| {} |\n|{}\n",
+            self.columns
+                .iter()
+                .map(|c| cell(&Value::String(c.clone())))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            "---|".repeat(self.columns.len())
+        );
+        for row in &self.rows {
+            let cells: Vec<String> = self
+                .columns
+                .iter()
+                .map(|c| cell(row.get(c).unwrap_or(&Value::Null)))
+                .collect();
+            table.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        table.push_str(&format!(
+            "\n{} row{}",
+            self.rows.len(),
+            if self.rows.len() == 1 { "" } else { "s" }
+        ));
+        if self.truncated {
+            table.push_str(", more rows were left out by the limit");
+        }
+        table
+    }
+}
+
+/// What csv_to_sql created
+#[derive(Debug)]
+pub struct ImportReport {
+    pub table: String,
+    /// The `CREATE TABLE` statement of the new table
+    pub schema: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnType {
+    Integer,
+    Real,
+    Text,
+}
+
+impl ColumnType {
+    fn name(&self) -> &'static str {
+        match self {
+            ColumnType::Integer => "INTEGER",
+            ColumnType::Real => "REAL",
+            ColumnType::Text => "TEXT",
+        }
+    }
+
+    /// The narrowest type holding both the column so far and `value`
+    fn widen(self, value: &str) -> Self {
+        match self {
+            ColumnType::Integer if value.parse::<i64>().is_ok() => ColumnType::Integer,
+            ColumnType::Integer | ColumnType::Real if value.parse::<f64>().is_ok() => {
+                ColumnType::Real
+            }
+            _ => ColumnType::Text,
+        }
+    }
+}
+
+/// `name` made into a plain identifier, so the table can be queried without quoting
+pub fn table_name(name: &str) -> String {
+    let mut table: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !table.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        table.insert(0, '_');
+    }
+    table
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+// Blank headers get a positional name and repeated ones a suffix, SQLite rejects duplicates
+fn column_names(headers: &csv::StringRecord) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (i, header) in headers.iter().enumerate() {
+        let header = header.trim();
+        let base = if header.is_empty() {
+            format!("column_{}", i + 1)
+        } else {
+            header.to_string()
+        };
+        let mut name = base.clone();
+        let mut n = 2;
+        while names
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(&name))
+        {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Load the CSV file into a new table of the database, creating the database when it does not
+/// exist. Column types are inferred from every value in the column and empty cells become NULL.
+/// The file is read twice, once to infer the types and once to insert the rows in a single
+/// transaction.
+pub fn import_csv(csv_path: &Path, db_path: &Path, table: &str) -> Result<ImportReport, String> {
+    let open = || {
+        csv::Reader::from_path(csv_path)
+            .map_err(|e| format!("Failed to open '{}': {}", csv_path.display(), e))
+    };
+
+    let mut reader = open()?;
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .clone();
+    if headers.is_empty() {
+        return Err(format!("'{}' has no columns", csv_path.display()));
+    }
+    let columns = column_names(&headers);
+    let mut types = vec![ColumnType::Integer; columns.len()];
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Failed to parse CSV file: {}", e))?;
+        for (column_type, value) in types.iter_mut().zip(record.iter()) {
+            if !value.is_empty() {
+                *column_type = column_type.widen(value);
+            }
+        }
+    }
+
+    let schema = format!(
+        "CREATE TABLE {} ({})",
+        quote_identifier(table),
+        columns
+            .iter()
+            .zip(&types)
+            .map(|(column, column_type)| {
+                format!("{} {}", quote_identifier(column), column_type.name())
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let insert = format!(
+        "INSERT INTO {} VALUES ({})",
+        quote_identifier(table),
+        vec!["?"; columns.len()].join(", ")
+    );
+
+    let mut conn = Connection::open(db_path)
+        .map_err(|e| format!("Failed to open '{}': {}", db_path.display(), e))?;
+    let transaction = conn.transaction().map_err(|e| e.to_string())?;
+    transaction
+        .execute(&schema, [])
+        .map_err(|e| format!("Failed to create table '{}': {}", table, e))?;
+    let mut rows = 0;
+    {
+        let mut statement = transaction.prepare(&insert).map_err(|e| e.to_string())?;
+        for record in open()?.records() {
+            let record = record.map_err(|e| format!("Failed to parse CSV file: {}", e))?;
+            // Values are bound as text, the column types make SQLite store numbers as numbers
+            let values = record
+                .iter()
+                .map(|value| (!value.is_empty()).then_some(value));
+            statement
+                .execute(rusqlite::params_from_iter(values))
+                .map_err(|e| format!("Failed to insert row {}: {}", rows + 1, e))?;
+            rows += 1;
+        }
+    }
+    transaction.commit().map_err(|e| e.to_string())?;
+
+    Ok(ImportReport {
+        table: table.to_string(),
+        schema,
+        rows,
+    })
+}
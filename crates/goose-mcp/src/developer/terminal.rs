@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::process::Child;
+
+/// How long a stopped recording gets to finish writing before asciinema is killed
+const STOP_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// An `asciinema rec` process started by terminal_record and the file it writes
+pub struct Recording {
+    pub child: Child,
+    pub path: PathBuf,
+}
+
+/// A new recording file in `dir`, named after the current time
+pub fn recording_path(dir: &Path) -> PathBuf {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    dir.join(format!("goose-recording-{}.cast", timestamp))
+}
+
+/// Arguments to `asciinema` recording `command` to `path`, or an interactive shell when no
+/// command is given
+pub fn record_args(path: &Path, command: Option<&str>, title: Option<&str>) -> Vec<String> {
+    let mut args = vec!["rec".to_string(), "--quiet".to_string()];
+    if let Some(command) = command {
+        args.extend(["--command".to_string(), command.to_string()]);
+    }
+    if let Some(title) = title {
+        args.extend(["--title".to_string(), title.to_string()]);
+    }
+    args.push(path.to_string_lossy().into_owned());
+    args
+}
+
+/// Arguments to `asciinema` playing `path` back, `speed` times as fast and with pauses cut to
+/// `idle_time_limit` seconds
+pub fn play_args(path: &Path, speed: Option<f64>, idle_time_limit: Option<f64>) -> Vec<String> {
+    let mut args = vec!["play".to_string()];
+    if let Some(speed) = speed {
+        args.extend(["--speed".to_string(), speed.to_string()]);
+    }
+    if let Some(idle_time_limit) = idle_time_limit {
+        args.extend(["--idle-time-limit".to_string(), idle_time_limit.to_string()]);
+    }
+    args.push(path.to_string_lossy().into_owned());
+    args
+}
+
+/// Stop the recording. asciinema passes SIGTERM on to the recorded command and saves the file
+/// once it exits, it is only killed when that takes too long.
+pub async fn stop(mut recording: Recording) -> PathBuf {
+    #[cfg(unix)]
+    if let Some(pid) = recording.child.id() {
+        // SAFETY: the child has not been reaped, so the pid still refers to it
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+    }
+    #[cfg(not(unix))]
+    recording.child.start_kill().ok();
+
+    if tokio::time::timeout(STOP_GRACE_PERIOD, recording.child.wait())
+        .await
+        .is_err()
+    {
+        recording.child.kill().await.ok();
+    }
+    recording.path
+}
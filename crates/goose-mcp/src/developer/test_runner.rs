@@ -0,0 +1,285 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+/// Longest message kept for each failure, enough for the assertion and where it failed
+const MAX_FAILURE_MESSAGE_CHARS: usize = 2000;
+
+/// Lines of output returned when the tests could not run at all, such as a compile error
+const ERROR_OUTPUT_TAIL_LINES: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunner {
+    Cargo,
+    Npm,
+    Pytest,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestFailure {
+    pub name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestReport {
+    pub runner: TestRunner,
+    pub command: String,
+    pub total: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub failures: Vec<TestFailure>,
+}
+
+impl TestRunner {
+    /// The runner for the project rooted at `dir`, going by its manifest or test config
+    pub fn detect(dir: &Path) -> Option<Self> {
+        if dir.join("Cargo.toml").is_file() {
+            Some(TestRunner::Cargo)
+        } else if dir.join("package.json").is_file() {
+            Some(TestRunner::Npm)
+        } else if dir.join("pytest.ini").is_file()
+            || std::fs::read_to_string(dir.join("pyproject.toml"))
+                .is_ok_and(|content| content.contains("[tool.pytest"))
+        {
+            Some(TestRunner::Pytest)
+        } else {
+            None
+        }
+    }
+
+    /// The program and arguments that run the tests, matching only `filter` when it is given
+    pub fn command(&self, filter: Option<&str>) -> Vec<String> {
+        let mut command: Vec<String> = match self {
+            TestRunner::Cargo => vec!["cargo", "test", "--no-fail-fast", "--color", "never"],
+            TestRunner::Npm => vec!["npm", "test", "--silent"],
+            TestRunner::Pytest => vec!["pytest", "-q", "-rf", "--tb=short", "--color=no"],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect();
+        if let Some(filter) = filter {
+            match self {
+                TestRunner::Cargo => command.push(filter.to_string()),
+                TestRunner::Npm => command.extend(["--".to_string(), filter.to_string()]),
+                TestRunner::Pytest => command.extend(["-k".to_string(), filter.to_string()]),
+            }
+        }
+        command
+    }
+
+    /// Read the counts and failures out of the runner's output, None when it has no summary,
+    /// which means the tests did not run
+    pub fn parse(&self, command: &str, output: &str) -> Option<TestReport> {
+        let (passed, failed, failures) = match self {
+            TestRunner::Cargo => parse_cargo(output)?,
+            TestRunner::Npm => parse_npm(output)?,
+            TestRunner::Pytest => parse_pytest(output)?,
+        };
+        Some(TestReport {
+            runner: *self,
+            command: command.to_string(),
+            total: passed + failed,
+            passed,
+            failed,
+            failures,
+        })
+    }
+}
+
+/// The number before `word` in `line`, such as 3 for `3 passed`
+fn count(line: &str, word: &str) -> u32 {
+    Regex::new(&format!(r"(\d+) {}\b", word))
+        .unwrap()
+        .captures(line)
+        .and_then(|captures| captures[1].parse().ok())
+        .unwrap_or(0)
+}
+
+fn failure(name: &str, message: &str) -> TestFailure {
+    let message = message.trim();
+    let message = match message.char_indices().nth(MAX_FAILURE_MESSAGE_CHARS) {
+        Some((end, _)) => format!("{}...", &message[..end]),
+        None => message.to_string(),
+    };
+    TestFailure {
+        name: name.trim().to_string(),
+        message,
+    }
+}
+
+type Counts = (u32, u32, Vec<TestFailure>);
+
+// libtest prints a `test result:` line per test binary, and the captured output of each failed
+// test in a `---- name stdout ----` section
+fn parse_cargo(output: &str) -> Option<Counts> {
+    let results: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("test result:"))
+        .collect();
+    if results.is_empty() {
+        return None;
+    }
+    let passed = results.iter().map(|line| count(line, "passed")).sum();
+    let failed = results.iter().map(|line| count(line, "failed")).sum();
+
+    let section = Regex::new(r"^---- (.+) stdout ----$").unwrap();
+    let mut failures = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    for line in output.lines() {
+        if let Some(captures) = section.captures(line) {
+            if let Some((name, lines)) = current.take() {
+                failures.push(failure(&name, &lines.join("\n")));
+            }
+            current = Some((captures[1].to_string(), Vec::new()));
+        } else if line == "failures:" || line.starts_with("test result:") {
+            if let Some((name, lines)) = current.take() {
+                failures.push(failure(&name, &lines.join("\n")));
+            }
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    if let Some((name, lines)) = current.take() {
+        failures.push(failure(&name, &lines.join("\n")));
+    }
+
+    // Failed tests without captured output, like a `should_panic` test that did not panic
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        {
+            if !failures.iter().any(|f| f.name == name) {
+                failures.push(failure(name, ""));
+            }
+        }
+    }
+    Some((passed, failed, failures))
+}
+
+// Jest prints `Tests: 1 failed, 2 passed, 3 total` and a `● Suite › test` block per failure,
+// Vitest `Tests  1 failed | 2 passed (3)` and Mocha `2 passing` and `1 failing`
+fn parse_npm(output: &str) -> Option<Counts> {
+    let summary = output.lines().map(str::trim).find(|line| {
+        line.starts_with("Tests:") || (line.starts_with("Tests ") && line.contains(" passed"))
+    });
+    let (passed, failed) = match summary {
+        Some(line) => (count(line, "passed"), count(line, "failed")),
+        None => {
+            let passing = output.lines().find(|line| count(line, "passing") > 0)?;
+            let failing = output.lines().map(|line| count(line, "failing")).max();
+            (count(passing, "passing"), failing.unwrap_or(0))
+        }
+    };
+
+    let mut failures = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("● ") {
+            if let Some((name, lines)) = current.take() {
+                failures.push(failure(&name, &lines.join("\n")));
+            }
+            if !name.starts_with("Console") {
+                current = Some((name.to_string(), Vec::new()));
+            }
+        } else if trimmed.starts_with("Test Suites:") || trimmed.starts_with("Tests:") {
+            if let Some((name, lines)) = current.take() {
+                failures.push(failure(&name, &lines.join("\n")));
+            }
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        } else if let Some(name) = trimmed.strip_prefix("FAIL ") {
+            // Vitest names the failed test on the FAIL line, Jest only the file
+            if name.contains(" > ") {
+                failures.push(failure(name, ""));
+            }
+        }
+    }
+    if let Some((name, lines)) = current.take() {
+        failures.push(failure(&name, &lines.join("\n")));
+    }
+    Some((passed, failed, failures))
+}
+
+// With `-q -rf` pytest ends with a `FAILED path::test - message` line per failure and a summary
+// such as `1 failed, 4 passed in 0.12s`
+fn parse_pytest(output: &str) -> Option<Counts> {
+    let duration = Regex::new(r" in \d+(\.\d+)?s").unwrap();
+    let summary = output.lines().rev().find(|line| duration.is_match(line))?;
+    let passed = count(summary, "passed");
+    let failed = count(summary, "failed") + count(summary, "errors?");
+
+    let failures = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("FAILED "))
+        .map(|rest| match rest.split_once(" - ") {
+            Some((name, message)) => failure(name, message),
+            None => failure(rest, ""),
+        })
+        .collect();
+    Some((passed, failed, failures))
+}
+
+/// Run the tests in `dir`, stopping the runner when it takes longer than `timeout` or `cancel`
+/// fires. Errors carry the end of the output when the tests did not run.
+pub async fn run(
+    runner: TestRunner,
+    dir: &Path,
+    filter: Option<&str>,
+    timeout: Option<Duration>,
+    cancel: &CancellationToken,
+) -> Result<TestReport, String> {
+    let command = runner.command(filter);
+    let command_line = command.join(" ");
+    let child = tokio::process::Command::new(&command[0])
+        .args(&command[1..])
+        .current_dir(dir)
+        // Keeps watch modes and interactive prompts of JavaScript test runners off
+        .env("CI", "true")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command_line, e))?;
+
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let output = tokio::select! {
+        output = child.wait_with_output() => output
+            .map_err(|e| format!("Failed to run '{}': {}", command_line, e))?,
+        _ = deadline => {
+            return Err(format!(
+                "'{}' timed out after {} seconds",
+                command_line,
+                timeout.unwrap_or_default().as_secs()
+            ))
+        }
+        _ = cancel.cancelled() => return Err(format!("'{}' was cancelled", command_line)),
+    };
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    runner.parse(&command_line, &text).ok_or_else(|| {
+        let lines: Vec<&str> = text.lines().collect();
+        let tail = lines[lines.len().saturating_sub(ERROR_OUTPUT_TAIL_LINES)..].join("\n");
+        format!(
+            "'{}' exited with {} without running the tests:\n{}",
+            command_line, output.status, tail
+        )
+    })
+}
@@ -14,7 +14,9 @@ mod memory;
 mod tutorial;
 
 pub use computercontroller::ComputerControllerRouter;
-pub use developer::DeveloperRouter;
+pub use developer::{
+    DeveloperRouter, DeveloperRouterConfig, EditorModel, EmbeddingClient, ToolPlugin,
+};
 pub use google_drive::GoogleDriveRouter;
 pub use memory::MemoryRouter;
 pub use tutorial::TutorialRouter;
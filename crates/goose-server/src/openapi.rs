@@ -4,8 +4,9 @@ use goose::agents::ExtensionConfig;
 use goose::config::permission::PermissionLevel;
 use goose::config::ExtensionEntry;
 use goose::message::{
-    ContextLengthExceeded, FrontendToolRequest, Message, MessageContent, RedactedThinkingContent,
-    SummarizationRequested, ThinkingContent, ToolConfirmationRequest, ToolRequest, ToolResponse,
+    ContextLengthExceeded, ContextSizeReport, FrontendToolRequest, Message, MessageContent,
+    RedactedThinkingContent, SummarizationRequested, ThinkingContent, ToolConfirmationRequest,
+    ToolRequest, ToolResponse,
 };
 use goose::permission::permission_confirmation::PrincipalType;
 use goose::providers::base::{ConfigKey, ModelInfo, ProviderMetadata};
@@ -357,6 +358,7 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         FrontendToolRequest,
         ResourceContentsSchema,
         ContextLengthExceeded,
+        ContextSizeReport,
         SummarizationRequested,
         RoleSchema,
         ProviderMetadata,
@@ -396,6 +398,9 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         goose::recipe::RecipeParameterRequirement,
         goose::recipe::Response,
         goose::recipe::SubRecipe,
+        goose::recipe::SubRecipeExecutionMode,
+        goose::recipe::RecipeAuditConfig,
+        goose::recipe::WebhookConfig,
         goose::agents::types::RetryConfig,
         goose::agents::types::SuccessCheck,
         super::routes::agent::AddSubRecipesRequest,
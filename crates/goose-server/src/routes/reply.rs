@@ -332,6 +332,7 @@ pub async fn confirm_permission(
         "always_allow" => Permission::AlwaysAllow,
         "allow_once" => Permission::AllowOnce,
         "deny" => Permission::DenyOnce,
+        "always_deny" => Permission::AlwaysDeny,
         _ => Permission::DenyOnce,
     };
 
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use futures::stream::BoxStream;
@@ -39,8 +40,12 @@ use crate::permission::permission_judge::check_tool_permissions;
 use crate::permission::PermissionConfirmation;
 use crate::providers::base::Provider;
 use crate::providers::errors::ProviderError;
-use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
+use crate::recipe::{
+    Author, Recipe, RecipeAuditConfig, Response, Settings, SubRecipe, SubRecipeExecutionMode,
+};
 use crate::scheduler_trait::SchedulerTrait;
+use crate::session;
+use crate::token_counter::create_async_token_counter;
 use crate::tool_monitor::{ToolCall, ToolMonitor};
 use crate::utils::is_token_cancelled;
 use mcp_core::{ToolError, ToolResult};
@@ -49,7 +54,7 @@ use rmcp::model::{Content, GetPromptResult, Prompt, ServerNotification, Tool};
 use serde_json::Value;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, Instrument};
 
 use super::final_output_tool::FinalOutputTool;
 use super::platform_tools;
@@ -58,6 +63,8 @@ use super::tool_execution::{ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE, DEC
 use crate::agents::subagent_task_config::TaskConfig;
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
+/// How many of the largest messages to point out when the context overflows
+const CONTEXT_REPORT_LARGEST_MESSAGES: usize = 5;
 
 /// The main goose Agent
 pub struct Agent {
@@ -77,6 +84,11 @@ pub struct Agent {
     pub(super) router_tool_selector: Mutex<Option<Arc<Box<dyn RouterToolSelector>>>>,
     pub(super) scheduler_service: Mutex<Option<Arc<dyn SchedulerTrait>>>,
     pub(super) retry_manager: RetryManager,
+    /// Cancels the reply in progress, see [`Agent::cancel_active_call`]
+    pub(super) active_call_token: Arc<Mutex<Option<CancellationToken>>>,
+    /// Tools the user chose to always deny by the id of the session they were denied in, their
+    /// calls are refused for the rest of that session. Replies without a session share one list.
+    pub(super) denied_tools: Mutex<HashMap<String, HashSet<String>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -152,6 +164,8 @@ impl Agent {
             router_tool_selector: Mutex::new(None),
             scheduler_service: Mutex::new(None),
             retry_manager,
+            active_call_token: Arc::new(Mutex::new(None)),
+            denied_tools: Mutex::new(HashMap::new()),
         }
     }
 
@@ -165,6 +179,14 @@ impl Agent {
         tool_monitor.as_ref().map(|monitor| monitor.get_stats())
     }
 
+    /// The `n` slowest tools by average execution time, None if the tool monitor is not configured
+    pub async fn tool_performance_report(&self, n: usize) -> Option<Vec<(String, Duration)>> {
+        let tool_monitor = self.tool_monitor.lock().await;
+        tool_monitor
+            .as_ref()
+            .map(|monitor| monitor.slowest_tools(n))
+    }
+
     pub async fn reset_tool_monitor(&self) {
         if let Some(monitor) = self.tool_monitor.lock().await.as_mut() {
             monitor.reset();
@@ -261,14 +283,43 @@ impl Agent {
         sub_recipe_manager.add_sub_recipe_tools(sub_recipes);
     }
 
+    pub async fn set_sub_recipe_execution_mode(
+        &self,
+        execution_mode: Option<SubRecipeExecutionMode>,
+    ) {
+        let mut sub_recipe_manager = self.sub_recipe_manager.lock().await;
+        sub_recipe_manager.set_execution_mode(execution_mode);
+    }
+
+    /// Audit logging for the builtin extensions added after this, taking the place of the
+    /// global `GOOSE_AUDIT_LOG` settings
+    pub async fn set_audit_config(&self, audit: Option<RecipeAuditConfig>) {
+        let mut extension_manager = self.extension_manager.write().await;
+        extension_manager.set_builtin_envs(
+            audit
+                .map(|audit| audit.extension_envs())
+                .unwrap_or_default(),
+        );
+    }
+
     /// Dispatch a single tool call to the appropriate client
-    #[instrument(skip(self, tool_call, request_id), fields(input, output))]
+    #[instrument(
+        skip(self, tool_call, request_id),
+        fields(
+            input,
+            output,
+            tool_name = %tool_call.name,
+            extension_name = %tool_call.name.split_once("__").map_or("", |(ext, _)| ext),
+        )
+    )]
     pub async fn dispatch_tool_call(
         &self,
         tool_call: mcp_core::tool::ToolCall,
         request_id: String,
         cancellation_token: Option<CancellationToken>,
     ) -> (String, Result<ToolCallResult, ToolError>) {
+        debug!("Dispatching tool call");
+
         // Check if this tool call should be allowed based on repetition monitoring
         if let Some(monitor) = self.tool_monitor.lock().await.as_mut() {
             let tool_call_info = ToolCall::new(tool_call.name.clone(), tool_call.arguments.clone());
@@ -324,6 +375,13 @@ impl Agent {
             };
         }
 
+        let call_handle = self
+            .tool_monitor
+            .lock()
+            .await
+            .as_ref()
+            .map(|monitor| monitor.start_call(&tool_call.name));
+
         let extension_manager = self.extension_manager.read().await;
         let sub_recipe_manager = self.sub_recipe_manager.lock().await;
         let result: ToolCallResult = if sub_recipe_manager.is_sub_recipe_tool(&tool_call.name) {
@@ -404,15 +462,27 @@ impl Agent {
             })
         };
 
+        // The tool only runs once the result future is polled, so the call is timed from
+        // dispatch until that future resolves
+        let tool_monitor = Arc::clone(&self.tool_monitor);
+        let tool_result = result
+            .result
+            .map(super::large_response_handler::process_tool_response);
+        let timed_result = async move {
+            let output = tool_result.await;
+            if let Some(handle) = call_handle {
+                if let Some(monitor) = tool_monitor.lock().await.as_mut() {
+                    monitor.end_call(handle, output.is_ok());
+                }
+            }
+            output
+        };
+
         (
             request_id,
             Ok(ToolCallResult {
                 notification_stream: result.notification_stream,
-                result: Box::new(
-                    result
-                        .result
-                        .map(super::large_response_handler::process_tool_response),
-                ),
+                result: Box::new(Box::pin(timed_result)),
             }),
         )
     }
@@ -706,7 +776,7 @@ impl Agent {
         }
     }
 
-    #[instrument(skip(self, messages, session), fields(user_message))]
+    #[instrument(skip(self, messages, session), fields(user_message, session_id))]
     pub async fn reply(
         &self,
         messages: &[Message],
@@ -716,9 +786,28 @@ impl Agent {
         let mut messages = messages.to_vec();
         let initial_messages = messages.clone();
         let reply_span = tracing::Span::current();
+        let session_id = session
+            .as_ref()
+            .map(|session| match &session.id {
+                session::Identifier::Name(name) => name.clone(),
+                session::Identifier::Path(path) => path.display().to_string(),
+            })
+            .unwrap_or_default();
+        if session.is_some() {
+            reply_span.record("session_id", tracing::field::display(&session_id));
+        }
         self.reset_retry_attempts().await;
         let config = Config::global();
 
+        // Each reply gets its own token so it can be cancelled through the agent, a child of the
+        // caller's token so cancelling that still stops it
+        let call_token = cancel_token
+            .as_ref()
+            .map(CancellationToken::child_token)
+            .unwrap_or_default();
+        *self.active_call_token.lock().await = Some(call_token.clone());
+        let cancel_token = Some(call_token);
+
         let (mut tools, mut toolshim_tools, mut system_prompt) =
             self.prepare_tools_and_prompt().await?;
         let goose_mode = Self::determine_goose_mode(session.as_ref(), config);
@@ -764,8 +853,11 @@ impl Agent {
                     break;
                 }
 
+                let provider = self.provider().await?;
+                Self::warn_if_near_context_limit(provider.as_ref(), &messages);
+
                 let mut stream = Self::stream_response_from_provider(
-                    self.provider().await?,
+                    provider,
                     &system_prompt,
                     &messages,
                     &tools,
@@ -861,6 +953,10 @@ impl Agent {
                                     yield AgentEvent::Message(msg);
                                 }
 
+                                let remaining_requests = self
+                                    .respond_to_denied_tools(&session_id, remaining_requests, message_tool_response.clone())
+                                    .await;
+
                                 let mode = goose_mode.clone();
                                 if mode.as_str() == "chat" {
                                     // Skip all tool calls in chat mode
@@ -891,6 +987,7 @@ impl Agent {
                                         if let Ok(tool_call) = request.tool_call.clone() {
                                             let (req_id, tool_result) = self
                                                 .dispatch_tool_call(tool_call, request.id.clone(), cancel_token.clone())
+                                                .instrument(reply_span.clone())
                                                 .await;
 
                                             tool_futures.push((
@@ -923,6 +1020,7 @@ impl Agent {
 
                                     // Process tools requiring approval
                                     let mut tool_approval_stream = self.handle_approval_tool_requests(
+                                        &session_id,
                                         &permission_check_result.needs_approval,
                                         tool_futures_arc.clone(),
                                         &mut permission_manager,
@@ -986,9 +1084,22 @@ impl Agent {
                             }
                         }
                         Err(ProviderError::ContextLengthExceeded(_)) => {
-                            yield AgentEvent::Message(Message::assistant().with_context_length_exceeded(
-                                    "The context length of the model has been exceeded. Please start a new session and try again.",
-                                ));
+                            let mut message = Message::assistant().with_context_length_exceeded(
+                                "The context length of the model has been exceeded. Please start a new session and try again.",
+                            );
+                            match create_async_token_counter().await {
+                                Ok(token_counter) => {
+                                    let report = token_counter.context_size_report(
+                                        &system_prompt,
+                                        &messages,
+                                        &tools,
+                                        CONTEXT_REPORT_LARGEST_MESSAGES,
+                                    );
+                                    message = message.with_context_size_report(report);
+                                }
+                                Err(e) => tracing::warn!("Failed to build context size report: {}", e),
+                            }
+                            yield AgentEvent::Message(message);
                             break;
                         }
                         Err(e) => {
@@ -1039,9 +1150,24 @@ impl Agent {
 
                 tokio::task::yield_now().await;
             }
+            self.active_call_token.lock().await.take();
         }))
     }
 
+    /// Cancel the reply in progress, such as from a supervising task in a headless deployment.
+    /// Tool calls still running are stopped and the reply stream ends after the current step.
+    pub async fn cancel_active_call(&self, reason: String) -> Result<()> {
+        let token = self
+            .active_call_token
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| anyhow!("No reply is in progress"))?;
+        info!("Cancelling the active reply: {}", reason);
+        token.cancel();
+        Ok(())
+    }
+
     fn determine_goose_mode(session: Option<&SessionConfig>, config: &Config) -> String {
         let mode = session.and_then(|s| s.execution_mode.as_deref());
 
@@ -1143,6 +1269,12 @@ impl Agent {
         prompt_manager.set_system_prompt_override(template);
     }
 
+    /// Replace the whole system prompt, including extension and extra instructions
+    pub async fn replace_system_prompt(&self, prompt: String) {
+        let mut prompt_manager = self.prompt_manager.lock().await;
+        prompt_manager.set_system_prompt_replacement(prompt);
+    }
+
     pub async fn list_extension_prompts(&self) -> HashMap<String, Vec<Prompt>> {
         let extension_manager = self.extension_manager.read().await;
         extension_manager
@@ -1328,6 +1460,9 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            system_prompt_override: None,
+            max_turns: None,
+            max_tool_calls: None,
         };
 
         let recipe = Recipe::builder()
@@ -1385,4 +1520,107 @@ mod tests {
         assert!(system_prompt.contains(&final_output_tool_system_prompt));
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_always_deny_refuses_later_calls() {
+        use crate::agents::tool_execution::ALWAYS_DENIED_RESPONSE;
+        use crate::message::{MessageContent, ToolRequest};
+        use crate::permission::permission_confirmation::PrincipalType;
+        use crate::permission::Permission;
+        use futures::TryStreamExt;
+
+        let agent = Agent::new();
+        let request = |id: &str| ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(mcp_core::tool::ToolCall::new(
+                "developer__shell",
+                serde_json::json!({"command": "rm -rf build"}),
+            )),
+        };
+        let denied_response = |response: &Message, id: &str| {
+            response.content.iter().any(|content| match content {
+                MessageContent::ToolResponse(response) => {
+                    response.id == id
+                        && matches!(
+                            &response.tool_result,
+                            Err(ToolError::ExecutionError(message))
+                                if message == ALWAYS_DENIED_RESPONSE
+                        )
+                }
+                _ => false,
+            })
+        };
+
+        agent
+            .handle_confirmation(
+                "1".to_string(),
+                PermissionConfirmation {
+                    principal_type: PrincipalType::Tool,
+                    permission: Permission::AlwaysDeny,
+                },
+            )
+            .await;
+        let requests = vec![request("1")];
+        let response = Arc::new(Mutex::new(Message::user()));
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut permission_manager =
+            PermissionManager::new(temp_dir.path().join("permission.yaml"));
+        let tool_futures = Arc::new(Mutex::new(Vec::new()));
+        let messages: Vec<Message> = agent
+            .handle_approval_tool_requests(
+                "session-1",
+                &requests,
+                tool_futures.clone(),
+                &mut permission_manager,
+                response.clone(),
+                None,
+            )
+            .try_collect()
+            .await
+            .unwrap();
+        // Asked once, then answered with the error and nothing dispatched
+        assert_eq!(messages.len(), 1);
+        assert!(tool_futures.lock().await.is_empty());
+        assert!(denied_response(&*response.lock().await, "1"));
+
+        // Later calls to the tool are refused before any confirmation is asked for
+        let response = Arc::new(Mutex::new(Message::user()));
+        let mut other = request("3");
+        other.tool_call = Ok(mcp_core::tool::ToolCall::new(
+            "developer__text_editor",
+            serde_json::json!({}),
+        ));
+        let remaining = agent
+            .respond_to_denied_tools("session-1", vec![request("2"), other], response.clone())
+            .await;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "3");
+        assert!(denied_response(&*response.lock().await, "2"));
+
+        // Other sessions sharing the agent can still call it
+        let response = Arc::new(Mutex::new(Message::user()));
+        let remaining = agent
+            .respond_to_denied_tools("session-2", vec![request("4")], response.clone())
+            .await;
+        assert_eq!(remaining.len(), 1);
+        assert!(!denied_response(&*response.lock().await, "4"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_active_call() {
+        let agent = Agent::new();
+        assert!(agent
+            .cancel_active_call("nothing running".to_string())
+            .await
+            .is_err());
+
+        let token = CancellationToken::new();
+        *agent.active_call_token.lock().await = Some(token.clone());
+        agent
+            .cancel_active_call("supervisor shutdown".to_string())
+            .await
+            .unwrap();
+        assert!(token.is_cancelled());
+        assert!(agent.active_call_token.lock().await.is_none());
+    }
 }
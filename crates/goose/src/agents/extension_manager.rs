@@ -35,6 +35,8 @@ pub struct ExtensionManager {
     clients: HashMap<String, McpClientBox>,
     instructions: HashMap<String, String>,
     resource_capable_extensions: HashSet<String>,
+    /// Passed to builtin extensions when they are started, see `set_builtin_envs`
+    builtin_envs: HashMap<String, String>,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -105,9 +107,15 @@ impl ExtensionManager {
             clients: HashMap::new(),
             instructions: HashMap::new(),
             resource_capable_extensions: HashSet::new(),
+            builtin_envs: HashMap::new(),
         }
     }
 
+    /// Environment for builtin extensions added from now on, on top of goose's own
+    pub fn set_builtin_envs(&mut self, envs: HashMap<String, String>) {
+        self.builtin_envs = envs;
+    }
+
     pub fn supports_resources(&self) -> bool {
         !self.resource_capable_extensions.is_empty()
     }
@@ -254,7 +262,7 @@ impl ExtensionManager {
                 let transport = StdioTransport::new(
                     &cmd,
                     vec!["mcp".to_string(), name.clone()],
-                    HashMap::new(),
+                    self.builtin_envs.clone(),
                 );
                 let handle = transport.start().await?;
                 Box::new(
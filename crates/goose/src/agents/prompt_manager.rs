@@ -10,6 +10,7 @@ use crate::{config::Config, prompt_template};
 
 pub struct PromptManager {
     system_prompt_override: Option<String>,
+    system_prompt_replacement: Option<String>,
     system_prompt_extras: Vec<String>,
     current_date_timestamp: String,
 }
@@ -24,6 +25,7 @@ impl PromptManager {
     pub fn new() -> Self {
         PromptManager {
             system_prompt_override: None,
+            system_prompt_replacement: None,
             system_prompt_extras: Vec::new(),
             // Use the fixed current date time so that prompt cache can be used.
             current_date_timestamp: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
@@ -40,6 +42,12 @@ impl PromptManager {
         self.system_prompt_override = Some(template);
     }
 
+    /// Use this text as the whole system prompt. Unlike an override it is not rendered as a
+    /// template and no extension instructions or extras are added to it
+    pub fn set_system_prompt_replacement(&mut self, prompt: String) {
+        self.system_prompt_replacement = Some(prompt);
+    }
+
     /// Normalize a model name (replace - and / with _, lower case)
     fn normalize_model_name(name: &str) -> String {
         name.replace(['-', '/', '.'], "_").to_lowercase()
@@ -71,6 +79,10 @@ impl PromptManager {
         model_name: Option<&str>,
         tool_selection_strategy: Option<RouterToolSelectionStrategy>,
     ) -> String {
+        if let Some(replacement) = &self.system_prompt_replacement {
+            return replacement.clone();
+        }
+
         let mut context: HashMap<&str, Value> = HashMap::new();
         let mut extensions_info = extensions_info.clone();
 
@@ -207,6 +219,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_system_prompt_replacement() {
+        let mut manager = PromptManager::new();
+        manager.add_system_prompt_extra("Recipe instructions".to_string());
+        manager.set_system_prompt_replacement("You only answer in haiku.".to_string());
+
+        let prompt = manager.build_system_prompt(
+            vec![ExtensionInfo::new(
+                "developer",
+                "Developer instructions",
+                false,
+            )],
+            None,
+            Value::Bool(false),
+            None,
+            None,
+        );
+        assert_eq!(prompt, "You only answer in haiku.");
+    }
+
     #[test]
     fn test_model_prompt_map_none() {
         // should return system.md for unrecognized/unsupported model names
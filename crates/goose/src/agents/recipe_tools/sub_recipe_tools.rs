@@ -8,7 +8,9 @@ use serde_json::{json, Map, Value};
 
 use crate::agents::subagent_execution_tool::lib::{ExecutionMode, Task};
 use crate::agents::subagent_execution_tool::tasks_manager::TasksManager;
-use crate::recipe::{Recipe, RecipeParameter, RecipeParameterRequirement, SubRecipe};
+use crate::recipe::{
+    Recipe, RecipeParameter, RecipeParameterRequirement, SubRecipe, SubRecipeExecutionMode,
+};
 
 use super::param_utils::prepare_command_params;
 
@@ -62,7 +64,8 @@ fn create_tasks_from_params(
                     "name": sub_recipe.name.clone(),
                     "command_parameters": task_command_param,
                     "recipe_path": sub_recipe.path.clone(),
-                    "sequential_when_repeated": sub_recipe.sequential_when_repeated
+                    "sequential_when_repeated": sub_recipe.sequential_when_repeated,
+                    "dependencies": sub_recipe.dependencies.clone().unwrap_or_default()
                 }
             });
             Task {
@@ -76,11 +79,16 @@ fn create_tasks_from_params(
     tasks
 }
 
-fn create_task_execution_payload(tasks: &[Task], sub_recipe: &SubRecipe) -> Value {
-    let execution_mode = if tasks.len() == 1 || sub_recipe.sequential_when_repeated {
-        ExecutionMode::Sequential
-    } else {
-        ExecutionMode::Parallel
+fn create_task_execution_payload(
+    tasks: &[Task],
+    sub_recipe: &SubRecipe,
+    recipe_execution_mode: Option<SubRecipeExecutionMode>,
+) -> Value {
+    let execution_mode = match recipe_execution_mode {
+        Some(SubRecipeExecutionMode::Dag) => ExecutionMode::Dag,
+        Some(SubRecipeExecutionMode::Sequential) => ExecutionMode::Sequential,
+        _ if tasks.len() == 1 || sub_recipe.sequential_when_repeated => ExecutionMode::Sequential,
+        _ => ExecutionMode::Parallel,
     };
     let task_ids: Vec<String> = tasks.iter().map(|task| task.id.clone()).collect();
     json!({
@@ -92,12 +100,14 @@ fn create_task_execution_payload(tasks: &[Task], sub_recipe: &SubRecipe) -> Valu
 pub async fn create_sub_recipe_task(
     sub_recipe: &SubRecipe,
     params: Value,
+    recipe_execution_mode: Option<SubRecipeExecutionMode>,
     tasks_manager: &TasksManager,
 ) -> Result<String> {
     let task_params_array = extract_task_parameters(&params);
     let command_params = prepare_command_params(sub_recipe, task_params_array.clone())?;
     let tasks = create_tasks_from_params(sub_recipe, &command_params);
-    let task_execution_payload = create_task_execution_payload(&tasks, sub_recipe);
+    let task_execution_payload =
+        create_task_execution_payload(&tasks, sub_recipe, recipe_execution_mode);
 
     let tasks_json = serde_json::to_string(&task_execution_payload)
         .map_err(|e| anyhow::anyhow!("Failed to serialize task list: {}", e))?;
@@ -14,6 +14,7 @@ mod tests {
             values: Some(HashMap::from([("key1".to_string(), "value1".to_string())])),
             sequential_when_repeated: true,
             description: Some("Test subrecipe".to_string()),
+            dependencies: None,
         };
         sub_recipe
     }
@@ -129,4 +130,60 @@ mod tests {
             );
         }
     }
+
+    mod create_task_execution_payload {
+        use super::*;
+        use crate::agents::recipe_tools::sub_recipe_tools::{
+            create_task_execution_payload, create_tasks_from_params,
+        };
+        use crate::recipe::SubRecipeExecutionMode;
+
+        fn execution_mode(
+            sub_recipe: &SubRecipe,
+            task_count: usize,
+            recipe_execution_mode: Option<SubRecipeExecutionMode>,
+        ) -> Value {
+            let command_params = vec![HashMap::new(); task_count];
+            let tasks = create_tasks_from_params(sub_recipe, &command_params);
+            create_task_execution_payload(&tasks, sub_recipe, recipe_execution_mode)
+                .get("execution_mode")
+                .unwrap()
+                .clone()
+        }
+
+        #[test]
+        fn test_recipe_execution_mode_overrides_task_count() {
+            let mut sub_recipe = setup_default_sub_recipe();
+            sub_recipe.sequential_when_repeated = false;
+
+            assert_eq!(execution_mode(&sub_recipe, 1, None), json!("sequential"));
+            assert_eq!(execution_mode(&sub_recipe, 2, None), json!("parallel"));
+            assert_eq!(
+                execution_mode(&sub_recipe, 2, Some(SubRecipeExecutionMode::Sequential)),
+                json!("sequential")
+            );
+            assert_eq!(
+                execution_mode(&sub_recipe, 1, Some(SubRecipeExecutionMode::Dag)),
+                json!("dag")
+            );
+
+            sub_recipe.sequential_when_repeated = true;
+            assert_eq!(
+                execution_mode(&sub_recipe, 2, Some(SubRecipeExecutionMode::Parallel)),
+                json!("sequential")
+            );
+        }
+
+        #[test]
+        fn test_tasks_carry_dependencies() {
+            let mut sub_recipe = setup_default_sub_recipe();
+            sub_recipe.dependencies = Some(vec!["setup".to_string()]);
+
+            let tasks = create_tasks_from_params(&sub_recipe, &[HashMap::new()]);
+            assert_eq!(
+                tasks[0].get_sub_recipe_dependencies(),
+                vec!["setup".to_string()]
+            );
+        }
+    }
 }
@@ -8,7 +8,7 @@ use futures::stream::StreamExt;
 use crate::agents::router_tool_selector::RouterToolSelectionStrategy;
 use crate::config::Config;
 use crate::message::{Message, MessageContent, ToolRequest};
-use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
+use crate::providers::base::{MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
 use crate::providers::toolshim::{
     augment_message_with_tool_calls, convert_tool_messages_to_text,
@@ -32,6 +32,12 @@ async fn toolshim_postprocess(
         .map_err(|e| ProviderError::ExecutionError(format!("Failed to augment message: {}", e)))
 }
 
+fn requires_internet(tool: &Tool) -> bool {
+    tool.annotations.as_ref().is_some_and(|annotations| {
+        mcp_core::tool::ToolAnnotations::from(annotations).requires_internet
+    })
+}
+
 impl Agent {
     /// Prepares tools and system prompt for a provider request
     pub(crate) async fn prepare_tools_and_prompt(
@@ -67,6 +73,14 @@ impl Agent {
             tools.push(frontend_tool.tool.clone());
         }
 
+        // In air-gapped environments tools that need the internet would only fail
+        if config
+            .get_param::<bool>("GOOSE_OFFLINE_MODE")
+            .unwrap_or(false)
+        {
+            tools.retain(|tool| !requires_internet(tool));
+        }
+
         // Prepare system prompt
         let extension_manager = self.extension_manager.read().await;
         let extensions_info = extension_manager.get_extensions_info().await;
@@ -153,7 +167,22 @@ impl Agent {
         Ok((response, usage))
     }
 
-    /// Stream a response from the LLM provider.
+    /// Log a warning when the conversation is estimated to fill most of the model's context,
+    /// since the request is then likely to fail with a context length error
+    pub(crate) fn warn_if_near_context_limit(provider: &dyn Provider, messages: &[Message]) {
+        let context_limit = provider.get_model_config().context_limit() as u64;
+        match provider.estimate_tokens(messages) {
+            Ok(estimate) if estimate * 10 > context_limit * 9 => tracing::warn!(
+                "Conversation is estimated at {} tokens, over 90% of the {} token context limit",
+                estimate,
+                context_limit
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Failed to estimate conversation tokens: {}", e),
+        }
+    }
+
+    /// Stream a response from the LLM provider, text as it is generated.
     /// Handles toolshim transformations if needed
     pub(crate) async fn stream_response_from_provider(
         provider: Arc<dyn Provider>,
@@ -177,19 +206,15 @@ impl Agent {
         let toolshim_tools = toolshim_tools.to_owned();
         let provider = provider.clone();
 
-        let mut stream = if provider.supports_streaming() {
-            provider
-                .stream(system_prompt.as_str(), &messages_for_provider, &tools)
-                .await?
-        } else {
-            let (message, usage) = provider
-                .complete(system_prompt.as_str(), &messages_for_provider, &tools)
-                .await?;
-            stream_from_single_message(message, usage)
-        };
+        let mut stream = provider
+            .streaming_complete(system_prompt.as_str(), &messages_for_provider, &tools)
+            .await?;
 
         Ok(Box::pin(try_stream! {
-            while let Some(Ok((mut message, usage))) = stream.next().await {
+            while let Some(Ok(partial)) = stream.next().await {
+                let usage = partial.usage.clone();
+                let mut message = partial.into_message();
+
                 // Store the model information in the global store
                 if let Some(usage) = usage.as_ref() {
                     crate::providers::base::set_current_model(&usage.model);
@@ -12,13 +12,15 @@ use crate::{
         subagent_execution_tool::tasks_manager::TasksManager,
         tool_execution::ToolCallResult,
     },
-    recipe::SubRecipe,
+    recipe::{SubRecipe, SubRecipeExecutionMode},
 };
 
 #[derive(Debug, Clone)]
 pub struct SubRecipeManager {
     pub sub_recipe_tools: HashMap<String, Tool>,
     pub sub_recipes: HashMap<String, SubRecipe>,
+    /// The recipe's `execution_mode`, None to pick one from the number of tasks
+    pub execution_mode: Option<SubRecipeExecutionMode>,
 }
 
 impl Default for SubRecipeManager {
@@ -32,6 +34,7 @@ impl SubRecipeManager {
         Self {
             sub_recipe_tools: HashMap::new(),
             sub_recipes: HashMap::new(),
+            execution_mode: None,
         }
     }
 
@@ -48,6 +51,10 @@ impl SubRecipeManager {
         }
     }
 
+    pub fn set_execution_mode(&mut self, execution_mode: Option<SubRecipeExecutionMode>) {
+        self.execution_mode = execution_mode;
+    }
+
     pub fn is_sub_recipe_tool(&self, tool_name: &str) -> bool {
         self.sub_recipe_tools.contains_key(tool_name)
     }
@@ -87,7 +94,7 @@ impl SubRecipeManager {
 
             ToolError::InvalidParameters(format!("Sub-recipe '{}' not found", sub_recipe_name))
         })?;
-        let output = create_sub_recipe_task(sub_recipe, params, tasks_manager)
+        let output = create_sub_recipe_task(sub_recipe, params, self.execution_mode, tasks_manager)
             .await
             .map_err(|e| {
                 ToolError::ExecutionError(format!("Sub-recipe task createion failed: {}", e))
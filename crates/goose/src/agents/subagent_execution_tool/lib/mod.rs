@@ -6,9 +6,12 @@ use crate::agents::subagent_execution_tool::{
     tasks_manager::TasksManager,
 };
 use crate::agents::subagent_task_config::TaskConfig;
+use crate::recipe::dependencies::dependency_levels;
 use rmcp::model::ServerNotification;
 use serde_json::{json, Value};
+use std::collections::HashSet;
 use tokio::sync::mpsc::Sender;
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
 pub async fn execute_tasks(
@@ -60,7 +63,84 @@ pub async fn execute_tasks(
                 handle_response(response)
             }
         }
+        ExecutionMode::Dag => {
+            let response =
+                execute_tasks_in_dag_order(tasks, notifier, task_config, cancellation_token)
+                    .await?;
+            handle_response(response)
+        }
+    }
+}
+
+/// Run the tasks of every sub-recipe in parallel once the tasks of the sub-recipes it depends
+/// on have completed. Dependencies on sub-recipes without tasks here are assumed to have run
+/// in an earlier call. Stops at the first level with a failed task.
+async fn execute_tasks_in_dag_order(
+    tasks: Vec<Task>,
+    notifier: Sender<ServerNotification>,
+    task_config: TaskConfig,
+    cancellation_token: Option<CancellationToken>,
+) -> Result<ExecutionResponse, String> {
+    let mut groups: Vec<(String, Vec<String>, Vec<Task>)> = Vec::new();
+    for task in tasks {
+        let name = task
+            .get_sub_recipe_name()
+            .ok_or_else(|| format!("Task '{}' is not a sub recipe task", task.id))?
+            .to_string();
+        match groups.iter_mut().find(|(n, _, _)| *n == name) {
+            Some((_, _, group)) => group.push(task),
+            None => groups.push((name, task.get_sub_recipe_dependencies(), vec![task])),
+        }
     }
+    let names: HashSet<String> = groups.iter().map(|(name, _, _)| name.clone()).collect();
+    for (_, dependencies, _) in groups.iter_mut() {
+        dependencies.retain(|d| names.contains(d));
+    }
+
+    let nodes: Vec<(&str, &[String])> = groups
+        .iter()
+        .map(|(name, dependencies, _)| (name.as_str(), dependencies.as_slice()))
+        .collect();
+    let levels: Vec<Vec<String>> = dependency_levels(&nodes)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|level| level.into_iter().map(str::to_string).collect())
+        .collect();
+
+    let start_time = Instant::now();
+    let mut results = Vec::new();
+    let (mut completed, mut failed) = (0, 0);
+    for level in levels {
+        let level_tasks: Vec<Task> = groups
+            .iter()
+            .filter(|(name, _, _)| level.contains(name))
+            .flat_map(|(_, _, tasks)| tasks.clone())
+            .collect();
+        let response = execute_tasks_in_parallel(
+            level_tasks,
+            notifier.clone(),
+            task_config.clone(),
+            cancellation_token.clone(),
+        )
+        .await;
+        completed += response.stats.completed;
+        failed += response.stats.failed;
+        results.extend(response.results);
+        if response.stats.failed > 0 {
+            break;
+        }
+    }
+
+    Ok(ExecutionResponse {
+        status: "completed".to_string(),
+        stats: ExecutionStats {
+            total_tasks: results.len(),
+            completed,
+            failed,
+            execution_time_ms: start_time.elapsed().as_millis(),
+        },
+        results,
+    })
 }
 
 fn extract_failed_tasks(results: &[TaskResult]) -> Vec<String> {
@@ -25,6 +25,7 @@ pub fn create_subagent_execute_task_tool() -> Tool {
         IMPLEMENTATION:
         - Sequential execution: Call this tool multiple times, passing exactly ONE task per call
         - Parallel execution: Call this tool once, passing an ARRAY of all tasks
+        - Dag execution: Create the tasks of every sub recipe first, then call this tool once with all of them; each sub recipe runs after the sub recipes it depends on
 
         EXAMPLES:
         User Intent Based:
@@ -37,7 +38,7 @@ pub fn create_subagent_execute_task_tool() -> Tool {
             "properties": {
                 "execution_mode": {
                     "type": "string",
-                    "enum": ["sequential", "parallel"],
+                    "enum": ["sequential", "parallel", "dag"],
                     "default": "sequential",
                     "description": "Execution strategy for multiple tasks. Use 'sequential' (default) unless user explicitly requests parallel execution with words like 'parallel', 'simultaneously', 'at the same time', or 'concurrently'."
                 },
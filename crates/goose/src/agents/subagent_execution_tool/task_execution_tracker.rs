@@ -1,6 +1,7 @@
 use rmcp::model::{
     LoggingLevel, LoggingMessageNotification, LoggingMessageNotificationMethod,
-    LoggingMessageNotificationParam, ServerNotification,
+    LoggingMessageNotificationParam, NumberOrString, ProgressNotification,
+    ProgressNotificationMethod, ProgressNotificationParam, ProgressToken, ServerNotification,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -57,6 +58,8 @@ pub struct TaskExecutionTracker {
     notifier: mpsc::Sender<ServerNotification>,
     display_mode: DisplayMode,
     cancellation_token: Option<CancellationToken>,
+    // Identifies this run's standard `notifications/progress` events
+    progress_token: ProgressToken,
 }
 
 impl TaskExecutionTracker {
@@ -90,6 +93,9 @@ impl TaskExecutionTracker {
             notifier,
             display_mode,
             cancellation_token,
+            progress_token: ProgressToken(NumberOrString::String(
+                format!("subagent-tasks-{}", uuid::Uuid::new_v4()).into(),
+            )),
         }
     }
 
@@ -126,6 +132,32 @@ impl TaskExecutionTracker {
         }
     }
 
+    /// Report how many tasks have finished as a standard MCP progress notification, for
+    /// clients that don't know the task specific events
+    fn send_progress(&self, finished: usize, total: usize) {
+        if self.is_cancelled() {
+            return;
+        }
+
+        if let Err(e) = self
+            .notifier
+            .try_send(ServerNotification::ProgressNotification(
+                ProgressNotification {
+                    method: ProgressNotificationMethod,
+                    params: ProgressNotificationParam {
+                        progress_token: self.progress_token.clone(),
+                        progress: finished as u32,
+                        total: Some(total as u32),
+                        message: Some(format!("{}/{} tasks finished", finished, total)),
+                    },
+                    extensions: Default::default(),
+                },
+            ))
+        {
+            self.log_notification_error(&e, "progress");
+        }
+    }
+
     pub async fn start_task(&self, task_id: &str) {
         let mut tasks = self.tasks.write().await;
         if let Some(task_info) = tasks.get_mut(task_id) {
@@ -143,7 +175,9 @@ impl TaskExecutionTracker {
             task_info.end_time = Some(Instant::now());
             task_info.result = Some(result);
         }
+        let (total, _, _, completed, failed) = count_by_status(&tasks);
         drop(tasks);
+        self.send_progress(completed + failed, total);
         self.force_refresh_display().await;
     }
 
@@ -307,3 +341,47 @@ impl TaskExecutionTracker {
         sleep(Duration::from_millis(COMPLETION_NOTIFICATION_DELAY_MS)).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            task_type: "text_instruction".to_string(),
+            payload: json!({"text_instruction": "do something"}),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_complete_task_sends_progress() {
+        let (notifier, mut receiver) = mpsc::channel(10);
+        let tracker = TaskExecutionTracker::new(
+            vec![task("task_1"), task("task_2")],
+            DisplayMode::SingleTaskOutput,
+            notifier,
+            None,
+        );
+
+        tracker
+            .complete_task(
+                "task_1",
+                TaskResult {
+                    task_id: "task_1".to_string(),
+                    status: TaskStatus::Completed,
+                    data: None,
+                    error: None,
+                },
+            )
+            .await;
+
+        let Ok(ServerNotification::ProgressNotification(notification)) = receiver.try_recv() else {
+            panic!("expected a progress notification");
+        };
+        assert_eq!(notification.params.progress, 1);
+        assert_eq!(notification.params.total, Some(2));
+        assert_eq!(notification.params.progress_token, tracker.progress_token);
+    }
+}
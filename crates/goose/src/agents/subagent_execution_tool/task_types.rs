@@ -13,6 +13,8 @@ pub enum ExecutionMode {
     #[default]
     Sequential,
     Parallel,
+    /// Sub-recipe tasks in parallel, level by level, after the sub-recipes they depend on
+    Dag,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +49,13 @@ impl Task {
             .and_then(|name| name.as_str())
     }
 
+    pub fn get_sub_recipe_dependencies(&self) -> Vec<String> {
+        self.get_sub_recipe()
+            .and_then(|sr| sr.get("dependencies"))
+            .and_then(|deps| serde_json::from_value(deps.clone()).ok())
+            .unwrap_or_default()
+    }
+
     pub fn get_sub_recipe_path(&self) -> Option<&str> {
         self.get_sub_recipe()
             .and_then(|sr| sr.get("recipe_path"))
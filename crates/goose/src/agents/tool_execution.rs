@@ -12,7 +12,7 @@ use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
 use crate::message::{Message, ToolRequest};
 use crate::permission::Permission;
-use mcp_core::ToolResult;
+use mcp_core::{ToolError, ToolResult};
 use rmcp::model::Content;
 
 // ToolCallResult combines the result of a tool call with an optional notification stream that
@@ -38,6 +38,8 @@ pub const DECLINED_RESPONSE: &str = "The user has declined to run this tool. \
     DO NOT attempt to call this tool again. \
     If there are no alternative methods to proceed, clearly explain the situation and STOP.";
 
+pub const ALWAYS_DENIED_RESPONSE: &str = "Tool permanently denied by user";
+
 pub const CHAT_MODE_TOOL_SKIPPED_RESPONSE: &str = "Let the user know the tool call was skipped in Goose chat mode. \
                                         DO NOT apologize for skipping the tool call. DO NOT say sorry. \
                                         Provide an explanation of what the tool call would do, structured as a \
@@ -50,6 +52,7 @@ pub const CHAT_MODE_TOOL_SKIPPED_RESPONSE: &str = "Let the user know the tool ca
 impl Agent {
     pub(crate) fn handle_approval_tool_requests<'a>(
         &'a self,
+        session_id: &'a str,
         tool_requests: &'a [ToolRequest],
         tool_futures: Arc<Mutex<Vec<(String, ToolStream)>>>,
         permission_manager: &'a mut PermissionManager,
@@ -88,6 +91,18 @@ impl Agent {
                                 if confirmation.permission == Permission::AlwaysAllow {
                                     permission_manager.update_user_permission(&tool_call.name, PermissionLevel::AlwaysAllow);
                                 }
+                            } else if confirmation.permission == Permission::AlwaysDeny {
+                                self.denied_tools
+                                    .lock()
+                                    .await
+                                    .entry(session_id.to_string())
+                                    .or_default()
+                                    .insert(tool_call.name.clone());
+                                let mut response = message_tool_response.lock().await;
+                                *response = response.clone().with_tool_response(
+                                    request.id.clone(),
+                                    Err(ToolError::ExecutionError(ALWAYS_DENIED_RESPONSE.to_string())),
+                                );
                             } else {
                                 // User declined - add declined response
                                 let mut response = message_tool_response.lock().await;
@@ -104,6 +119,37 @@ impl Agent {
         }.boxed()
     }
 
+    /// Answer requests for tools the user chose to always deny in this session without running
+    /// them or asking again, and return the other requests
+    pub(crate) async fn respond_to_denied_tools(
+        &self,
+        session_id: &str,
+        tool_requests: Vec<ToolRequest>,
+        message_tool_response: Arc<Mutex<Message>>,
+    ) -> Vec<ToolRequest> {
+        let denied_tools = self.denied_tools.lock().await;
+        let Some(denied_tools) = denied_tools.get(session_id) else {
+            return tool_requests;
+        };
+        let (denied, allowed): (Vec<_>, Vec<_>) = tool_requests.into_iter().partition(|request| {
+            request
+                .tool_call
+                .as_ref()
+                .is_ok_and(|tool_call| denied_tools.contains(&tool_call.name))
+        });
+
+        let mut response = message_tool_response.lock().await;
+        for request in denied {
+            *response = response.clone().with_tool_response(
+                request.id,
+                Err(ToolError::ExecutionError(
+                    ALWAYS_DENIED_RESPONSE.to_string(),
+                )),
+            );
+        }
+        allowed
+    }
+
     pub(crate) fn handle_frontend_tool_requests<'a>(
         &'a self,
         tool_requests: &'a [ToolRequest],
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use rmcp::model::Tool;
 
 use crate::{
-    message::Message,
+    message::{Message, MessageContent},
     providers::base::Provider,
     token_counter::{AsyncTokenCounter, TokenCounter},
 };
@@ -11,6 +11,70 @@ use crate::{
 const ESTIMATE_FACTOR: f32 = 0.7;
 const SYSTEM_PROMPT_TOKEN_OVERHEAD: usize = 3_000;
 const TOOLS_TOKEN_OVERHEAD: usize = 5_000;
+/// Number of recent messages the adaptive strategy looks at
+const ADAPTIVE_WINDOW: usize = 10;
+
+/// How to recover when the conversation no longer fits in the model's context,
+/// configured with `GOOSE_CONTEXT_STRATEGY`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextStrategy {
+    Clear,
+    Truncate,
+    Summarize,
+    /// Truncate or summarize depending on what the recent messages contain
+    Adaptive,
+}
+
+impl ContextStrategy {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "clear" => Some(Self::Clear),
+            "truncate" => Some(Self::Truncate),
+            "summarize" => Some(Self::Summarize),
+            "adaptive" => Some(Self::Adaptive),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Clear => "clear",
+            Self::Truncate => "truncate",
+            Self::Summarize => "summarize",
+            Self::Adaptive => "adaptive",
+        }
+    }
+
+    /// Pick the concrete strategy to apply, only `Adaptive` depends on the messages.
+    ///
+    /// Tool heavy stretches (file contents, command output) are cheap to drop and expensive
+    /// to summarize, so they are truncated. Conversational stretches carry decisions that
+    /// truncation would lose, so they are summarized.
+    pub fn resolve(self, messages: &[Message]) -> Self {
+        if self != Self::Adaptive {
+            return self;
+        }
+
+        let recent = &messages[messages.len().saturating_sub(ADAPTIVE_WINDOW)..];
+        let tool_messages = recent
+            .iter()
+            .filter(|message| {
+                message.content.iter().any(|content| {
+                    matches!(
+                        content,
+                        MessageContent::ToolRequest(_) | MessageContent::ToolResponse(_)
+                    )
+                })
+            })
+            .count();
+
+        if tool_messages * 2 > recent.len() {
+            Self::Truncate
+        } else {
+            Self::Summarize
+        }
+    }
+}
 
 pub fn estimate_target_context_limit(provider: Arc<dyn Provider>) -> usize {
     let model_context_limit = provider.get_model_config().context_limit();
@@ -92,3 +156,53 @@ pub fn get_token_counts_async(
         messages: messages_token_count,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn tool_exchange(id: &str) -> Vec<Message> {
+        vec![
+            Message::assistant().with_tool_request(
+                id,
+                Ok(ToolCall::new("text_editor", json!({"command": "view"}))),
+            ),
+            Message::user().with_tool_response(id, Ok(vec![Content::text("fn main() {}")])),
+        ]
+    }
+
+    #[test]
+    fn test_adaptive_strategy() {
+        let mut messages = vec![Message::user().with_text("Refactor the parser")];
+        for i in 0..4 {
+            messages.extend(tool_exchange(&i.to_string()));
+        }
+        assert_eq!(
+            ContextStrategy::Adaptive.resolve(&messages),
+            ContextStrategy::Truncate
+        );
+
+        for i in 0..4 {
+            messages.push(Message::user().with_text(format!("What about option {}?", i)));
+            messages.push(Message::assistant().with_text("That would also work."));
+        }
+        assert_eq!(
+            ContextStrategy::Adaptive.resolve(&messages),
+            ContextStrategy::Summarize
+        );
+
+        // Concrete strategies are never changed
+        assert_eq!(
+            ContextStrategy::Clear.resolve(&messages),
+            ContextStrategy::Clear
+        );
+        assert_eq!(
+            ContextStrategy::from_config("adaptive"),
+            Some(ContextStrategy::Adaptive)
+        );
+        assert_eq!(ContextStrategy::from_config("prompt"), None);
+    }
+}
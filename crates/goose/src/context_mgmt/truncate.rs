@@ -1,8 +1,8 @@
 use crate::message::{Message, MessageContent};
 use crate::utils::safe_truncate;
 use anyhow::{anyhow, Result};
-use rmcp::model::{RawContent, ResourceContents, Role};
-use std::collections::HashSet;
+use rmcp::model::{Content, RawContent, ResourceContents, Role};
+use std::collections::{HashMap, HashSet};
 use std::ops::DerefMut;
 use tracing::{debug, warn};
 
@@ -168,6 +168,63 @@ fn estimate_message_tokens(message: &Message, estimate_fn: &dyn Fn(&str) -> usiz
     total_tokens
 }
 
+/// Bytes of text or encoded data in a tool result
+fn tool_result_size(contents: &[Content]) -> usize {
+    contents
+        .iter()
+        .map(|content| match &**content {
+            RawContent::Text(text) => text.text.len(),
+            RawContent::Image(image) => image.data.len(),
+            RawContent::Audio(audio) => audio.data.len(),
+            RawContent::Resource(resource) => match &resource.resource {
+                ResourceContents::TextResourceContents { text, .. } => text.len(),
+                ResourceContents::BlobResourceContents { blob, .. } => blob.len(),
+            },
+        })
+        .sum()
+}
+
+/// Replaces tool results larger than `max_content_bytes` with a short note naming the tool, so
+/// the messages keep their structure while the bulk of large file reads or command output is
+/// dropped. Returns the number of tool results that were pruned.
+pub fn prune_tool_results(messages: &mut [Message], max_content_bytes: usize) -> usize {
+    let tool_names: HashMap<String, String> = messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| content.as_tool_request())
+        .filter_map(|request| {
+            let call = request.tool_call.as_ref().ok()?;
+            Some((request.id.clone(), call.name.clone()))
+        })
+        .collect();
+
+    let mut pruned = 0;
+    for message in messages.iter_mut() {
+        for content in &mut message.content {
+            let MessageContent::ToolResponse(response) = content else {
+                continue;
+            };
+            let Ok(result) = &mut response.tool_result else {
+                continue;
+            };
+            let size = tool_result_size(result);
+            if size <= max_content_bytes {
+                continue;
+            }
+            let tool_name = tool_names
+                .get(&response.id)
+                .map(String::as_str)
+                .unwrap_or("unknown");
+            *result = vec![Content::text(format!(
+                "[pruned: {} bytes, tool: {}]",
+                size, tool_name
+            ))];
+            pruned += 1;
+        }
+    }
+    pruned
+}
+
 /// Truncates the messages to fit within the model's context window.
 /// Mutates the input messages and token counts in place.
 /// Returns an error if it's impossible to truncate the messages within the context limit.
@@ -417,6 +474,37 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_prune_tool_results() {
+        let mut messages = vec![
+            Message::user().with_text("Read both files"),
+            Message::assistant()
+                .with_tool_request("read_1", Ok(ToolCall::new("read_file", json!({}))))
+                .with_tool_request("read_2", Ok(ToolCall::new("read_file", json!({})))),
+            Message::user()
+                .with_tool_response("read_1", Ok(vec![Content::text("x".repeat(5000))]))
+                .with_tool_response("read_2", Ok(vec![Content::text("short")])),
+            Message::user().with_tool_response("orphan", Ok(vec![Content::text("y".repeat(2000))])),
+        ];
+
+        assert_eq!(prune_tool_results(&mut messages, 1000), 2);
+        assert_eq!(
+            messages[2].content[0].as_tool_response_text().unwrap(),
+            "[pruned: 5000 bytes, tool: read_file]"
+        );
+        assert_eq!(
+            messages[2].content[1].as_tool_response_text().unwrap(),
+            "short"
+        );
+        assert_eq!(
+            messages[3].content[0].as_tool_response_text().unwrap(),
+            "[pruned: 2000 bytes, tool: unknown]"
+        );
+
+        // Pruned results are small enough to be left alone the next time
+        assert_eq!(prune_tool_results(&mut messages, 1000), 0);
+    }
+
     // Helper function to create messages with alternating user and assistant
     // text messages of a fixed token count
     fn create_messages_with_counts(
@@ -88,9 +88,22 @@ pub struct FrontendToolRequest {
     pub tool_call: ToolResult<ToolCall>,
 }
 
+/// Where the tokens went when the context overflowed, so the user can tell which messages are
+/// worth summarizing or removing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSizeReport {
+    pub total_tokens: u64,
+    /// Index in the conversation, token count and a preview of the largest messages, largest
+    /// first
+    pub largest_messages: Vec<(usize, u64, String)>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct ContextLengthExceeded {
     pub msg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<ContextSizeReport>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -176,7 +189,10 @@ impl MessageContent {
     }
 
     pub fn context_length_exceeded<S: Into<String>>(msg: S) -> Self {
-        MessageContent::ContextLengthExceeded(ContextLengthExceeded { msg: msg.into() })
+        MessageContent::ContextLengthExceeded(ContextLengthExceeded {
+            msg: msg.into(),
+            report: None,
+        })
     }
 
     pub fn summarization_requested<S: Into<String>>(msg: S) -> Self {
@@ -446,6 +462,16 @@ impl Message {
         self.with_content(MessageContent::context_length_exceeded(msg))
     }
 
+    /// Attach the report to the context length exceeded content of the message
+    pub fn with_context_size_report(mut self, report: ContextSizeReport) -> Self {
+        for content in &mut self.content {
+            if let MessageContent::ContextLengthExceeded(exceeded) = content {
+                exceeded.report = Some(report.clone());
+            }
+        }
+        self
+    }
+
     /// Get the concatenated text content of the message, separated by newlines
     pub fn as_concat_text(&self) -> String {
         self.content
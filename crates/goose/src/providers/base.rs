@@ -1,9 +1,9 @@
 use anyhow::Result;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 
 use super::errors::ProviderError;
-use crate::message::Message;
+use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
 use rmcp::model::Tool;
 use utoipa::ToSchema;
@@ -76,6 +76,19 @@ impl ModelInfo {
     }
 }
 
+/// A model offered by a provider, as reported by the provider's models API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct AvailableModel {
+    /// The name to configure as GOOSE_MODEL
+    pub id: String,
+    /// The maximum context length, when the provider reports it
+    pub context_window: Option<u64>,
+    /// Whether the model accepts tool definitions
+    pub supports_tools: bool,
+    /// Whether the model accepts images as input
+    pub supports_vision: bool,
+}
+
 /// Metadata about a provider's configuration requirements and capabilities
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProviderMetadata {
@@ -290,6 +303,20 @@ pub trait Provider: Send + Sync {
         Ok(None)
     }
 
+    /// List the models the provider offers and what they support, by asking its API.
+    /// Providers without a models API that reports capabilities return NotImplemented.
+    async fn list_models(&self) -> Result<Vec<AvailableModel>, ProviderError> {
+        Err(ProviderError::NotImplemented(
+            "This provider does not support listing models".to_string(),
+        ))
+    }
+
+    /// Estimate how many tokens `messages` would take up in a request, without calling the API.
+    /// The default counts them with a local tokenizer, which is close enough for most models.
+    fn estimate_tokens(&self, messages: &[Message]) -> Result<u64, ProviderError> {
+        Ok(crate::token_counter::estimate_conversation_tokens(messages))
+    }
+
     /// Check if this provider supports embeddings
     fn supports_embeddings(&self) -> bool {
         false
@@ -307,6 +334,13 @@ pub trait Provider: Send + Sync {
         ))
     }
 
+    /// Score how relevant each of `documents` is to `query`, higher is more relevant, one score
+    /// per document in the same order. Providers with a cross-encoder endpoint override this,
+    /// the default ranks by TF-IDF locally.
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f64>, ProviderError> {
+        Ok(super::rerank::tfidf_scores(query, documents))
+    }
+
     /// Check if this provider is a LeadWorkerProvider
     /// This is used for logging model information at startup
     fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
@@ -328,6 +362,31 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// Generate the next message as it is produced, text arriving as deltas. Built on `stream`
+    /// for providers that support it, others yield their whole response as a single piece.
+    async fn streaming_complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<PartialMessageStream, ProviderError> {
+        let stream = if self.supports_streaming() {
+            self.stream(system, messages, tools).await?
+        } else {
+            let (message, usage) = self.complete(system, messages, tools).await?;
+            stream_from_single_message(message, usage)
+        };
+        Ok(Box::pin(stream.map(|item| {
+            item.map(|(message, usage)| match message {
+                Some(message) => PartialMessage::from_message(message, usage),
+                None => PartialMessage {
+                    usage,
+                    ..Default::default()
+                },
+            })
+        })))
+    }
+
     /// Get the currently active model name
     /// For regular providers, this returns the configured model
     /// For LeadWorkerProvider, this returns the currently active model (lead or worker)
@@ -347,6 +406,66 @@ pub type MessageStream = Pin<
     Box<dyn Stream<Item = Result<(Option<Message>, Option<ProviderUsage>), ProviderError>> + Send>,
 >;
 
+/// A piece of a response from [`Provider::streaming_complete`]. Text comes as a delta to
+/// append to the text so far, other content such as tool requests comes complete. A response
+/// that is not streamed comes as one piece holding all of its content.
+#[derive(Debug, Clone, Default)]
+pub struct PartialMessage {
+    /// Same for every piece of a response, when the provider reports one
+    pub id: Option<String>,
+    pub text_delta: String,
+    pub content: Vec<MessageContent>,
+    /// Usage of the whole response, set on the piece where the provider reports it
+    pub usage: Option<ProviderUsage>,
+}
+
+impl PartialMessage {
+    /// A piece holding a streamed message. A message with only text is a delta, any other keeps
+    /// its content as it is, so thinking stays ahead of the text that follows it.
+    pub fn from_message(message: Message, usage: Option<ProviderUsage>) -> Self {
+        let mut partial = PartialMessage {
+            id: message.id.clone(),
+            usage,
+            ..Default::default()
+        };
+        if message
+            .content
+            .iter()
+            .all(|content| matches!(content, MessageContent::Text(_)))
+        {
+            partial.text_delta = message.as_concat_text();
+        } else {
+            partial.content = message.content;
+        }
+        partial
+    }
+
+    /// This piece as an assistant message, None when it holds no content. Pieces keep the id of
+    /// their response, so `push_message` adds each to the message before it.
+    pub fn into_message(self) -> Option<Message> {
+        let mut message = Message::assistant();
+        self.append_to(&mut message);
+        (!message.content.is_empty()).then_some(message)
+    }
+
+    /// Add this piece to the message assembled from the pieces before it
+    pub fn append_to(self, message: &mut Message) {
+        if message.id.is_none() {
+            message.id = self.id;
+        }
+        if !self.text_delta.is_empty() {
+            match message.content.last_mut() {
+                Some(MessageContent::Text(text)) => text.text.push_str(&self.text_delta),
+                _ => message.content.push(MessageContent::text(self.text_delta)),
+            }
+        }
+        message.content.extend(self.content);
+    }
+}
+
+pub type PartialMessageStream =
+    Pin<Box<dyn Stream<Item = Result<PartialMessage, ProviderError>> + Send>>;
+
 pub fn stream_from_single_message(message: Message, usage: ProviderUsage) -> MessageStream {
     let stream = futures::stream::once(async move { Ok((Some(message), Some(usage))) });
     Box::pin(stream)
@@ -481,4 +600,97 @@ mod tests {
         assert_eq!(info.output_token_cost, Some(0.00001));
         assert_eq!(info.currency, Some("$".to_string()));
     }
+
+    struct StreamingProvider;
+
+    #[async_trait]
+    impl Provider for StreamingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("streaming-model".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            unreachable!("the provider streams")
+        }
+
+        async fn stream(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<MessageStream, ProviderError> {
+            let delta = |content: MessageContent| {
+                let mut message = Message::assistant().with_content(content);
+                message.id = Some("msg_1".to_string());
+                Some(message)
+            };
+            let usage = ProviderUsage::new("streaming-model".to_string(), Usage::default());
+            Ok(Box::pin(futures::stream::iter(vec![
+                Ok((delta(MessageContent::text("Let me ")), None)),
+                Ok((delta(MessageContent::text("check.")), None)),
+                Ok((
+                    delta(MessageContent::tool_request(
+                        "call_1",
+                        Ok(mcp_core::tool::ToolCall::new("developer__shell", json!({}))),
+                    )),
+                    None,
+                )),
+                Ok((None, Some(usage))),
+            ])))
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_complete() {
+        let parts: Vec<PartialMessage> = StreamingProvider
+            .streaming_complete("system", &[], &[])
+            .await
+            .unwrap()
+            .map(|part| part.unwrap())
+            .collect()
+            .await;
+
+        let deltas: Vec<&str> = parts.iter().map(|p| p.text_delta.as_str()).collect();
+        assert_eq!(deltas, vec!["Let me ", "check.", "", ""]);
+        assert!(parts[3].usage.is_some());
+
+        let mut message = Message::assistant();
+        for part in parts {
+            part.append_to(&mut message);
+        }
+        assert_eq!(message.id.as_deref(), Some("msg_1"));
+        assert_eq!(message.content.len(), 2);
+        assert_eq!(message.as_concat_text(), "Let me check.");
+        assert!(message.content[1].as_tool_request().is_some());
+    }
+
+    #[test]
+    fn test_partial_message_keeps_whole_responses() {
+        let response = Message::assistant()
+            .with_thinking("The user wants a listing", "signature")
+            .with_text("Let me check.")
+            .with_tool_request(
+                "call_1",
+                Ok(mcp_core::tool::ToolCall::new("developer__shell", json!({}))),
+            );
+
+        let partial = PartialMessage::from_message(response.clone(), None);
+        assert!(partial.text_delta.is_empty());
+        assert_eq!(partial.into_message().unwrap().content, response.content);
+
+        assert!(PartialMessage::default().into_message().is_none());
+    }
 }
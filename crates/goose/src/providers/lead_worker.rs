@@ -4,7 +4,9 @@ use std::ops::Deref;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use super::base::{LeadWorkerProviderTrait, Provider, ProviderMetadata, ProviderUsage};
+use super::base::{
+    AvailableModel, LeadWorkerProviderTrait, Provider, ProviderMetadata, ProviderUsage,
+};
 use super::errors::ProviderError;
 use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
@@ -409,6 +411,16 @@ impl Provider for LeadWorkerProvider {
         final_result
     }
 
+    async fn list_models(&self) -> Result<Vec<AvailableModel>, ProviderError> {
+        let mut models = self.lead_provider.list_models().await?;
+        for model in self.worker_provider.list_models().await? {
+            if !models.iter().any(|m| m.id == model.id) {
+                models.push(model);
+            }
+        }
+        Ok(models)
+    }
+
     async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
         // Combine models from both providers
         let lead_models = self.lead_provider.fetch_supported_models_async().await?;
@@ -445,6 +457,10 @@ impl Provider for LeadWorkerProvider {
         }
     }
 
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f64>, ProviderError> {
+        self.lead_provider.rerank(query, documents).await
+    }
+
     /// Check if this provider is a LeadWorkerProvider
     fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
         Some(self)
@@ -26,6 +26,7 @@ pub struct LiteLLMProvider {
     api_key: String,
     model: ModelConfig,
     custom_headers: Option<HashMap<String, String>>,
+    rerank_model: Option<String>,
 }
 
 impl Default for LiteLLMProvider {
@@ -52,6 +53,7 @@ impl LiteLLMProvider {
             .or_else(|_| config.get_param("LITELLM_CUSTOM_HEADERS"))
             .ok()
             .map(parse_custom_headers);
+        let rerank_model: Option<String> = config.get_param("LITELLM_RERANK_MODEL").ok();
         let timeout_secs: u64 = config.get_param("LITELLM_TIMEOUT").unwrap_or(600);
         let client = Client::builder()
             .timeout(Duration::from_secs(timeout_secs))
@@ -64,6 +66,7 @@ impl LiteLLMProvider {
             api_key,
             model,
             custom_headers,
+            rerank_model,
         })
     }
 
@@ -169,6 +172,7 @@ impl Provider for LiteLLMProvider {
                 ),
                 ConfigKey::new("LITELLM_CUSTOM_HEADERS", false, true, None),
                 ConfigKey::new("LITELLM_TIMEOUT", false, false, Some("600")),
+                ConfigKey::new("LITELLM_RERANK_MODEL", false, false, None),
             ],
         )
     }
@@ -221,6 +225,38 @@ impl Provider for LiteLLMProvider {
         self.model.model_name.to_lowercase().contains("claude")
     }
 
+    /// Uses the proxy's `/rerank` endpoint when a rerank model is configured
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f64>, ProviderError> {
+        let Some(rerank_model) = &self.rerank_model else {
+            return Ok(super::rerank::tfidf_scores(query, documents));
+        };
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let base_url = Url::parse(&self.host)
+            .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
+        let url = base_url.join("v1/rerank").map_err(|e| {
+            ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
+        })?;
+        let payload = json!({
+            "model": rerank_model,
+            "query": query,
+            "documents": documents,
+            "top_n": documents.len(),
+        });
+
+        let request = self
+            .client
+            .post(url)
+            .header("Authorization", format!("Bearer {}", self.api_key));
+        let request = self.add_headers(request);
+        let response = request.json(&payload).send().await?;
+        let response = handle_response_openai_compat(response).await?;
+
+        super::rerank::scores_from_response(&response, documents.len())
+    }
+
     async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
         match self.fetch_models().await {
             Ok(models) => {
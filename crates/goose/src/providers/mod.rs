@@ -22,6 +22,7 @@ pub mod ollama;
 pub mod openai;
 pub mod openrouter;
 pub mod pricing;
+pub mod rerank;
 pub mod sagemaker_tgi;
 pub mod snowflake;
 pub mod testprovider;
@@ -4,7 +4,7 @@ use reqwest::Client;
 use serde_json::{json, Value};
 use std::time::Duration;
 
-use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
+use super::base::{AvailableModel, ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::errors::ProviderError;
 use super::utils::{
     emit_debug_trace, get_model, handle_response_google_compat, handle_response_openai_compat,
@@ -123,11 +123,36 @@ impl OpenRouterProvider {
         // No error detected, return the response body
         Ok(response_body)
     }
+
+    fn models_url(&self) -> Result<Url, ProviderError> {
+        let base_url = Url::parse(&self.host)
+            .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
+        base_url.join("api/v1/models").map_err(|e| {
+            ProviderError::RequestFailed(format!("Failed to construct models URL: {e}"))
+        })
+    }
 }
 
 /// Update the request when using anthropic model.
 /// For anthropic model, we can enable prompt caching to save cost. Since openrouter is the OpenAI compatible
 /// endpoint, we need to modify the open ai request to have anthropic cache control field.
+/// An entry of the models API, which lists the request parameters and input modalities each
+/// model accepts
+fn parse_model(model: &Value) -> Option<AvailableModel> {
+    let id = model.get("id").and_then(|v| v.as_str())?;
+    let contains = |list: &Value, item: &str| {
+        list.as_array()
+            .is_some_and(|values| values.iter().any(|v| v.as_str() == Some(item)))
+    };
+
+    Some(AvailableModel {
+        id: id.to_string(),
+        context_window: model.get("context_length").and_then(|v| v.as_u64()),
+        supports_tools: contains(&model["supported_parameters"], "tools"),
+        supports_vision: contains(&model["architecture"]["input_modalities"], "image"),
+    })
+}
+
 fn update_request_for_anthropic(original_payload: &Value) -> Value {
     let mut payload = original_payload.clone();
 
@@ -273,12 +298,39 @@ impl Provider for OpenRouterProvider {
     }
 
     /// Fetch supported models from OpenRouter API (only models with tool support)
-    async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
-        let base_url = Url::parse(&self.host)
-            .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
-        let url = base_url.join("api/v1/models").map_err(|e| {
-            ProviderError::RequestFailed(format!("Failed to construct models URL: {e}"))
+    async fn list_models(&self) -> Result<Vec<AvailableModel>, ProviderError> {
+        let json: Value = self
+            .client
+            .get(self.models_url()?)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("HTTP-Referer", "https://block.github.io/goose")
+            .header("X-Title", "Goose")
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(err_obj) = json.get("error") {
+            let msg = err_obj
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error");
+            return Err(ProviderError::RequestFailed(format!(
+                "OpenRouter API returned an error: {}",
+                msg
+            )));
+        }
+
+        let data = json.get("data").and_then(|v| v.as_array()).ok_or_else(|| {
+            ProviderError::UsageError("Missing data field in JSON response".into())
         })?;
+        let mut models: Vec<AvailableModel> = data.iter().filter_map(parse_model).collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(models)
+    }
+
+    async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        let url = self.models_url()?;
 
         // Handle request failures gracefully
         // If the request fails, fall back to manual entry
@@ -369,3 +421,35 @@ impl Provider for OpenRouterProvider {
             .starts_with(OPENROUTER_MODEL_PREFIX_ANTHROPIC)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_model() {
+        let model = parse_model(&json!({
+            "id": "openai/gpt-4o",
+            "context_length": 128000,
+            "architecture": { "input_modalities": ["text", "image"] },
+            "supported_parameters": ["tools", "temperature"]
+        }))
+        .unwrap();
+        assert_eq!(
+            model,
+            AvailableModel {
+                id: "openai/gpt-4o".to_string(),
+                context_window: Some(128000),
+                supports_tools: true,
+                supports_vision: true,
+            }
+        );
+
+        let model = parse_model(&json!({ "id": "some/text-model" })).unwrap();
+        assert_eq!(model.context_window, None);
+        assert!(!model.supports_tools);
+        assert!(!model.supports_vision);
+
+        assert!(parse_model(&json!({ "name": "missing id" })).is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+
+use super::errors::ProviderError;
+
+fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Relevance of each document to `query` by TF-IDF, for providers without a cross-encoder.
+/// Each query term counts by how often it appears in the document, relative to the document's
+/// length, weighted up when few documents contain it.
+pub fn tfidf_scores(query: &str, documents: &[String]) -> Vec<f64> {
+    let query_terms: HashSet<String> = terms(query).into_iter().collect();
+    let documents: Vec<Vec<String>> = documents.iter().map(|d| terms(d)).collect();
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in &documents {
+        let unique: HashSet<&str> = document.iter().map(String::as_str).collect();
+        for term in unique {
+            if query_terms.contains(term) {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+    }
+
+    let count = documents.len() as f64;
+    documents
+        .iter()
+        .map(|document| {
+            if document.is_empty() {
+                return 0.0;
+            }
+            query_terms
+                .iter()
+                .map(|term| {
+                    let frequency = document.iter().filter(|t| *t == term).count();
+                    if frequency == 0 {
+                        return 0.0;
+                    }
+                    let df = document_frequency.get(term.as_str()).copied().unwrap_or(0) as f64;
+                    let idf = ((count + 1.0) / (df + 1.0)).ln() + 1.0;
+                    frequency as f64 / document.len() as f64 * idf
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Scores out of a Cohere style rerank response, `{"results": [{"index", "relevance_score"}]}`,
+/// in the order of the documents. Documents the response leaves out score 0.
+pub fn scores_from_response(response: &Value, count: usize) -> Result<Vec<f64>, ProviderError> {
+    let results = response["results"].as_array().ok_or_else(|| {
+        ProviderError::RequestFailed("Rerank response is missing results".to_string())
+    })?;
+    let mut scores = vec![0.0; count];
+    for result in results {
+        let index = result["index"].as_u64().map(|i| i as usize);
+        let score = result["relevance_score"].as_f64();
+        match (index, score) {
+            (Some(index), Some(score)) if index < count => scores[index] = score,
+            _ => {
+                return Err(ProviderError::RequestFailed(format!(
+                    "Unexpected rerank result: {}",
+                    result
+                )))
+            }
+        }
+    }
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tfidf_scores() {
+        let documents = vec![
+            "The cat sat on the mat".to_string(),
+            "Rust ownership and borrowing, the borrow checker".to_string(),
+            "Borrowing rules in Rust".to_string(),
+            String::new(),
+        ];
+        let scores = tfidf_scores("rust borrowing", &documents);
+
+        assert_eq!(scores.len(), 4);
+        assert_eq!(scores[0], 0.0);
+        assert_eq!(scores[3], 0.0);
+        // Both terms in a shorter document
+        assert!(scores[2] > scores[1]);
+        assert!(scores[1] > 0.0);
+
+        assert!(tfidf_scores("anything", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_scores_from_response() {
+        let response = json!({"results": [
+            {"index": 2, "relevance_score": 0.9},
+            {"index": 0, "relevance_score": 0.1}
+        ]});
+        assert_eq!(
+            scores_from_response(&response, 3).unwrap(),
+            vec![0.1, 0.0, 0.9]
+        );
+
+        let out_of_range = json!({"results": [{"index": 3, "relevance_score": 0.5}]});
+        assert!(scores_from_response(&out_of_range, 3).is_err());
+        assert!(scores_from_response(&json!({}), 3).is_err());
+    }
+}
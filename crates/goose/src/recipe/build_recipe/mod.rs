@@ -1,10 +1,13 @@
 use crate::recipe::read_recipe_file_content::RecipeFile;
 use crate::recipe::template_recipe::{parse_recipe_content, render_recipe_content_with_params};
 use crate::recipe::{
-    Recipe, RecipeParameter, RecipeParameterRequirement, BUILT_IN_RECIPE_DIR_PARAM,
+    Recipe, RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement,
+    BUILT_IN_DEPENDENCY_SESSION_IDS_PARAM, BUILT_IN_RECIPE_DIR_PARAM,
 };
 use anyhow::Result;
+use minijinja::Value;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 #[derive(Debug, thiserror::Error)]
 pub enum RecipeError {
@@ -22,7 +25,7 @@ pub fn render_recipe_template<F>(
     user_prompt_fn: Option<F>,
 ) -> Result<(String, Vec<String>)>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
     let RecipeFile {
         content: recipe_file_content,
@@ -32,8 +35,11 @@ where
     let recipe_dir_str = recipe_parent_dir
         .to_str()
         .ok_or_else(|| anyhow::anyhow!("Error getting recipe directory"))?;
-    let recipe_parameters = validate_recipe_parameters(&recipe_file_content, recipe_dir_str)?;
+    let (recipe_parameters, file_variables) =
+        parse_recipe_parameters(&recipe_file_content, recipe_dir_str)?;
 
+    // Values from the variable files come first so the ones the user passed override them
+    let params: Vec<(String, String)> = file_variables.into_iter().chain(params).collect();
     let (params_for_template, missing_params) =
         apply_values_to_parameters(&params, recipe_parameters, recipe_dir_str, user_prompt_fn)?;
 
@@ -50,12 +56,68 @@ pub fn validate_recipe_parameters(
     recipe_file_content: &str,
     recipe_dir_str: &str,
 ) -> Result<Option<Vec<RecipeParameter>>> {
-    let (raw_recipe, template_variables) =
+    let (recipe_parameters, _) = parse_recipe_parameters(recipe_file_content, recipe_dir_str)?;
+    Ok(recipe_parameters)
+}
+
+/// A recipe's parameters and the values read from its variable files
+type ParsedParameters = (Option<Vec<RecipeParameter>>, Vec<(String, String)>);
+
+/// The recipe's parameters, checked against the variables in its template, and the values read
+/// from its variable files. Variables with a value in those files need no parameter.
+fn parse_recipe_parameters(
+    recipe_file_content: &str,
+    recipe_dir_str: &str,
+) -> Result<ParsedParameters> {
+    let (raw_recipe, mut template_variables) =
         parse_recipe_content(recipe_file_content, recipe_dir_str.to_string())?;
+    let file_variables = load_variable_files(&raw_recipe, recipe_dir_str)?;
+    template_variables.retain(|name| !file_variables.iter().any(|(key, _)| key == name));
     let recipe_parameters = raw_recipe.parameters;
     validate_optional_parameters(&recipe_parameters)?;
     validate_parameters_in_template(&recipe_parameters, &template_variables)?;
-    Ok(recipe_parameters)
+    Ok((recipe_parameters, file_variables))
+}
+
+/// Read the template variables from the recipe's `variable_files`, paths relative to the recipe
+/// directory. Each file holds a mapping of names to values, in YAML or JSON, and files later in
+/// the list override earlier ones. Lists become comma separated text, as on the command line.
+pub fn load_variable_files(recipe: &Recipe, recipe_dir_str: &str) -> Result<Vec<(String, String)>> {
+    let mut variables: Vec<(String, String)> = Vec::new();
+    for file in recipe.variable_files.iter().flatten() {
+        let path = Path::new(recipe_dir_str).join(file);
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            anyhow::anyhow!("Failed to read variable file '{}': {}", path.display(), e)
+        })?;
+        let parsed: serde_json::Value = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)?
+        } else {
+            serde_yaml::from_str(&content)?
+        };
+        let serde_json::Value::Object(mapping) = parsed else {
+            return Err(anyhow::anyhow!(
+                "Variable file '{}' must hold a mapping of names to values",
+                path.display()
+            ));
+        };
+        for (key, value) in mapping {
+            let value = match value {
+                serde_json::Value::String(text) => text,
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .map(|item| match item {
+                        serde_json::Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+                other => other.to_string(),
+            };
+            variables.retain(|(existing, _)| *existing != key);
+            variables.push((key, value));
+        }
+    }
+    Ok(variables)
 }
 
 pub fn build_recipe_from_template<F>(
@@ -64,7 +126,7 @@ pub fn build_recipe_from_template<F>(
     user_prompt_fn: Option<F>,
 ) -> Result<Recipe, RecipeError>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
     let (rendered_content, missing_params) =
         render_recipe_template(recipe_file, params.clone(), user_prompt_fn)
@@ -87,6 +149,7 @@ fn validate_parameters_in_template(
 ) -> Result<()> {
     let mut template_variables = template_variables.clone();
     template_variables.remove(BUILT_IN_RECIPE_DIR_PARAM);
+    template_variables.remove(BUILT_IN_DEPENDENCY_SESSION_IDS_PARAM);
 
     let param_keys: HashSet<String> = recipe_parameters
         .as_ref()
@@ -151,14 +214,17 @@ fn validate_optional_parameters(parameters: &Option<Vec<RecipeParameter>>) -> Re
     }
 }
 
+/// Collect the values for the recipe's parameters from `user_params`, defaults and the user
+/// prompt, as they are passed to the template. Array parameters are given as comma separated
+/// text and become lists.
 pub fn apply_values_to_parameters<F>(
     user_params: &[(String, String)],
     recipe_parameters: Option<Vec<RecipeParameter>>,
     recipe_parent_dir: &str,
     user_prompt_fn: Option<F>,
-) -> Result<(HashMap<String, String>, Vec<String>)>
+) -> Result<(HashMap<String, Value>, Vec<String>)>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
     let mut param_map: HashMap<String, String> = user_params.iter().cloned().collect();
     param_map.insert(
@@ -166,13 +232,13 @@ where
         recipe_parent_dir.to_string(),
     );
     let mut missing_params: Vec<String> = Vec::new();
-    for param in recipe_parameters.unwrap_or_default() {
+    let recipe_parameters = recipe_parameters.unwrap_or_default();
+    for param in &recipe_parameters {
         if !param_map.contains_key(&param.key) {
             match (&param.default, &param.requirement) {
                 (Some(default), _) => param_map.insert(param.key.clone(), default.clone()),
                 (None, RecipeParameterRequirement::UserPrompt) if user_prompt_fn.is_some() => {
-                    let input_value =
-                        user_prompt_fn.as_ref().unwrap()(&param.key, &param.description)?;
+                    let input_value = user_prompt_fn.as_ref().unwrap()(param)?;
                     param_map.insert(param.key.clone(), input_value)
                 }
                 _ => {
@@ -182,7 +248,33 @@ where
             };
         }
     }
-    Ok((param_map, missing_params))
+
+    let values = param_map
+        .into_iter()
+        .map(|(key, value)| {
+            let is_array = recipe_parameters.iter().any(|param| {
+                param.key == key
+                    && matches!(param.input_type, RecipeParameterInputType::Array { .. })
+            });
+            let value = if is_array {
+                Value::from(split_list(&value))
+            } else {
+                Value::from(value)
+            };
+            (key, value)
+        })
+        .collect();
+    Ok((values, missing_params))
+}
+
+/// The items of a comma separated list, blank items are left out
+pub fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 #[cfg(test)]
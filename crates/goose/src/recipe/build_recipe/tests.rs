@@ -2,10 +2,12 @@
 mod tests {
     use crate::recipe::build_recipe::{build_recipe_from_template, RecipeError};
     use crate::recipe::read_recipe_file_content::RecipeFile;
-    use crate::recipe::{RecipeParameterInputType, RecipeParameterRequirement};
+    use crate::recipe::{RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement};
     use tempfile::TempDir;
 
-    const NO_USER_PROMPT: Option<fn(&str, &str) -> Result<String, anyhow::Error>> = None;
+    type UserPrompt = fn(&RecipeParameter) -> Result<String, anyhow::Error>;
+
+    const NO_USER_PROMPT: Option<UserPrompt> = None;
 
     fn setup_recipe_file(instructions_and_parameters: &str) -> (TempDir, RecipeFile) {
         let recipe_content = format!(
@@ -92,6 +94,40 @@ mod tests {
         assert_eq!(param.description, "A test parameter");
     }
 
+    #[test]
+    fn test_build_recipe_from_template_array_parameter() {
+        let instructions_and_parameters = r#"
+                "instructions": "Review{% for file in files %} {{ file }};{% endfor %}",
+                "parameters": [
+                    {
+                        "key": "files",
+                        "input_type": { "array": { "item_type": "file" } },
+                        "requirement": "user_prompt",
+                        "description": "Files to review"
+                    }
+                ]"#;
+
+        let (_temp_dir, recipe_file) = setup_recipe_file(instructions_and_parameters);
+
+        let params = vec![("files".to_string(), "src/main.rs, ,README.md".to_string())];
+        let recipe = build_recipe_from_template(recipe_file, params, NO_USER_PROMPT).unwrap();
+        assert_eq!(
+            recipe.instructions.unwrap(),
+            "Review src/main.rs; README.md;"
+        );
+        let param = &recipe.parameters.as_ref().unwrap()[0];
+        assert_eq!(param.input_type.to_string(), "array of file");
+
+        // The user prompt answers with comma separated text too
+        let (_temp_dir, recipe_file) = setup_recipe_file(instructions_and_parameters);
+        let prompt = |param: &RecipeParameter| -> Result<String, anyhow::Error> {
+            assert_eq!(param.key, "files");
+            Ok("Cargo.toml".to_string())
+        };
+        let recipe = build_recipe_from_template(recipe_file, Vec::new(), Some(prompt)).unwrap();
+        assert_eq!(recipe.instructions.unwrap(), "Review Cargo.toml;");
+    }
+
     #[test]
     fn test_build_recipe_from_template_success_variable_in_prompt() {
         let instructions_and_parameters = r#"
@@ -278,6 +314,40 @@ mod tests {
         assert!(recipe.parameters.is_none());
     }
 
+    #[test]
+    fn test_build_recipe_from_template_variable_files() {
+        let instructions_and_parameters = r#"
+                "instructions": "Deploy {{ service }} to {{ region }} as {{ owner }}",
+                "variable_files": ["defaults.yaml", "overrides.json"],
+                "parameters": [
+                    {
+                        "key": "owner",
+                        "input_type": "string",
+                        "requirement": "required",
+                        "description": "Who runs the deploy"
+                    }
+                ]"#;
+        let (temp_dir, recipe_file) = setup_recipe_file(instructions_and_parameters);
+        std::fs::write(
+            temp_dir.path().join("defaults.yaml"),
+            "service: api\nregion: us-east-1\nowner: ops\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("overrides.json"),
+            r#"{"region": "eu-west-1"}"#,
+        )
+        .unwrap();
+
+        // Later files override earlier ones and the user's values override both
+        let params = vec![("owner".to_string(), "alice".to_string())];
+        let recipe = build_recipe_from_template(recipe_file, params, NO_USER_PROMPT).unwrap();
+        assert_eq!(
+            recipe.instructions.unwrap(),
+            "Deploy api to eu-west-1 as alice"
+        );
+    }
+
     #[test]
     fn test_template_inheritance() {
         let parent_content = r#"
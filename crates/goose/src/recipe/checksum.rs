@@ -0,0 +1,157 @@
+use anyhow::{anyhow, bail, Result};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use super::Recipe;
+
+pub const SHA256_ALGORITHM: &str = "sha256";
+pub const ED25519_ALGORITHM: &str = "ed25519";
+
+/// Integrity check for a recipe, covering everything in it except the checksum itself.
+///
+/// The recipe is hashed as parsed rather than as text, so reformatting it or converting it
+/// between YAML and JSON keeps the checksum valid. Templated recipes are checked after their
+/// parameters are filled in, so only recipes without parameters can carry a checksum.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct RecipeChecksum {
+    /// `sha256`, or `ed25519` for a signature
+    pub algorithm: String,
+    /// Hex digest for `sha256`. For `ed25519` the base64 public key and signature, separated
+    /// by a `:`
+    pub value: String,
+}
+
+impl RecipeChecksum {
+    pub fn sha256(recipe: &Recipe) -> Result<Self> {
+        let digest = Sha256::digest(checksummed_bytes(recipe)?);
+        Ok(Self {
+            algorithm: SHA256_ALGORITHM.to_string(),
+            value: format!("{:x}", digest),
+        })
+    }
+
+    /// Sign the recipe with a 32 byte ed25519 private key
+    pub fn ed25519(recipe: &Recipe, private_key: &[u8]) -> Result<Self> {
+        let secret: &[u8; 32] = private_key.try_into().map_err(|_| {
+            anyhow!(
+                "An ed25519 private key is 32 bytes, got {}",
+                private_key.len()
+            )
+        })?;
+        let signing_key = SigningKey::from_bytes(secret);
+        let signature = signing_key.sign(&checksummed_bytes(recipe)?);
+        Ok(Self {
+            algorithm: ED25519_ALGORITHM.to_string(),
+            value: format!(
+                "{}:{}",
+                BASE64_STANDARD.encode(signing_key.verifying_key().as_bytes()),
+                BASE64_STANDARD.encode(signature.to_bytes())
+            ),
+        })
+    }
+
+    /// The key an `ed25519` checksum was signed with. A valid signature only shows the recipe
+    /// is unchanged since it was signed, callers decide whether they trust the signer.
+    pub fn public_key(&self) -> Option<Vec<u8>> {
+        if self.algorithm != ED25519_ALGORITHM {
+            return None;
+        }
+        let (public_key, _) = self.value.split_once(':')?;
+        BASE64_STANDARD.decode(public_key).ok()
+    }
+
+    pub fn verify(&self, recipe: &Recipe) -> Result<()> {
+        match self.algorithm.as_str() {
+            SHA256_ALGORITHM => {
+                let expected = Self::sha256(recipe)?;
+                if !expected.value.eq_ignore_ascii_case(&self.value) {
+                    bail!("Recipe checksum does not match, the recipe has been modified");
+                }
+                Ok(())
+            }
+            ED25519_ALGORITHM => {
+                let (public_key, signature) = self.value.split_once(':').ok_or_else(|| {
+                    anyhow!("Malformed ed25519 checksum, expected <public key>:<signature>")
+                })?;
+                let public_key: [u8; 32] = BASE64_STANDARD
+                    .decode(public_key)?
+                    .try_into()
+                    .map_err(|_| anyhow!("An ed25519 public key is 32 bytes"))?;
+                let verifying_key = VerifyingKey::from_bytes(&public_key)?;
+                let signature = Signature::from_slice(&BASE64_STANDARD.decode(signature)?)?;
+                verifying_key
+                    .verify(&checksummed_bytes(recipe)?, &signature)
+                    .map_err(|_| {
+                        anyhow!("Recipe signature is not valid, the recipe has been modified")
+                    })
+            }
+            other => bail!("Unsupported recipe checksum algorithm: {}", other),
+        }
+    }
+}
+
+/// The recipe as JSON with sorted keys and without its checksum
+fn checksummed_bytes(recipe: &Recipe) -> Result<Vec<u8>> {
+    let mut value = serde_json::to_value(recipe)?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove("checksum");
+    }
+    Ok(serde_json::to_vec(&value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe() -> Recipe {
+        Recipe::builder()
+            .title("Test")
+            .description("A test recipe")
+            .instructions("Do the thing")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sha256_checksum() {
+        let mut recipe = recipe();
+        let checksum = RecipeChecksum::sha256(&recipe).unwrap();
+        recipe.checksum = Some(checksum.clone());
+        assert!(checksum.verify(&recipe).is_ok());
+
+        let yaml = serde_yaml::to_string(&recipe).unwrap();
+        assert!(Recipe::from_content(&yaml).is_ok());
+
+        let tampered = yaml.replace("Do the thing", "Do something else");
+        let err = Recipe::from_content(&tampered).unwrap_err();
+        assert!(err.to_string().contains("does not match"));
+    }
+
+    #[test]
+    fn test_ed25519_signature() {
+        let private_key = [7u8; 32];
+        let mut recipe = recipe();
+        let checksum = recipe.sign(&private_key).unwrap();
+        assert_eq!(
+            checksum.public_key().unwrap(),
+            SigningKey::from_bytes(&private_key)
+                .verifying_key()
+                .as_bytes()
+                .to_vec()
+        );
+        recipe.checksum = Some(checksum);
+
+        let json = serde_json::to_string(&recipe).unwrap();
+        assert!(Recipe::from_content(&json).is_ok());
+
+        let tampered = json.replace("Do the thing", "Do something else");
+        let err = Recipe::from_content(&tampered).unwrap_err();
+        assert!(err.to_string().contains("signature is not valid"));
+
+        assert!(recipe.sign(&[0u8; 16]).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use minijinja::UndefinedBehavior;
+use serde::Serialize;
+
+use super::Recipe;
+use crate::config::Config;
+use crate::providers::base::Provider;
+use crate::providers::pricing::get_model_pricing;
+use crate::token_counter::AsyncTokenCounter;
+
+/// Output allowance used when the model config does not set `max_tokens`
+const DEFAULT_OUTPUT_TOKENS: u64 = 4096;
+
+/// Rough token and cost range for a single run of a recipe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostEstimate {
+    /// Tokens in the rendered instructions, prompt and context
+    pub min_tokens: u64,
+    /// `min_tokens` plus the model's full output allowance
+    pub max_tokens: u64,
+    /// Cost of `max_tokens` at the model's cached per-token price
+    pub estimated_usd_cents: f64,
+}
+
+impl Recipe {
+    /// The text sent to the model before any tool calls, with `params` substituted
+    fn rendered_prompt_text(&self, params: &HashMap<String, String>) -> Result<String> {
+        let mut env = minijinja::Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Lenient);
+
+        let sections = self
+            .instructions
+            .iter()
+            .chain(self.prompt.iter())
+            .chain(self.context.iter().flatten());
+
+        let mut rendered = Vec::new();
+        for section in sections {
+            rendered.push(
+                env.render_str(section, params)
+                    .map_err(|e| anyhow!("Failed to render the recipe {}", e))?,
+            );
+        }
+        Ok(rendered.join("\n"))
+    }
+
+    /// Estimate what one run of this recipe costs on `provider`'s current model.
+    ///
+    /// Prices come from the pricing cache, so `initialize_pricing_cache` should have run first.
+    pub async fn estimate_cost(
+        &self,
+        provider: &dyn Provider,
+        params: &HashMap<String, String>,
+    ) -> Result<CostEstimate> {
+        let text = self.rendered_prompt_text(params)?;
+        let counter = AsyncTokenCounter::new().await.map_err(|e| anyhow!(e))?;
+        let min_tokens = counter.count_tokens(&text) as u64;
+
+        let model_config = provider.get_model_config();
+        let output_tokens = model_config
+            .max_tokens
+            .map(|t| t.max(0) as u64)
+            .unwrap_or(DEFAULT_OUTPUT_TOKENS);
+
+        let provider_name = match self
+            .settings
+            .as_ref()
+            .and_then(|s| s.goose_provider.clone())
+        {
+            Some(name) => name,
+            None => Config::global()
+                .get_param::<String>("GOOSE_PROVIDER")
+                .map_err(|_| anyhow!("No provider configured to look up pricing for"))?,
+        };
+
+        let pricing = get_model_pricing(&provider_name, &model_config.model_name)
+            .await
+            .ok_or_else(|| {
+                anyhow!(
+                    "No pricing data available for {}/{}",
+                    provider_name,
+                    model_config.model_name
+                )
+            })?;
+
+        let usd =
+            pricing.input_cost * min_tokens as f64 + pricing.output_cost * output_tokens as f64;
+
+        Ok(CostEstimate {
+            min_tokens,
+            max_tokens: min_tokens + output_tokens,
+            estimated_usd_cents: usd * 100.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rendered_prompt_text() {
+        let recipe = Recipe::builder()
+            .title("Review")
+            .description("Review a pull request")
+            .instructions("Review PR {{ pr_number }} carefully.")
+            .prompt("Start with {{ focus }}.")
+            .context(vec!["Repository: {{ repo }}".to_string()])
+            .build()
+            .unwrap();
+
+        let params = HashMap::from([
+            ("pr_number".to_string(), "42".to_string()),
+            ("repo".to_string(), "goose".to_string()),
+        ]);
+
+        // Missing parameters render as empty rather than failing the estimate
+        assert_eq!(
+            recipe.rendered_prompt_text(&params).unwrap(),
+            "Review PR 42 carefully.\nStart with .\nRepository: goose"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::recipe::read_recipe_file_content::read_recipe_file;
+use crate::recipe::template_recipe::parse_recipe_content;
+
+const RECIPE_EXTENSIONS: [&str; 3] = ["yaml", "yml", "json"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum DependencyError {
+    #[error("Recipe dependencies form a cycle: {}", cycle.join(" -> "))]
+    DependencyCycle { cycle: Vec<String> },
+    #[error("Dependency '{name}' of {recipe} was not found")]
+    NotFound { name: String, recipe: String },
+    #[error("Failed to load recipe {recipe}: {source}")]
+    Load {
+        recipe: String,
+        source: anyhow::Error,
+    },
+}
+
+/// A recipe file and the recipe files it directly depends on
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyNode {
+    pub path: PathBuf,
+    pub dependencies: Vec<PathBuf>,
+}
+
+/// Every recipe that `root` transitively depends on, in an order where each recipe comes after
+/// all of its dependencies, followed by `root` itself. A recipe shared by several others is
+/// only listed once.
+pub fn execution_order(root: &Path) -> Result<Vec<DependencyNode>, DependencyError> {
+    let root = root.canonicalize().map_err(|e| DependencyError::Load {
+        recipe: root.display().to_string(),
+        source: e.into(),
+    })?;
+
+    let mut order = Vec::new();
+    visit(root, &mut Vec::new(), &mut HashSet::new(), &mut order)?;
+    Ok(order)
+}
+
+fn visit(
+    path: PathBuf,
+    stack: &mut Vec<PathBuf>,
+    done: &mut HashSet<PathBuf>,
+    order: &mut Vec<DependencyNode>,
+) -> Result<(), DependencyError> {
+    if done.contains(&path) {
+        return Ok(());
+    }
+    if let Some(start) = stack.iter().position(|p| p == &path) {
+        let cycle = stack[start..]
+            .iter()
+            .chain(std::iter::once(&path))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(DependencyError::DependencyCycle { cycle });
+    }
+
+    let load_error = |source| DependencyError::Load {
+        recipe: path.display().to_string(),
+        source,
+    };
+    let recipe_file = read_recipe_file(&path).map_err(load_error)?;
+    let recipe_dir = recipe_file.parent_dir.to_string_lossy().into_owned();
+    let (recipe, _) = parse_recipe_content(&recipe_file.content, recipe_dir).map_err(load_error)?;
+
+    let dependencies = recipe
+        .dependencies
+        .unwrap_or_default()
+        .iter()
+        .map(|name| resolve_dependency(name, &path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    stack.push(path.clone());
+    for dependency in &dependencies {
+        visit(dependency.clone(), stack, done, order)?;
+    }
+    stack.pop();
+
+    done.insert(path.clone());
+    order.push(DependencyNode { path, dependencies });
+    Ok(())
+}
+
+/// Group named nodes into levels where every node only depends on nodes in earlier levels, so
+/// each level can run in parallel once the previous one is done. Nodes keep their input order
+/// within a level.
+pub fn dependency_levels<'a>(
+    nodes: &[(&'a str, &'a [String])],
+) -> Result<Vec<Vec<&'a str>>, DependencyError> {
+    let names: HashSet<&str> = nodes.iter().map(|(name, _)| *name).collect();
+    for (name, dependencies) in nodes {
+        if let Some(missing) = dependencies.iter().find(|d| !names.contains(d.as_str())) {
+            return Err(DependencyError::NotFound {
+                name: missing.clone(),
+                recipe: name.to_string(),
+            });
+        }
+    }
+
+    let mut levels: Vec<Vec<&str>> = Vec::new();
+    let mut done: HashSet<&str> = HashSet::new();
+    let mut remaining: Vec<&(&str, &[String])> = nodes.iter().collect();
+    while !remaining.is_empty() {
+        let (ready, blocked): (Vec<_>, Vec<_>) = remaining
+            .into_iter()
+            .partition(|(_, dependencies)| dependencies.iter().all(|d| done.contains(d.as_str())));
+        if ready.is_empty() {
+            return Err(DependencyError::DependencyCycle {
+                cycle: find_cycle(&blocked),
+            });
+        }
+        let level: Vec<&str> = ready.iter().map(|(name, _)| *name).collect();
+        done.extend(level.iter().copied());
+        levels.push(level);
+        remaining = blocked;
+    }
+    Ok(levels)
+}
+
+// Every blocked node waits on another blocked node, so following those edges has to loop
+fn find_cycle(blocked: &[&(&str, &[String])]) -> Vec<String> {
+    let blocked_on = |name: &str| {
+        blocked
+            .iter()
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, dependencies)| {
+                dependencies
+                    .iter()
+                    .find(|d| blocked.iter().any(|(n, _)| *n == d.as_str()))
+            })
+    };
+    let mut path: Vec<&str> = vec![blocked[0].0];
+    while let Some(next) = blocked_on(path[path.len() - 1]) {
+        if let Some(start) = path.iter().position(|n| *n == next.as_str()) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|n| n.to_string()).collect();
+            cycle.push(next.clone());
+            return cycle;
+        }
+        path.push(next.as_str());
+    }
+    path.iter().map(|n| n.to_string()).collect()
+}
+
+/// Dependencies are paths relative to the recipe declaring them, or names of recipe files
+/// next to it without the extension
+fn resolve_dependency(name: &str, recipe_path: &Path) -> Result<PathBuf, DependencyError> {
+    let dir = recipe_path.parent().unwrap_or(Path::new("."));
+    let candidates = std::iter::once(dir.join(name)).chain(
+        RECIPE_EXTENSIONS
+            .iter()
+            .map(|extension| dir.join(format!("{}.{}", name, extension))),
+    );
+
+    for candidate in candidates {
+        if candidate.is_file() {
+            return candidate.canonicalize().map_err(|e| DependencyError::Load {
+                recipe: candidate.display().to_string(),
+                source: e.into(),
+            });
+        }
+    }
+    Err(DependencyError::NotFound {
+        name: name.to_string(),
+        recipe: recipe_path.display().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_recipe(dir: &TempDir, name: &str, dependencies: &[&str]) -> PathBuf {
+        let path = dir.path().join(format!("{}.yaml", name));
+        let mut content = format!(
+            "title: {}\ndescription: test\ninstructions: do {}\n",
+            name, name
+        );
+        if !dependencies.is_empty() {
+            content.push_str("dependencies:\n");
+            for dependency in dependencies {
+                content.push_str(&format!("  - {}\n", dependency));
+            }
+        }
+        std::fs::write(&path, content).unwrap();
+        path.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_execution_order() {
+        let dir = TempDir::new().unwrap();
+        let setup = write_recipe(&dir, "setup", &[]);
+        let build = write_recipe(&dir, "build", &["setup"]);
+        let lint = write_recipe(&dir, "lint", &["setup.yaml"]);
+        let test = write_recipe(&dir, "test", &["build", "lint"]);
+
+        let order = execution_order(&test).unwrap();
+        let paths: Vec<&PathBuf> = order.iter().map(|node| &node.path).collect();
+        assert_eq!(paths, vec![&setup, &build, &lint, &test]);
+        assert_eq!(order[3].dependencies, vec![build, lint]);
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        let dir = TempDir::new().unwrap();
+        write_recipe(&dir, "a", &["b"]);
+        write_recipe(&dir, "b", &["c"]);
+        write_recipe(&dir, "c", &["a"]);
+        let root = write_recipe(&dir, "root", &["a"]);
+
+        let err = execution_order(&root).unwrap_err();
+        let DependencyError::DependencyCycle { cycle } = err else {
+            panic!("expected a cycle, got {:?}", err);
+        };
+        let names: Vec<&str> = cycle
+            .iter()
+            .map(|p| Path::new(p).file_stem().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_dependency_levels() {
+        let none: Vec<String> = vec![];
+        let setup = vec!["setup".to_string()];
+        let both = vec!["build".to_string(), "lint".to_string()];
+        let nodes = [
+            ("test", both.as_slice()),
+            ("build", setup.as_slice()),
+            ("setup", none.as_slice()),
+            ("lint", setup.as_slice()),
+        ];
+        let levels = dependency_levels(&nodes).unwrap();
+        assert_eq!(
+            levels,
+            vec![vec!["setup"], vec!["build", "lint"], vec!["test"]]
+        );
+
+        let a = vec!["b".to_string()];
+        let b = vec!["a".to_string()];
+        let err = dependency_levels(&[("a", a.as_slice()), ("b", b.as_slice())]).unwrap_err();
+        assert!(
+            matches!(err, DependencyError::DependencyCycle { cycle } if cycle == ["a", "b", "a"])
+        );
+
+        let err = dependency_levels(&[("a", a.as_slice())]).unwrap_err();
+        assert!(matches!(err, DependencyError::NotFound { name, .. } if name == "b"));
+    }
+
+    #[test]
+    fn test_missing_dependency() {
+        let dir = TempDir::new().unwrap();
+        let root = write_recipe(&dir, "root", &["missing"]);
+
+        let err = execution_order(&root).unwrap_err();
+        assert!(matches!(err, DependencyError::NotFound { name, .. } if name == "missing"));
+    }
+}
@@ -5,15 +5,26 @@ use std::fmt;
 
 use crate::agents::extension::ExtensionConfig;
 use crate::agents::types::RetryConfig;
+use etcetera::{choose_app_strategy, AppStrategy};
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 pub mod build_recipe;
+pub mod checksum;
+pub mod cost_estimate;
+pub mod dependencies;
+pub mod openapi;
 pub mod read_recipe_file_content;
+pub mod registry;
 pub mod template_recipe;
 
+pub use checksum::RecipeChecksum;
+pub use cost_estimate::CostEstimate;
+
 pub const BUILT_IN_RECIPE_DIR_PARAM: &str = "recipe_dir";
+/// Comma separated session ids of the recipes this one depends on, set when run with its dependencies
+pub const BUILT_IN_DEPENDENCY_SESSION_IDS_PARAM: &str = "dependency_session_ids";
 
 fn default_version() -> String {
     "1.0.0".to_string()
@@ -39,6 +50,10 @@ fn default_version() -> String {
 /// * `parameters` - Additional parameters for the Recipe
 /// * `response` - Response configuration including JSON schema validation
 /// * `retry` - Retry configuration for automated validation and recovery
+/// * `execution_mode` - How tasks from different sub-recipes are dispatched
+/// * `dependencies` - Recipes (paths or names) that have to run before this one
+/// * `audit` - Audit logging for sessions running the Recipe, overriding `GOOSE_AUDIT_LOG`
+/// * `checksum` - Hash or signature checked when the Recipe is loaded
 /// # Example
 ///
 ///
@@ -67,7 +82,14 @@ fn default_version() -> String {
 ///     parameters: None,
 ///     response: None,
 ///     sub_recipes: None,
+///     execution_mode: None,
 ///     retry: None,
+///     dependencies: None,
+///     audit: None,
+///     checksum: None,
+///     ttl_seconds: None,
+///     variable_files: None,
+///     on_complete: None,
 /// };
 ///
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -112,8 +134,29 @@ pub struct Recipe {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sub_recipes: Option<Vec<SubRecipe>>, // sub-recipes for the recipe
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_mode: Option<SubRecipeExecutionMode>, // how sub-recipe tasks are dispatched
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<String>>, // recipes to run before this one, by path or name
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<RecipeAuditConfig>, // audit logging for sessions running this recipe
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<RecipeChecksum>, // integrity check verified when the recipe is loaded
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<u64>, // how long a scheduled run's session is reused instead of running again
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable_files: Option<Vec<String>>, // YAML or JSON files with values for the template variables
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<WebhookConfig>, // webhook notified when a scheduled run finishes
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -135,6 +178,18 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+
+    /// Used as the entire system prompt in place of the extension and recipe instructions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt_override: Option<String>,
+
+    /// Overrides GOOSE_MAX_TURNS for sessions running this recipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+
+    /// Stops a scheduled run of this recipe once it has made more tool calls than this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -153,6 +208,85 @@ pub struct SubRecipe {
     pub sequential_when_repeated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Names of the sub-recipes that have to finish before this one runs in `dag` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<String>>,
+}
+
+/// How the tasks created from a recipe's sub-recipes are run
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubRecipeExecutionMode {
+    /// One task at a time
+    #[default]
+    Sequential,
+    /// All tasks at once, except for sub-recipes marked `sequential_when_repeated`
+    Parallel,
+    /// Sub-recipes run in parallel once the sub-recipes they depend on have finished
+    Dag,
+}
+
+/// A webhook the scheduler POSTs to when a run of the recipe finishes, with a JSON body holding
+/// `session_id`, `status`, `message_count` and `total_tokens`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Sent with the request, e.g. an `Authorization` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    /// Add the final text of the agent to the body as `summary`
+    #[serde(default)]
+    pub include_session_summary: bool,
+}
+
+/// Audit logging for the sessions running a recipe. It takes the place of the global
+/// `GOOSE_AUDIT_LOG` settings, so a recipe can turn auditing on or off regardless of them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, ToSchema)]
+pub struct RecipeAuditConfig {
+    pub enabled: bool,
+    /// Record the content tools return, not only their parameters and outcome
+    #[serde(default)]
+    pub include_tool_outputs: bool,
+    /// Regular expressions whose matches are masked in every recorded entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact_patterns: Vec<String>,
+}
+
+impl RecipeAuditConfig {
+    /// Environment for the builtin extensions of the session, which read their audit settings
+    /// from it. Entries go to the `GOOSE_AUDIT_LOG` file when one is configured, otherwise to
+    /// `audit.jsonl` in the goose log directory.
+    pub fn extension_envs(&self) -> HashMap<String, String> {
+        let mut envs = HashMap::new();
+        if !self.enabled {
+            envs.insert("GOOSE_AUDIT_LOG".to_string(), "off".to_string());
+            return envs;
+        }
+
+        let path = crate::config::Config::global()
+            .get_param::<String>("GOOSE_AUDIT_LOG")
+            .ok()
+            .filter(|path| !path.is_empty() && path != "off")
+            .or_else(|| {
+                let strategy = choose_app_strategy(crate::config::APP_STRATEGY.clone()).ok()?;
+                let logs = strategy
+                    .in_state_dir("logs")
+                    .unwrap_or_else(|| strategy.in_data_dir("logs"));
+                Some(logs.join("audit.jsonl").to_string_lossy().into_owned())
+            });
+        if let Some(path) = path {
+            envs.insert("GOOSE_AUDIT_LOG".to_string(), path);
+        }
+        envs.insert(
+            "GOOSE_AUDIT_INCLUDE_TOOL_OUTPUTS".to_string(),
+            self.include_tool_outputs.to_string(),
+        );
+        envs.insert(
+            "GOOSE_AUDIT_REDACT_PATTERNS".to_string(),
+            serde_json::to_string(&self.redact_patterns).unwrap_or_default(),
+        );
+        envs
+    }
 }
 
 fn deserialize_value_map_as_string<'de, D>(
@@ -207,21 +341,30 @@ pub enum RecipeParameterInputType {
     Date,
     File,
     Select,
+    /// A list of values, given as comma separated text and passed to the template as a list
+    Array {
+        item_type: Box<RecipeParameterInputType>,
+    },
 }
 
 impl fmt::Display for RecipeParameterInputType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            serde_json::to_string(self).unwrap().trim_matches('"')
-        )
+        match self {
+            RecipeParameterInputType::Array { item_type } => write!(f, "array of {}", item_type),
+            _ => write!(
+                f,
+                "{}",
+                serde_json::to_string(self).unwrap().trim_matches('"')
+            ),
+        }
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct RecipeParameter {
     pub key: String,
+    // Written as `array: { item_type: string }` in YAML rather than with a `!array` tag
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
     pub input_type: RecipeParameterInputType,
     pub requirement: RecipeParameterRequirement,
     pub description: String,
@@ -249,7 +392,14 @@ pub struct RecipeBuilder {
     parameters: Option<Vec<RecipeParameter>>,
     response: Option<Response>,
     sub_recipes: Option<Vec<SubRecipe>>,
+    execution_mode: Option<SubRecipeExecutionMode>,
     retry: Option<RetryConfig>,
+    dependencies: Option<Vec<String>>,
+    audit: Option<RecipeAuditConfig>,
+    checksum: Option<RecipeChecksum>,
+    ttl_seconds: Option<u64>,
+    variable_files: Option<Vec<String>>,
+    on_complete: Option<WebhookConfig>,
 }
 
 impl Recipe {
@@ -282,9 +432,22 @@ impl Recipe {
             parameters: None,
             response: None,
             sub_recipes: None,
+            execution_mode: None,
             retry: None,
+            dependencies: None,
+            audit: None,
+            checksum: None,
+            ttl_seconds: None,
+            variable_files: None,
+            on_complete: None,
         }
     }
+
+    /// Sign the recipe with a 32 byte ed25519 private key, see [`RecipeChecksum`]
+    pub fn sign(&self, private_key: &[u8]) -> Result<RecipeChecksum> {
+        RecipeChecksum::ed25519(self, private_key)
+    }
+
     pub fn from_content(content: &str) -> Result<Self> {
         let recipe: Recipe =
             if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(content) {
@@ -305,6 +468,20 @@ impl Recipe {
                 ));
             };
 
+        if let Some(ref checksum) = recipe.checksum {
+            checksum.verify(&recipe)?;
+        }
+
+        recipe.validate_sub_recipe_dependencies()?;
+
+        if let Some(ref audit) = recipe.audit {
+            for pattern in &audit.redact_patterns {
+                regex::Regex::new(pattern).map_err(|e| {
+                    anyhow::anyhow!("Invalid audit redact pattern '{}': {}", pattern, e)
+                })?;
+            }
+        }
+
         if let Some(ref retry_config) = recipe.retry {
             if let Err(validation_error) = retry_config.validate() {
                 return Err(anyhow::anyhow!(
@@ -316,6 +493,77 @@ impl Recipe {
 
         Ok(recipe)
     }
+
+    /// Sub-recipes in `dag` mode have to declare their dependencies, and those have to name
+    /// other sub-recipes of this recipe without forming a cycle
+    pub fn validate_sub_recipe_dependencies(&self) -> Result<()> {
+        if self.execution_mode != Some(SubRecipeExecutionMode::Dag) {
+            return Ok(());
+        }
+        let sub_recipes = self.sub_recipes.as_deref().unwrap_or_default();
+        if sub_recipes.iter().all(|s| s.dependencies.is_none()) {
+            return Err(anyhow::anyhow!(
+                "execution_mode 'dag' requires the sub-recipes to declare their dependencies"
+            ));
+        }
+        let nodes: Vec<(&str, &[String])> = sub_recipes
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.dependencies.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect();
+        dependencies::dependency_levels(&nodes)
+            .map_err(|e| anyhow::anyhow!("Invalid sub-recipe dependencies: {}", e))?;
+        Ok(())
+    }
+
+    /// Render a recipe template with `params` and parse the result, like loading a recipe file
+    /// but without touching the filesystem. Included templates are resolved against `recipe_dir`
+    /// from `params`, or the current directory when it is not set.
+    pub fn from_template_string(template: &str, params: &HashMap<String, String>) -> Result<Self> {
+        let mut params = params.clone();
+        if !params.contains_key(BUILT_IN_RECIPE_DIR_PARAM) {
+            let cwd = std::env::current_dir()?;
+            params.insert(
+                BUILT_IN_RECIPE_DIR_PARAM.to_string(),
+                cwd.to_string_lossy().into_owned(),
+            );
+        }
+        let rendered = template_recipe::render_recipe_content_with_params(template, &params)?;
+        Self::from_content(&rendered)
+    }
+
+    /// The recipe as YAML with its template variables filled in from `params`, falling back to
+    /// the parameter defaults, to check what a run would see without running it
+    pub fn render_preview(&self, params: &HashMap<String, String>) -> Result<String> {
+        let mut values: HashMap<String, String> = self
+            .parameters
+            .iter()
+            .flatten()
+            .filter_map(|p| p.default.clone().map(|default| (p.key.clone(), default)))
+            .collect();
+        values.extend(params.clone());
+        template_recipe::render_recipe_yaml_for_preview(&serde_yaml::to_string(self)?, &values)
+    }
+
+    /// A unified diff of the recipe rendered with `params_a` and with `params_b`, empty when
+    /// both render the same
+    pub fn diff_preview(
+        &self,
+        params_a: &HashMap<String, String>,
+        params_b: &HashMap<String, String>,
+    ) -> Result<String> {
+        let rendered_a = self.render_preview(params_a)?;
+        let rendered_b = self.render_preview(params_b)?;
+        Ok(similar::TextDiff::from_lines(&rendered_a, &rendered_b)
+            .unified_diff()
+            .context_radius(3)
+            .header("a/recipe.yaml", "b/recipe.yaml")
+            .to_string())
+    }
 }
 
 impl RecipeBuilder {
@@ -393,12 +641,54 @@ impl RecipeBuilder {
         self
     }
 
+    /// Sets how tasks from the sub-recipes are dispatched
+    pub fn execution_mode(mut self, execution_mode: SubRecipeExecutionMode) -> Self {
+        self.execution_mode = Some(execution_mode);
+        self
+    }
+
     /// Sets the retry configuration for the Recipe
     pub fn retry(mut self, retry: RetryConfig) -> Self {
         self.retry = Some(retry);
         self
     }
 
+    /// Sets the recipes that have to run before this one
+    pub fn dependencies(mut self, dependencies: Vec<String>) -> Self {
+        self.dependencies = Some(dependencies);
+        self
+    }
+
+    /// Sets the audit logging for sessions running the Recipe
+    pub fn audit(mut self, audit: RecipeAuditConfig) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Sets the checksum for the Recipe
+    pub fn checksum(mut self, checksum: RecipeChecksum) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
+    /// Sets how long the session of a scheduled run is reused before the Recipe runs again
+    pub fn ttl_seconds(mut self, ttl_seconds: u64) -> Self {
+        self.ttl_seconds = Some(ttl_seconds);
+        self
+    }
+
+    /// Sets the files the template variables of the Recipe are read from
+    pub fn variable_files(mut self, variable_files: Vec<String>) -> Self {
+        self.variable_files = Some(variable_files);
+        self
+    }
+
+    /// Sets the webhook notified when a scheduled run of the Recipe finishes
+    pub fn on_complete(mut self, on_complete: WebhookConfig) -> Self {
+        self.on_complete = Some(on_complete);
+        self
+    }
+
     /// Builds the Recipe instance
     ///
     /// Returns an error if any required fields are missing
@@ -424,7 +714,14 @@ impl RecipeBuilder {
             parameters: self.parameters,
             response: self.response,
             sub_recipes: self.sub_recipes,
+            execution_mode: self.execution_mode,
             retry: self.retry,
+            dependencies: self.dependencies,
+            audit: self.audit,
+            checksum: self.checksum,
+            ttl_seconds: self.ttl_seconds,
+            variable_files: self.variable_files,
+            on_complete: self.on_complete,
         })
     }
 }
@@ -670,6 +967,115 @@ sub_recipes:
         assert_eq!(activities, vec!["activity1", "activity2"]);
     }
 
+    #[test]
+    fn test_from_content_with_ttl_seconds() {
+        let content = r#"title: News Summary
+description: Summarize today's headlines
+prompt: Summarize the news
+ttl_seconds: 3600
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        assert_eq!(recipe.ttl_seconds, Some(3600));
+
+        // The TTL is part of the recipe, so changing it changes the checksum runs are matched by
+        let other = Recipe {
+            ttl_seconds: Some(60),
+            ..recipe.clone()
+        };
+        assert_ne!(
+            RecipeChecksum::sha256(&recipe).unwrap(),
+            RecipeChecksum::sha256(&other).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_content_with_on_complete() {
+        let content = r#"title: Nightly Build
+description: Build and report
+prompt: Run the build
+on_complete:
+  url: https://ci.example.com/hooks/goose
+  headers:
+    Authorization: Bearer abc
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        let webhook = recipe.on_complete.unwrap();
+        assert_eq!(webhook.url, "https://ci.example.com/hooks/goose");
+        assert_eq!(
+            webhook.headers.unwrap().get("Authorization").unwrap(),
+            "Bearer abc"
+        );
+        assert!(!webhook.include_session_summary);
+    }
+
+    #[test]
+    fn test_from_content_with_dag_execution_mode() {
+        let content = r#"title: Test Recipe
+description: A test recipe
+instructions: Test instructions
+execution_mode: dag
+sub_recipes:
+  - name: setup
+    path: setup.yaml
+  - name: build
+    path: build.yaml
+    dependencies: [setup]
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        assert_eq!(recipe.execution_mode, Some(SubRecipeExecutionMode::Dag));
+        let sub_recipes = recipe.sub_recipes.unwrap();
+        assert_eq!(sub_recipes[1].dependencies, Some(vec!["setup".to_string()]));
+
+        let without_dependencies = content.replace("    dependencies: [setup]\n", "");
+        let err = Recipe::from_content(&without_dependencies).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("requires the sub-recipes to declare"));
+
+        let unknown = content.replace("[setup]", "[deploy]");
+        let err = Recipe::from_content(&unknown).unwrap_err();
+        assert!(err.to_string().contains("'deploy'"));
+    }
+
+    #[test]
+    fn test_from_content_with_audit() {
+        let content = r#"title: Test Recipe
+description: A test recipe
+instructions: Test instructions
+audit:
+  enabled: true
+  redact_patterns: ['sk-[A-Za-z0-9]+']
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        let audit = recipe.audit.unwrap();
+        assert_eq!(
+            audit,
+            RecipeAuditConfig {
+                enabled: true,
+                include_tool_outputs: false,
+                redact_patterns: vec!["sk-[A-Za-z0-9]+".to_string()],
+            }
+        );
+        let envs = audit.extension_envs();
+        assert_eq!(envs["GOOSE_AUDIT_INCLUDE_TOOL_OUTPUTS"], "false");
+        assert_eq!(
+            envs["GOOSE_AUDIT_REDACT_PATTERNS"],
+            r#"["sk-[A-Za-z0-9]+"]"#
+        );
+
+        let disabled = RecipeAuditConfig::default().extension_envs();
+        assert_eq!(disabled.len(), 1);
+        assert_eq!(disabled["GOOSE_AUDIT_LOG"], "off");
+
+        let invalid = content.replace("sk-[A-Za-z0-9]+", "sk-[");
+        let err = Recipe::from_content(&invalid).unwrap_err();
+        assert!(err.to_string().contains("Invalid audit redact pattern"));
+    }
+
     #[test]
     fn test_from_content_with_nested_recipe_yaml() {
         let content = r#"name: test_recipe
@@ -699,4 +1105,74 @@ isGlobal: true"#;
         let extensions = recipe.extensions.unwrap();
         assert_eq!(extensions.len(), 0);
     }
+
+    #[test]
+    fn test_from_template_string() {
+        let template = r#"version: 1.0.0
+title: Review {{ repo }}
+description: Review open pull requests
+instructions: Review the {{ count }} most recent pull requests in {{ repo }}."#;
+        let params = HashMap::from([
+            ("repo".to_string(), "goose".to_string()),
+            ("count".to_string(), "3".to_string()),
+        ]);
+
+        let recipe = Recipe::from_template_string(template, &params).unwrap();
+        assert_eq!(recipe.title, "Review goose");
+        assert_eq!(
+            recipe.instructions,
+            Some("Review the 3 most recent pull requests in goose.".to_string())
+        );
+
+        // Parameters are required, as they are when rendering a recipe file
+        let err = Recipe::from_template_string(template, &HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("Failed to render the recipe"));
+    }
+
+    #[test]
+    fn test_render_preview() {
+        let recipe = Recipe::builder()
+            .title("Deploy")
+            .description("Deploy the service")
+            .instructions("Deploy {{ service }} to {{ environment }}.")
+            .parameters(vec![RecipeParameter {
+                key: "environment".to_string(),
+                input_type: RecipeParameterInputType::String,
+                requirement: RecipeParameterRequirement::Optional,
+                description: "Where to deploy".to_string(),
+                default: Some("staging".to_string()),
+                options: None,
+            }])
+            .build()
+            .unwrap();
+
+        // Defaults fill in what is not given and unknown variables stay as they are
+        let preview = recipe.render_preview(&HashMap::new()).unwrap();
+        assert!(
+            preview.contains("Deploy {{ service }} to staging."),
+            "{}",
+            preview
+        );
+
+        let params = HashMap::from([("service".to_string(), "api".to_string())]);
+        let preview = recipe.render_preview(&params).unwrap();
+        assert!(preview.contains("Deploy api to staging."), "{}", preview);
+
+        let production = HashMap::from([
+            ("service".to_string(), "api".to_string()),
+            ("environment".to_string(), "production".to_string()),
+        ]);
+        let diff = recipe.diff_preview(&params, &production).unwrap();
+        assert!(
+            diff.contains("-instructions: Deploy api to staging."),
+            "{}",
+            diff
+        );
+        assert!(
+            diff.contains("+instructions: Deploy api to production."),
+            "{}",
+            diff
+        );
+        assert!(recipe.diff_preview(&params, &params).unwrap().is_empty());
+    }
 }
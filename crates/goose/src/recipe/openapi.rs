@@ -0,0 +1,208 @@
+use serde_json::{json, Map, Value};
+
+use super::build_recipe::split_list;
+use super::{Recipe, RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement};
+
+const PARAMETERS_SCHEMA: &str = "RecipeParameters";
+
+impl Recipe {
+    /// An OpenAPI 3.1 document describing this recipe as a single `POST /run` endpoint, with a
+    /// request body built from the recipe parameters. The response schema is the recipe's
+    /// `response.json_schema` when it has one.
+    pub fn export_openapi(&self) -> Value {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for parameter in self.parameters.iter().flatten() {
+            properties.insert(parameter.key.clone(), parameter_schema(parameter));
+            // There is nobody to prompt when the recipe runs behind an API
+            if !matches!(parameter.requirement, RecipeParameterRequirement::Optional) {
+                required.push(parameter.key.clone());
+            }
+        }
+
+        let output_schema = self
+            .response
+            .as_ref()
+            .and_then(|response| response.json_schema.clone())
+            .unwrap_or_else(|| json!({ "type": "object" }));
+
+        json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": self.title,
+                "description": self.description,
+                "version": self.version,
+            },
+            "paths": {
+                "/run": {
+                    "post": {
+                        "operationId": "runRecipe",
+                        "summary": self.title,
+                        "description": self.description,
+                        "requestBody": {
+                            "required": !required.is_empty(),
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "$ref": format!("#/components/schemas/{}", PARAMETERS_SCHEMA)
+                                    }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "200": {
+                                "description": "The recipe ran to completion",
+                                "content": {
+                                    "application/json": { "schema": output_schema }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    PARAMETERS_SCHEMA: {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                        "additionalProperties": false,
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn parameter_schema(parameter: &RecipeParameter) -> Value {
+    let mut schema = input_type_schema(&parameter.input_type, parameter.options.as_ref());
+    schema["description"] = json!(parameter.description);
+    if let Some(default) = &parameter.default {
+        schema["default"] = default_value(&parameter.input_type, default);
+    }
+    schema
+}
+
+fn input_type_schema(
+    input_type: &RecipeParameterInputType,
+    options: Option<&Vec<String>>,
+) -> Value {
+    match input_type {
+        RecipeParameterInputType::String => json!({ "type": "string" }),
+        RecipeParameterInputType::Number => json!({ "type": "number" }),
+        RecipeParameterInputType::Boolean => json!({ "type": "boolean" }),
+        RecipeParameterInputType::Date => json!({ "type": "string", "format": "date" }),
+        RecipeParameterInputType::File => json!({ "type": "string", "format": "uri-reference" }),
+        RecipeParameterInputType::Select => json!({
+            "type": "string",
+            "enum": options.cloned().unwrap_or_default(),
+        }),
+        // The options of an array parameter are the values its items can take
+        RecipeParameterInputType::Array { item_type } => json!({
+            "type": "array",
+            "items": input_type_schema(item_type, options),
+        }),
+    }
+}
+
+/// Defaults are written as text in recipes, typed ones are given in their own type
+fn default_value(input_type: &RecipeParameterInputType, default: &str) -> Value {
+    match input_type {
+        RecipeParameterInputType::Number | RecipeParameterInputType::Boolean => {
+            serde_json::from_str(default).unwrap_or_else(|_| json!(default))
+        }
+        RecipeParameterInputType::Array { item_type } => split_list(default)
+            .iter()
+            .map(|item| default_value(item_type, item))
+            .collect(),
+        _ => json!(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_openapi() {
+        let recipe: Recipe = serde_yaml::from_str(
+            r#"
+            title: Triage
+            description: Triage an issue
+            instructions: Triage {{ issue }} in {{ repo }}
+            parameters:
+              - key: issue
+                input_type: number
+                requirement: user_prompt
+                description: Issue number
+              - key: repo
+                input_type: select
+                requirement: optional
+                default: goose
+                options: [goose, docs]
+                description: Repository
+              - key: dry_run
+                input_type: boolean
+                requirement: optional
+                default: "true"
+                description: Only report
+              - key: labels
+                input_type:
+                  array:
+                    item_type: select
+                requirement: optional
+                default: bug, ui
+                options: [bug, ui, docs]
+                description: Labels to consider
+            response:
+              json_schema:
+                type: object
+                properties:
+                  label:
+                    type: string
+            "#,
+        )
+        .unwrap();
+
+        let spec = recipe.export_openapi();
+        assert_eq!(spec["openapi"], "3.1.0");
+        assert_eq!(spec["info"]["title"], "Triage");
+
+        let post = &spec["paths"]["/run"]["post"];
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/RecipeParameters"
+        );
+        assert_eq!(
+            post["responses"]["200"]["content"]["application/json"]["schema"]["properties"]
+                ["label"]["type"],
+            "string"
+        );
+
+        let schema = &spec["components"]["schemas"]["RecipeParameters"];
+        assert_eq!(schema["required"], json!(["issue"]));
+        assert_eq!(
+            schema["properties"]["issue"],
+            json!({ "type": "number", "description": "Issue number" })
+        );
+        assert_eq!(
+            schema["properties"]["repo"],
+            json!({
+                "type": "string",
+                "enum": ["goose", "docs"],
+                "description": "Repository",
+                "default": "goose",
+            })
+        );
+        assert_eq!(schema["properties"]["dry_run"]["default"], json!(true));
+        assert_eq!(
+            schema["properties"]["labels"],
+            json!({
+                "type": "array",
+                "items": { "type": "string", "enum": ["bug", "ui", "docs"] },
+                "description": "Labels to consider",
+                "default": ["bug", "ui"],
+            })
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use etcetera::{choose_app_strategy, AppStrategy};
+use once_cell::sync::OnceCell;
+
+use super::Recipe;
+use crate::config::APP_STRATEGY;
+
+static GLOBAL_REGISTRY: OnceCell<RecipeRegistry> = OnceCell::new();
+
+/// Recipes registered under a name, so they can be loaded without knowing where the file is
+#[derive(Debug, Default)]
+pub struct RecipeRegistry {
+    recipes: DashMap<String, Recipe>,
+}
+
+impl RecipeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the global registry, loaded from `default_path` the first time it is used.
+    pub fn global() -> &'static RecipeRegistry {
+        GLOBAL_REGISTRY.get_or_init(|| {
+            let path = Self::default_path();
+            Self::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("Failed to load recipe registry {}: {}", path.display(), e);
+                Self::new()
+            })
+        })
+    }
+
+    /// Where the global registry is persisted
+    /// - macOS/Linux: ~/.config/goose/registry.yaml
+    /// - Windows:     ~\AppData\Roaming\Block\goose\config\registry.yaml
+    pub fn default_path() -> PathBuf {
+        choose_app_strategy(APP_STRATEGY.clone())
+            .expect("goose requires a home dir")
+            .config_dir()
+            .join("registry.yaml")
+    }
+
+    /// Load a registry saved with `save`, a missing file is an empty registry
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut registry = Self::new();
+        if !path.exists() {
+            return Ok(registry);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let recipes: BTreeMap<String, Recipe> = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        registry.recipes.extend(recipes);
+        Ok(registry)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let recipes: BTreeMap<String, Recipe> = self.list().into_iter().collect();
+        fs::write(path, serde_yaml::to_string(&recipes)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Save to `default_path`, for the global registry
+    pub fn persist(&self) -> Result<()> {
+        self.save(&Self::default_path())
+    }
+
+    /// Add a recipe, returning the one it replaced if the name was taken
+    pub fn register(&self, name: impl Into<String>, recipe: Recipe) -> Option<Recipe> {
+        self.recipes.insert(name.into(), recipe)
+    }
+
+    pub fn unregister(&self, name: &str) -> Option<Recipe> {
+        self.recipes.remove(name).map(|(_, recipe)| recipe)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Recipe> {
+        self.recipes.get(name).map(|recipe| recipe.clone())
+    }
+
+    /// All registered recipes, sorted by name
+    pub fn list(&self) -> Vec<(String, Recipe)> {
+        let mut recipes: Vec<_> = self
+            .recipes
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        recipes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        recipes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn recipe(title: &str) -> Recipe {
+        Recipe::builder()
+            .title(title)
+            .description("A test recipe")
+            .instructions("Do the thing")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_register_and_persist() {
+        let registry = RecipeRegistry::new();
+        assert!(registry.register("deploy", recipe("Deploy")).is_none());
+        assert!(registry.register("build", recipe("Build")).is_none());
+        assert_eq!(
+            registry
+                .register("deploy", recipe("Deploy v2"))
+                .map(|r| r.title),
+            Some("Deploy".to_string())
+        );
+        assert_eq!(registry.get("deploy").unwrap().title, "Deploy v2");
+        assert!(registry.get("missing").is_none());
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("registry.yaml");
+        registry.save(&path).unwrap();
+
+        let loaded = RecipeRegistry::load(&path).unwrap();
+        let names: Vec<_> = loaded.list().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["build", "deploy"]);
+        assert_eq!(loaded.get("build").unwrap().title, "Build");
+
+        assert!(loaded.unregister("build").is_some());
+        assert!(loaded.get("build").is_none());
+
+        let empty = RecipeRegistry::load(&temp_dir.path().join("missing.yaml")).unwrap();
+        assert!(empty.list().is_empty());
+    }
+}
@@ -1,12 +1,14 @@
 use std::{
     collections::{HashMap, HashSet},
+    fmt,
     path::Path,
 };
 
 use crate::recipe::{Recipe, BUILT_IN_RECIPE_DIR_PARAM};
 use anyhow::Result;
-use minijinja::{Environment, UndefinedBehavior};
+use minijinja::{Environment, UndefinedBehavior, Value};
 use regex::Regex;
+use serde::Serialize;
 
 const CURRENT_TEMPLATE_NAME: &str = "current_template";
 const OPEN_BRACE: &str = "{{";
@@ -83,9 +85,10 @@ fn replace_unparseable_vars_with_raw(
     Ok(result)
 }
 
-pub fn render_recipe_content_with_params(
+/// Render the recipe template with `params`, which are text or, for array parameters, lists
+pub fn render_recipe_content_with_params<V: Serialize + fmt::Display>(
     content: &str,
-    params: &HashMap<String, String>,
+    params: &HashMap<String, V>,
 ) -> Result<String> {
     // Pre-process content to replace empty double quotes with single quotes
     // This prevents MiniJinja from escaping "" to "\"\"" which would break YAML parsing
@@ -98,7 +101,7 @@ pub fn render_recipe_content_with_params(
 
     let env = add_template_in_env(
         &content_with_safe_variables,
-        params.get(BUILT_IN_RECIPE_DIR_PARAM).unwrap().clone(),
+        params.get(BUILT_IN_RECIPE_DIR_PARAM).unwrap().to_string(),
         UndefinedBehavior::Strict,
     )?;
     let template = env.get_template(CURRENT_TEMPLATE_NAME).unwrap();
@@ -169,10 +172,10 @@ pub fn parse_recipe_content(
 }
 
 // render the recipe for validation, deeplink and explain, etc.
-pub fn render_recipe_for_preview(
+pub fn render_recipe_for_preview<V: Serialize>(
     content: &str,
     recipe_dir: String,
-    params: &HashMap<String, String>,
+    params: &HashMap<String, V>,
 ) -> Result<Recipe> {
     // Pre-process template variables to handle invalid variable names
     let preprocessed_content = preprocess_template_variables(content)?;
@@ -184,14 +187,40 @@ pub fn render_recipe_for_preview(
     )?;
     let template = env.get_template(CURRENT_TEMPLATE_NAME).unwrap();
     // if the variables are not provided, the template will be rendered with the variables, otherwise it will keep the variables as is
-    let mut ctx = preserve_vars(&template_variables).clone();
-    ctx.extend(params.clone());
+    let mut ctx: HashMap<String, Value> = preserve_vars(&template_variables)
+        .into_iter()
+        .map(|(key, value)| (key, Value::from(value)))
+        .collect();
+    ctx.extend(
+        params
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::from_serialize(value))),
+    );
     let rendered_content = template
         .render(ctx)
         .map_err(|e| anyhow::anyhow!("Failed to parse the recipe {}", e))?;
     Recipe::from_content(&rendered_content)
 }
 
+/// Render the template variables left in an already parsed recipe, written out as YAML. Variables
+/// without a value in `params` are kept as they are.
+pub fn render_recipe_yaml_for_preview(
+    content: &str,
+    params: &HashMap<String, String>,
+) -> Result<String> {
+    let preprocessed_content = preprocess_template_variables(content)?;
+
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Lenient);
+    env.add_template(CURRENT_TEMPLATE_NAME, &preprocessed_content)?;
+    let template = env.get_template(CURRENT_TEMPLATE_NAME)?;
+    let mut ctx = preserve_vars(&template.undeclared_variables(false));
+    ctx.extend(params.clone());
+    template
+        .render(ctx)
+        .map_err(|e| anyhow::anyhow!("Failed to render the recipe {}", e))
+}
+
 fn preserve_vars(variables: &HashSet<String>) -> HashMap<String, String> {
     let mut context = HashMap::<String, String>::new();
     for template_var in variables {
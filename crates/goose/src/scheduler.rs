@@ -18,7 +18,12 @@ use crate::config::{self, Config};
 use crate::message::Message;
 use crate::providers::base::Provider as GooseProvider; // Alias to avoid conflict in test section
 use crate::providers::create;
-use crate::recipe::Recipe;
+use crate::recipe::build_recipe::{build_recipe_from_template, RecipeError};
+use crate::recipe::dependencies;
+use crate::recipe::read_recipe_file_content::read_recipe_file;
+use crate::recipe::{
+    Recipe, RecipeChecksum, RecipeParameter, WebhookConfig, BUILT_IN_DEPENDENCY_SESSION_IDS_PARAM,
+};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::session;
 use crate::session::storage::SessionMetadata;
@@ -1113,6 +1118,158 @@ async fn run_scheduled_job_internal(
         }
     }?;
 
+    if recipe
+        .dependencies
+        .as_ref()
+        .is_some_and(|dependencies| !dependencies.is_empty())
+    {
+        return run_with_dependencies(&job, recipe_path, provider_override, jobs_arc, job_id).await;
+    }
+
+    execute_recipe(&job, recipe, provider_override, jobs_arc, job_id).await
+}
+
+/// Run the recipes a job's recipe depends on in dependency order, then the recipe itself. Each
+/// recipe gets the session ids of its direct dependencies as a built-in template parameter.
+async fn run_with_dependencies(
+    job: &ScheduledJob,
+    recipe_path: &Path,
+    provider_override: Option<Arc<dyn GooseProvider>>,
+    jobs_arc: Option<Arc<Mutex<JobsMap>>>,
+    job_id: Option<String>,
+) -> std::result::Result<String, JobExecutionError> {
+    let order = dependencies::execution_order(recipe_path).map_err(|e| JobExecutionError {
+        job_id: job.id.clone(),
+        error: e.to_string(),
+    })?;
+
+    let mut session_ids: HashMap<PathBuf, String> = HashMap::new();
+    let mut last_session_id = String::new();
+    for node in order {
+        let dependency_session_ids: Vec<String> = node
+            .dependencies
+            .iter()
+            .filter_map(|dependency| session_ids.get(dependency).cloned())
+            .collect();
+        let params = vec![(
+            BUILT_IN_DEPENDENCY_SESSION_IDS_PARAM.to_string(),
+            dependency_session_ids.join(","),
+        )];
+
+        let recipe = read_recipe_file(&node.path)
+            .map_err(|source| RecipeError::TemplateRendering { source })
+            .and_then(|recipe_file| {
+                build_recipe_from_template(
+                    recipe_file,
+                    params,
+                    None::<fn(&RecipeParameter) -> Result<String>>,
+                )
+            })
+            .map_err(|e| JobExecutionError {
+                job_id: job.id.clone(),
+                error: format!("Failed to load recipe '{}': {}", node.path.display(), e),
+            })?;
+
+        tracing::info!("[Job {}] Running recipe '{}'", job.id, node.path.display());
+        last_session_id = execute_recipe(
+            job,
+            recipe,
+            provider_override.clone(),
+            jobs_arc.clone(),
+            job_id.clone(),
+        )
+        .await?;
+        session_ids.insert(node.path, last_session_id.clone());
+    }
+
+    Ok(last_session_id)
+}
+
+/// The most recent session that ran the recipe with this checksum to completion and was last
+/// written less than `ttl_seconds` ago
+fn cached_session_id(recipe_checksum: &str, ttl_seconds: u64) -> Option<String> {
+    let ttl = std::time::Duration::from_secs(ttl_seconds);
+    session::storage::list_sessions()
+        .ok()?
+        .into_iter()
+        .filter_map(|(id, path)| {
+            let metadata = session::storage::read_metadata(&path).ok()?;
+            if metadata.recipe_checksum.as_deref() != Some(recipe_checksum)
+                || metadata.stop_reason.is_some()
+            {
+                return None;
+            }
+            let modified = fs::metadata(&path).ok()?.modified().ok()?;
+            let age = modified.elapsed().ok()?;
+            (age < ttl).then_some((age, id))
+        })
+        .min()
+        .map(|(_, id)| id)
+}
+
+/// How long a recipe's `on_complete` webhook has to answer
+const WEBHOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The body POSTed to a recipe's `on_complete` webhook when a run finishes
+fn completion_payload(
+    webhook: &WebhookConfig,
+    session_id: &str,
+    status: &str,
+    messages: &[Message],
+    total_tokens: Option<i32>,
+) -> serde_json::Value {
+    let mut payload = serde_json::json!({
+        "session_id": session_id,
+        "status": status,
+        "message_count": messages.len(),
+        "total_tokens": total_tokens,
+    });
+    if webhook.include_session_summary {
+        payload["summary"] = messages
+            .iter()
+            .rev()
+            .find(|message| message.role == rmcp::model::Role::Assistant)
+            .map(Message::as_concat_text)
+            .into();
+    }
+    payload
+}
+
+async fn post_webhook(webhook: &WebhookConfig, payload: &serde_json::Value) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?;
+    let mut request = client.post(&webhook.url).json(payload);
+    for (name, value) in webhook.headers.iter().flatten() {
+        request = request.header(name, value);
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+async fn execute_recipe(
+    job: &ScheduledJob,
+    recipe: Recipe,
+    provider_override: Option<Arc<dyn GooseProvider>>,
+    jobs_arc: Option<Arc<Mutex<JobsMap>>>,
+    job_id: Option<String>,
+) -> std::result::Result<String, JobExecutionError> {
+    let recipe_checksum = recipe
+        .ttl_seconds
+        .and_then(|_| RecipeChecksum::sha256(&recipe).ok())
+        .map(|checksum| checksum.value);
+    if let (Some(ttl_seconds), Some(checksum)) = (recipe.ttl_seconds, recipe_checksum.as_ref()) {
+        if let Some(session_id) = cached_session_id(checksum, ttl_seconds) {
+            tracing::info!(
+                "[Job {}] Reusing session '{}', it ran the same recipe less than {} seconds ago",
+                job.id,
+                session_id,
+                ttl_seconds
+            );
+            return Ok(session_id);
+        }
+    }
+
     let agent: Agent = Agent::new();
 
     let agent_provider: Arc<dyn GooseProvider>; // Use the aliased GooseProvider
@@ -1184,6 +1341,9 @@ async fn run_scheduled_job_internal(
         }
     };
 
+    let max_tool_calls = recipe.settings.as_ref().and_then(|s| s.max_tool_calls);
+    let on_complete = recipe.on_complete.clone();
+
     if let Some(prompt_text) = recipe.prompt {
         let mut all_session_messages: Vec<Message> =
             vec![Message::user().with_text(prompt_text.clone())];
@@ -1214,6 +1374,10 @@ async fn run_scheduled_job_internal(
             Ok(mut stream) => {
                 use futures::StreamExt;
 
+                let mut tool_calls: u32 = 0;
+                let mut stop_reason: Option<String> = None;
+                let mut failed = false;
+
                 while let Some(message_result) = stream.next().await {
                     // Check if the task has been cancelled
                     tokio::task::yield_now().await;
@@ -1223,6 +1387,24 @@ async fn run_scheduled_job_internal(
                             if msg.role == rmcp::model::Role::Assistant {
                                 tracing::info!("[Job {}] Assistant: {:?}", job.id, msg.content);
                             }
+                            if msg.is_tool_call() {
+                                tool_calls += msg.get_tool_request_ids().len() as u32;
+                            }
+                            if let Some(limit) = max_tool_calls.filter(|limit| tool_calls > *limit)
+                            {
+                                // Dropping the stream before the requests are answered keeps the
+                                // agent from running them, so the message is left out too
+                                let reason = format!(
+                                    "Stopped after exceeding the recipe's limit of {} tool calls",
+                                    limit
+                                );
+                                tracing::warn!("[Job {}] {}", job.id, reason);
+                                all_session_messages.push(
+                                    Message::assistant().with_text(format!("Error: {}", reason)),
+                                );
+                                stop_reason = Some(reason);
+                                break;
+                            }
                             all_session_messages.push(msg);
                         }
                         Ok(AgentEvent::McpNotification(_)) => {
@@ -1238,6 +1420,7 @@ async fn run_scheduled_job_internal(
                                 job.id,
                                 e
                             );
+                            failed = true;
                             break;
                         }
                     }
@@ -1246,6 +1429,8 @@ async fn run_scheduled_job_internal(
                 match crate::session::storage::read_metadata(&session_file_path) {
                     Ok(mut updated_metadata) => {
                         updated_metadata.message_count = all_session_messages.len();
+                        updated_metadata.stop_reason = stop_reason.clone();
+                        updated_metadata.recipe_checksum = recipe_checksum.clone();
                         if let Err(e) = crate::session::storage::save_messages_with_metadata(
                             &session_file_path,
                             &updated_metadata,
@@ -1276,6 +1461,13 @@ async fn run_scheduled_job_internal(
                             accumulated_total_tokens: None,
                             accumulated_input_tokens: None,
                             accumulated_output_tokens: None,
+                            tags: Vec::new(),
+                            content_hash: None,
+                            provider: None,
+                            model: None,
+                            stop_reason: stop_reason.clone(),
+                            recipe_checksum: recipe_checksum.clone(),
+                            parent_session_id: None,
                         };
                         if let Err(e_fb) = crate::session::storage::save_messages_with_metadata(
                             &session_file_path,
@@ -1286,6 +1478,35 @@ async fn run_scheduled_job_internal(
                         }
                     }
                 }
+
+                // A webhook that cannot be reached does not fail the job, the session is saved
+                if let Some(webhook) = on_complete.as_ref() {
+                    let status = match (&stop_reason, failed) {
+                        (Some(_), _) => "stopped",
+                        (None, true) => "failed",
+                        (None, false) => "completed",
+                    };
+                    let total_tokens = crate::session::storage::read_metadata(&session_file_path)
+                        .ok()
+                        .and_then(|metadata| {
+                            metadata.accumulated_total_tokens.or(metadata.total_tokens)
+                        });
+                    let payload = completion_payload(
+                        webhook,
+                        &session_id_for_return,
+                        status,
+                        &all_session_messages,
+                        total_tokens,
+                    );
+                    if let Err(e) = post_webhook(webhook, &payload).await {
+                        tracing::warn!(
+                            "[Job {}] Failed to notify {} of the finished run: {}",
+                            job.id,
+                            webhook.url,
+                            e
+                        );
+                    }
+                }
             }
             Err(e) => {
                 return Err(JobExecutionError {
@@ -1343,6 +1564,36 @@ mod tests {
     use std::io::Write;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_completion_payload() {
+        let messages = vec![
+            Message::user().with_text("Summarize the news"),
+            Message::assistant().with_text("Nothing happened today"),
+        ];
+        let mut webhook = WebhookConfig {
+            url: "https://ci.example.com/hooks/goose".to_string(),
+            headers: None,
+            include_session_summary: false,
+        };
+
+        let payload = completion_payload(&webhook, "20250101_120000", "completed", &messages, None);
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "session_id": "20250101_120000",
+                "status": "completed",
+                "message_count": 2,
+                "total_tokens": null,
+            })
+        );
+
+        webhook.include_session_summary = true;
+        let payload =
+            completion_payload(&webhook, "20250101_120000", "stopped", &messages, Some(420));
+        assert_eq!(payload["total_tokens"], 420);
+        assert_eq!(payload["summary"], "Nothing happened today");
+    }
+
     #[derive(Clone)]
     struct MockSchedulerTestProvider {
         model_config: ModelConfig,
@@ -1425,7 +1676,14 @@ mod tests {
             settings: None,
             response: None,
             sub_recipes: None,
+            execution_mode: None,
             retry: None,
+            dependencies: None,
+            audit: None,
+            checksum: None,
+            ttl_seconds: None,
+            variable_files: None,
+            on_complete: None,
         };
         let mut recipe_file = File::create(&recipe_filename)?;
         writeln!(
@@ -13,6 +13,8 @@ use chrono::Local;
 use etcetera::{choose_app_strategy, AppStrategy, AppStrategyArgs};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
 use std::io::{self, BufRead, Write};
 use std::ops::DerefMut;
@@ -25,6 +27,12 @@ const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB
 const MAX_MESSAGE_COUNT: usize = 5000;
 const MAX_LINE_LENGTH: usize = 1024 * 1024; // 1MB per line
 
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("Session file {} failed its integrity check, its messages do not match the stored content hash", path.display())]
+    IntegrityViolation { path: PathBuf },
+}
+
 fn get_home_dir() -> PathBuf {
     choose_app_strategy(crate::config::APP_STRATEGY.clone())
         .expect("goose requires a home dir")
@@ -64,6 +72,20 @@ pub struct SessionMetadata {
     pub accumulated_input_tokens: Option<i32>,
     /// The number of output tokens used in the session. Accumulated across all messages.
     pub accumulated_output_tokens: Option<i32>,
+    /// User-defined labels for finding the session later
+    pub tags: Vec<String>,
+    /// SHA-256 hash of the serialized messages, set whenever the session is saved
+    pub content_hash: Option<String>,
+    /// Provider the session was switched to mid-conversation, if any
+    pub provider: Option<String>,
+    /// Model the session was switched to mid-conversation, if any
+    pub model: Option<String>,
+    /// Why the run that produced the session was stopped before the agent finished, if it was
+    pub stop_reason: Option<String>,
+    /// SHA-256 checksum of the recipe a scheduled run executed, to find its output again
+    pub recipe_checksum: Option<String>,
+    /// Name of the session this one was forked from, if any
+    pub parent_session_id: Option<String>,
 }
 
 // Custom deserializer to handle old sessions without working_dir
@@ -85,6 +107,14 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: Option<i32>,
             accumulated_output_tokens: Option<i32>,
             working_dir: Option<PathBuf>,
+            #[serde(default)]
+            tags: Vec<String>,
+            content_hash: Option<String>,
+            provider: Option<String>,
+            model: Option<String>,
+            stop_reason: Option<String>,
+            recipe_checksum: Option<String>,
+            parent_session_id: Option<String>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
@@ -107,6 +137,13 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: helper.accumulated_input_tokens,
             accumulated_output_tokens: helper.accumulated_output_tokens,
             working_dir,
+            tags: helper.tags,
+            content_hash: helper.content_hash,
+            provider: helper.provider,
+            model: helper.model,
+            stop_reason: helper.stop_reason,
+            recipe_checksum: helper.recipe_checksum,
+            parent_session_id: helper.parent_session_id,
         })
     }
 }
@@ -132,6 +169,13 @@ impl SessionMetadata {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            tags: Vec::new(),
+            content_hash: None,
+            provider: None,
+            model: None,
+            stop_reason: None,
+            recipe_checksum: None,
+            parent_session_id: None,
         }
     }
 }
@@ -384,6 +428,42 @@ pub fn list_sessions() -> Result<Vec<(String, PathBuf)>> {
     Ok(entries)
 }
 
+/// List the sessions whose metadata includes `tag`
+pub fn sessions_by_tag(tag: &str) -> Result<Vec<Identifier>> {
+    let sessions = list_sessions()?
+        .into_iter()
+        .filter(|(_, path)| {
+            read_metadata(path).is_ok_and(|metadata| metadata.tags.iter().any(|t| t == tag))
+        })
+        .map(|(name, _)| Identifier::Name(name))
+        .collect();
+
+    Ok(sessions)
+}
+
+/// The metadata of the session and each session it was forked from, starting with the session
+/// itself and ending at the first one without a parent. Parents that were deleted end the chain
+/// early.
+pub fn session_lineage(id: &Identifier) -> Result<Vec<SessionMetadata>> {
+    let path = get_path(id.clone())?;
+    let mut lineage = vec![read_metadata(&path)?];
+    let mut seen = HashSet::new();
+    while let Some(parent) = lineage.last().and_then(|m| m.parent_session_id.clone()) {
+        if !seen.insert(parent.clone()) {
+            return Err(anyhow::anyhow!(
+                "Session {} appears twice in its own lineage",
+                parent
+            ));
+        }
+        let parent_path = get_path(Identifier::Name(parent))?;
+        if !parent_path.exists() {
+            break;
+        }
+        lineage.push(read_metadata(&parent_path)?);
+    }
+    Ok(lineage)
+}
+
 /// Generate a session ID using timestamp format (yyyymmdd_hhmmss)
 pub fn generate_session_id() -> String {
     Local::now().format("%Y%m%d_%H%M%S").to_string()
@@ -469,6 +549,8 @@ pub fn read_messages_with_truncation(
     let mut corrupted_lines = Vec::new();
     let mut line_number = 1;
     let mut message_count = 0;
+    let mut expected_hash = None;
+    let mut hasher = Sha256::new();
 
     // Read the first line as metadata or create default if empty/missing
     if let Some(line_result) = lines.next() {
@@ -481,8 +563,9 @@ pub fn read_messages_with_truncation(
                 }
 
                 // Try to parse as metadata, but if it fails, treat it as a message
-                if let Ok(_metadata) = serde_json::from_str::<SessionMetadata>(&line) {
+                if let Ok(metadata) = serde_json::from_str::<SessionMetadata>(&line) {
                     // Metadata successfully parsed, continue with the rest of the lines as messages
+                    expected_hash = metadata.content_hash;
                 } else {
                     // This is not metadata, it's a message
                     match parse_message_with_truncation(&line, max_content_size) {
@@ -539,6 +622,9 @@ pub fn read_messages_with_truncation(
 
         match line_result {
             Ok(line) => {
+                hasher.update(line.as_bytes());
+                hasher.update(b"\n");
+
                 // Security check: line length
                 if line.len() > MAX_LINE_LENGTH {
                     tracing::warn!("Line {} exceeds length limit", line_number);
@@ -626,6 +712,16 @@ pub fn read_messages_with_truncation(
         }
     }
 
+    if let Some(expected_hash) = expected_hash {
+        if format!("{:x}", hasher.finalize()) != expected_hash {
+            tracing::warn!("Session file failed integrity check: {:?}", session_file);
+            return Err(SessionError::IntegrityViolation {
+                path: session_file.to_path_buf(),
+            }
+            .into());
+        }
+    }
+
     Ok(messages)
 }
 
@@ -1200,6 +1296,23 @@ pub fn save_messages_with_metadata(
         anyhow::anyhow!("Failed to lock session file")
     })?;
 
+    // Serialize the messages up front, the metadata line carries their hash
+    let mut hasher = Sha256::new();
+    let mut message_lines = Vec::with_capacity(messages.len());
+    for (i, message) in messages.iter().enumerate() {
+        let line = serde_json::to_string(message).map_err(|e| {
+            tracing::error!("Failed to serialize message {}: {}", i, e);
+            anyhow::anyhow!("Failed to write session message")
+        })?;
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+        message_lines.push(line);
+    }
+    let metadata = SessionMetadata {
+        content_hash: Some(format!("{:x}", hasher.finalize())),
+        ..metadata.clone()
+    };
+
     // Write to temporary file
     {
         let mut writer = io::BufWriter::new(&file);
@@ -1211,13 +1324,9 @@ pub fn save_messages_with_metadata(
         })?;
         writeln!(writer)?;
 
-        // Write all messages with progress tracking
-        for (i, message) in messages.iter().enumerate() {
-            serde_json::to_writer(&mut writer, &message).map_err(|e| {
-                tracing::error!("Failed to serialize message {}: {}", i, e);
-                anyhow::anyhow!("Failed to write session message")
-            })?;
-            writeln!(writer)?;
+        // Write all messages
+        for line in &message_lines {
+            writeln!(writer, "{}", line)?;
         }
 
         // Ensure all data is written to disk
@@ -1364,6 +1473,66 @@ pub async fn generate_description_with_schedule_id(
     save_messages_with_metadata(&secure_path, &metadata, messages)
 }
 
+/// Replace the result of every tool response that repeats an earlier one, like the same file
+/// read twice, with a reference to the first. Error results and results shorter than the
+/// reference are kept. Returns the number of responses replaced.
+pub fn dedupe_tool_responses(messages: &mut [Message]) -> usize {
+    use crate::message::MessageContent;
+    use std::collections::HashMap;
+
+    let mut first_ids: HashMap<Vec<u8>, String> = HashMap::new();
+    let mut replaced = 0;
+    for message in messages.iter_mut() {
+        for content in message.content.iter_mut() {
+            let MessageContent::ToolResponse(response) = content else {
+                continue;
+            };
+            let Ok(result) = &response.tool_result else {
+                continue;
+            };
+            let Ok(serialized) = serde_json::to_string(result) else {
+                continue;
+            };
+            let hash = Sha256::digest(serialized.as_bytes()).to_vec();
+            match first_ids.get(&hash) {
+                Some(first_id) => {
+                    let reference = format!("[same as tool response {}]", first_id);
+                    if reference.len() < serialized.len() {
+                        response.tool_result = Ok(vec![rmcp::model::Content::text(reference)]);
+                        replaced += 1;
+                    }
+                }
+                None => {
+                    first_ids.insert(hash, response.id.clone());
+                }
+            }
+        }
+    }
+    replaced
+}
+
+/// Keep only the last `count` messages, dropping more from the front until the conversation starts
+/// with a user message that is not a tool response, which would be missing its request. Returns
+/// the number of messages removed.
+pub fn trim_to_last_messages(messages: &mut Vec<Message>, count: usize) -> usize {
+    use crate::message::MessageContent;
+
+    let mut start = messages.len().saturating_sub(count);
+    while start < messages.len() {
+        let message = &messages[start];
+        let is_tool_response = message
+            .content
+            .iter()
+            .any(|content| matches!(content, MessageContent::ToolResponse(_)));
+        if message.role == rmcp::model::Role::User && !is_tool_response {
+            break;
+        }
+        start += 1;
+    }
+    messages.drain(..start);
+    start
+}
+
 /// Update only the metadata in a session file, preserving all messages
 ///
 /// Security features:
@@ -1701,6 +1870,149 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_tags_are_preserved() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("tags.jsonl");
+
+        let metadata = SessionMetadata {
+            tags: vec!["release".to_string(), "bugfix".to_string()],
+            ..Default::default()
+        };
+        save_messages_with_metadata(&file_path, &metadata, &[])?;
+
+        // Persisting more messages keeps the tags set earlier
+        let messages = vec![Message::user().with_text("test message")];
+        persist_messages_with_schedule_id(&file_path, &messages, None, None, None).await?;
+        assert_eq!(read_metadata(&file_path)?.tags, vec!["release", "bugfix"]);
+
+        // Sessions written before tags existed read back without any
+        let old_path = dir.path().join("old.jsonl");
+        fs::write(&old_path, "{\"description\":\"old\",\"message_count\":0}\n")?;
+        assert!(read_metadata(&old_path)?.tags.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_lineage() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("fork.jsonl");
+
+        // A parent that no longer exists ends the lineage at the session itself
+        let metadata = SessionMetadata {
+            description: "fork".to_string(),
+            parent_session_id: Some("20000101_000000_deleted".to_string()),
+            ..Default::default()
+        };
+        save_messages_with_metadata(&file_path, &metadata, &[])?;
+
+        let lineage = session_lineage(&Identifier::Path(file_path))?;
+        assert_eq!(lineage.len(), 1);
+        assert_eq!(lineage[0].description, "fork");
+        assert_eq!(
+            lineage[0].parent_session_id.as_deref(),
+            Some("20000101_000000_deleted")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_content_hash_detects_tampering() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("integrity.jsonl");
+
+        let messages = vec![
+            Message::user().with_text("delete the temp files"),
+            Message::assistant().with_text("Done"),
+        ];
+        save_messages_with_metadata(&file_path, &SessionMetadata::default(), &messages)?;
+        assert!(read_metadata(&file_path)?.content_hash.is_some());
+        assert_eq!(read_messages(&file_path)?.len(), 2);
+
+        let contents = fs::read_to_string(&file_path)?;
+        fs::write(
+            &file_path,
+            contents.replace("delete the temp files", "delete the home directory"),
+        )?;
+
+        let err = read_messages(&file_path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SessionError>(),
+            Some(SessionError::IntegrityViolation { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedupe_tool_responses() {
+        let file = vec![rmcp::model::Content::text("fn main() {}\n".repeat(20))];
+        let mut messages = vec![
+            Message::user().with_tool_response("read_1", Ok(file.clone())),
+            Message::user().with_tool_response("read_2", Ok(vec![])),
+            Message::user().with_tool_response("read_3", Ok(file.clone())),
+            Message::user().with_tool_response("read_4", Ok(vec![])),
+        ];
+
+        assert_eq!(dedupe_tool_responses(&mut messages), 1);
+        let MessageContent::ToolResponse(first) = &messages[0].content[0] else {
+            panic!("expected a tool response");
+        };
+        assert_eq!(first.tool_result.as_ref().unwrap(), &file);
+        let MessageContent::ToolResponse(repeated) = &messages[2].content[0] else {
+            panic!("expected a tool response");
+        };
+        assert_eq!(
+            repeated.tool_result.as_ref().unwrap()[0]
+                .as_text()
+                .unwrap()
+                .text,
+            "[same as tool response read_1]"
+        );
+        // Too short to be worth a reference
+        let MessageContent::ToolResponse(empty) = &messages[3].content[0] else {
+            panic!("expected a tool response");
+        };
+        assert!(empty.tool_result.as_ref().unwrap().is_empty());
+
+        assert_eq!(dedupe_tool_responses(&mut messages), 0);
+    }
+
+    #[test]
+    fn test_trim_to_last_messages() {
+        let conversation = vec![
+            Message::user().with_text("List the files"),
+            Message::assistant().with_tool_request(
+                "ls",
+                Ok(mcp_core::tool::ToolCall::new(
+                    "shell",
+                    serde_json::json!({}),
+                )),
+            ),
+            Message::user().with_tool_response("ls", Ok(vec![])),
+            Message::assistant().with_text("There are no files"),
+            Message::user().with_text("Create one"),
+            Message::assistant().with_text("Done"),
+        ];
+
+        // The last four start with a tool response, so the trim goes on to the next user message
+        let mut messages = conversation.clone();
+        assert_eq!(trim_to_last_messages(&mut messages, 4), 4);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].as_concat_text(), "Create one");
+
+        let mut messages = conversation.clone();
+        assert_eq!(trim_to_last_messages(&mut messages, 10), 0);
+        assert_eq!(messages, conversation);
+
+        // Nothing valid is left when only an assistant reply fits
+        let mut messages = conversation.clone();
+        assert_eq!(trim_to_last_messages(&mut messages, 1), 6);
+        assert!(messages.is_empty());
+    }
+
     #[test]
     fn test_invalid_working_dir() -> Result<()> {
         let dir = tempdir()?;
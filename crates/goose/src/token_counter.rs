@@ -2,18 +2,28 @@ use ahash::AHasher;
 use dashmap::DashMap;
 use rmcp::model::Tool;
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tiktoken_rs::CoreBPE;
 use tokio::sync::OnceCell;
 
-use crate::message::Message;
+use crate::message::{ContextSizeReport, Message, MessageContent};
 
 // Global tokenizer instance to avoid repeated initialization
 static TOKENIZER: OnceCell<Arc<CoreBPE>> = OnceCell::const_new();
 
+// Counter behind estimate_conversation_tokens, its cache keeps earlier messages from being
+// tokenized again on every turn
+static ESTIMATE_COUNTER: OnceLock<AsyncTokenCounter> = OnceLock::new();
+
 // Cache size limits to prevent unbounded growth
 const MAX_TOKEN_CACHE_SIZE: usize = 10_000;
 
+// Characters of each message shown in a ContextSizeReport
+const REPORT_PREVIEW_CHARS: usize = 80;
+
+// Rough size of a token in English text and code, for when the tokenizer cannot be loaded
+const BYTES_PER_TOKEN: usize = 4;
+
 /// Async token counter with caching capabilities
 pub struct AsyncTokenCounter {
     tokenizer: Arc<CoreBPE>,
@@ -141,31 +151,69 @@ impl AsyncTokenCounter {
         }
 
         for message in messages {
-            num_tokens += tokens_per_message;
-            for content in &message.content {
-                if let Some(content_text) = content.as_text() {
-                    num_tokens += self.count_tokens(content_text);
-                } else if let Some(tool_request) = content.as_tool_request() {
-                    let tool_call = tool_request.tool_call.as_ref().unwrap();
+            num_tokens += self.count_message_tokens(message);
+        }
+
+        if !tools.is_empty() {
+            num_tokens += self.count_tokens_for_tools(tools);
+        }
+
+        num_tokens += 3; // Reply primer
+
+        num_tokens
+    }
+
+    /// Count the tokens of a single message, including its framing (using cached count_tokens)
+    pub fn count_message_tokens(&self, message: &Message) -> usize {
+        let tokens_per_message = 4;
+        let mut num_tokens = tokens_per_message;
+        for content in &message.content {
+            if let Some(content_text) = content.as_text() {
+                num_tokens += self.count_tokens(content_text);
+            } else if let Some(tool_request) = content.as_tool_request() {
+                // Tool calls the model got wrong hold no call to count
+                if let Ok(tool_call) = &tool_request.tool_call {
                     // Note: separators are tokenized with adjacent tokens, keep original for accuracy
                     let text = format!(
                         "{}:{}:{}",
                         tool_request.id, tool_call.name, tool_call.arguments
                     );
                     num_tokens += self.count_tokens(&text);
-                } else if let Some(tool_response_text) = content.as_tool_response_text() {
-                    num_tokens += self.count_tokens(&tool_response_text);
                 }
+            } else if let Some(tool_response_text) = content.as_tool_response_text() {
+                num_tokens += self.count_tokens(&tool_response_text);
             }
         }
+        num_tokens
+    }
 
-        if !tools.is_empty() {
-            num_tokens += self.count_tokens_for_tools(tools);
-        }
-
-        num_tokens += 3; // Reply primer
+    /// Break down the size of a conversation that no longer fits the context: its total and
+    /// the `top_n` largest messages
+    pub fn context_size_report(
+        &self,
+        system_prompt: &str,
+        messages: &[Message],
+        tools: &[Tool],
+        top_n: usize,
+    ) -> ContextSizeReport {
+        let mut largest_messages: Vec<(usize, u64, String)> = messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| {
+                (
+                    index,
+                    self.count_message_tokens(message) as u64,
+                    message_preview(message),
+                )
+            })
+            .collect();
+        largest_messages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        largest_messages.truncate(top_n);
 
-        num_tokens
+        ContextSizeReport {
+            total_tokens: self.count_chat_tokens(system_prompt, messages, tools) as u64,
+            largest_messages,
+        }
     }
 
     /// Count everything including resources (using cached count_tokens)
@@ -295,12 +343,13 @@ impl TokenCounter {
                 if let Some(content_text) = content.as_text() {
                     num_tokens += self.count_tokens(content_text);
                 } else if let Some(tool_request) = content.as_tool_request() {
-                    let tool_call = tool_request.tool_call.as_ref().unwrap();
-                    let text = format!(
-                        "{}:{}:{}",
-                        tool_request.id, tool_call.name, tool_call.arguments
-                    );
-                    num_tokens += self.count_tokens(&text);
+                    if let Ok(tool_call) = &tool_request.tool_call {
+                        let text = format!(
+                            "{}:{}:{}",
+                            tool_request.id, tool_call.name, tool_call.arguments
+                        );
+                        num_tokens += self.count_tokens(&text);
+                    }
                 } else if let Some(tool_response_text) = content.as_tool_response_text() {
                     num_tokens += self.count_tokens(&tool_response_text);
                 } else {
@@ -339,6 +388,37 @@ impl TokenCounter {
     }
 }
 
+/// The start of a message on a single line, naming the tool for tool calls
+fn message_preview(message: &Message) -> String {
+    let text = message
+        .content
+        .iter()
+        .map(|content| match content {
+            MessageContent::ToolRequest(request) => match &request.tool_call {
+                Ok(call) => format!("[tool call: {}]", call.name),
+                Err(_) => "[invalid tool call]".to_string(),
+            },
+            MessageContent::ToolResponse(_) => format!(
+                "[tool result] {}",
+                content.as_tool_response_text().unwrap_or_default()
+            ),
+            MessageContent::Image(_) => "[image]".to_string(),
+            other => other.as_text().unwrap_or_default().to_string(),
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > REPORT_PREVIEW_CHARS {
+        format!(
+            "{}...",
+            text.chars().take(REPORT_PREVIEW_CHARS).collect::<String>()
+        )
+    } else {
+        text
+    }
+}
+
 /// Get the global tokenizer instance (async version)
 /// Fixed encoding for all tokenization - using o200k_base for GPT-4o and o1 models
 async fn get_tokenizer() -> Result<Arc<CoreBPE>, String> {
@@ -372,6 +452,29 @@ fn get_tokenizer_blocking() -> Result<Arc<CoreBPE>, String> {
     }
 }
 
+/// Estimate the tokens `messages` take up without calling a model, with the local tokenizer or,
+/// when it cannot be loaded, from the size of their content
+pub fn estimate_conversation_tokens(messages: &[Message]) -> u64 {
+    match get_tokenizer_blocking() {
+        Ok(tokenizer) => ESTIMATE_COUNTER
+            .get_or_init(|| AsyncTokenCounter {
+                tokenizer,
+                token_cache: Arc::new(DashMap::new()),
+            })
+            .count_chat_tokens("", messages, &[]) as u64,
+        Err(e) => {
+            tracing::debug!("Estimating tokens from message size: {}", e);
+            messages
+                .iter()
+                .map(|message| {
+                    serde_json::to_string(&message.content).map_or(0, |json| json.len())
+                        / BYTES_PER_TOKEN
+                })
+                .sum::<usize>() as u64
+        }
+    }
+}
+
 /// Factory function for creating async token counters with proper error handling
 pub async fn create_async_token_counter() -> Result<AsyncTokenCounter, String> {
     AsyncTokenCounter::new().await
@@ -381,6 +484,7 @@ pub async fn create_async_token_counter() -> Result<AsyncTokenCounter, String> {
 mod tests {
     use super::*;
     use crate::message::{Message, MessageContent};
+    use mcp_core::handler::ToolError;
     use rmcp::model::{Role, Tool};
     use rmcp::object;
 
@@ -573,6 +677,67 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_context_size_report() {
+        let counter = create_async_token_counter().await.unwrap();
+
+        let long_text = "word ".repeat(200);
+        let messages = vec![
+            Message::user().with_text("Hi"),
+            Message::assistant().with_text(format!("First line\n{}", long_text)),
+            Message::user().with_text("Thanks, that helps a lot"),
+        ];
+
+        let report = counter.context_size_report("system", &messages, &[], 2);
+        assert_eq!(
+            report.total_tokens,
+            counter.count_chat_tokens("system", &messages, &[]) as u64
+        );
+        assert_eq!(report.largest_messages.len(), 2);
+        assert_eq!(report.largest_messages[0].0, 1);
+        assert_eq!(report.largest_messages[1].0, 2);
+        assert!(report.largest_messages[0].1 > report.largest_messages[1].1);
+        assert!(report.largest_messages[0]
+            .2
+            .starts_with("First line word word"));
+        assert!(report.largest_messages[0].2.ends_with("..."));
+        assert_eq!(report.largest_messages[1].2, "Thanks, that helps a lot");
+    }
+
+    #[test]
+    fn test_estimate_conversation_tokens() {
+        let messages = vec![
+            Message::user().with_text("Hello, how are you?"),
+            Message::assistant().with_text("I'm fine, thanks."),
+        ];
+        let counter = TokenCounter::new();
+        assert_eq!(
+            estimate_conversation_tokens(&messages),
+            counter.count_chat_tokens("", &messages, &[]) as u64
+        );
+        assert!(
+            estimate_conversation_tokens(&messages[..1]) < estimate_conversation_tokens(&messages)
+        );
+    }
+
+    #[test]
+    fn test_failed_tool_calls_are_skipped() {
+        let messages = vec![Message::assistant().with_tool_request(
+            "call_1",
+            Err(ToolError::ExecutionError("invalid arguments".to_string())),
+        )];
+        let empty = vec![Message::assistant()];
+        let counter = TokenCounter::new();
+        assert_eq!(
+            counter.count_chat_tokens("", &messages, &[]),
+            counter.count_chat_tokens("", &empty, &[])
+        );
+        assert_eq!(
+            estimate_conversation_tokens(&messages),
+            estimate_conversation_tokens(&empty)
+        );
+    }
+
     #[tokio::test]
     async fn test_async_cache_management() {
         let counter = create_async_token_counter().await.unwrap();
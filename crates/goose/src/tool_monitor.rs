@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCall {
@@ -17,12 +18,44 @@ impl ToolCall {
     }
 }
 
+/// Marks an in-flight tool call, pass it back to `ToolMonitor::end_call` once the call finishes
+#[derive(Debug)]
+pub struct ToolCallHandle {
+    tool_name: String,
+    started_at: Instant,
+}
+
+impl ToolCallHandle {
+    pub fn tool_name(&self) -> &str {
+        &self.tool_name
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolTiming {
+    pub calls: u32,
+    pub failures: u32,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl ToolTiming {
+    pub fn average(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.calls
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ToolMonitor {
     max_repetitions: Option<u32>,
     last_call: Option<ToolCall>,
     repeat_count: u32,
     call_counts: HashMap<String, u32>,
+    timings: HashMap<String, ToolTiming>,
 }
 
 impl ToolMonitor {
@@ -32,9 +65,44 @@ impl ToolMonitor {
             last_call: None,
             repeat_count: 0,
             call_counts: HashMap::new(),
+            timings: HashMap::new(),
+        }
+    }
+
+    pub fn start_call(&self, tool_name: &str) -> ToolCallHandle {
+        ToolCallHandle {
+            tool_name: tool_name.to_string(),
+            started_at: Instant::now(),
         }
     }
 
+    pub fn end_call(&mut self, handle: ToolCallHandle, success: bool) {
+        let elapsed = handle.started_at.elapsed();
+        let timing = self.timings.entry(handle.tool_name).or_default();
+        timing.calls += 1;
+        if !success {
+            timing.failures += 1;
+        }
+        timing.total += elapsed;
+        timing.max = timing.max.max(elapsed);
+    }
+
+    /// The `n` tools with the highest average execution time, slowest first
+    pub fn slowest_tools(&self, n: usize) -> Vec<(String, Duration)> {
+        let mut averages: Vec<(String, Duration)> = self
+            .timings
+            .iter()
+            .map(|(name, timing)| (name.clone(), timing.average()))
+            .collect();
+        averages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        averages.truncate(n);
+        averages
+    }
+
+    pub fn get_timings(&self) -> HashMap<String, ToolTiming> {
+        self.timings.clone()
+    }
+
     pub fn check_tool_call(&mut self, tool_call: ToolCall) -> bool {
         let total_calls = self.call_counts.entry(tool_call.name.clone()).or_insert(0);
         *total_calls += 1;
@@ -70,5 +138,44 @@ impl ToolMonitor {
         self.last_call = None;
         self.repeat_count = 0;
         self.call_counts.clear();
+        self.timings.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slowest_tools() {
+        let mut monitor = ToolMonitor::new(None);
+        let now = Instant::now();
+
+        for (name, millis, success) in [
+            ("shell", 300, true),
+            ("shell", 100, false),
+            ("text_editor", 50, true),
+            ("glob", 500, true),
+        ] {
+            let handle = ToolCallHandle {
+                tool_name: name.to_string(),
+                started_at: now - Duration::from_millis(millis),
+            };
+            monitor.end_call(handle, success);
+        }
+
+        let slowest = monitor.slowest_tools(2);
+        assert_eq!(slowest.len(), 2);
+        assert_eq!(slowest[0].0, "glob");
+        assert_eq!(slowest[1].0, "shell");
+        assert!(slowest[1].1 >= Duration::from_millis(200));
+
+        let timings = monitor.get_timings();
+        assert_eq!(timings["shell"].calls, 2);
+        assert_eq!(timings["shell"].failures, 1);
+        assert!(timings["shell"].max >= Duration::from_millis(300));
+
+        monitor.reset();
+        assert!(monitor.slowest_tools(5).is_empty());
     }
 }
@@ -412,5 +412,12 @@ pub fn create_test_session_metadata(message_count: usize, working_dir: &str) ->
         accumulated_total_tokens: Some(100),
         accumulated_input_tokens: Some(50),
         accumulated_output_tokens: Some(50),
+        tags: Vec::new(),
+        content_hash: None,
+        provider: None,
+        model: None,
+        stop_reason: None,
+        recipe_checksum: None,
+        parent_session_id: None,
     }
 }
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use goose::agents::types::SessionConfig;
+use goose::agents::Agent;
+use goose::message::{Message, MessageContent};
+use goose::model::ModelConfig;
+use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+use goose::providers::errors::ProviderError;
+use goose::session;
+use mcp_core::tool::ToolCall;
+use rmcp::model::Tool;
+use serde_json::json;
+use tracing_test::traced_test;
+
+const SESSION_NAME: &str = "tracing-test-session";
+
+/// Asks for a single shell call, then finishes once it sees the tool result
+#[derive(Clone)]
+struct ToolCallingProvider {
+    model_config: ModelConfig,
+}
+
+#[async_trait]
+impl Provider for ToolCallingProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::empty()
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.model_config.clone()
+    }
+
+    async fn complete(
+        &self,
+        _system: &str,
+        messages: &[Message],
+        _tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let has_tool_result = messages.iter().any(|message| {
+            message
+                .content
+                .iter()
+                .any(|c| matches!(c, MessageContent::ToolResponse(_)))
+        });
+
+        let message = if has_tool_result {
+            Message::assistant().with_text("Done.")
+        } else {
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(ToolCall::new(
+                    "developer__shell",
+                    json!({"command": "echo hello"}),
+                )),
+            )
+        };
+        Ok((
+            message,
+            ProviderUsage::new("mock".to_string(), Usage::default()),
+        ))
+    }
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_tool_calls_are_traced_with_session_context() -> Result<()> {
+    let agent = Agent::new();
+    let provider = Arc::new(ToolCallingProvider {
+        model_config: ModelConfig::new("test-model".to_string()),
+    });
+    agent.update_provider(provider).await?;
+
+    let session = SessionConfig {
+        id: session::Identifier::Name(SESSION_NAME.to_string()),
+        working_dir: PathBuf::from("."),
+        schedule_id: None,
+        // Runs tools without asking for confirmation, like a headless session
+        execution_mode: Some("foreground".to_string()),
+        max_turns: Some(3),
+        retry_config: None,
+    };
+
+    let messages = vec![Message::user().with_text("say hello")];
+    let mut stream = agent.reply(&messages, Some(session), None).await?;
+    while let Some(event) = stream.next().await {
+        event?;
+    }
+
+    assert!(logs_contain("session_id"));
+    assert!(logs_contain("tool_name"));
+    assert!(logs_contain("extension_name"));
+
+    // Tool dispatch is nested under the session's reply span, so every line it logs
+    // carries both the session and the tool it belongs to
+    logs_assert(|lines: &[&str]| {
+        lines
+            .iter()
+            .find(|line| line.contains("Dispatching tool call"))
+            .ok_or_else(|| "tool dispatch was not logged".to_string())
+            .and_then(|line| {
+                for field in [
+                    format!("session_id={}", SESSION_NAME),
+                    "tool_name=developer__shell".to_string(),
+                    "extension_name=developer".to_string(),
+                ] {
+                    if !line.contains(&field) {
+                        return Err(format!("expected `{}` in: {}", field, line));
+                    }
+                }
+                Ok(())
+            })
+    });
+
+    Ok(())
+}
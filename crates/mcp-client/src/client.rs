@@ -1,11 +1,13 @@
+use futures::future;
 use mcp_core::protocol::{
     CallToolResult, Implementation, InitializeResult, ListPromptsResult, ListResourcesResult,
     ListToolsResult, ReadResourceResult, ServerCapabilities, METHOD_NOT_FOUND,
 };
+use mcp_core::{ToolCall, ToolError};
 use rmcp::model::{
-    GetPromptResult, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest,
-    JsonRpcResponse, JsonRpcVersion2_0, Notification, NumberOrString, Request, RequestId,
-    ServerNotification,
+    Content, GetPromptResult, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest,
+    JsonRpcResponse, JsonRpcVersion2_0, Notification, NumberOrString, Request, RequestId, Resource,
+    ResourceContents, ServerNotification,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -99,10 +101,54 @@ pub trait McpClientTrait: Send + Sync {
 
     async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error>;
 
+    /// Every resource the server has, following the pagination cursors of `list_resources`
+    async fn list_all_resources(&self) -> Result<Vec<Resource>, Error> {
+        let mut resources = Vec::new();
+        let mut next_cursor = None;
+        loop {
+            let page = self.list_resources(next_cursor).await?;
+            resources.extend(page.resources);
+            match page.next_cursor {
+                Some(cursor) => next_cursor = Some(cursor),
+                None => return Ok(resources),
+            }
+        }
+    }
+
+    /// The contents of a single resource, for the common case of a URI that reads as one item
+    async fn get_resource(&self, uri: &str) -> Result<ResourceContents, Error> {
+        self.read_resource(uri)
+            .await?
+            .contents
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::UnexpectedResponse(format!("Resource '{}' returned no contents", uri))
+            })
+    }
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error>;
 
     async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, Error>;
 
+    /// Call several tools at once, with the results in the same order as the calls. The
+    /// requests are all in flight together, saving a round trip per call even when the server
+    /// handles them one at a time.
+    async fn batch_call_tool(&self, calls: Vec<ToolCall>) -> Vec<Result<Vec<Content>, ToolError>> {
+        let calls = calls
+            .iter()
+            .map(|call| self.call_tool(&call.name, call.arguments.clone()));
+        future::join_all(calls)
+            .await
+            .into_iter()
+            .map(|result| {
+                result
+                    .map(|call| call.content)
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))
+            })
+            .collect()
+    }
+
     async fn list_prompts(&self, next_cursor: Option<String>) -> Result<ListPromptsResult, Error>;
 
     async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult, Error>;
@@ -193,8 +239,6 @@ where
     where
         R: for<'de> Deserialize<'de>,
     {
-        let mut service = self.service.lock().await;
-        service.ready().await.map_err(|_| Error::NotReady)?;
         let id_num = self.next_id_counter.fetch_add(1, Ordering::SeqCst);
         let id = RequestId::Number(id_num as u32);
 
@@ -213,19 +257,24 @@ where
             },
         });
 
-        let response_msg = service
-            .call(request)
-            .await
-            .map_err(|e| Error::McpServerError {
-                server: self
-                    .server_info
-                    .as_ref()
-                    .map(|s| s.name.clone())
-                    .unwrap_or("".to_string()),
-                method: method.to_string(),
-                // we don't need include params because it can be really large
-                source: Box::<Error>::new(e.into()),
-            })?;
+        // Only hold the service while sending, responses are matched to requests by id so
+        // other requests can go out while this one waits
+        let response = {
+            let mut service = self.service.lock().await;
+            service.ready().await.map_err(|_| Error::NotReady)?;
+            service.call(request)
+        };
+
+        let response_msg = response.await.map_err(|e| Error::McpServerError {
+            server: self
+                .server_info
+                .as_ref()
+                .map(|s| s.name.clone())
+                .unwrap_or("".to_string()),
+            method: method.to_string(),
+            // we don't need include params because it can be really large
+            source: Box::<Error>::new(e.into()),
+        })?;
 
         match response_msg {
             JsonRpcMessage::Response(JsonRpcResponse { id, result, .. }) => {
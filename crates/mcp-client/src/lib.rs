@@ -1,4 +1,5 @@
 pub mod client;
+pub mod middleware;
 pub mod oauth;
 pub mod service;
 pub mod transport;
@@ -7,6 +8,7 @@ pub mod transport;
 mod oauth_tests;
 
 pub use client::{ClientCapabilities, ClientInfo, Error, McpClient, McpClientTrait};
+pub use middleware::{LoggingMiddleware, Middleware, Next, RetryMiddleware};
 pub use oauth::{authenticate_service, ServiceConfig};
 pub use service::McpService;
 pub use transport::{
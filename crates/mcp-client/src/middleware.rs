@@ -0,0 +1,227 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use rmcp::model::JsonRpcMessage;
+
+use crate::transport::{Error, TransportMessageRecv};
+
+type Handler =
+    dyn Fn(JsonRpcMessage) -> BoxFuture<'static, Result<TransportMessageRecv, Error>> + Send + Sync;
+
+/// The rest of the middleware chain, ending with sending the message over the transport
+#[derive(Clone)]
+pub struct Next {
+    handler: Arc<Handler>,
+}
+
+impl Next {
+    pub(crate) fn new<F>(handler: F) -> Self
+    where
+        F: Fn(JsonRpcMessage) -> BoxFuture<'static, Result<TransportMessageRecv, Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            handler: Arc::new(handler),
+        }
+    }
+
+    pub fn run(
+        &self,
+        message: JsonRpcMessage,
+    ) -> BoxFuture<'static, Result<TransportMessageRecv, Error>> {
+        (self.handler)(message)
+    }
+}
+
+/// Wraps every message an [`McpService`](crate::McpService) sends. A middleware can change the
+/// message before passing it on with `next.run`, change the response or error that comes back,
+/// or call `next` more than once.
+pub trait Middleware: Send + Sync + 'static {
+    fn handle(
+        &self,
+        message: JsonRpcMessage,
+        next: Next,
+    ) -> BoxFuture<'static, Result<TransportMessageRecv, Error>>;
+}
+
+fn method_of(message: &JsonRpcMessage) -> String {
+    serde_json::to_value(message)
+        .ok()
+        .and_then(|value| value.get("method")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Traces every request and notification with its duration and outcome
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingMiddleware;
+
+impl Middleware for LoggingMiddleware {
+    fn handle(
+        &self,
+        message: JsonRpcMessage,
+        next: Next,
+    ) -> BoxFuture<'static, Result<TransportMessageRecv, Error>> {
+        Box::pin(async move {
+            let method = method_of(&message);
+            let start = Instant::now();
+            tracing::debug!(method = %method, "sending MCP message");
+            let result = next.run(message).await;
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            match &result {
+                Ok(_) => tracing::debug!(method = %method, elapsed_ms, "MCP call succeeded"),
+                Err(e) => {
+                    tracing::warn!(method = %method, elapsed_ms, error = %e, "MCP call failed")
+                }
+            }
+            result
+        })
+    }
+}
+
+/// Sends a message again when the transport fails with an I/O error, waiting twice as long
+/// before every attempt up to `max_delay`
+#[derive(Debug, Clone)]
+pub struct RetryMiddleware {
+    max_retries: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryMiddleware {
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(100))
+    }
+}
+
+impl RetryMiddleware {
+    pub fn new(max_retries: u32, initial_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_delay,
+            max_delay: Duration::from_secs(10),
+        }
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+impl Middleware for RetryMiddleware {
+    fn handle(
+        &self,
+        message: JsonRpcMessage,
+        next: Next,
+    ) -> BoxFuture<'static, Result<TransportMessageRecv, Error>> {
+        let retry = self.clone();
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                match next.run(message.clone()).await {
+                    Err(Error::Io(e)) if attempt < retry.max_retries => {
+                        let delay = retry.delay(attempt);
+                        tracing::warn!(
+                            error = %e,
+                            attempt = attempt + 1,
+                            delay_ms = delay.as_millis() as u64,
+                            "MCP transport I/O error, retrying"
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    result => return result,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{
+        JsonRpcNotification, JsonRpcResponse, JsonRpcVersion2_0, Notification, NumberOrString,
+    };
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn response() -> TransportMessageRecv {
+        JsonRpcMessage::Response(JsonRpcResponse {
+            jsonrpc: JsonRpcVersion2_0,
+            id: NumberOrString::Number(1),
+            result: serde_json::Map::new(),
+        })
+    }
+
+    fn failing_times(failures: u32, error: fn() -> Error) -> (Next, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let next = Next::new(move |_| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                if call < failures {
+                    Err(error())
+                } else {
+                    Ok(response())
+                }
+            })
+        });
+        (next, calls)
+    }
+
+    fn io_error() -> Error {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "broken pipe",
+        ))
+    }
+
+    fn notification() -> JsonRpcMessage {
+        JsonRpcMessage::Notification(JsonRpcNotification {
+            jsonrpc: JsonRpcVersion2_0,
+            notification: Notification {
+                method: "notifications/initialized".to_string(),
+                params: serde_json::Map::new(),
+                extensions: Default::default(),
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_retries_io_errors() {
+        let retry = RetryMiddleware::new(3, Duration::from_millis(1));
+
+        let (next, calls) = failing_times(2, io_error);
+        assert!(retry.handle(notification(), next).await.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let (next, calls) = failing_times(10, io_error);
+        assert!(matches!(
+            retry.handle(notification(), next).await,
+            Err(Error::Io(_))
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // Only I/O errors are retried
+        let (next, calls) = failing_times(1, || Error::ChannelClosed);
+        assert!(retry.handle(notification(), next).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        let retry = RetryMiddleware::new(5, Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(500));
+        let delays: Vec<u128> = (0..5).map(|a| retry.delay(a).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+    }
+}
@@ -6,6 +6,7 @@ use std::task::{Context, Poll};
 use tokio::sync::{oneshot, RwLock};
 use tower::{timeout::Timeout, Service, ServiceBuilder};
 
+use crate::middleware::{Middleware, Next};
 use crate::transport::{Error, TransportHandle, TransportMessageRecv};
 
 /// A wrapper service that implements Tower's Service trait for MCP transport
@@ -13,6 +14,7 @@ use crate::transport::{Error, TransportHandle, TransportMessageRecv};
 pub struct McpService<T: TransportHandle> {
     inner: Arc<T>,
     pending_requests: Arc<PendingRequests>,
+    middlewares: Vec<Arc<dyn Middleware>>,
 }
 
 impl<T: TransportHandle> McpService<T> {
@@ -20,9 +22,17 @@ impl<T: TransportHandle> McpService<T> {
         Self {
             inner: Arc::new(transport),
             pending_requests: Arc::new(PendingRequests::default()),
+            middlewares: Vec::new(),
         }
     }
 
+    /// Run every message through `middleware`. Middlewares added first see the message first
+    /// and the response last.
+    pub fn middleware<M: Middleware>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
     pub async fn respond(&self, id: &str, response: Result<TransportMessageRecv, Error>) {
         self.pending_requests.respond(id, response).await
     }
@@ -48,32 +58,53 @@ where
     fn call(&mut self, request: JsonRpcMessage) -> Self::Future {
         let transport = self.inner.clone();
         let pending_requests = self.pending_requests.clone();
+        let send = Next::new(move |request| {
+            Box::pin(send_message(
+                transport.clone(),
+                pending_requests.clone(),
+                request,
+            ))
+        });
+
+        let chain = self
+            .middlewares
+            .iter()
+            .rev()
+            .fold(send, |next, middleware| {
+                let middleware = middleware.clone();
+                Next::new(move |request| middleware.handle(request, next.clone()))
+            });
+        chain.run(request)
+    }
+}
 
-        Box::pin(async move {
-            match &request {
-                JsonRpcMessage::Request(JsonRpcRequest { id, .. }) => {
-                    // Create a channel to receive the response
-                    let (sender, receiver) = oneshot::channel();
-                    pending_requests.insert(id.to_string(), sender).await;
-
-                    transport.send(request).await?;
-                    receiver.await.map_err(|_| Error::ChannelClosed)?
-                }
-                JsonRpcMessage::Notification(_) => {
-                    // Handle notifications without waiting for a response
-                    transport.send(request).await?;
-                    // Return a dummy response for notifications
-                    let dummy_response: Self::Response =
-                        JsonRpcMessage::Response(rmcp::model::JsonRpcResponse {
-                            jsonrpc: rmcp::model::JsonRpcVersion2_0,
-                            id: rmcp::model::RequestId::Number(0),
-                            result: serde_json::Map::new(),
-                        });
-                    Ok(dummy_response)
-                }
-                _ => Err(Error::UnsupportedMessage),
-            }
-        })
+async fn send_message<T: TransportHandle>(
+    transport: Arc<T>,
+    pending_requests: Arc<PendingRequests>,
+    request: JsonRpcMessage,
+) -> Result<TransportMessageRecv, Error> {
+    match &request {
+        JsonRpcMessage::Request(JsonRpcRequest { id, .. }) => {
+            // Create a channel to receive the response
+            let (sender, receiver) = oneshot::channel();
+            pending_requests.insert(id.to_string(), sender).await;
+
+            transport.send(request).await?;
+            receiver.await.map_err(|_| Error::ChannelClosed)?
+        }
+        JsonRpcMessage::Notification(_) => {
+            // Handle notifications without waiting for a response
+            transport.send(request).await?;
+            // Return a dummy response for notifications
+            let dummy_response: TransportMessageRecv =
+                JsonRpcMessage::Response(rmcp::model::JsonRpcResponse {
+                    jsonrpc: rmcp::model::JsonRpcVersion2_0,
+                    id: rmcp::model::RequestId::Number(0),
+                    result: serde_json::Map::new(),
+                });
+            Ok(dummy_response)
+        }
+        _ => Err(Error::UnsupportedMessage),
     }
 }
 
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
 
 use async_trait::async_trait;
-use rmcp::model::JsonRpcMessage;
+use rmcp::model::{JsonRpcMessage, NumberOrString};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::{mpsc, Mutex};
+use tokio::time::{Instant, Interval};
 
 // Import nix crate components instead of libc
 #[cfg(unix)]
@@ -21,6 +23,17 @@ use super::{serialize_and_send, Error, Transport, TransportHandle};
 // Global to track process groups we've created
 static PROCESS_GROUP: AtomicI32 = AtomicI32::new(-1);
 
+// Heartbeat pings use string ids with this prefix so they never clash with client requests
+const HEARTBEAT_ID_PREFIX: &str = "heartbeat-";
+
+/// How often to ping the process and how long to wait for its answer, see
+/// `StdioTransport::with_heartbeat`
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    interval: Duration,
+    timeout: Duration,
+}
+
 /// A `StdioTransport` uses a child process's stdin/stdout as a communication channel.
 ///
 /// It uses channels for message passing and handles responses asynchronously through a background task.
@@ -32,6 +45,7 @@ pub struct StdioActor {
     stdin: Option<ChildStdin>,
     stdout: Option<ChildStdout>,
     stderr: Option<ChildStderr>,
+    heartbeat: Option<Heartbeat>,
 }
 
 impl Drop for StdioActor {
@@ -59,9 +73,10 @@ impl StdioActor {
         let stdin = self.stdin.take().expect("stdin should be available");
         let msg_inbox = self.receiver.take().expect("receiver should be available");
         let msg_outbox = self.sender.take().expect("sender should be available");
+        let (pong_tx, pong_rx) = mpsc::channel(1);
 
-        let incoming = Self::handle_proc_output(stdout, msg_outbox);
-        let outgoing = Self::handle_proc_input(stdin, msg_inbox);
+        let incoming = Self::handle_proc_output(stdout, msg_outbox, pong_tx);
+        let outgoing = Self::handle_proc_input(stdin, msg_inbox, self.heartbeat, pong_rx);
 
         // take ownership of futures for tokio::select
         pin!(incoming);
@@ -74,6 +89,12 @@ impl StdioActor {
             }
             result = &mut outgoing => {
                 tracing::debug!("Stdout handler completed: {:?}", result);
+                if let Err(error) = result {
+                    // The process may be hung rather than gone, so don't wait on its stderr.
+                    // Dropping the actor kills it.
+                    let _ = self.error_sender.send(error).await;
+                    return;
+                }
             }
             // capture the status so we don't need to wait for a timeout
             status = self.process.wait() => {
@@ -100,7 +121,11 @@ impl StdioActor {
         }
     }
 
-    async fn handle_proc_output(stdout: ChildStdout, sender: mpsc::Sender<TransportMessageRecv>) {
+    async fn handle_proc_output(
+        stdout: ChildStdout,
+        sender: mpsc::Sender<TransportMessageRecv>,
+        pongs: mpsc::Sender<String>,
+    ) {
         let mut reader = BufReader::new(stdout);
         let mut line = String::new();
         loop {
@@ -115,7 +140,23 @@ impl StdioActor {
                             message = ?message,
                             "Received incoming message"
                         );
-                        let _ = sender.send(message).await;
+                        // Answers to heartbeat pings stay in the transport, an error
+                        // answer still shows the process is alive
+                        let id = match &message {
+                            JsonRpcMessage::Response(response) => Some(&response.id),
+                            JsonRpcMessage::Error(error) => Some(&error.id),
+                            _ => None,
+                        };
+                        match id {
+                            Some(NumberOrString::String(id))
+                                if id.starts_with(HEARTBEAT_ID_PREFIX) =>
+                            {
+                                let _ = pongs.try_send(id.to_string());
+                            }
+                            _ => {
+                                let _ = sender.send(message).await;
+                            }
+                        }
                     } else {
                         tracing::warn!(
                             message = ?line,
@@ -132,8 +173,55 @@ impl StdioActor {
         }
     }
 
-    async fn handle_proc_input(mut stdin: ChildStdin, mut receiver: mpsc::Receiver<String>) {
-        while let Some(message_str) = receiver.recv().await {
+    /// Write outgoing messages to the process, pinging it in between when a heartbeat is set.
+    /// Fails with a broken pipe when a ping goes unanswered.
+    async fn handle_proc_input(
+        mut stdin: ChildStdin,
+        mut receiver: mpsc::Receiver<String>,
+        heartbeat: Option<Heartbeat>,
+        mut pongs: mpsc::Receiver<String>,
+    ) -> Result<(), Error> {
+        let mut ticker = heartbeat.map(|heartbeat| {
+            tokio::time::interval_at(Instant::now() + heartbeat.interval, heartbeat.interval)
+        });
+        let mut pings = 0u64;
+        // The id of the ping waiting for an answer and when it has to arrive by
+        let mut pending: Option<(String, Instant)> = None;
+
+        loop {
+            let message_str = tokio::select! {
+                message = receiver.recv() => match message {
+                    Some(message) => message,
+                    None => break,
+                },
+                Some(id) = pongs.recv() => {
+                    if pending.as_ref().is_some_and(|(pending_id, _)| *pending_id == id) {
+                        pending = None;
+                    }
+                    continue;
+                }
+                _ = sleep_until(pending.as_ref().map(|(_, deadline)| *deadline)) => {
+                    let message = format!(
+                        "Extension process did not answer a heartbeat within {:?}",
+                        heartbeat.map(|h| h.timeout).unwrap_or_default()
+                    );
+                    tracing::error!("{}", message);
+                    return Err(Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        message,
+                    )));
+                }
+                _ = tick(&mut ticker) => {
+                    let Some(heartbeat) = heartbeat.filter(|_| pending.is_none()) else {
+                        continue;
+                    };
+                    pings += 1;
+                    let id = format!("{}{}", HEARTBEAT_ID_PREFIX, pings);
+                    pending = Some((id.clone(), Instant::now() + heartbeat.timeout));
+                    serde_json::json!({"jsonrpc": "2.0", "id": id, "method": "ping"}).to_string()
+                }
+            };
+
             tracing::debug!(message = ?message_str, "Sending outgoing message");
 
             if let Err(e) = stdin.write_all(format!("{message_str}\n").as_bytes()).await {
@@ -146,6 +234,23 @@ impl StdioActor {
                 break;
             }
         }
+        Ok(())
+    }
+}
+
+async fn tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
     }
 }
 
@@ -194,6 +299,7 @@ pub struct StdioTransport {
     command: String,
     args: Vec<String>,
     env: HashMap<String, String>,
+    heartbeat: Option<Heartbeat>,
 }
 
 impl StdioTransport {
@@ -206,9 +312,18 @@ impl StdioTransport {
             command: command.into(),
             args,
             env,
+            heartbeat: None,
         }
     }
 
+    /// Ping the process every `interval` and close the transport with a broken pipe error if
+    /// it does not answer within `timeout`. This catches processes that stop responding
+    /// without closing their pipes, like when they are killed for running out of memory.
+    pub fn with_heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.heartbeat = Some(Heartbeat { interval, timeout });
+        self
+    }
+
     async fn spawn_process(&self) -> Result<(Child, ChildStdin, ChildStdout, ChildStderr), Error> {
         let mut command = Command::new(&self.command);
         command
@@ -290,6 +405,7 @@ impl Transport for StdioTransport {
             stdin: Some(stdin),
             stdout: Some(stdout),
             stderr: Some(stderr),
+            heartbeat: self.heartbeat,
         };
 
         tokio::spawn(actor.run());
@@ -317,3 +433,24 @@ impl Transport for StdioTransport {
         Ok(())
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_heartbeat_closes_unresponsive_process() {
+        // `sleep` never reads its stdin, so the ping goes unanswered
+        let transport = StdioTransport::new("sleep", vec!["30".to_string()], HashMap::new())
+            .with_heartbeat(Duration::from_millis(50), Duration::from_millis(100));
+        let handle = transport.start().await.unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), handle.receive())
+            .await
+            .expect("the heartbeat should close the transport");
+        match result {
+            Err(Error::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::BrokenPipe),
+            other => panic!("expected a broken pipe, got {:?}", other),
+        }
+    }
+}
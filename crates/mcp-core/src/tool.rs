@@ -50,6 +50,13 @@ pub struct ToolAnnotations {
     /// Default: true
     #[serde(default = "default_true")]
     pub open_world_hint: bool,
+
+    /// If true, the tool needs internet access to work, so it can be left out
+    /// in air-gapped environments.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub requires_internet: bool,
 }
 
 impl Default for ToolAnnotations {
@@ -60,6 +67,31 @@ impl Default for ToolAnnotations {
             destructive_hint: true,
             idempotent_hint: false,
             open_world_hint: true,
+            requires_internet: false,
+        }
+    }
+}
+
+/// rmcp annotations have no `requires_internet`, so a tool that declares an open world is taken to
+/// need the internet. Tools that may or may not, like shell commands, leave `open_world_hint` unset.
+impl From<&rmcp::model::ToolAnnotations> for ToolAnnotations {
+    fn from(annotations: &rmcp::model::ToolAnnotations) -> Self {
+        let defaults = ToolAnnotations::default();
+        ToolAnnotations {
+            title: annotations.title.clone(),
+            read_only_hint: annotations
+                .read_only_hint
+                .unwrap_or(defaults.read_only_hint),
+            destructive_hint: annotations
+                .destructive_hint
+                .unwrap_or(defaults.destructive_hint),
+            idempotent_hint: annotations
+                .idempotent_hint
+                .unwrap_or(defaults.idempotent_hint),
+            open_world_hint: annotations
+                .open_world_hint
+                .unwrap_or(defaults.open_world_hint),
+            requires_internet: annotations.open_world_hint == Some(true),
         }
     }
 }
@@ -98,6 +130,11 @@ impl ToolAnnotations {
         self.open_world_hint = open_world;
         self
     }
+
+    pub fn with_requires_internet(mut self, requires_internet: bool) -> Self {
+        self.requires_internet = requires_internet;
+        self
+    }
 }
 
 /// A tool that can be used by a model.
@@ -154,3 +191,41 @@ impl ToolCall {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_requires_internet() {
+        let annotations: ToolAnnotations =
+            serde_json::from_value(json!({"title": "Fetch"})).unwrap();
+        assert!(!annotations.requires_internet);
+
+        let annotations = ToolAnnotations::new().with_requires_internet(true);
+        assert_eq!(
+            serde_json::to_value(&annotations).unwrap()["requiresInternet"],
+            json!(true)
+        );
+
+        let web = rmcp::model::ToolAnnotations {
+            title: None,
+            read_only_hint: Some(true),
+            destructive_hint: None,
+            idempotent_hint: None,
+            open_world_hint: Some(true),
+        };
+        assert!(ToolAnnotations::from(&web).requires_internet);
+
+        // Unset means it depends, as for shell commands
+        let shell = rmcp::model::ToolAnnotations {
+            open_world_hint: None,
+            ..web
+        };
+        let converted = ToolAnnotations::from(&shell);
+        assert!(!converted.requires_internet);
+        assert!(converted.read_only_hint);
+        assert!(converted.destructive_hint);
+    }
+}
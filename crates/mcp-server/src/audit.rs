@@ -0,0 +1,295 @@
+use std::{future::Future, path::PathBuf, pin::Pin};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use rmcp::model::RequestId;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::Mutex};
+
+/// Parameter keys containing any of these fragments are masked before they are recorded
+const SENSITIVE_KEY_FRAGMENTS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "credential",
+    "private_key",
+];
+
+const MASK: &str = "********";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    /// The request completed but the tool reported an error
+    ToolError {
+        message: String,
+    },
+    /// The request failed before a response could be produced
+    Failed {
+        message: String,
+    },
+}
+
+/// A single request handled by the server, see `AuditSink`
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub request_id: RequestId,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    pub parameters: Value,
+    pub outcome: AuditOutcome,
+    /// The content a tool call returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+}
+
+impl AuditEntry {
+    /// Build an entry for a request, sensitive parameters are masked here so sinks never see them
+    pub fn new(
+        request_id: RequestId,
+        method: impl Into<String>,
+        parameters: Value,
+        outcome: AuditOutcome,
+    ) -> Self {
+        let method = method.into();
+        let tool_name = if method == "tools/call" {
+            parameters
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        } else {
+            None
+        };
+
+        Self {
+            timestamp: Utc::now(),
+            request_id,
+            method,
+            tool_name,
+            parameters: mask_sensitive(parameters),
+            outcome,
+            output: None,
+        }
+    }
+
+    pub fn with_output(mut self, output: Value) -> Self {
+        self.output = Some(output);
+        self
+    }
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_lowercase().replace('-', "_");
+    SENSITIVE_KEY_FRAGMENTS
+        .iter()
+        .any(|fragment| key.contains(fragment))
+}
+
+/// Replace the values of sensitive looking keys, at any depth, with a fixed mask
+pub fn mask_sensitive(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    if is_sensitive_key(&key) {
+                        (key, Value::String(MASK.to_string()))
+                    } else {
+                        (key, mask_sensitive(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(mask_sensitive).collect()),
+        other => other,
+    }
+}
+
+fn redact_text(text: String, patterns: &[Regex]) -> String {
+    patterns.iter().fold(text, |text, pattern| {
+        pattern.replace_all(&text, MASK).into_owned()
+    })
+}
+
+/// Replace every match of the patterns in string values, at any depth, with a fixed mask
+pub fn redact_matches(value: Value, patterns: &[Regex]) -> Value {
+    match value {
+        Value::String(text) => Value::String(redact_text(text, patterns)),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, redact_matches(value, patterns)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| redact_matches(value, patterns))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Receives a record of every request the server handles
+pub trait AuditSink: Send + Sync + 'static {
+    fn record(&self, entry: AuditEntry) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+/// Appends entries as newline-delimited JSON to a file. Tool outputs are left out unless
+/// `with_tool_outputs` is set.
+pub struct FileAuditSink {
+    path: PathBuf,
+    include_tool_outputs: bool,
+    redact_patterns: Vec<Regex>,
+    // Serializes writes so concurrent entries are never interleaved
+    lock: Mutex<()>,
+}
+
+impl FileAuditSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            include_tool_outputs: false,
+            redact_patterns: Vec::new(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Record the content returned by tool calls along with their parameters
+    pub fn with_tool_outputs(mut self, include_tool_outputs: bool) -> Self {
+        self.include_tool_outputs = include_tool_outputs;
+        self
+    }
+
+    /// Mask every match of these patterns in the recorded parameters, outputs and errors
+    pub fn with_redact_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.redact_patterns = patterns;
+        self
+    }
+
+    fn prepare(&self, mut entry: AuditEntry) -> AuditEntry {
+        if !self.include_tool_outputs {
+            entry.output = None;
+        }
+        if self.redact_patterns.is_empty() {
+            return entry;
+        }
+        let patterns = &self.redact_patterns;
+        entry.parameters = redact_matches(entry.parameters, patterns);
+        entry.output = entry.output.map(|output| redact_matches(output, patterns));
+        match &mut entry.outcome {
+            AuditOutcome::ToolError { message } | AuditOutcome::Failed { message } => {
+                *message = redact_text(std::mem::take(message), patterns);
+            }
+            AuditOutcome::Success => {}
+        }
+        entry
+    }
+
+    async fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().await;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, entry: AuditEntry) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            let entry = self.prepare(entry);
+            if let Err(e) = self.append(&entry).await {
+                tracing::error!(
+                    path = %self.path.display(),
+                    error = %e,
+                    "Failed to write audit entry"
+                );
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mask_sensitive() {
+        let masked = mask_sensitive(json!({
+            "name": "deploy",
+            "arguments": {
+                "API-Key": "abc",
+                "headers": [{"Authorization": "Bearer xyz", "accept": "json"}],
+                "db_password": "hunter2",
+                "count": 3
+            }
+        }));
+
+        assert_eq!(
+            masked,
+            json!({
+                "name": "deploy",
+                "arguments": {
+                    "API-Key": MASK,
+                    "headers": [{"Authorization": MASK, "accept": "json"}],
+                    "db_password": MASK,
+                    "count": 3
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_file_sink_prepare() {
+        let entry = AuditEntry::new(
+            RequestId::Number(1),
+            "tools/call",
+            json!({"name": "shell", "arguments": {"command": "curl -u admin:s3cret example.com"}}),
+            AuditOutcome::ToolError {
+                message: "401 for admin:s3cret".to_string(),
+            },
+        )
+        .with_output(json!([{"type": "text", "text": "denied admin:s3cret"}]));
+
+        let sink = FileAuditSink::new("audit.jsonl");
+        assert!(sink.prepare(entry.clone()).output.is_none());
+
+        let sink = FileAuditSink::new("audit.jsonl")
+            .with_tool_outputs(true)
+            .with_redact_patterns(vec![Regex::new(r"admin:\S+").unwrap()]);
+        let prepared = sink.prepare(entry);
+        assert_eq!(
+            prepared.parameters["arguments"]["command"],
+            format!("curl -u {} example.com", MASK)
+        );
+        assert_eq!(
+            prepared.output,
+            Some(json!([{"type": "text", "text": format!("denied {}", MASK)}]))
+        );
+        assert_eq!(
+            prepared.outcome,
+            AuditOutcome::ToolError {
+                message: format!("401 for {}", MASK)
+            }
+        );
+    }
+}
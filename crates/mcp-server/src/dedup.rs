@@ -0,0 +1,130 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use rmcp::model::{JsonRpcResponse, RequestId};
+
+const DEFAULT_CAPACITY: usize = 1024;
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Remembers the responses to recent requests so a client that retries a request it already
+/// sent, e.g. after its response was lost, gets the same response without running it again.
+///
+/// Entries expire `ttl` after the response was sent, and the least recently used entry is
+/// dropped once `capacity` responses are cached.
+#[derive(Debug)]
+pub struct RequestDeduplicator {
+    capacity: usize,
+    ttl: Duration,
+    responses: HashMap<RequestId, (Instant, JsonRpcResponse)>,
+    // Least recently used first
+    order: VecDeque<RequestId>,
+}
+
+impl Default for RequestDeduplicator {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_TTL)
+    }
+}
+
+impl RequestDeduplicator {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            responses: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// The response already sent for this request id, if it has not expired
+    pub fn get(&mut self, id: &RequestId) -> Option<JsonRpcResponse> {
+        let (sent_at, response) = self.responses.get(id)?;
+        if sent_at.elapsed() >= self.ttl {
+            self.remove(id);
+            return None;
+        }
+        let response = response.clone();
+        self.touch(id);
+        Some(response)
+    }
+
+    pub fn insert(&mut self, response: JsonRpcResponse) {
+        let id = response.id.clone();
+        if self
+            .responses
+            .insert(id.clone(), (Instant::now(), response))
+            .is_some()
+        {
+            self.touch(&id);
+        } else {
+            self.order.push_back(id);
+        }
+
+        while self.responses.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.responses.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    fn touch(&mut self, id: &RequestId) {
+        if let Some(position) = self.order.iter().position(|entry| entry == id) {
+            self.order.remove(position);
+        }
+        self.order.push_back(id.clone());
+    }
+
+    fn remove(&mut self, id: &RequestId) {
+        self.responses.remove(id);
+        self.order.retain(|entry| entry != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::JsonRpcVersion2_0;
+    use rmcp::object;
+
+    fn response(id: u32) -> JsonRpcResponse {
+        JsonRpcResponse {
+            jsonrpc: JsonRpcVersion2_0,
+            id: RequestId::Number(id),
+            result: object!({ "value": id }),
+        }
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted() {
+        let mut dedup = RequestDeduplicator::new(2, DEFAULT_TTL);
+        dedup.insert(response(1));
+        dedup.insert(response(2));
+        assert_eq!(dedup.get(&RequestId::Number(1)), Some(response(1)));
+
+        // 2 was used least recently
+        dedup.insert(response(3));
+        assert_eq!(dedup.len(), 2);
+        assert!(dedup.get(&RequestId::Number(2)).is_none());
+        assert!(dedup.get(&RequestId::Number(1)).is_some());
+        assert!(dedup.get(&RequestId::Number(3)).is_some());
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let mut dedup = RequestDeduplicator::new(8, Duration::ZERO);
+        dedup.insert(response(1));
+        assert!(dedup.get(&RequestId::Number(1)).is_none());
+        assert!(dedup.is_empty());
+    }
+}
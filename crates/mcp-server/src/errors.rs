@@ -51,6 +51,12 @@ pub enum RouterError {
     #[error("Tool not found: {0}")]
     ToolNotFound(String),
 
+    #[error("Invalid parameters for tool {tool}: {reason}")]
+    InvalidParameters { tool: String, reason: String },
+
+    #[error("Tool {tool} failed: {source}")]
+    ExecutionFailed { tool: String, source: BoxError },
+
     #[error("Resource not found: {0}")]
     ResourceNotFound(String),
 
@@ -58,6 +64,15 @@ pub enum RouterError {
     PromptNotFound(String),
 }
 
+#[derive(Error, Debug)]
+pub enum FilterError {
+    #[error("Invalid filter pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Content filter failed: {0}")]
+    Failed(String),
+}
+
 impl From<RouterError> for rmcp::model::ErrorData {
     fn from(err: RouterError) -> Self {
         use rmcp::model::*;
@@ -78,17 +93,27 @@ impl From<RouterError> for rmcp::model::ErrorData {
                 data: None,
             },
             RouterError::ToolNotFound(msg) => ErrorData {
-                code: ErrorCode::INVALID_REQUEST,
+                code: ErrorCode::INVALID_PARAMS,
                 message: Cow::from(msg),
                 data: None,
             },
+            RouterError::InvalidParameters { ref tool, .. } => ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                data: Some(serde_json::json!({ "tool": tool })),
+                message: Cow::from(err.to_string()),
+            },
+            RouterError::ExecutionFailed { ref tool, .. } => ErrorData {
+                code: ErrorCode::INTERNAL_ERROR,
+                data: Some(serde_json::json!({ "tool": tool })),
+                message: Cow::from(err.to_string()),
+            },
             RouterError::ResourceNotFound(msg) => ErrorData {
-                code: ErrorCode::INVALID_REQUEST,
+                code: ErrorCode::RESOURCE_NOT_FOUND,
                 message: Cow::from(msg),
                 data: None,
             },
             RouterError::PromptNotFound(msg) => ErrorData {
-                code: ErrorCode::INVALID_REQUEST,
+                code: ErrorCode::INVALID_PARAMS,
                 message: Cow::from(msg),
                 data: None,
             },
@@ -100,7 +125,7 @@ impl From<mcp_core::handler::ResourceError> for RouterError {
     fn from(err: mcp_core::handler::ResourceError) -> Self {
         match err {
             mcp_core::handler::ResourceError::NotFound(msg) => RouterError::ResourceNotFound(msg),
-            _ => RouterError::Internal("Unknown resource error".to_string()),
+            mcp_core::handler::ResourceError::ExecutionError(msg) => RouterError::Internal(msg),
         }
     }
 }
@@ -0,0 +1,115 @@
+use regex::Regex;
+use rmcp::model::{Content, RawContent, ResourceContents};
+
+use crate::FilterError;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Rewrites tool output before it is sent to the client, see `Server::with_content_filter`
+pub trait ContentFilter: Send + Sync + 'static {
+    fn filter(&self, content: &mut Vec<Content>) -> Result<(), FilterError>;
+}
+
+/// Masks every match of a set of patterns in text content and text resources
+#[derive(Debug, Clone)]
+pub struct RedactionFilter {
+    patterns: Vec<Regex>,
+    replacement: String,
+}
+
+impl RedactionFilter {
+    pub fn new<I, P>(patterns: I) -> Result<Self, FilterError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                Regex::new(pattern.as_ref()).map_err(|e| FilterError::InvalidPattern(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            patterns,
+            replacement: REDACTED.to_string(),
+        })
+    }
+
+    /// Text to put in place of each match, `[REDACTED]` by default
+    pub fn with_replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+
+    fn redact(&self, text: &mut String) {
+        for pattern in &self.patterns {
+            if pattern.is_match(text) {
+                // The replacement is literal, `$` in it is not a capture group
+                *text = pattern
+                    .replace_all(text, regex::NoExpand(&self.replacement))
+                    .into_owned();
+            }
+        }
+    }
+}
+
+impl ContentFilter for RedactionFilter {
+    fn filter(&self, content: &mut Vec<Content>) -> Result<(), FilterError> {
+        for item in content.iter_mut() {
+            match &mut item.raw {
+                RawContent::Text(text) => self.redact(&mut text.text),
+                RawContent::Resource(embedded) => {
+                    if let ResourceContents::TextResourceContents { text, .. } =
+                        &mut embedded.resource
+                    {
+                        self.redact(text);
+                    }
+                }
+                RawContent::Image(_) | RawContent::Audio(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction_filter() {
+        let filter = RedactionFilter::new([r"\b\d{3}-\d{2}-\d{4}\b", r"[\w.]+@[\w.]+"]).unwrap();
+        let mut content = vec![
+            Content::text("ssn 123-45-6789, email jane.doe@example.com"),
+            Content::embedded_text("db://users/1", "contact: joe@example.com"),
+        ];
+        filter.filter(&mut content).unwrap();
+
+        assert_eq!(
+            content[0].as_text().unwrap().text,
+            "ssn [REDACTED], email [REDACTED]"
+        );
+        match &content[1].raw {
+            RawContent::Resource(embedded) => match &embedded.resource {
+                ResourceContents::TextResourceContents { text, .. } => {
+                    assert_eq!(text, "contact: [REDACTED]")
+                }
+                _ => panic!("expected text resource"),
+            },
+            _ => panic!("expected embedded resource"),
+        }
+
+        let filter = RedactionFilter::new(["secret"])
+            .unwrap()
+            .with_replacement("$1");
+        let mut content = vec![Content::text("a secret")];
+        filter.filter(&mut content).unwrap();
+        assert_eq!(content[0].as_text().unwrap().text, "a $1");
+
+        assert!(matches!(
+            RedactionFilter::new(["("]),
+            Err(FilterError::InvalidPattern(_))
+        ));
+    }
+}
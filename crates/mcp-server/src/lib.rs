@@ -1,25 +1,39 @@
 use std::{
+    collections::VecDeque,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
 use futures::{Future, Stream};
 use pin_project::pin_project;
 use rmcp::model::{
-    ErrorData, JsonRpcError, JsonRpcMessage, JsonRpcResponse, JsonRpcVersion2_0, RequestId,
+    Content, ErrorData, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcResponse,
+    JsonRpcVersion2_0, Notification, RequestId,
 };
+use rmcp::object;
 use router::McpRequest;
 use tokio::{
     io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
     sync::mpsc,
 };
+use tokio_util::sync::CancellationToken;
 use tower_service::Service;
 
 mod errors;
-pub use errors::{BoxError, RouterError, ServerError, TransportError};
+pub use errors::{BoxError, FilterError, RouterError, ServerError, TransportError};
+
+pub mod audit;
+pub use audit::{AuditEntry, AuditOutcome, AuditSink, FileAuditSink};
+
+pub mod dedup;
+pub use dedup::RequestDeduplicator;
+
+pub mod filter;
+pub use filter::{ContentFilter, RedactionFilter};
 
 pub mod router;
-pub use router::Router;
+pub use router::{ResourceSubscriptions, Router};
 
 /// A transport layer that handles JSON-RPC messages over byte
 #[pin_project]
@@ -38,11 +52,20 @@ where
     R: AsyncRead,
     W: AsyncWrite,
 {
+    /// Read buffer size used by `new`
+    pub const DEFAULT_CAPACITY: usize = 2 * 1024 * 1024;
+
     pub fn new(reader: R, writer: W) -> Self {
+        // Default BufReader capacity is 8 * 1024, increase this to 2MB to the file size limit
+        // allows the buffer to have the capacity to read very large calls
+        Self::with_capacity(reader, writer, Self::DEFAULT_CAPACITY)
+    }
+
+    /// Like `new` with a read buffer of `capacity` bytes, for servers that exchange
+    /// messages larger than the default
+    pub fn with_capacity(reader: R, writer: W, capacity: usize) -> Self {
         Self {
-            // Default BufReader capacity is 8 * 1024, increase this to 2MB to the file size limit
-            // allows the buffer to have the capacity to read very large calls
-            reader: BufReader::with_capacity(2 * 1024 * 1024, reader),
+            reader: BufReader::with_capacity(capacity, reader),
             writer,
         }
     }
@@ -125,6 +148,73 @@ where
 /// The main server type that processes incoming requests
 pub struct Server<S> {
     service: S,
+    resource_updates: Option<mpsc::Receiver<String>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    content_filter: Option<Arc<dyn ContentFilter>>,
+    deduplicator: Option<RequestDeduplicator>,
+}
+
+// Tool failures are reported inside a successful response, pick them out for the audit log
+fn audit_outcome(response: &JsonRpcResponse) -> AuditOutcome {
+    if response.result.get("isError").and_then(|v| v.as_bool()) == Some(true) {
+        let message = response
+            .result
+            .get("content")
+            .and_then(|content| content.get(0))
+            .and_then(|content| content.get("text"))
+            .and_then(|text| text.as_str())
+            .unwrap_or_default()
+            .to_string();
+        AuditOutcome::ToolError { message }
+    } else {
+        AuditOutcome::Success
+    }
+}
+
+// Runs the filter over a tool call's content. If the filter fails the content is withheld
+// rather than sent unfiltered
+fn filter_tool_response(filter: &dyn ContentFilter, response: &mut JsonRpcResponse) {
+    let Some(content) = response.result.get_mut("content") else {
+        return;
+    };
+    let filtered = serde_json::from_value::<Vec<Content>>(content.take())
+        .map_err(|e| FilterError::Failed(e.to_string()))
+        .and_then(|mut items| {
+            filter.filter(&mut items)?;
+            serde_json::to_value(items).map_err(|e| FilterError::Failed(e.to_string()))
+        });
+
+    match filtered {
+        Ok(filtered) => *content = filtered,
+        Err(e) => {
+            tracing::error!(error = %e, "Content filter failed, withholding tool response");
+            *content = serde_json::json!([Content::text(e.to_string())]);
+            response
+                .result
+                .insert("isError".to_string(), serde_json::Value::Bool(true));
+        }
+    }
+}
+
+// The request a `notifications/cancelled` refers to, None for any other notification
+fn cancelled_request_id(notification: &JsonRpcNotification) -> Option<RequestId> {
+    if notification.notification.method != "notifications/cancelled" {
+        return None;
+    }
+    notification
+        .notification
+        .params
+        .get("requestId")
+        .cloned()
+        .and_then(|id| serde_json::from_value(id).ok())
+}
+
+// Waits for the next updated resource uri, or forever if no updates are configured
+async fn next_resource_update(updates: &mut Option<mpsc::Receiver<String>>) -> Option<String> {
+    match updates {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
 }
 
 impl<S> Server<S>
@@ -134,7 +224,40 @@ where
     S::Future: Send,
 {
     pub fn new(service: S) -> Self {
-        Self { service }
+        Self {
+            service,
+            resource_updates: None,
+            audit_sink: None,
+            content_filter: None,
+            deduplicator: None,
+        }
+    }
+
+    /// Pass the content of every tool call response through the filter before it is sent,
+    /// for example to redact sensitive data with a `RedactionFilter`
+    pub fn with_content_filter(mut self, filter: impl ContentFilter) -> Self {
+        self.content_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Record every request and its outcome to the given sink
+    pub fn with_audit_sink(mut self, sink: impl AuditSink) -> Self {
+        self.audit_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Answer a request whose id was already answered with the cached response instead of
+    /// running it again, which makes retries over at-least-once transports safe
+    pub fn with_deduplicator(mut self, deduplicator: RequestDeduplicator) -> Self {
+        self.deduplicator = Some(deduplicator);
+        self
+    }
+
+    /// Forward uris received on this channel to the client as
+    /// `notifications/resources/updated`, see `ResourceSubscriptions`
+    pub fn with_resource_updates(mut self, updates: mpsc::Receiver<String>) -> Self {
+        self.resource_updates = Some(updates);
+        self
     }
 
     // TODO transport trait instead of byte transport if we implement others
@@ -145,9 +268,47 @@ where
     {
         use futures::StreamExt;
         let mut service = self.service;
+        let mut resource_updates = self.resource_updates;
+        let audit_sink = self.audit_sink;
+        let content_filter = self.content_filter;
+        let mut deduplicator = self.deduplicator;
+        // Messages that arrived while a request was in progress, handled before reading more
+        let mut queued = VecDeque::new();
+        let mut transport_closed = false;
 
         tracing::info!("Server started");
-        while let Some(msg_result) = transport.next().await {
+        loop {
+            let msg_result = if let Some(msg_result) = queued.pop_front() {
+                msg_result
+            } else {
+                tokio::select! {
+                    msg = transport.next() => match msg {
+                        Some(msg_result) => msg_result,
+                        None => break,
+                    },
+                    update = next_resource_update(&mut resource_updates) => {
+                        match update {
+                            Some(uri) => {
+                                tracing::debug!(uri = %uri, "Sending resource updated notification");
+                                let notification = JsonRpcMessage::Notification(JsonRpcNotification {
+                                    jsonrpc: JsonRpcVersion2_0,
+                                    notification: Notification {
+                                        method: "notifications/resources/updated".to_string(),
+                                        params: object!({ "uri": uri }),
+                                        extensions: Default::default(),
+                                    },
+                                });
+                                if let Err(e) = transport.write_message(notification).await {
+                                    return Err(ServerError::Transport(TransportError::Io(e)));
+                                }
+                            }
+                            // All senders are gone, stop polling the channel
+                            None => resource_updates = None,
+                        }
+                        continue;
+                    }
+                }
+            };
             let _span = tracing::span!(tracing::Level::INFO, "message_processing").entered();
             match msg_result {
                 Ok(msg) => {
@@ -162,44 +323,155 @@ where
                                 "Received request"
                             );
 
+                            let request_id = request.id.clone();
+                            if let Some(response) = deduplicator
+                                .as_mut()
+                                .and_then(|dedup| dedup.get(&request_id))
+                            {
+                                tracing::info!(
+                                    request_id = ?request_id,
+                                    "Replaying the response to a retried request"
+                                );
+                                if let Err(e) = transport
+                                    .write_message(JsonRpcMessage::Response(response))
+                                    .await
+                                {
+                                    return Err(ServerError::Transport(TransportError::Io(e)));
+                                }
+                                continue;
+                            }
+                            let is_tool_call = request.request.method == "tools/call";
+
+                            // Keep what the audit log needs before the request is handed off
+                            let audit_request = audit_sink.as_ref().map(|_| {
+                                (
+                                    request.id.clone(),
+                                    request.request.method.clone(),
+                                    serde_json::Value::Object(request.request.params.clone()),
+                                )
+                            });
+
                             // Process the request using our service
                             let (notify_tx, mut notify_rx) = mpsc::channel(256);
+                            let cancel_token = CancellationToken::new();
                             let mcp_request = McpRequest {
                                 request,
                                 notifier: notify_tx,
+                                cancel_token: cancel_token.clone(),
                             };
 
-                            let transport_fut = tokio::spawn(async move {
-                                while let Some(notification) = notify_rx.recv().await {
-                                    if transport.write_message(notification).await.is_err() {
-                                        break;
+                            // Keep reading while the request runs so the client can cancel it,
+                            // anything else that arrives is handled once it is done
+                            let call = service.call(mcp_request);
+                            tokio::pin!(call);
+                            let result = loop {
+                                tokio::select! {
+                                    result = &mut call => break result,
+                                    Some(notification) = notify_rx.recv() => {
+                                        if let Err(e) = transport.write_message(notification).await {
+                                            return Err(ServerError::Transport(TransportError::Io(e)));
+                                        }
                                     }
+                                    msg = transport.next(), if !transport_closed => match msg {
+                                        Some(Ok(JsonRpcMessage::Notification(notification)))
+                                            if cancelled_request_id(&notification).as_ref()
+                                                == Some(&request_id) =>
+                                        {
+                                            tracing::info!(
+                                                request_id = ?request_id,
+                                                "Request cancelled by client"
+                                            );
+                                            cancel_token.cancel();
+                                        }
+                                        Some(msg_result) => queued.push_back(msg_result),
+                                        None => transport_closed = true,
+                                    },
                                 }
-                                transport
-                            });
+                            };
+                            while let Ok(notification) = notify_rx.try_recv() {
+                                if let Err(e) = transport.write_message(notification).await {
+                                    return Err(ServerError::Transport(TransportError::Io(e)));
+                                }
+                            }
 
-                            let response = match service.call(mcp_request).await {
+                            // The client has stopped waiting, so no response is sent
+                            if cancel_token.is_cancelled() {
+                                if let (Some(sink), Some((id, method, params))) =
+                                    (&audit_sink, audit_request)
+                                {
+                                    let outcome = AuditOutcome::Failed {
+                                        message: "Request cancelled by client".to_string(),
+                                    };
+                                    sink.record(AuditEntry::new(id, method, params, outcome))
+                                        .await;
+                                }
+                                continue;
+                            }
+
+                            let mut response = match result {
                                 Ok(resp) => resp,
                                 Err(e) => {
-                                    let error_msg = e.into().to_string();
+                                    let error: BoxError = e.into();
+                                    let error_msg = error.to_string();
                                     tracing::error!(error = %error_msg, "Request processing failed");
 
+                                    if let (Some(sink), Some((id, method, params))) =
+                                        (&audit_sink, audit_request)
+                                    {
+                                        let outcome = AuditOutcome::Failed {
+                                            message: error_msg.clone(),
+                                        };
+                                        sink.record(AuditEntry::new(id, method, params, outcome))
+                                            .await;
+                                    }
+
+                                    // Router errors are the client's mistake or a failure of this one
+                                    // request, answer them and keep serving
+                                    let error = match error.downcast::<RouterError>() {
+                                        Ok(router_error) => {
+                                            let mut error_data = ErrorData::from(*router_error);
+                                            error_data.message = error_msg.into();
+                                            let error_response =
+                                                JsonRpcMessage::Error(JsonRpcError {
+                                                    jsonrpc: JsonRpcVersion2_0,
+                                                    id: request_id,
+                                                    error: error_data,
+                                                });
+                                            if let Err(e) =
+                                                transport.write_message(error_response).await
+                                            {
+                                                return Err(ServerError::Transport(
+                                                    TransportError::Io(e),
+                                                ));
+                                            }
+                                            continue;
+                                        }
+                                        Err(error) => error,
+                                    };
+
                                     // Return an error response instead of a regular response
                                     return Err(ServerError::Transport(TransportError::Protocol(
-                                        error_msg,
+                                        error.to_string(),
                                     )));
                                 }
                             };
 
-                            transport = match transport_fut.await {
-                                Ok(transport) => transport,
-                                Err(e) => {
-                                    tracing::error!(error = %e, "Failed to spawn transport task");
-                                    return Err(ServerError::Transport(TransportError::Io(
-                                        e.into(),
-                                    )));
+                            if let (Some(filter), true) = (&content_filter, is_tool_call) {
+                                filter_tool_response(filter.as_ref(), &mut response);
+                            }
+
+                            if let (Some(sink), Some((id, method, params))) =
+                                (&audit_sink, audit_request)
+                            {
+                                let outcome = audit_outcome(&response);
+                                let mut entry = AuditEntry::new(id, method, params, outcome);
+                                if let (true, Some(content)) =
+                                    (is_tool_call, response.result.get("content"))
+                                {
+                                    entry = entry.with_output(content.clone());
                                 }
-                            };
+                                sink.record(entry).await;
+                            }
 
                             // Serialize response for logging
                             let response_json = serde_json::to_string(&response)
@@ -210,6 +482,9 @@ where
                                 json = %response_json,
                                 "Sending response"
                             );
+                            if let Some(dedup) = deduplicator.as_mut() {
+                                dedup.insert(response.clone());
+                            }
                             // Send the response back
                             if let Err(e) = transport
                                 .write_message(JsonRpcMessage::Response(response))
@@ -289,3 +564,337 @@ impl<T> BoundedService for T where
         + 'static
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::{
+        CapabilitiesBuilder, Router, RouterService, SamplingRequest, SamplingResponse,
+    };
+    use mcp_core::handler::{PromptError, ResourceError, ToolError};
+    use mcp_core::protocol::ServerCapabilities;
+    use rmcp::model::{Content, Prompt, Resource, Tool};
+    use serde_json::{json, Value};
+    use static_assertions::assert_impl_all;
+    use tokio::io::AsyncWriteExt;
+
+    #[derive(Clone)]
+    struct NoopRouter;
+
+    impl Router for NoopRouter {
+        fn name(&self) -> String {
+            "noop".to_string()
+        }
+
+        fn instructions(&self) -> String {
+            String::new()
+        }
+
+        fn capabilities(&self) -> ServerCapabilities {
+            CapabilitiesBuilder::new().build()
+        }
+
+        fn list_tools(&self) -> Vec<Tool> {
+            Vec::new()
+        }
+
+        fn call_tool(
+            &self,
+            tool_name: &str,
+            _arguments: Value,
+            _notifier: mpsc::Sender<JsonRpcMessage>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>
+        {
+            let error = match tool_name {
+                "bad_args" => ToolError::InvalidParameters("path is required".to_string()),
+                "broken" => ToolError::ExecutionError("disk full".to_string()),
+                "schema" => ToolError::SchemaError("not an object".to_string()),
+                _ => ToolError::NotFound(tool_name.to_string()),
+            };
+            Box::pin(async move { Err(error) })
+        }
+
+        fn call_tool_with_cancellation(
+            &self,
+            tool_name: &str,
+            arguments: Value,
+            notifier: mpsc::Sender<JsonRpcMessage>,
+            cancel_token: CancellationToken,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>
+        {
+            if tool_name != "wait" {
+                return self.call_tool(tool_name, arguments, notifier);
+            }
+            Box::pin(async move {
+                cancel_token.cancelled().await;
+                Ok(Vec::new())
+            })
+        }
+
+        fn list_resources(&self) -> Vec<Resource> {
+            Vec::new()
+        }
+
+        fn read_resource(
+            &self,
+            uri: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+            let uri = uri.to_string();
+            Box::pin(async move { Err(ResourceError::NotFound(uri)) })
+        }
+
+        fn list_prompts(&self) -> Vec<Prompt> {
+            Vec::new()
+        }
+
+        fn get_prompt(
+            &self,
+            prompt_name: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+            let prompt_name = prompt_name.to_string();
+            Box::pin(async move { Err(PromptError::NotFound(prompt_name)) })
+        }
+
+        fn sampling_create_message(
+            &self,
+            request: SamplingRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<SamplingResponse, RouterError>> + Send + 'static>>
+        {
+            Box::pin(async move {
+                let last = request.messages.last().and_then(|m| m.content.as_text());
+                Ok(SamplingResponse {
+                    model: "echo".to_string(),
+                    stop_reason: Some(SamplingResponse::STOP_REASON_END_TURN.to_string()),
+                    message: rmcp::model::SamplingMessage {
+                        role: rmcp::model::Role::Assistant,
+                        content: Content::text(last.map_or("", |t| t.text.as_str())),
+                    },
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_request_gets_no_response() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server);
+        let server = Server::new(RouterService(NoopRouter))
+            .run(ByteTransport::new(server_read, server_write));
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let client = async move {
+            for message in [
+                json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "wait"}}),
+                json!({"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {"requestId": 1}}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list", "params": {}}),
+            ] {
+                client_write
+                    .write_all(format!("{}\n", message).as_bytes())
+                    .await
+                    .unwrap();
+            }
+
+            // The cancelled call is dropped, the next response is for the request after it
+            let mut lines = BufReader::new(client_read).lines();
+            let line = lines.next_line().await.unwrap().unwrap();
+            let response: Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(response["id"], 2);
+            assert!(response["result"]["tools"].is_array());
+
+            drop(client_write);
+            drop(lines);
+        };
+
+        let (result, _) = tokio::join!(server, client);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_router_errors_are_answered() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server);
+        let server = Server::new(RouterService(NoopRouter))
+            .run(ByteTransport::new(server_read, server_write));
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let client = async move {
+            for message in [
+                json!({"jsonrpc": "2.0", "id": 1, "method": "resources/read", "params": {"uri": "file:///missing"}}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {}}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "tools/list", "params": {}}),
+            ] {
+                client_write
+                    .write_all(format!("{}\n", message).as_bytes())
+                    .await
+                    .unwrap();
+            }
+
+            let mut lines = BufReader::new(client_read).lines();
+            let mut responses = Vec::new();
+            for _ in 0..3 {
+                let line = lines.next_line().await.unwrap().unwrap();
+                responses.push(serde_json::from_str::<Value>(&line).unwrap());
+            }
+            assert_eq!(responses[0]["id"], 1);
+            assert_eq!(responses[0]["error"]["code"], -32002);
+            assert_eq!(responses[1]["id"], 2);
+            assert_eq!(responses[1]["error"]["code"], -32602);
+            assert_eq!(responses[2]["id"], 3);
+            assert!(responses[2]["result"]["tools"].is_array());
+
+            drop(client_write);
+            drop(lines);
+        };
+
+        let (result, _) = tokio::join!(server, client);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_tool_errors_are_answered() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server);
+        let server = Server::new(RouterService(NoopRouter))
+            .run(ByteTransport::new(server_read, server_write));
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let client = async move {
+            let mut messages: Vec<Value> = [(1, "missing"), (2, "bad_args"), (3, "broken"), (4, "schema")]
+                .into_iter()
+                .map(|(id, name)| {
+                    json!({"jsonrpc": "2.0", "id": id, "method": "tools/call", "params": {"name": name}})
+                })
+                .collect();
+            messages.push(json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": {}}));
+            for message in &messages {
+                client_write
+                    .write_all(format!("{}\n", message).as_bytes())
+                    .await
+                    .unwrap();
+            }
+
+            let mut lines = BufReader::new(client_read).lines();
+            let mut responses = Vec::new();
+            for _ in 0..messages.len() {
+                let line = lines.next_line().await.unwrap().unwrap();
+                responses.push(serde_json::from_str::<Value>(&line).unwrap());
+            }
+            responses.sort_by_key(|response| response["id"].as_i64());
+
+            // Failed tool calls are a result the model can read
+            for (response, text) in responses.iter().zip([
+                "Tool not found: missing",
+                "Invalid parameters: path is required",
+                "Execution failed: disk full",
+                "Schema error: not an object",
+            ]) {
+                assert_eq!(response["result"]["isError"], true);
+                assert_eq!(response["result"]["content"][0]["text"], text);
+            }
+
+            // A request without a tool name never reaches the tool
+            assert_eq!(responses[4]["error"]["code"], -32602);
+            assert_eq!(
+                responses[4]["error"]["message"],
+                "Invalid parameters: Missing tool name"
+            );
+
+            drop(client_write);
+            drop(lines);
+        };
+
+        let (result, _) = tokio::join!(server, client);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_sampling_create_message() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server);
+        let server = Server::new(RouterService(NoopRouter))
+            .run(ByteTransport::new(server_read, server_write));
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let client = async move {
+            for message in [
+                json!({"jsonrpc": "2.0", "id": 1, "method": "sampling/createMessage", "params": {
+                    "messages": [{"role": "user", "content": {"type": "text", "text": "ping"}}],
+                    "maxTokens": 10
+                }}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "sampling/createMessage", "params": {"messages": []}}),
+            ] {
+                client_write
+                    .write_all(format!("{}\n", message).as_bytes())
+                    .await
+                    .unwrap();
+            }
+
+            let mut lines = BufReader::new(client_read).lines();
+            let mut responses = Vec::new();
+            for _ in 0..2 {
+                let line = lines.next_line().await.unwrap().unwrap();
+                responses.push(serde_json::from_str::<Value>(&line).unwrap());
+            }
+            responses.sort_by_key(|response| response["id"].as_i64());
+            assert_eq!(responses[0]["result"]["model"], "echo");
+            assert_eq!(responses[0]["result"]["role"], "assistant");
+            assert_eq!(responses[0]["result"]["content"]["text"], "ping");
+            // maxTokens is required
+            assert_eq!(responses[1]["error"]["code"], -32602);
+
+            drop(client_write);
+            drop(lines);
+        };
+
+        let (result, _) = tokio::join!(server, client);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_retried_request_gets_cached_response() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server);
+        let server = Server::new(RouterService(NoopRouter))
+            .with_deduplicator(RequestDeduplicator::default())
+            .run(ByteTransport::new(server_read, server_write));
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let client = async move {
+            for message in [
+                json!({"jsonrpc": "2.0", "id": 7, "method": "tools/list", "params": {}}),
+                // Would never finish if it ran, the id was answered so it is replayed instead
+                json!({"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": {"name": "wait"}}),
+            ] {
+                client_write
+                    .write_all(format!("{}\n", message).as_bytes())
+                    .await
+                    .unwrap();
+            }
+
+            let mut lines = BufReader::new(client_read).lines();
+            let first = lines.next_line().await.unwrap().unwrap();
+            let second = lines.next_line().await.unwrap().unwrap();
+            assert_eq!(first, second);
+            let response: Value = serde_json::from_str(&second).unwrap();
+            assert_eq!(response["id"], 7);
+            assert!(response["result"]["tools"].is_array());
+
+            drop(client_write);
+            drop(lines);
+        };
+
+        let (result, _) = tokio::join!(server, client);
+        assert!(result.is_ok());
+    }
+
+    // The blanket impl of BoundedService stops applying without an error at the impl if
+    // RouterService's associated types drift from its bounds, fail here instead of in every
+    // server binary
+    assert_impl_all!(RouterService<NoopRouter>: BoundedService);
+    assert_impl_all!(Server<RouterService<NoopRouter>>: Send);
+
+    const _: fn() = || {
+        fn assert_static<T: 'static>() {}
+        assert_static::<Server<RouterService<NoopRouter>>>();
+    };
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use mcp_core::handler::{PromptError, ResourceError};
 use mcp_core::{handler::ToolError, protocol::ServerCapabilities};
 use mcp_server::router::{CapabilitiesBuilder, RouterService};
-use mcp_server::{ByteTransport, Router, Server};
+use mcp_server::{ByteTransport, ResourceSubscriptions, Router, Server};
 use rmcp::model::{
     Content, JsonRpcMessage, Prompt, PromptArgument, RawResource, Resource, Tool, ToolAnnotations,
 };
@@ -21,24 +21,28 @@ use tracing_subscriber::{self, EnvFilter};
 #[derive(Clone)]
 struct CounterRouter {
     counter: Arc<Mutex<i32>>,
+    subscriptions: ResourceSubscriptions,
 }
 
 impl CounterRouter {
-    fn new() -> Self {
+    fn new(subscriptions: ResourceSubscriptions) -> Self {
         Self {
             counter: Arc::new(Mutex::new(0)),
+            subscriptions,
         }
     }
 
     async fn increment(&self) -> Result<i32, ToolError> {
         let mut counter = self.counter.lock().await;
         *counter += 1;
+        self.subscriptions.notify_updated("counter://value");
         Ok(*counter)
     }
 
     async fn decrement(&self) -> Result<i32, ToolError> {
         let mut counter = self.counter.lock().await;
         *counter -= 1;
+        self.subscriptions.notify_updated("counter://value");
         Ok(*counter)
     }
 
@@ -64,7 +68,7 @@ impl Router for CounterRouter {
     fn capabilities(&self) -> ServerCapabilities {
         CapabilitiesBuilder::new()
             .with_tools(false)
-            .with_resources(false, false)
+            .with_resources(true, false)
             .with_prompts(false)
             .build()
     }
@@ -154,6 +158,7 @@ impl Router for CounterRouter {
         vec![
             self._create_resource_text("str:////Users/to/some/path/", "cwd"),
             self._create_resource_text("memo://insights", "memo-name"),
+            self._create_resource_text("counter://value", "counter-value"),
         ]
     }
 
@@ -161,9 +166,14 @@ impl Router for CounterRouter {
         &self,
         uri: &str,
     ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let this = self.clone();
         let uri = uri.to_string();
         Box::pin(async move {
             match uri.as_str() {
+                "counter://value" => {
+                    let value = this.counter.lock().await;
+                    Ok(value.to_string())
+                }
                 "str:////Users/to/some/path/" => {
                     let cwd = "/Users/to/some/path/";
                     Ok(cwd.to_string())
@@ -181,6 +191,22 @@ impl Router for CounterRouter {
         })
     }
 
+    fn subscribe_resource(&self, uri: &str) -> Result<(), ResourceError> {
+        if uri != "counter://value" {
+            return Err(ResourceError::NotFound(format!(
+                "Resource {} does not support subscriptions",
+                uri
+            )));
+        }
+        self.subscriptions.subscribe(uri);
+        Ok(())
+    }
+
+    fn unsubscribe_resource(&self, uri: &str) -> Result<(), ResourceError> {
+        self.subscriptions.unsubscribe(uri);
+        Ok(())
+    }
+
     fn list_prompts(&self) -> Vec<Prompt> {
         vec![Prompt::new(
             "example_prompt",
@@ -231,10 +257,11 @@ async fn main() -> Result<()> {
     tracing::info!("Starting MCP server");
 
     // Create an instance of our counter router
-    let router = RouterService(CounterRouter::new());
+    let (subscriptions, resource_updates) = ResourceSubscriptions::new();
+    let router = RouterService(CounterRouter::new(subscriptions));
 
     // Create and run the server
-    let server = Server::new(router);
+    let server = Server::new(router).with_resource_updates(resource_updates);
     let transport = ByteTransport::new(stdin(), stdout());
 
     tracing::info!("Server initialized and ready to handle requests");
@@ -1,10 +1,14 @@
 use std::{
+    collections::HashSet,
     future::Future,
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
 };
 
 type PromptFuture = Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>>;
+type SamplingFuture =
+    Pin<Box<dyn Future<Output = Result<SamplingResponse, RouterError>> + Send + 'static>>;
 use mcp_core::{
     handler::{PromptError, ResourceError, ToolError},
     protocol::{
@@ -14,15 +18,23 @@ use mcp_core::{
     },
 };
 use rmcp::model::{
-    Content, GetPromptResult, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, JsonRpcVersion2_0,
-    Prompt, PromptMessage, PromptMessageRole, RequestId, Resource, ResourceContents,
+    Content, CreateMessageRequestParam, CreateMessageResult, GetPromptResult, JsonRpcMessage,
+    JsonRpcRequest, JsonRpcResponse, JsonRpcVersion2_0, Prompt, PromptMessage, PromptMessageRole,
+    RequestId, Resource, ResourceContents,
 };
 use serde_json::Value;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tower_service::Service;
 
 use crate::{BoxError, RouterError};
 
+/// Params of a `sampling/createMessage` request: the conversation to continue and how to
+/// generate the reply
+pub type SamplingRequest = CreateMessageRequestParam;
+/// The generated message and the model that wrote it
+pub type SamplingResponse = CreateMessageResult;
+
 /// Builder for configuring and constructing capabilities
 pub struct CapabilitiesBuilder {
     tools: Option<ToolsCapability>,
@@ -81,6 +93,51 @@ impl CapabilitiesBuilder {
     }
 }
 
+/// Tracks the resources a client has subscribed to and forwards change events for
+/// them to the server, which pushes `notifications/resources/updated` to the client
+#[derive(Clone)]
+pub struct ResourceSubscriptions {
+    subscribed: Arc<Mutex<HashSet<String>>>,
+    updates: mpsc::Sender<String>,
+}
+
+impl ResourceSubscriptions {
+    /// Create a new subscription set, the receiver should be handed to
+    /// `Server::with_resource_updates`
+    pub fn new() -> (Self, mpsc::Receiver<String>) {
+        let (updates, rx) = mpsc::channel(256);
+        (
+            Self {
+                subscribed: Arc::new(Mutex::new(HashSet::new())),
+                updates,
+            },
+            rx,
+        )
+    }
+
+    pub fn subscribe(&self, uri: &str) {
+        self.subscribed.lock().unwrap().insert(uri.to_string());
+    }
+
+    /// Returns false if the uri was not subscribed
+    pub fn unsubscribe(&self, uri: &str) -> bool {
+        self.subscribed.lock().unwrap().remove(uri)
+    }
+
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        self.subscribed.lock().unwrap().contains(uri)
+    }
+
+    /// Signal that a resource changed, only subscribed resources are forwarded
+    pub fn notify_updated(&self, uri: &str) {
+        if self.is_subscribed(uri) {
+            if let Err(e) = self.updates.try_send(uri.to_string()) {
+                tracing::warn!(uri = %uri, error = %e, "Failed to queue resource update");
+            }
+        }
+    }
+}
+
 pub trait Router: Send + Sync + 'static {
     fn name(&self) -> String;
     // in the protocol, instructions are optional but we make it required
@@ -93,6 +150,20 @@ pub trait Router: Send + Sync + 'static {
         arguments: Value,
         notifier: mpsc::Sender<JsonRpcMessage>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>;
+
+    // Called for `tools/call`, the token is cancelled when the client sends
+    // `notifications/cancelled` for the request. Routers with long running tools override
+    // this to stop early, the default ignores the token
+    fn call_tool_with_cancellation(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+        _cancel_token: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        self.call_tool(tool_name, arguments, notifier)
+    }
+
     fn list_resources(&self) -> Vec<Resource>;
     fn read_resource(
         &self,
@@ -101,6 +172,32 @@ pub trait Router: Send + Sync + 'static {
     fn list_prompts(&self) -> Vec<Prompt>;
     fn get_prompt(&self, prompt_name: &str) -> PromptFuture;
 
+    // Subscriptions are optional, routers that support them should also advertise
+    // `with_resources(true, _)` in their capabilities
+    fn subscribe_resource(&self, uri: &str) -> Result<(), ResourceError> {
+        Err(ResourceError::ExecutionError(format!(
+            "Resource subscriptions are not supported, cannot subscribe to {}",
+            uri
+        )))
+    }
+
+    fn unsubscribe_resource(&self, uri: &str) -> Result<(), ResourceError> {
+        Err(ResourceError::ExecutionError(format!(
+            "Resource subscriptions are not supported, cannot unsubscribe from {}",
+            uri
+        )))
+    }
+
+    // Called for `sampling/createMessage`, routers with access to a model override this to
+    // generate the message. The default answers that the method is not supported
+    fn sampling_create_message(&self, _request: SamplingRequest) -> SamplingFuture {
+        Box::pin(async {
+            Err(RouterError::MethodNotFound(
+                "sampling/createMessage is not supported by this server".into(),
+            ))
+        })
+    }
+
     // Helper method to create base response
     fn create_response(&self, id: RequestId) -> JsonRpcResponse {
         JsonRpcResponse {
@@ -170,6 +267,7 @@ pub trait Router: Send + Sync + 'static {
         &self,
         req: JsonRpcRequest,
         notifier: mpsc::Sender<JsonRpcMessage>,
+        cancel_token: CancellationToken,
     ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
         async move {
             let params = &req.request.params;
@@ -181,7 +279,10 @@ pub trait Router: Send + Sync + 'static {
 
             let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
 
-            let result = match self.call_tool(name, arguments, notifier).await {
+            let result = match self
+                .call_tool_with_cancellation(name, arguments, notifier, cancel_token)
+                .await
+            {
                 Ok(result) => CallToolResult {
                     content: result,
                     is_error: None,
@@ -243,6 +344,60 @@ pub trait Router: Send + Sync + 'static {
         }
     }
 
+    fn handle_resources_subscribe(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let uri = req
+                .request
+                .params
+                .get("uri")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RouterError::InvalidParams("Missing resource URI".into()))?;
+
+            self.subscribe_resource(uri).map_err(RouterError::from)?;
+
+            Ok(self.create_response(req.id))
+        }
+    }
+
+    fn handle_resources_unsubscribe(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let uri = req
+                .request
+                .params
+                .get("uri")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RouterError::InvalidParams("Missing resource URI".into()))?;
+
+            self.unsubscribe_resource(uri).map_err(RouterError::from)?;
+
+            Ok(self.create_response(req.id))
+        }
+    }
+
+    fn handle_sampling_create_message(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let request: SamplingRequest =
+                serde_json::from_value(Value::Object(req.request.params.clone())).map_err(|e| {
+                    RouterError::InvalidParams(format!("Invalid sampling request: {}", e))
+                })?;
+
+            let result = self.sampling_create_message(request).await?;
+
+            let mut response = self.create_response(req.id);
+            self.set_result(&mut response, result)?;
+            Ok(response)
+        }
+    }
+
     fn handle_prompts_list(
         &self,
         req: JsonRpcRequest,
@@ -384,6 +539,8 @@ pub struct RouterService<T>(pub T);
 pub struct McpRequest {
     pub request: JsonRpcRequest,
     pub notifier: mpsc::Sender<JsonRpcMessage>,
+    /// Cancelled when the client sends `notifications/cancelled` for this request
+    pub cancel_token: CancellationToken,
 }
 
 impl<T> Service<McpRequest> for RouterService<T>
@@ -405,11 +562,17 @@ where
             let result = match req.request.request.method.as_str() {
                 "initialize" => this.handle_initialize(req.request).await,
                 "tools/list" => this.handle_tools_list(req.request).await,
-                "tools/call" => this.handle_tools_call(req.request, req.notifier).await,
+                "tools/call" => {
+                    this.handle_tools_call(req.request, req.notifier, req.cancel_token)
+                        .await
+                }
                 "resources/list" => this.handle_resources_list(req.request).await,
                 "resources/read" => this.handle_resources_read(req.request).await,
+                "resources/subscribe" => this.handle_resources_subscribe(req.request).await,
+                "resources/unsubscribe" => this.handle_resources_unsubscribe(req.request).await,
                 "prompts/list" => this.handle_prompts_list(req.request).await,
                 "prompts/get" => this.handle_prompts_get(req.request).await,
+                "sampling/createMessage" => this.handle_sampling_create_message(req.request).await,
                 _ => {
                     return Err(
                         RouterError::MethodNotFound(req.request.request.method.clone()).into(),